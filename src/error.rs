@@ -23,6 +23,12 @@ pub enum GradleError {
     #[error("Gradle wrapper not found at: {0}")]
     WrapperNotFound(String),
 
+    #[error("No build.gradle.kts or build.gradle found in: {0}")]
+    BuildFileNotFound(String),
+
+    #[error("Invalid Gradle configuration name: {0}")]
+    InvalidConfiguration(String),
+
     #[error("Gradle command failed: {0}")]
     CommandFailed(String),
 
@@ -0,0 +1,197 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use super::DependencyNode;
+
+/// Cache directory, relative to the project root.
+pub const CACHE_DIR: &str = ".kotlin-java-mcp/gradle-cache";
+
+/// How long resolved dependencies stay fresh unless configured otherwise.
+pub const DEFAULT_TTL: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    /// Seconds since the Unix epoch when the entry was written
+    created_at: u64,
+    dependencies: Vec<DependencyNode>,
+}
+
+/// On-disk cache of resolved dependencies, one JSON file per module and configuration.
+/// A zero TTL disables the cache.
+pub struct DependencyCache {
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+impl DependencyCache {
+    pub fn new(project_root: &Path, ttl: Duration) -> Self {
+        Self {
+            dir: project_root.join(CACHE_DIR),
+            ttl,
+        }
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Return the cached `configuration` dependencies of `module` if the entry is younger
+    /// than the TTL.
+    pub fn get(&self, module: &str, configuration: &str) -> Option<Vec<DependencyNode>> {
+        if self.ttl.is_zero() {
+            return None;
+        }
+        let path = self.entry_path(module, configuration);
+        let content = std::fs::read_to_string(&path).ok()?;
+        let entry: CacheEntry = match serde_json::from_str(&content) {
+            Ok(entry) => entry,
+            Err(e) => {
+                debug!("Ignoring unreadable cache entry {}: {}", path.display(), e);
+                return None;
+            }
+        };
+
+        let age = now_secs().saturating_sub(entry.created_at);
+        if age >= self.ttl.as_secs() {
+            debug!("Cache entry for {} expired ({}s old)", normalize_module(module), age);
+            return None;
+        }
+        debug!("Using cached dependencies for {}", normalize_module(module));
+        Some(entry.dependencies)
+    }
+
+    /// Store the `configuration` dependencies of `module`. Failures are logged and otherwise
+    /// ignored, since the cache only saves a Gradle invocation.
+    pub fn put(&self, module: &str, configuration: &str, dependencies: &[DependencyNode]) {
+        if self.ttl.is_zero() {
+            return;
+        }
+        let entry = CacheEntry {
+            created_at: now_secs(),
+            dependencies: dependencies.to_vec(),
+        };
+        let path = self.entry_path(module, configuration);
+        let result = std::fs::create_dir_all(&self.dir)
+            .and_then(|_| serde_json::to_string(&entry).map_err(std::io::Error::other))
+            .and_then(|json| std::fs::write(&path, json));
+        if let Err(e) = result {
+            debug!("Failed to write cache entry {}: {}", path.display(), e);
+        }
+    }
+
+    /// Remove all cached entries.
+    pub fn clear(&self) {
+        if let Err(e) = std::fs::remove_dir_all(&self.dir) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                debug!("Failed to clear {}: {}", self.dir.display(), e);
+            }
+        }
+    }
+
+    /// `:app` and `app` share `app@compileClasspath.json`; nested `:feature:login` becomes
+    /// `feature.login@compileClasspath.json`.
+    fn entry_path(&self, module: &str, configuration: &str) -> PathBuf {
+        let normalized = normalize_module(module);
+        let name = match normalized.trim_start_matches(':') {
+            "" => "root".to_string(),
+            path => path.replace(':', "."),
+        };
+        self.dir.join(format!("{}@{}.json", name, configuration))
+    }
+}
+
+/// Normalize a Gradle module path to its absolute form (`app` → `:app`).
+pub fn normalize_module(module: &str) -> String {
+    if module.starts_with(':') {
+        module.to_string()
+    } else {
+        format!(":{}", module)
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_deps() -> Vec<DependencyNode> {
+        vec![DependencyNode {
+            group: "com.google.code.gson".to_string(),
+            artifact: "gson".to_string(),
+            version: "2.10.1".to_string(),
+            resolved_version: Some("2.11.0".to_string()),
+            is_project: false,
+            is_transitive_duplicate: false,
+            configuration: None,
+            children: Vec::new(),
+        }]
+    }
+
+    #[test]
+    fn test_cache_round_trip_normalizes_module() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DependencyCache::new(dir.path(), DEFAULT_TTL);
+        assert!(cache.get(":app", "compileClasspath").is_none());
+
+        cache.put(":app", "compileClasspath", &sample_deps());
+        assert!(dir.path().join(CACHE_DIR).join("app@compileClasspath.json").is_file());
+
+        // Both forms of the module path hit the same entry
+        for module in [":app", "app"] {
+            let deps = cache.get(module, "compileClasspath").unwrap();
+            assert_eq!(deps.len(), 1);
+            assert_eq!(deps[0].artifact, "gson");
+            assert_eq!(deps[0].resolved_version.as_deref(), Some("2.11.0"));
+        }
+        assert!(cache.get(":core", "compileClasspath").is_none());
+        // Each configuration has its own entry
+        assert!(cache.get(":app", "runtimeClasspath").is_none());
+    }
+
+    #[test]
+    fn test_cache_ignores_expired_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DependencyCache::new(dir.path(), Duration::from_secs(60));
+        let entry = CacheEntry {
+            created_at: now_secs() - 120,
+            dependencies: sample_deps(),
+        };
+        std::fs::create_dir_all(dir.path().join(CACHE_DIR)).unwrap();
+        std::fs::write(
+            dir.path().join(CACHE_DIR).join("app@compileClasspath.json"),
+            serde_json::to_string(&entry).unwrap(),
+        )
+        .unwrap();
+
+        assert!(cache.get("app", "compileClasspath").is_none());
+    }
+
+    #[test]
+    fn test_cache_clear_and_zero_ttl() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DependencyCache::new(dir.path(), DEFAULT_TTL);
+        cache.put("feature:login", "runtimeClasspath", &sample_deps());
+        assert!(dir
+            .path()
+            .join(CACHE_DIR)
+            .join("feature.login@runtimeClasspath.json")
+            .is_file());
+
+        cache.clear();
+        assert!(cache.get(":feature:login", "runtimeClasspath").is_none());
+
+        let disabled = DependencyCache::new(dir.path(), Duration::ZERO);
+        disabled.put(":app", "compileClasspath", &sample_deps());
+        assert!(disabled.get(":app", "compileClasspath").is_none());
+        assert!(!dir.path().join(CACHE_DIR).exists());
+    }
+}
@@ -0,0 +1,519 @@
+use std::path::{Path, PathBuf};
+
+use super::{DependencyNode, GradleModule};
+
+/// Build script file names, in lookup order.
+const BUILD_FILE_NAMES: &[&str] = &["build.gradle.kts", "build.gradle"];
+
+/// Find the build script in a module directory, preferring the Kotlin DSL.
+pub fn find_build_file(module_dir: &Path) -> Option<PathBuf> {
+    BUILD_FILE_NAMES
+        .iter()
+        .map(|name| module_dir.join(name))
+        .find(|path| path.is_file())
+}
+
+/// Settings script file names, in lookup order.
+const SETTINGS_FILE_NAMES: &[&str] = &["settings.gradle.kts", "settings.gradle"];
+
+/// Find the settings script in the project root, preferring the Kotlin DSL.
+pub fn find_settings_file(project_root: &Path) -> Option<PathBuf> {
+    SETTINGS_FILE_NAMES
+        .iter()
+        .map(|name| project_root.join(name))
+        .find(|path| path.is_file())
+}
+
+/// Parse the top-level `include` statements of a `settings.gradle.kts` or `settings.gradle`
+/// script into the modules they declare, in declaration order.
+///
+/// Supported notations:
+/// ```text
+/// include(":app", ":core")             // Kotlin DSL
+/// include ':app', 'feature:login'      // Groovy DSL; the leading colon is optional
+/// ```
+/// Includes whose paths aren't string literals (variables, loops) are skipped.
+pub fn parse_settings_file(content: &str) -> Vec<GradleModule> {
+    let source = strip_comments(content);
+    let mut modules: Vec<GradleModule> = Vec::new();
+
+    for statement in split_statements(&source) {
+        let rest = match statement.strip_prefix("include") {
+            Some(rest) => rest,
+            None => continue,
+        };
+        // `includeBuild(...)` and `includeFlat` aren't modules of this build
+        if rest.starts_with(|c: char| c.is_alphanumeric() || c == '_') {
+            continue;
+        }
+        let rest = rest.trim_start();
+        let args = if rest.starts_with('(') {
+            match matching_close(rest, 0, b'(', b')') {
+                Some(close) => &rest[1..close],
+                None => continue,
+            }
+        } else {
+            rest
+        };
+        for arg in split_args(args) {
+            if let Arg::Positional(path) = arg {
+                let path = format!(":{}", path.trim_start_matches(':'));
+                if modules.iter().any(|m| m.path == path) {
+                    continue;
+                }
+                let name = path.rsplit(':').next().unwrap_or(&path).to_string();
+                modules.push(GradleModule { path, name });
+            }
+        }
+    }
+
+    modules
+}
+
+/// Parse the top-level `dependencies { ... }` blocks of a `build.gradle.kts` or
+/// `build.gradle` script. Returns the declared (unresolved) dependencies, each
+/// tagged with its configuration (e.g., `implementation`, `api`, `testImplementation`).
+///
+/// Supported notations:
+/// ```text
+/// implementation("group:artifact:version")             // Kotlin DSL string
+/// implementation 'group:artifact:version'              // Groovy DSL string
+/// api(group = "g", name = "a", version = "v")          // Kotlin DSL map
+/// api group: 'g', name: 'a', version: 'v'              // Groovy DSL map
+/// implementation(project(":core"))                     // project dependency
+/// ```
+/// Anything else (version catalog accessors, `kotlin("...")`, `platform(...)`) is skipped.
+pub fn parse_build_file(content: &str) -> Vec<DependencyNode> {
+    let source = strip_comments(content);
+    let mut deps = Vec::new();
+
+    for block in top_level_dependency_blocks(&source) {
+        for statement in split_statements(block) {
+            if let Some(dep) = parse_dependency_statement(statement) {
+                deps.push(dep);
+            }
+        }
+    }
+
+    deps
+}
+
+/// Replace `//` and `/* */` comments with spaces, leaving string literals intact.
+fn strip_comments(content: &str) -> String {
+    let chars: Vec<char> = content.chars().collect();
+    let mut out = String::with_capacity(content.len());
+    let mut quote: Option<char> = None;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if let Some(q) = quote {
+            out.push(c);
+            if c == '\\' && i + 1 < chars.len() {
+                out.push(chars[i + 1]);
+                i += 1;
+            } else if c == q {
+                quote = None;
+            }
+        } else if c == '"' || c == '\'' {
+            quote = Some(c);
+            out.push(c);
+        } else if c == '/' && chars.get(i + 1) == Some(&'/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            continue;
+        } else if c == '/' && chars.get(i + 1) == Some(&'*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                if chars[i] == '\n' {
+                    out.push('\n');
+                }
+                i += 1;
+            }
+            i += 2;
+            out.push(' ');
+            continue;
+        } else {
+            out.push(c);
+        }
+        i += 1;
+    }
+
+    out
+}
+
+/// Return the bodies of `dependencies { ... }` blocks at brace depth 0.
+/// Nested blocks (e.g., `buildscript { dependencies { ... } }`) are ignored.
+fn top_level_dependency_blocks(source: &str) -> Vec<&str> {
+    let bytes = source.as_bytes();
+    let mut blocks = Vec::new();
+    let mut depth = 0usize;
+    let mut quote: Option<u8> = None;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let b = bytes[i];
+        if let Some(q) = quote {
+            if b == b'\\' {
+                i += 1;
+            } else if b == q {
+                quote = None;
+            }
+        } else {
+            match b {
+                b'"' | b'\'' => quote = Some(b),
+                b'{' => depth += 1,
+                b'}' => depth = depth.saturating_sub(1),
+                b'd' if depth == 0 && is_keyword_at(source, i, "dependencies") => {
+                    let after = i + "dependencies".len();
+                    let rest = &source[after..];
+                    let open = after + (rest.len() - rest.trim_start().len());
+                    if bytes.get(open) == Some(&b'{') {
+                        if let Some(close) = matching_close(source, open, b'{', b'}') {
+                            blocks.push(&source[open + 1..close]);
+                            i = close + 1;
+                            continue;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        i += 1;
+    }
+
+    blocks
+}
+
+fn is_keyword_at(source: &str, pos: usize, keyword: &str) -> bool {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    source[pos..].starts_with(keyword)
+        && !source[..pos].chars().next_back().is_some_and(is_ident)
+        && !source[pos + keyword.len()..].chars().next().is_some_and(is_ident)
+}
+
+/// Find the byte offset of the delimiter closing the one at `open`, skipping string literals.
+fn matching_close(source: &str, open: usize, open_ch: u8, close_ch: u8) -> Option<usize> {
+    let bytes = source.as_bytes();
+    let mut depth = 0usize;
+    let mut quote: Option<u8> = None;
+    let mut i = open;
+
+    while i < bytes.len() {
+        let b = bytes[i];
+        if let Some(q) = quote {
+            if b == b'\\' {
+                i += 1;
+            } else if b == q {
+                quote = None;
+            }
+        } else if b == b'"' || b == b'\'' {
+            quote = Some(b);
+        } else if b == open_ch {
+            depth += 1;
+        } else if b == close_ch {
+            depth -= 1;
+            if depth == 0 {
+                return Some(i);
+            }
+        }
+        i += 1;
+    }
+
+    None
+}
+
+/// Split a block body into statements on newlines and `;` outside of parentheses,
+/// braces and string literals, so multi-line calls and trailing closures stay together.
+fn split_statements(block: &str) -> Vec<&str> {
+    let mut statements = Vec::new();
+    let mut depth = 0usize;
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    let mut start = 0;
+
+    for (i, c) in block.char_indices() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' => quote = Some(c),
+            '(' | '{' | '[' => depth += 1,
+            ')' | '}' | ']' => depth = depth.saturating_sub(1),
+            '\n' | ';' if depth == 0 => {
+                statements.push(block[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    statements.push(block[start..].trim());
+
+    statements.into_iter().filter(|s| !s.is_empty()).collect()
+}
+
+/// Parse a single statement such as `implementation("g:a:v")` into a dependency.
+fn parse_dependency_statement(statement: &str) -> Option<DependencyNode> {
+    let config_len = statement
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(statement.len());
+    if config_len == 0 {
+        return None;
+    }
+    let configuration = &statement[..config_len];
+    let rest = statement[config_len..].trim_start();
+
+    let args = if rest.starts_with('(') {
+        let close = matching_close(rest, 0, b'(', b')')?;
+        &rest[1..close]
+    } else {
+        // Groovy command syntax: `implementation 'g:a:v'`; drop any trailing closure
+        match rest.find('{') {
+            Some(brace) => rest[..brace].trim_end().trim_end_matches(','),
+            None => rest,
+        }
+    };
+
+    let mut dep = parse_dependency_args(args.trim())?;
+    dep.configuration = Some(configuration.to_string());
+    Some(dep)
+}
+
+fn parse_dependency_args(args: &str) -> Option<DependencyNode> {
+    if let Some(inner) = args.strip_prefix("project").map(str::trim_start) {
+        if inner.starts_with('(') {
+            let close = matching_close(inner, 0, b'(', b')')?;
+            // `project(":core")`, `project(path = ":core")` or `project(path: ':core')`
+            let path = split_args(&inner[1..close])
+                .into_iter()
+                .find_map(|arg| match arg {
+                    Arg::Positional(value) => Some(value),
+                    Arg::Named(key, value) if key == "path" => Some(value),
+                    Arg::Named(..) => None,
+                })?;
+            return Some(declared_node("project", path.trim_matches(':'), "", true));
+        }
+    }
+
+    let parsed = split_args(args);
+    match parsed.first()? {
+        Arg::Positional(notation) => {
+            let segments: Vec<&str> = notation.split(':').collect();
+            if segments.len() < 2 {
+                return None;
+            }
+            let version = segments.get(2).copied().unwrap_or("");
+            Some(declared_node(segments[0], segments[1], version, false))
+        }
+        Arg::Named(..) => {
+            let named = |wanted: &str| {
+                parsed.iter().find_map(|arg| match arg {
+                    Arg::Named(key, value) if key == wanted => Some(value.as_str()),
+                    _ => None,
+                })
+            };
+            let artifact = named("name")?;
+            Some(declared_node(
+                named("group").unwrap_or(""),
+                artifact,
+                named("version").unwrap_or(""),
+                false,
+            ))
+        }
+    }
+}
+
+fn declared_node(group: &str, artifact: &str, version: &str, is_project: bool) -> DependencyNode {
+    DependencyNode {
+        group: group.to_string(),
+        artifact: artifact.to_string(),
+        version: version.to_string(),
+        resolved_version: None,
+        is_project,
+        is_transitive_duplicate: false,
+        configuration: None,
+        children: Vec::new(),
+    }
+}
+
+enum Arg {
+    Positional(String),
+    Named(String, String),
+}
+
+/// Split a call's argument list on top-level commas, keeping only arguments whose
+/// value is a plain string literal. Named arguments use `=` (Kotlin) or `:` (Groovy).
+fn split_args(args: &str) -> Vec<Arg> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut quote: Option<char> = None;
+    let mut start = 0;
+
+    for (i, c) in args.char_indices() {
+        if let Some(q) = quote {
+            if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' => quote = Some(c),
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(&args[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&args[start..]);
+
+    parts
+        .into_iter()
+        .filter_map(|part| {
+            let part = part.trim();
+            if let Some(value) = string_literal(part) {
+                return Some(Arg::Positional(value));
+            }
+            let sep = part.find(['=', ':'])?;
+            let key = part[..sep].trim();
+            if key.is_empty() || !key.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                return None;
+            }
+            let value = string_literal(part[sep + 1..].trim())?;
+            Some(Arg::Named(key.to_string(), value))
+        })
+        .collect()
+}
+
+/// Return the contents of a `"..."` or `'...'` literal spanning all of `s`.
+fn string_literal(s: &str) -> Option<String> {
+    let quote = s.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let inner = s.strip_prefix(quote)?.strip_suffix(quote)?;
+    if inner.contains(quote) {
+        return None;
+    }
+    Some(inner.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_settings_includes() {
+        let content = r#"
+pluginManagement {
+    repositories { gradlePluginPortal() }
+}
+rootProject.name = "shop"
+include(":app", ":core")
+include ':feature:login', 'feature:search' // Groovy
+// include(":disabled")
+includeBuild("build-logic")
+include(":core")
+"#;
+        let modules = parse_settings_file(content);
+        let paths: Vec<(&str, &str)> =
+            modules.iter().map(|m| (m.path.as_str(), m.name.as_str())).collect();
+        assert_eq!(
+            paths,
+            vec![
+                (":app", "app"),
+                (":core", "core"),
+                (":feature:login", "login"),
+                (":feature:search", "search"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_kotlin_dsl_string_notation() {
+        let content = r#"
+plugins {
+    kotlin("jvm")
+}
+
+dependencies {
+    implementation("com.google.code.gson:gson:2.10.1")
+    api("org.slf4j:slf4j-api:2.0.9") // logging
+    testImplementation("junit:junit:4.13.2")
+}
+"#;
+        let deps = parse_build_file(content);
+        assert_eq!(deps.len(), 3);
+        assert_eq!(deps[0].group, "com.google.code.gson");
+        assert_eq!(deps[0].artifact, "gson");
+        assert_eq!(deps[0].version, "2.10.1");
+        assert_eq!(deps[0].configuration.as_deref(), Some("implementation"));
+        assert_eq!(deps[1].configuration.as_deref(), Some("api"));
+        assert_eq!(deps[2].configuration.as_deref(), Some("testImplementation"));
+    }
+
+    #[test]
+    fn test_parse_map_notation() {
+        let kotlin = r#"dependencies {
+    implementation(group = "org.apache.commons", name = "commons-lang3", version = "3.14.0")
+}"#;
+        let groovy = r#"dependencies {
+    implementation group: 'org.apache.commons', name: 'commons-lang3', version: '3.14.0'
+}"#;
+        for content in [kotlin, groovy] {
+            let deps = parse_build_file(content);
+            assert_eq!(deps.len(), 1, "content: {}", content);
+            assert_eq!(deps[0].group, "org.apache.commons");
+            assert_eq!(deps[0].artifact, "commons-lang3");
+            assert_eq!(deps[0].version, "3.14.0");
+        }
+    }
+
+    #[test]
+    fn test_parse_project_dependencies() {
+        let content = r#"dependencies {
+    implementation(project(":core"))
+    api project(path: ':feature')
+}"#;
+        let deps = parse_build_file(content);
+        assert_eq!(deps.len(), 2);
+        assert!(deps[0].is_project);
+        assert_eq!(deps[0].artifact, "core");
+        assert!(deps[1].is_project);
+        assert_eq!(deps[1].artifact, "feature");
+        assert_eq!(deps[1].configuration.as_deref(), Some("api"));
+    }
+
+    #[test]
+    fn test_ignores_buildscript_and_comments() {
+        let content = r#"
+buildscript {
+    dependencies {
+        classpath("com.android.tools.build:gradle:8.2.0")
+    }
+}
+/*
+dependencies {
+    implementation("commented:out:1.0")
+}
+*/
+dependencies {
+    // implementation("also:commented:1.0")
+    implementation(libs.kotlinx.coroutines)
+    implementation("com.squareup.okhttp3:okhttp:4.12.0") {
+        exclude(group = "org.jetbrains.kotlin")
+    }
+}
+"#;
+        let deps = parse_build_file(content);
+        assert_eq!(deps.len(), 1);
+        assert_eq!(deps[0].artifact, "okhttp");
+    }
+}
@@ -1,26 +1,38 @@
+pub mod cache;
+pub mod kts_parser;
 pub mod parser;
 
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::time::Duration;
 
 use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
 use tracing::debug;
 
 use crate::error::GradleError;
 
+use cache::{normalize_module, DependencyCache};
+
+/// Configuration resolved when none is requested.
+pub const DEFAULT_CONFIGURATION: &str = "compileClasspath";
+
 #[derive(Debug, Clone, Default)]
 pub struct GradleInfo {
     pub modules: Vec<GradleModule>,
     pub root_path: PathBuf,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GradleModule {
     pub path: String,
     pub name: String,
 }
 
-#[derive(Debug, Clone)]
+/// One dependency and what it pulls in. Serialized with camelCase keys (`resolvedVersion`,
+/// `isProject`, ...), both for `dependency_tree` JSON output and the dependency cache.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct DependencyNode {
     pub group: String,
     pub artifact: String,
@@ -28,22 +40,37 @@ pub struct DependencyNode {
     pub resolved_version: Option<String>,
     pub is_project: bool,
     pub is_transitive_duplicate: bool,
+    /// Declaring configuration (e.g., `implementation`), set for dependencies read from build scripts
+    pub configuration: Option<String>,
     pub children: Vec<DependencyNode>,
 }
 
 pub struct GradleRunner {
     project_root: PathBuf,
+    offline: bool,
     cached_info: RwLock<Option<GradleInfo>>,
+    dependency_cache: DependencyCache,
 }
 
 impl GradleRunner {
-    pub fn new(project_root: PathBuf) -> Self {
+    /// Create a runner for `project_root`. With `offline` set, dependencies are read
+    /// from build scripts instead of invoking the Gradle wrapper.
+    pub fn new(project_root: PathBuf, offline: bool) -> Self {
         Self {
+            dependency_cache: DependencyCache::new(&project_root, cache::DEFAULT_TTL),
             project_root,
+            offline,
             cached_info: RwLock::new(None),
         }
     }
 
+    /// Set how long resolved dependencies are reused from the on-disk cache
+    /// under `<root>/.kotlin-java-mcp/gradle-cache/`. A zero TTL disables it.
+    pub fn with_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.dependency_cache = DependencyCache::new(&self.project_root, ttl);
+        self
+    }
+
     fn gradlew_path(&self) -> PathBuf {
         self.project_root.join("gradlew")
     }
@@ -52,8 +79,15 @@ impl GradleRunner {
         self.gradlew_path().exists()
     }
 
+    /// Whether dependencies come from parsing build scripts rather than Gradle resolution.
+    pub fn uses_declared_dependencies(&self) -> bool {
+        self.offline || !self.has_gradlew()
+    }
+
+    /// Drop the cached module list and all cached dependency trees.
     pub fn invalidate_cache(&self) {
         *self.cached_info.write() = None;
+        self.dependency_cache.clear();
     }
 
     pub fn get_modules(&self) -> Result<Vec<GradleModule>, GradleError> {
@@ -95,26 +129,47 @@ impl GradleRunner {
         Ok(modules)
     }
 
+    /// Resolve the dependencies of `module` in `configuration` (e.g., `runtimeClasspath`),
+    /// or [`DEFAULT_CONFIGURATION`] when None. Read from build scripts, all declared
+    /// dependencies are listed unless a configuration is requested; see
+    /// [`declared_configurations`].
     pub fn get_dependencies(
         &self,
         module: &str,
+        configuration: Option<&str>,
     ) -> Result<Vec<DependencyNode>, GradleError> {
-        if !self.has_gradlew() {
-            return Err(GradleError::WrapperNotFound(
-                self.gradlew_path().display().to_string(),
-            ));
+        if let Some(configuration) = configuration {
+            validate_configuration(configuration)?;
         }
 
-        let module_arg = if module.starts_with(':') {
-            format!("{}:dependencies", module)
-        } else {
-            format!(":{}:dependencies", module)
-        };
+        if self.uses_declared_dependencies() {
+            let deps = self.get_declared_dependencies(module)?;
+            return Ok(match configuration {
+                Some(configuration) => {
+                    let declared = declared_configurations(configuration);
+                    deps.into_iter()
+                        .filter(|dep| {
+                            dep.configuration
+                                .as_deref()
+                                .is_some_and(|c| declared.contains(&c))
+                        })
+                        .collect()
+                }
+                None => deps,
+            });
+        }
+
+        let configuration = configuration.unwrap_or(DEFAULT_CONFIGURATION);
+        if let Some(deps) = self.dependency_cache.get(module, configuration) {
+            return Ok(deps);
+        }
+
+        let module_arg = format!("{}:dependencies", normalize_module(module));
 
         let output = Command::new(self.gradlew_path())
             .arg(&module_arg)
             .arg("--configuration")
-            .arg("compileClasspath")
+            .arg(configuration)
             .arg("-q")
             .current_dir(&self.project_root)
             .output()?;
@@ -126,11 +181,122 @@ impl GradleRunner {
 
         let stdout = String::from_utf8_lossy(&output.stdout);
         let deps = parser::parse_dependencies_output(&stdout);
+        self.dependency_cache.put(module, configuration, &deps);
 
         Ok(deps)
     }
 
+    /// Read the declared dependencies of a module from its `build.gradle.kts` or
+    /// `build.gradle`, without invoking Gradle. Versions are not resolved.
+    pub fn get_declared_dependencies(
+        &self,
+        module: &str,
+    ) -> Result<Vec<DependencyNode>, GradleError> {
+        let module_dir = self.module_dir(module);
+        let build_file = kts_parser::find_build_file(&module_dir).ok_or_else(|| {
+            GradleError::BuildFileNotFound(module_dir.display().to_string())
+        })?;
+        debug!("Reading declared dependencies from {}", build_file.display());
+
+        let content = std::fs::read_to_string(&build_file)?;
+        Ok(kts_parser::parse_build_file(&content))
+    }
+
+    /// The project's modules: from `gradlew projects`, or from the settings script when
+    /// dependencies are read from build scripts (see [`Self::uses_declared_dependencies`]).
+    pub fn get_project_modules(&self) -> Result<Vec<GradleModule>, GradleError> {
+        if self.uses_declared_dependencies() {
+            self.get_declared_modules()
+        } else {
+            self.get_modules()
+        }
+    }
+
+    /// List the modules included by the settings script, without invoking Gradle. A
+    /// project without a settings script is a single-project build with no modules.
+    pub fn get_declared_modules(&self) -> Result<Vec<GradleModule>, GradleError> {
+        match kts_parser::find_settings_file(&self.project_root) {
+            Some(settings_file) => {
+                debug!("Reading modules from {}", settings_file.display());
+                let content = std::fs::read_to_string(&settings_file)?;
+                Ok(kts_parser::parse_settings_file(&content))
+            }
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// The conventional directory of `module`: `:feature:login` lives in
+    /// `<root>/feature/login`. Custom `projectDir` settings aren't taken into account.
+    pub fn module_dir(&self, module: &str) -> PathBuf {
+        module
+            .split(':')
+            .filter(|segment| !segment.is_empty())
+            .fold(self.project_root.clone(), |dir, segment| dir.join(segment))
+    }
+
     pub fn project_root(&self) -> &Path {
         &self.project_root
     }
 }
+
+/// Check that `configuration` looks like a Gradle configuration name (`runtimeClasspath`,
+/// `debugCompileClasspath`) before it is passed to `gradlew`.
+pub fn validate_configuration(configuration: &str) -> Result<(), GradleError> {
+    let mut chars = configuration.chars();
+    let valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if valid {
+        Ok(())
+    } else {
+        Err(GradleError::InvalidConfiguration(configuration.to_string()))
+    }
+}
+
+/// The build-script configurations whose dependencies end up on `configuration`: a
+/// classpath of the `main` or `test` source set collects its declaring configurations
+/// (`runtimeClasspath` takes `api`, `implementation` and `runtimeOnly`), any other
+/// name only itself (e.g., `testImplementation`).
+pub fn declared_configurations(configuration: &str) -> Vec<&str> {
+    match configuration {
+        "compileClasspath" => vec!["api", "implementation", "compileOnly", "compileOnlyApi"],
+        "runtimeClasspath" => vec!["api", "implementation", "runtimeOnly"],
+        "testCompileClasspath" => {
+            vec!["api", "implementation", "testImplementation", "testCompileOnly"]
+        }
+        "testRuntimeClasspath" => vec![
+            "api",
+            "implementation",
+            "runtimeOnly",
+            "testImplementation",
+            "testRuntimeOnly",
+        ],
+        other => vec![other],
+    }
+}
+
+/// Find the root of the project containing `start`, so that running from a module
+/// directory still indexes its sibling modules. Walks up from `start` to the enclosing
+/// repository root (the nearest directory with `.git`) and picks the outermost directory
+/// with a `settings.gradle` or `settings.gradle.kts`; a multi-project build can nest
+/// included builds with their own settings file. Without a settings file the repository
+/// root is used, and without either `start` itself.
+pub fn detect_project_root(start: &Path) -> PathBuf {
+    let mut outermost_settings = None;
+    let mut repository_root = None;
+    for dir in start.ancestors() {
+        if ["settings.gradle", "settings.gradle.kts"]
+            .iter()
+            .any(|name| dir.join(name).is_file())
+        {
+            outermost_settings = Some(dir);
+        }
+        if dir.join(".git").exists() {
+            repository_root = Some(dir);
+            break;
+        }
+    }
+    outermost_settings
+        .or(repository_root)
+        .unwrap_or(start)
+        .to_path_buf()
+}
@@ -161,6 +161,7 @@ fn parse_dependency_line(line: &str) -> Option<DependencyNode> {
             resolved_version: None,
             is_project: true,
             is_transitive_duplicate,
+            configuration: None,
             children: Vec::new(),
         });
     }
@@ -179,6 +180,7 @@ fn parse_dependency_line(line: &str) -> Option<DependencyNode> {
             resolved_version: resolved,
             is_project: false,
             is_transitive_duplicate,
+            configuration: None,
             children: Vec::new(),
         })
     } else if segments.len() == 2 {
@@ -189,6 +191,7 @@ fn parse_dependency_line(line: &str) -> Option<DependencyNode> {
             resolved_version: resolved,
             is_project: false,
             is_transitive_duplicate,
+            configuration: None,
             children: Vec::new(),
         })
     } else {
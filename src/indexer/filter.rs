@@ -0,0 +1,238 @@
+use std::path::{Component, Path, PathBuf};
+
+use glob::{MatchOptions, Pattern, PatternError};
+
+/// `*` stays within one path segment; `**` crosses segments.
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// Include/exclude globs applied during file discovery, matched against paths relative
+/// to the project root (e.g., `app/src/main/kotlin/Foo.kt`).
+///
+/// A pattern that matches a directory covers everything beneath it, so `--exclude legacy`
+/// skips the whole `legacy` module and `--exclude '**/generated/**'` skips every
+/// `generated` directory. Excluded directories are never descended into. With include
+/// patterns, only files inside a matching path are discovered.
+///
+/// An optional size cap leaves out files too large to be worth parsing, such as giant
+/// generated sources; indexing reports the files it skips. Test sources can be left out
+/// too, see [`is_test_source_dir`].
+#[derive(Debug, Clone, Default)]
+pub struct FileFilter {
+    include: Vec<PathGlob>,
+    exclude: Vec<PathGlob>,
+    /// Largest file size in bytes that is indexed; `None` means no limit
+    max_file_size: Option<u64>,
+    /// Skip test source roots
+    exclude_tests: bool,
+}
+
+#[derive(Debug, Clone)]
+struct PathGlob {
+    pattern: Pattern,
+    /// `foo/**` also matches the directory `foo` itself
+    dir_pattern: Option<Pattern>,
+    /// Leading segments without wildcards: `app/src/**/*.kt` → `app/src`
+    literal_prefix: PathBuf,
+}
+
+impl PathGlob {
+    fn new(glob: &str) -> Result<Self, PatternError> {
+        let glob = glob.trim_start_matches("./").trim_end_matches('/');
+        let dir_pattern = match glob.strip_suffix("/**") {
+            Some(dir) => Some(Pattern::new(dir)?),
+            None => None,
+        };
+        let literal_prefix = glob
+            .split('/')
+            .take_while(|segment| !segment.contains(['*', '?', '[']))
+            .collect();
+        Ok(Self {
+            pattern: Pattern::new(glob)?,
+            dir_pattern,
+            literal_prefix,
+        })
+    }
+
+    fn matches(&self, rel: &Path, is_dir: bool) -> bool {
+        self.pattern.matches_path_with(rel, MATCH_OPTIONS)
+            || (is_dir
+                && self
+                    .dir_pattern
+                    .as_ref()
+                    .is_some_and(|p| p.matches_path_with(rel, MATCH_OPTIONS)))
+    }
+
+    /// Whether files matching this glob may lie beneath the directory `rel`.
+    fn may_match_under(&self, rel: &Path) -> bool {
+        rel.starts_with(&self.literal_prefix) || self.literal_prefix.starts_with(rel)
+    }
+}
+
+impl FileFilter {
+    pub fn new(include: &[String], exclude: &[String]) -> Result<Self, PatternError> {
+        Ok(Self {
+            include: include.iter().map(|g| PathGlob::new(g)).collect::<Result<_, _>>()?,
+            exclude: exclude.iter().map(|g| PathGlob::new(g)).collect::<Result<_, _>>()?,
+            max_file_size: None,
+            exclude_tests: false,
+        })
+    }
+
+    /// Skip files larger than `max_file_size` bytes.
+    pub fn with_max_file_size(mut self, max_file_size: Option<u64>) -> Self {
+        self.max_file_size = max_file_size;
+        self
+    }
+
+    /// Skip test source roots (`src/test`, `src/androidTest`, any `test` directory).
+    pub fn with_exclude_tests(mut self, exclude_tests: bool) -> Self {
+        self.exclude_tests = exclude_tests;
+        self
+    }
+
+    pub fn max_file_size(&self) -> Option<u64> {
+        self.max_file_size
+    }
+
+    /// Whether a file of `size` bytes is small enough to be indexed.
+    pub fn allows_size(&self, size: u64) -> bool {
+        self.max_file_size.into_iter().all(|max| size <= max)
+    }
+
+    /// Whether discovery should descend into the directory at `rel`.
+    pub fn allows_dir(&self, rel: &Path) -> bool {
+        if is_root(rel) {
+            return true;
+        }
+        if self.exclude.iter().any(|g| g.matches(rel, true)) {
+            return false;
+        }
+        if self.exclude_tests && is_test_source_dir(rel) {
+            return false;
+        }
+        self.include.is_empty()
+            || self
+                .include
+                .iter()
+                .any(|g| g.may_match_under(rel) || g.matches(rel, true))
+    }
+
+    /// Whether the file at `rel` should be indexed. Its directories have already passed
+    /// [`allows_dir`](Self::allows_dir), so only include matches need the ancestors.
+    pub fn allows_file(&self, rel: &Path) -> bool {
+        if self.exclude.iter().any(|g| g.matches(rel, false)) {
+            return false;
+        }
+        self.include.is_empty()
+            || rel
+                .ancestors()
+                .filter(|ancestor| !is_root(ancestor))
+                .any(|ancestor| {
+                    let is_dir = ancestor != rel;
+                    self.include.iter().any(|g| g.matches(ancestor, is_dir))
+                })
+    }
+}
+
+/// Whether `dir` is a test source root by Gradle convention: a directory named `test` or
+/// `androidTest` anywhere, or a `src/test*` or `src/androidTest*` source set such as
+/// `src/testFixtures` or `src/androidTestDebug`.
+pub fn is_test_source_dir(dir: &Path) -> bool {
+    let name = match dir.file_name() {
+        Some(name) => name.to_string_lossy(),
+        None => return false,
+    };
+    if name == "test" || name == "androidTest" {
+        return true;
+    }
+    let in_src = dir
+        .parent()
+        .and_then(|parent| parent.file_name())
+        .is_some_and(|parent| parent == "src");
+    in_src && (name.starts_with("test") || name.starts_with("androidTest"))
+}
+
+/// Whether `rel`, a path relative to the project root, lies under a test source root.
+pub fn is_test_source(rel: &Path) -> bool {
+    rel.ancestors().skip(1).any(is_test_source_dir)
+}
+
+fn is_root(rel: &Path) -> bool {
+    rel.components().all(|c| matches!(c, Component::CurDir))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(include: &[&str], exclude: &[&str]) -> FileFilter {
+        let to_strings = |globs: &[&str]| globs.iter().map(|g| g.to_string()).collect::<Vec<_>>();
+        FileFilter::new(&to_strings(include), &to_strings(exclude)).unwrap()
+    }
+
+    #[test]
+    fn test_exclude_directory_globs() {
+        let f = filter(&[], &["**/generated/**", "legacy"]);
+        assert!(!f.allows_dir(Path::new("app/build-src/generated")));
+        assert!(!f.allows_dir(Path::new("generated")));
+        assert!(!f.allows_dir(Path::new("legacy")));
+        assert!(f.allows_dir(Path::new("app/legacy")));
+        assert!(f.allows_dir(Path::new("app/src")));
+        assert!(!f.allows_file(Path::new("app/generated/Foo.kt")));
+        assert!(f.allows_file(Path::new("app/src/Foo.kt")));
+    }
+
+    #[test]
+    fn test_exclude_file_globs() {
+        let f = filter(&[], &["**/*Test.kt"]);
+        assert!(f.allows_dir(Path::new("app/src/test")));
+        assert!(!f.allows_file(Path::new("app/src/test/UserTest.kt")));
+        assert!(!f.allows_file(Path::new("UserTest.kt")));
+        assert!(f.allows_file(Path::new("app/src/main/User.kt")));
+    }
+
+    #[test]
+    fn test_include_restricts_to_subtrees() {
+        let f = filter(&["core", "app/src/main/**"], &[]);
+        assert!(f.allows_dir(Path::new("core/src")));
+        assert!(f.allows_dir(Path::new("app")));
+        assert!(f.allows_dir(Path::new("app/src/main/kotlin")));
+        assert!(!f.allows_dir(Path::new("feature")));
+        assert!(!f.allows_dir(Path::new("app/src/test")));
+        assert!(f.allows_file(Path::new("core/src/User.kt")));
+        assert!(f.allows_file(Path::new("app/src/main/kotlin/App.kt")));
+        assert!(!f.allows_file(Path::new("app/build.gradle.kts")));
+    }
+
+    #[test]
+    fn test_max_file_size() {
+        let f = filter(&[], &[]).with_max_file_size(Some(1024));
+        assert!(f.allows_size(1024));
+        assert!(!f.allows_size(1025));
+        assert!(filter(&[], &[]).allows_size(u64::MAX));
+    }
+
+    #[test]
+    fn test_exclude_test_sources() {
+        let f = filter(&[], &[]).with_exclude_tests(true);
+        assert!(!f.allows_dir(Path::new("app/src/test")));
+        assert!(!f.allows_dir(Path::new("app/src/androidTest")));
+        assert!(!f.allows_dir(Path::new("app/src/testFixtures")));
+        assert!(!f.allows_dir(Path::new("tools/test")));
+        assert!(f.allows_dir(Path::new("app/src/main")));
+        assert!(f.allows_dir(Path::new("app/src/main/kotlin/com/example/testing")));
+        assert!(filter(&[], &[]).allows_dir(Path::new("app/src/test")));
+
+        assert!(is_test_source(Path::new("app/src/test/kotlin/UserTest.kt")));
+        assert!(!is_test_source(Path::new("app/src/main/kotlin/test.kt")));
+    }
+
+    #[test]
+    fn test_invalid_pattern_is_an_error() {
+        assert!(FileFilter::new(&["[".to_string()], &[]).is_err());
+    }
+}
@@ -2,21 +2,18 @@ use std::path::Path;
 
 use tracing::warn;
 
-use super::parser::{build_fqn, find_child_name, node_text, resolve_reference};
-use super::scope::ScopeTree;
-use super::{FileInfo, ImportInfo, SymbolKind, SymbolOccurrence};
+use super::parser::{
+    assign_enclosing_fqns, build_fqn, count_parse_errors, drop_type_parameter_references,
+    find_child_name, find_child_of_kind, local_fqn, node_text, positioned_scope,
+    resolve_qualified_type, resolve_reference, type_parameter_names, ParsedFile,
+};
+use super::scope::{
+    is_local, ScopeTree, ANONYMOUS_SCOPE_PREFIX, LOCAL_SCOPE_PREFIX, LOOP_SCOPE_PREFIX,
+};
+use super::{DeclarationModifiers, FileInfo, ImportInfo, SymbolKind, SymbolOccurrence, Visibility};
 
 /// Parse a single Java file and extract symbols.
-/// Returns (FileInfo, occurrences, type_aliases, lombok_accessor_mappings).
-pub fn parse_java_file(
-    path: &Path,
-    source: &str,
-) -> (
-    FileInfo,
-    Vec<SymbolOccurrence>,
-    Vec<(String, String)>,
-    Vec<(String, Vec<String>)>,
-) {
+pub fn parse_java_file(path: &Path, source: &str) -> ParsedFile {
     let mut parser = tree_sitter::Parser::new();
     let language = tree_sitter_java::LANGUAGE;
     parser
@@ -27,16 +24,7 @@ pub fn parse_java_file(
         Some(t) => t,
         None => {
             warn!("Failed to parse {}", path.display());
-            return (
-                FileInfo {
-                    path: path.to_path_buf(),
-                    package: None,
-                    imports: vec![],
-                },
-                vec![],
-                vec![],
-                vec![],
-            );
+            return ParsedFile::empty(path);
         }
     };
 
@@ -48,7 +36,6 @@ pub fn parse_java_file(
     let scope_tree = build_scope_tree_java(&root, src);
 
     let mut occurrences = Vec::new();
-    let type_aliases = Vec::new();
     let mut lombok_accessors = Vec::new();
 
     extract_declarations_java(
@@ -71,6 +58,16 @@ pub fn parse_java_file(
         &mut occurrences,
     );
 
+    let mut supertypes = Vec::new();
+    extract_supertypes_java(
+        &root,
+        src,
+        package.as_deref(),
+        &scope_tree,
+        &imports,
+        &mut supertypes,
+    );
+
     // Add import occurrences
     for imp in &imports {
         let name = if imp.is_wildcard {
@@ -87,16 +84,31 @@ pub fn parse_java_file(
             column: imp.column,
             byte_range: imp.byte_range.clone(),
             receiver_type: None,
+            enclosing_fqn: None,
+            signature: None,
+            platform_modifier: None,
+            modifiers: None,
         });
     }
 
+    assign_enclosing_fqns(&mut occurrences);
+
     let file_info = FileInfo {
         path: path.to_path_buf(),
         package,
         imports,
+        parse_errors: count_parse_errors(&root),
     };
 
-    (file_info, occurrences, type_aliases, lombok_accessors)
+    ParsedFile {
+        file_info,
+        occurrences,
+        type_aliases: vec![],
+        lombok_accessors,
+        supertypes,
+        annotations: vec![],
+        data_class_members: vec![],
+    }
 }
 
 fn extract_package_java(root: &tree_sitter::Node, src: &[u8]) -> Option<String> {
@@ -160,18 +172,35 @@ fn parse_java_import(node: &tree_sitter::Node, src: &[u8]) -> Option<ImportInfo>
     // the path includes the member name. We store the full path.
     // For wildcard static imports like `import static com.example.Foo.*`,
     // path is the class FQN and is_wildcard is true.
-    let _ = is_static; // tracked for potential future use
-
     path.map(|path| ImportInfo {
         path,
         alias: None,
         is_wildcard,
+        is_static,
         line: node.start_position().row + 1,
         column: node.start_position().column + 1,
         byte_range: node.byte_range(),
     })
 }
 
+/// Resolve a method called without a receiver through the file's static imports:
+/// `emptyList()` after `import static java.util.Collections.emptyList`, or `assertEquals()`
+/// after `import static org.junit.Assert.*` when that is the only static wildcard import.
+/// Methods of the calling class itself are preferred later, by `cross_reference`.
+fn resolve_static_import(name: &str, imports: &[ImportInfo]) -> Option<String> {
+    let explicit = imports.iter().find(|imp| {
+        imp.is_static && !imp.is_wildcard && imp.path.rsplit('.').next() == Some(name)
+    });
+    if let Some(imp) = explicit {
+        return Some(imp.path.clone());
+    }
+    let mut wildcards = imports.iter().filter(|imp| imp.is_static && imp.is_wildcard);
+    match (wildcards.next(), wildcards.next()) {
+        (Some(imp), None) => Some(format!("{}.{}", imp.path, name)),
+        _ => None,
+    }
+}
+
 fn build_scope_tree_java(root: &tree_sitter::Node, src: &[u8]) -> ScopeTree {
     let mut scope_tree = ScopeTree::new();
     collect_scopes_java(root, src, &mut scope_tree);
@@ -189,6 +218,12 @@ fn collect_scopes_java(node: &tree_sitter::Node, src: &[u8], tree: &mut ScopeTre
                 }
             }
         }
+        "object_creation_expression" => {
+            // Anonymous class `new Runnable() { ... }`: members become `pkg.Outer.<anon@l:c>.run`
+            if let Some(range) = find_java_body_range(node) {
+                tree.add_scope(positioned_scope(ANONYMOUS_SCOPE_PREFIX, node), range);
+            }
+        }
         _ => {}
     }
 
@@ -198,6 +233,72 @@ fn collect_scopes_java(node: &tree_sitter::Node, src: &[u8], tree: &mut ScopeTre
     }
 }
 
+/// Record the direct supertypes named in `extends`/`implements` clauses of classes,
+/// interfaces, enums and records. `class Foo extends Bar implements Baz<T>` yields
+/// `(pkg.Foo, [pkg.Bar, pkg.Baz])`.
+fn extract_supertypes_java(
+    node: &tree_sitter::Node,
+    src: &[u8],
+    package: Option<&str>,
+    scope_tree: &ScopeTree,
+    imports: &[ImportInfo],
+    supertypes: &mut Vec<(String, Vec<String>)>,
+) {
+    if matches!(
+        node.kind(),
+        "class_declaration" | "interface_declaration" | "enum_declaration" | "record_declaration"
+    ) {
+        if let Some(name) = find_child_name(node, src) {
+            let mut type_names = Vec::new();
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                if matches!(child.kind(), "superclass" | "super_interfaces" | "extends_interfaces") {
+                    collect_java_type_names(&child, src, &mut type_names);
+                }
+            }
+            let parents: Vec<String> = type_names
+                .into_iter()
+                .filter_map(|type_name| {
+                    if type_name.contains('.') {
+                        Some(type_name)
+                    } else {
+                        resolve_reference(&type_name, package, imports)
+                    }
+                })
+                .collect();
+            if !parents.is_empty() {
+                let fqn = build_fqn(package, scope_tree, node.start_byte(), &name);
+                supertypes.push((fqn, parents));
+            }
+        }
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        extract_supertypes_java(&child, src, package, scope_tree, imports, supertypes);
+    }
+}
+
+/// Collect the type names in a `superclass`, `super_interfaces` or `extends_interfaces`
+/// node, dropping type arguments (`List<String>` → `List`).
+fn collect_java_type_names(node: &tree_sitter::Node, src: &[u8], names: &mut Vec<String>) {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        match child.kind() {
+            "type_identifier" | "scoped_type_identifier" => {
+                names.push(node_text(&child, src).to_string());
+            }
+            "generic_type" => {
+                if let Some(base) = child.child(0) {
+                    names.push(node_text(&base, src).to_string());
+                }
+            }
+            "type_list" => collect_java_type_names(&child, src, names),
+            _ => {}
+        }
+    }
+}
+
 fn extract_declarations_java(
     node: &tree_sitter::Node,
     src: &[u8],
@@ -212,15 +313,20 @@ fn extract_declarations_java(
             if let Some(name) = find_child_name(node, src) {
                 let fqn = build_fqn(package, scope_tree, node.start_byte(), &name);
                 occurrences.push(SymbolOccurrence {
-                    name,
-                    fqn: Some(fqn),
+                    name: name.clone(),
+                    fqn: Some(fqn.clone()),
                     kind: SymbolKind::ClassDeclaration,
                     file: path.to_path_buf(),
                     line: node.start_position().row + 1,
                     column: node.start_position().column + 1,
                     byte_range: node.byte_range(),
                     receiver_type: None,
+                    enclosing_fqn: None,
+                    signature: None,
+                    platform_modifier: None,
+                    modifiers: Some(declaration_modifiers(node, src)),
                 });
+                synthesize_lombok_class_members(node, src, path, &name, &fqn, occurrences, lombok_accessors);
             }
         }
         "interface_declaration" => {
@@ -235,6 +341,10 @@ fn extract_declarations_java(
                     column: node.start_position().column + 1,
                     byte_range: node.byte_range(),
                     receiver_type: None,
+                    enclosing_fqn: None,
+                    signature: None,
+                    platform_modifier: None,
+                    modifiers: Some(declaration_modifiers(node, src)),
                 });
             }
         }
@@ -250,6 +360,10 @@ fn extract_declarations_java(
                     column: node.start_position().column + 1,
                     byte_range: node.byte_range(),
                     receiver_type: None,
+                    enclosing_fqn: None,
+                    signature: None,
+                    platform_modifier: None,
+                    modifiers: Some(declaration_modifiers(node, src)),
                 });
             }
         }
@@ -265,6 +379,10 @@ fn extract_declarations_java(
                     column: node.start_position().column + 1,
                     byte_range: node.byte_range(),
                     receiver_type: None,
+                    enclosing_fqn: None,
+                    signature: None,
+                    platform_modifier: None,
+                    modifiers: Some(declaration_modifiers(node, src)),
                 });
             }
         }
@@ -280,6 +398,10 @@ fn extract_declarations_java(
                     column: node.start_position().column + 1,
                     byte_range: node.byte_range(),
                     receiver_type: None,
+                    enclosing_fqn: None,
+                    signature: None,
+                    platform_modifier: None,
+                    modifiers: Some(declaration_modifiers(node, src)),
                 });
             }
         }
@@ -295,6 +417,10 @@ fn extract_declarations_java(
                     column: node.start_position().column + 1,
                     byte_range: node.byte_range(),
                     receiver_type: None,
+                    enclosing_fqn: None,
+                    signature: None,
+                    platform_modifier: None,
+                    modifiers: Some(declaration_modifiers(node, src)),
                 });
             }
         }
@@ -312,6 +438,10 @@ fn extract_declarations_java(
                     column: node.start_position().column + 1,
                     byte_range: node.byte_range(),
                     receiver_type: None,
+                    enclosing_fqn: None,
+                    signature: None,
+                    platform_modifier: None,
+                    modifiers: Some(declaration_modifiers(node, src)),
                 });
             }
         }
@@ -327,6 +457,10 @@ fn extract_declarations_java(
                     column: node.start_position().column + 1,
                     byte_range: node.byte_range(),
                     receiver_type: None,
+                    enclosing_fqn: None,
+                    signature: None,
+                    platform_modifier: None,
+                    modifiers: Some(declaration_modifiers(node, src)),
                 });
             }
         }
@@ -336,6 +470,48 @@ fn extract_declarations_java(
             // Don't recurse into children; we handle them above
             return;
         }
+        "local_variable_declaration" => {
+            // `var roster = ...` and `int count = ...` are local to their block, like the
+            // variables of enhanced for statements. Initializers may still hold anonymous
+            // classes and are traversed below.
+            for (declarator, name) in local_variables(node, src) {
+                let fqn = local_fqn(LOCAL_SCOPE_PREFIX, package, scope_tree, &declarator, &name);
+                occurrences.push(SymbolOccurrence {
+                    name,
+                    fqn: Some(fqn),
+                    kind: SymbolKind::PropertyDeclaration,
+                    file: path.to_path_buf(),
+                    line: declarator.start_position().row + 1,
+                    column: declarator.start_position().column + 1,
+                    byte_range: declarator.byte_range(),
+                    receiver_type: None,
+                    enclosing_fqn: None,
+                    signature: None,
+                    platform_modifier: None,
+                    modifiers: None,
+                });
+            }
+        }
+        "enhanced_for_statement" => {
+            // `for (User user : users)` declares `user` local to the loop
+            if let Some(variable) = node.child_by_field_name("name") {
+                let name = node_text(&variable, src).to_string();
+                occurrences.push(SymbolOccurrence {
+                    fqn: Some(local_fqn(LOOP_SCOPE_PREFIX, package, scope_tree, node, &name)),
+                    name,
+                    kind: SymbolKind::ParameterDeclaration,
+                    file: path.to_path_buf(),
+                    line: variable.start_position().row + 1,
+                    column: variable.start_position().column + 1,
+                    byte_range: variable.byte_range(),
+                    receiver_type: None,
+                    enclosing_fqn: None,
+                    signature: None,
+                    platform_modifier: None,
+                    modifiers: None,
+                });
+            }
+        }
         _ => {}
     }
 
@@ -395,6 +571,10 @@ fn extract_field_declarations(
                     column: child.start_position().column + 1,
                     byte_range: child.byte_range(),
                     receiver_type: None,
+                    enclosing_fqn: None,
+                    signature: None,
+                    platform_modifier: None,
+                    modifiers: Some(declaration_modifiers(node, src)),
                 });
 
                 // Synthesize Lombok accessor declarations
@@ -412,6 +592,10 @@ fn extract_field_declarations(
                         column: child.start_position().column + 1,
                         byte_range: child.byte_range(),
                         receiver_type: None,
+                        enclosing_fqn: None,
+                        signature: None,
+                        platform_modifier: None,
+                        modifiers: None,
                     });
                     accessor_fqns.push(getter_fqn);
                 }
@@ -428,6 +612,10 @@ fn extract_field_declarations(
                         column: child.start_position().column + 1,
                         byte_range: child.byte_range(),
                         receiver_type: None,
+                        enclosing_fqn: None,
+                        signature: None,
+                        platform_modifier: None,
+                        modifiers: None,
                     });
                     accessor_fqns.push(setter_fqn);
                 }
@@ -440,31 +628,206 @@ fn extract_field_declarations(
     }
 }
 
+/// A non-static field that Lombok's generated constructors and builder take as a parameter.
+struct LombokField {
+    name: String,
+    /// `final` or `@NonNull` without an initializer, so `@RequiredArgsConstructor` takes it
+    required: bool,
+}
+
+/// Synthesize the constructors generated by `@NoArgsConstructor`, `@RequiredArgsConstructor`,
+/// `@AllArgsConstructor`, `@Builder` and `@Data`, plus the `builder()` method and nested
+/// `FooBuilder` class of a class-level `@Builder`. Each is anchored at the annotation that
+/// generates it. Builder setters share the field's name, so they are recorded as field
+/// accessors and field usages include `Foo.builder().name(..)` calls.
+fn synthesize_lombok_class_members(
+    node: &tree_sitter::Node,
+    src: &[u8],
+    path: &Path,
+    class_name: &str,
+    class_fqn: &str,
+    occurrences: &mut Vec<SymbolOccurrence>,
+    lombok_accessors: &mut Vec<(String, Vec<String>)>,
+) {
+    let fields = lombok_fields(node, src);
+
+    let constructor_fqn = format!("{}.{}", class_fqn, class_name);
+    for (annotation, _) in lombok_constructors(node, src, &fields) {
+        occurrences.push(lombok_declaration(
+            class_name,
+            &constructor_fqn,
+            SymbolKind::ConstructorDeclaration,
+            path,
+            &annotation,
+        ));
+    }
+
+    if let Some(annotation) = find_annotation(node, src, "Builder") {
+        let builder_name = format!("{}Builder", class_name);
+        let builder_fqn = format!("{}.{}", class_fqn, builder_name);
+        occurrences.push(lombok_declaration(
+            "builder",
+            &format!("{}.builder", class_fqn),
+            SymbolKind::FunctionDeclaration,
+            path,
+            &annotation,
+        ));
+        occurrences.push(lombok_declaration(
+            &builder_name,
+            &builder_fqn,
+            SymbolKind::ClassDeclaration,
+            path,
+            &annotation,
+        ));
+        occurrences.push(lombok_declaration(
+            "build",
+            &format!("{}.build", builder_fqn),
+            SymbolKind::FunctionDeclaration,
+            path,
+            &annotation,
+        ));
+        for field in &fields {
+            let setter_fqn = format!("{}.{}", builder_fqn, field.name);
+            occurrences.push(lombok_declaration(
+                &field.name,
+                &setter_fqn,
+                SymbolKind::FunctionDeclaration,
+                path,
+                &annotation,
+            ));
+            lombok_accessors.push((format!("{}.{}", class_fqn, field.name), vec![setter_fqn]));
+        }
+    }
+}
+
+fn lombok_declaration(
+    name: &str,
+    fqn: &str,
+    kind: SymbolKind,
+    path: &Path,
+    anchor: &tree_sitter::Node,
+) -> SymbolOccurrence {
+    SymbolOccurrence {
+        name: name.to_string(),
+        fqn: Some(fqn.to_string()),
+        kind,
+        file: path.to_path_buf(),
+        line: anchor.start_position().row + 1,
+        column: anchor.start_position().column + 1,
+        byte_range: anchor.byte_range(),
+        receiver_type: None,
+        enclosing_fqn: None,
+        signature: None,
+        platform_modifier: None,
+        modifiers: None,
+    }
+}
+
+/// The non-static fields of a class in declaration order. Initialized final fields are
+/// left out, since no generated constructor or builder can set them.
+fn lombok_fields(class_node: &tree_sitter::Node, src: &[u8]) -> Vec<LombokField> {
+    let mut fields = Vec::new();
+    let body = match find_child_of_kind(class_node, "class_body") {
+        Some(body) => body,
+        None => return fields,
+    };
+    let mut cursor = body.walk();
+    for member in body.children(&mut cursor) {
+        if member.kind() != "field_declaration" || has_modifier(&member, "static") {
+            continue;
+        }
+        let is_final = has_modifier(&member, "final");
+        let is_non_null = has_annotation(&member, src, "NonNull");
+        let mut inner = member.walk();
+        for declarator in member.children(&mut inner) {
+            if declarator.kind() != "variable_declarator" {
+                continue;
+            }
+            let initialized = declarator.child_by_field_name("value").is_some();
+            if is_final && initialized {
+                continue;
+            }
+            if let Some(name) = find_child_name(&declarator, src) {
+                fields.push(LombokField {
+                    name,
+                    required: (is_final || is_non_null) && !initialized,
+                });
+            }
+        }
+    }
+    fields
+}
+
+/// The constructors Lombok generates for a class: each generating annotation with the
+/// parameter (field) names. `@Builder` implies an all-args constructor and `@Data` a
+/// required-args one, but only when the class has no explicit constructor and no
+/// `@XArgsConstructor` annotation.
+fn lombok_constructors<'a>(
+    class_node: &tree_sitter::Node<'a>,
+    src: &[u8],
+    fields: &[LombokField],
+) -> Vec<(tree_sitter::Node<'a>, Vec<String>)> {
+    let all_args: Vec<String> = fields.iter().map(|f| f.name.clone()).collect();
+    let required_args: Vec<String> = fields.iter().filter(|f| f.required).map(|f| f.name.clone()).collect();
+
+    let mut candidates = Vec::new();
+    for (annotation_name, params) in [
+        ("NoArgsConstructor", Vec::new()),
+        ("RequiredArgsConstructor", required_args.clone()),
+        ("AllArgsConstructor", all_args.clone()),
+    ] {
+        if let Some(annotation) = find_annotation(class_node, src, annotation_name) {
+            candidates.push((annotation, params));
+        }
+    }
+    let has_explicit_constructor = find_child_of_kind(class_node, "class_body")
+        .is_some_and(|body| find_child_of_kind(&body, "constructor_declaration").is_some());
+    if candidates.is_empty() && !has_explicit_constructor {
+        if let Some(annotation) = find_annotation(class_node, src, "Builder") {
+            candidates.push((annotation, all_args));
+        } else if let Some(annotation) = find_annotation(class_node, src, "Data") {
+            candidates.push((annotation, required_args));
+        }
+    }
+
+    // Annotations that yield the same parameter list describe a single constructor
+    let mut constructors: Vec<(tree_sitter::Node<'a>, Vec<String>)> = Vec::new();
+    for (annotation, params) in candidates {
+        if !constructors.iter().any(|(_, existing)| *existing == params) {
+            constructors.push((annotation, params));
+        }
+    }
+    constructors
+}
+
 // --- Lombok helpers ---
 
 /// Check if a node's `modifiers` child contains an annotation with the given name.
 /// Matches both simple (`@Data`) and qualified (`@lombok.Data`) forms.
 fn has_annotation(node: &tree_sitter::Node, src: &[u8], annotation_name: &str) -> bool {
-    let mut cursor = node.walk();
-    for child in node.children(&mut cursor) {
-        if child.kind() == "modifiers" {
-            let mut inner = child.walk();
-            for modifier in child.children(&mut inner) {
-                if modifier.kind() == "marker_annotation" || modifier.kind() == "annotation" {
-                    if let Some(name_node) = modifier.child_by_field_name("name") {
-                        let text = node_text(&name_node, src);
-                        // Match "Data" or "lombok.Data"
-                        if text == annotation_name
-                            || text.ends_with(&format!(".{}", annotation_name))
-                        {
-                            return true;
-                        }
-                    }
+    find_annotation(node, src, annotation_name).is_some()
+}
+
+/// Find the annotation with the given name in a node's `modifiers` child.
+fn find_annotation<'a>(
+    node: &tree_sitter::Node<'a>,
+    src: &[u8],
+    annotation_name: &str,
+) -> Option<tree_sitter::Node<'a>> {
+    let modifiers = find_child_of_kind(node, "modifiers")?;
+    let mut cursor = modifiers.walk();
+    for modifier in modifiers.children(&mut cursor) {
+        if modifier.kind() == "marker_annotation" || modifier.kind() == "annotation" {
+            if let Some(name_node) = modifier.child_by_field_name("name") {
+                let text = node_text(&name_node, src);
+                // Match "Data" or "lombok.Data"
+                if text == annotation_name || text.ends_with(&format!(".{}", annotation_name)) {
+                    return Some(modifier);
                 }
             }
         }
     }
-    false
+    None
 }
 
 /// Check if a node's `modifiers` child contains a specific keyword (e.g., "final").
@@ -483,6 +846,23 @@ fn has_modifier(node: &tree_sitter::Node, keyword: &str) -> bool {
     false
 }
 
+/// The modifiers of a Java declaration recorded on its occurrence.
+fn declaration_modifiers(node: &tree_sitter::Node, src: &[u8]) -> DeclarationModifiers {
+    let visibility = [
+        ("private", Visibility::Private),
+        ("protected", Visibility::Protected),
+        ("public", Visibility::Public),
+    ]
+    .into_iter()
+    .find(|(keyword, _)| has_modifier(node, keyword))
+    .map(|(_, visibility)| visibility);
+    DeclarationModifiers {
+        is_var: false,
+        visibility,
+        is_override: has_annotation(node, src, "Override"),
+    }
+}
+
 /// Check if a field_declaration has a boolean type (primitive `boolean`).
 fn field_type_is_boolean(node: &tree_sitter::Node, src: &[u8]) -> bool {
     let mut cursor = node.walk();
@@ -531,6 +911,8 @@ fn extract_references_java(
     occurrences: &mut Vec<SymbolOccurrence>,
 ) {
     match node.kind() {
+        // Names in `//`, `/* */` and Javadoc comments are prose, never references
+        "line_comment" | "block_comment" => return,
         "method_invocation" => {
             // method_invocation has "name" field for the method name and "object" field for receiver
             if let Some(name_node) = node.child_by_field_name("name") {
@@ -538,16 +920,25 @@ fn extract_references_java(
                 let receiver = node
                     .child_by_field_name("object")
                     .map(|r| node_text(&r, src).to_string());
-                let fqn = resolve_reference(&name, package, imports);
+                let fqn = match receiver {
+                    Some(_) => resolve_reference(&name, package, imports),
+                    None => resolve_static_import(&name, imports)
+                        .or_else(|| resolve_reference(&name, package, imports)),
+                };
+                // Position of the method name, not the receiver: `a.b().c()` reports `c` at its own column
                 occurrences.push(SymbolOccurrence {
                     name,
                     fqn,
                     kind: SymbolKind::CallSite,
                     file: path.to_path_buf(),
-                    line: node.start_position().row + 1,
-                    column: node.start_position().column + 1,
-                    byte_range: node.byte_range(),
+                    line: name_node.start_position().row + 1,
+                    column: name_node.start_position().column + 1,
+                    byte_range: name_node.byte_range(),
                     receiver_type: receiver,
+                    enclosing_fqn: None,
+                    signature: None,
+                    platform_modifier: None,
+                    modifiers: None,
                 });
             }
             // Recurse into children (arguments, receiver) but skip the name node
@@ -565,29 +956,134 @@ fn extract_references_java(
             }
             return;
         }
+        "method_reference" => {
+            // `System.out::println`, `String::valueOf`, `this::handle`, `User::new`: the member
+            // after `::` is called later, so it's a call site with the part before `::` as receiver
+            let receiver_node = node.named_child(0);
+            let receiver = receiver_node.map(|r| node_text(&r, src).to_string());
+            let is_constructor = node
+                .child(node.child_count().saturating_sub(1))
+                .is_some_and(|last| last.kind() == "new");
+            if is_constructor {
+                // `User::new` calls the constructor, like `new User(...)`
+                if let Some(type_node) = receiver_node {
+                    if matches!(
+                        type_node.kind(),
+                        "identifier" | "type_identifier" | "scoped_type_identifier" | "field_access"
+                    ) {
+                        push_qualified_type(
+                            &type_node, SymbolKind::CallSite, src, path, package, imports,
+                            occurrences,
+                        );
+                    } else {
+                        extract_references_java(
+                            &type_node, src, path, package, scope_tree, imports, occurrences,
+                        );
+                    }
+                }
+                return;
+            }
+            let name_node = node
+                .named_child(node.named_child_count().saturating_sub(1))
+                .filter(|n| n.kind() == "identifier");
+            if let Some(name_node) = name_node {
+                let name = node_text(&name_node, src).to_string();
+                let fqn = resolve_reference(&name, package, imports);
+                occurrences.push(SymbolOccurrence {
+                    name,
+                    fqn,
+                    kind: SymbolKind::CallSite,
+                    file: path.to_path_buf(),
+                    line: name_node.start_position().row + 1,
+                    column: name_node.start_position().column + 1,
+                    byte_range: name_node.byte_range(),
+                    receiver_type: receiver,
+                    enclosing_fqn: None,
+                    signature: None,
+                    platform_modifier: None,
+                    modifiers: None,
+                });
+            }
+            // The receiver (`System.out`, `List<String>`) and type arguments, but not the name
+            let mut cursor = node.walk();
+            for child in node.named_children(&mut cursor) {
+                if name_node.is_some_and(|n| n.id() == child.id()) {
+                    continue;
+                }
+                extract_references_java(&child, src, path, package, scope_tree, imports, occurrences);
+            }
+            return;
+        }
+        "lambda_expression" => {
+            // `x -> x.foo()`, `(a, b) -> a + b`, `(String s) -> s.trim()`: untyped parameter
+            // names declare locals, typed ones go through formal_parameter. The body is
+            // traversed like any other expression or block.
+            let mut cursor = node.walk();
+            for child in node.named_children(&mut cursor) {
+                if matches!(child.kind(), "identifier" | "inferred_parameters")
+                    && node
+                        .child_by_field_name("parameters")
+                        .is_some_and(|p| p.id() == child.id())
+                {
+                    continue;
+                }
+                extract_references_java(&child, src, path, package, scope_tree, imports, occurrences);
+            }
+            return;
+        }
         "object_creation_expression" => {
-            // `new Foo(...)` — the type is the first type_identifier child
+            // `new Foo(...)` — the type is the first type_identifier child.
+            // An anonymous class `new Foo() { ... }` implements or extends Foo rather than calling it.
+            let is_anonymous = find_java_body_range(node).is_some();
             if let Some(type_node) = find_type_child(node) {
                 let name = node_text(&type_node, src).to_string();
                 let fqn = resolve_reference(&name, package, imports);
                 occurrences.push(SymbolOccurrence {
                     name,
                     fqn,
-                    kind: SymbolKind::CallSite,
+                    kind: if is_anonymous {
+                        SymbolKind::TypeReference
+                    } else {
+                        SymbolKind::CallSite
+                    },
                     file: path.to_path_buf(),
                     line: node.start_position().row + 1,
                     column: node.start_position().column + 1,
                     byte_range: node.byte_range(),
                     receiver_type: None,
+                    enclosing_fqn: None,
+                    signature: None,
+                    platform_modifier: None,
+                    modifiers: None,
                 });
+            } else if let Some(type_node) = find_scoped_type_child(node) {
+                let kind = if is_anonymous {
+                    SymbolKind::TypeReference
+                } else {
+                    SymbolKind::CallSite
+                };
+                push_qualified_type(&type_node, kind, src, path, package, imports, occurrences);
             }
-            // Recurse into arguments
+            // Recurse into arguments, the type arguments of `new Foo<Bar>()` and anonymous class bodies
             let mut cursor = node.walk();
             for child in node.children(&mut cursor) {
-                if child.kind() == "argument_list" {
-                    extract_references_java(
-                        &child, src, path, package, scope_tree, imports, occurrences,
-                    );
+                match child.kind() {
+                    "argument_list" | "type_arguments" | "class_body" => {
+                        extract_references_java(
+                            &child, src, path, package, scope_tree, imports, occurrences,
+                        );
+                    }
+                    "generic_type" => {
+                        let mut inner = child.walk();
+                        for c in child.children(&mut inner) {
+                            if c.kind() == "type_arguments" {
+                                extract_references_java(
+                                    &c, src, path, package, scope_tree, imports, occurrences,
+                                );
+                            }
+                        }
+                    }
+                    _ => {}
                 }
             }
             return;
@@ -600,15 +1096,24 @@ fn extract_references_java(
                     .child_by_field_name("object")
                     .map(|r| node_text(&r, src).to_string());
                 let fqn = resolve_reference(&name, package, imports);
+                let kind = if is_nested_type_qualifier(node, &name) {
+                    SymbolKind::TypeReference
+                } else {
+                    SymbolKind::PropertyReference
+                };
                 occurrences.push(SymbolOccurrence {
                     name,
                     fqn,
-                    kind: SymbolKind::PropertyReference,
+                    kind,
                     file: path.to_path_buf(),
-                    line: node.start_position().row + 1,
-                    column: node.start_position().column + 1,
-                    byte_range: node.byte_range(),
+                    line: field_node.start_position().row + 1,
+                    column: field_node.start_position().column + 1,
+                    byte_range: field_node.byte_range(),
                     receiver_type: receiver,
+                    enclosing_fqn: None,
+                    signature: None,
+                    platform_modifier: None,
+                    modifiers: None,
                 });
             }
             // Process the receiver
@@ -637,7 +1142,10 @@ fn extract_references_java(
                         | "scoped_identifier"
                         | "scoped_type_identifier"
                 );
-                if !is_decl_name {
+                // `var x = ...` (locals, `for` variables, lambda parameters) infers the type;
+                // `var` is a reserved type name, never a reference to a declared type
+                let is_inferred = node_text(node, src) == "var";
+                if !is_decl_name && !is_inferred {
                     let name = node_text(node, src).to_string();
                     if !name.is_empty() {
                         let fqn = resolve_reference(&name, package, imports);
@@ -650,12 +1158,124 @@ fn extract_references_java(
                             column: node.start_position().column + 1,
                             byte_range: node.byte_range(),
                             receiver_type: None,
+                            enclosing_fqn: None,
+                            signature: None,
+                            platform_modifier: None,
+                            modifiers: None,
                         });
                     }
                 }
             }
             return;
         }
+        "marker_annotation" | "annotation" => {
+            // `@Override`, `@GetMapping("/x")`, `@com.example.Audited(by = Foo.class)`
+            if let Some(name_node) = node.child_by_field_name("name") {
+                let (name, fqn) = resolve_qualified_type(node_text(&name_node, src), package, imports);
+                occurrences.push(SymbolOccurrence {
+                    name,
+                    fqn,
+                    kind: SymbolKind::AnnotationReference,
+                    file: path.to_path_buf(),
+                    line: node.start_position().row + 1,
+                    column: node.start_position().column + 1,
+                    byte_range: node.byte_range(),
+                    receiver_type: None,
+                    enclosing_fqn: None,
+                    signature: None,
+                    platform_modifier: None,
+                    modifiers: None,
+                });
+            }
+            // Element values may reference other symbols (constants, `Foo.class`)
+            if let Some(args) = node.child_by_field_name("arguments") {
+                extract_references_java(&args, src, path, package, scope_tree, imports, occurrences);
+            }
+            return;
+        }
+        "scoped_type_identifier" => {
+            // `Outer.Inner field;`, `extends Outer.Inner`: one reference to the nested type
+            push_qualified_type(
+                node, SymbolKind::TypeReference, src, path, package, imports, occurrences,
+            );
+            return;
+        }
+        "type_parameter" => {
+            // `<T extends Entity>`, `<T extends Comparable<T>>`: the bound is a type
+            // reference, the name (and uses of it inside the bound) a type variable.
+            let mut names = node
+                .parent()
+                .map(|list| type_parameter_names(&list, src))
+                .unwrap_or_default();
+            names.extend(find_child_name(node, src));
+
+            let start = occurrences.len();
+            let mut cursor = node.walk();
+            for child in node.named_children(&mut cursor) {
+                if child.kind() != "type_identifier" {
+                    extract_references_java(&child, src, path, package, scope_tree, imports, occurrences);
+                }
+            }
+            drop_type_parameter_references(occurrences, start, &names);
+            return;
+        }
+        "type_pattern" | "record_pattern" | "record_pattern_component" => {
+            // Pattern labels in switch statements/expressions and `instanceof`:
+            // `case Circle c when c.radius() > 10 ->`, `case Shape.Square s ->`,
+            // `case Rectangle(double w, var h) ->`. The type is a reference; the binding
+            // name declares a variable. Guards are siblings in the switch_label and recurse normally.
+            let mut cursor = node.walk();
+            for child in node.named_children(&mut cursor) {
+                match child.kind() {
+                    // A record pattern's type may parse as a plain identifier
+                    "identifier" if node.kind() == "record_pattern" => {
+                        push_qualified_type(
+                            &child, SymbolKind::TypeReference, src, path, package, imports, occurrences,
+                        );
+                    }
+                    "scoped_type_identifier" => {
+                        push_qualified_type(
+                            &child, SymbolKind::TypeReference, src, path, package, imports, occurrences,
+                        );
+                    }
+                    "identifier" | "underscore_pattern" => {}
+                    _ => extract_references_java(
+                        &child, src, path, package, scope_tree, imports, occurrences,
+                    ),
+                }
+            }
+            return;
+        }
+        "enhanced_for_statement" => {
+            // The iterated expression is a reference; uses of the loop variable in the body
+            // refer to it, like the loop variables of Kotlin for-loops
+            let variable = node.child_by_field_name("name");
+            let body = node.child_by_field_name("body");
+            let mut body_start = None;
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                if variable.is_some_and(|v| v.id() == child.id()) {
+                    continue;
+                }
+                if body.is_some_and(|b| b.id() == child.id()) {
+                    body_start = Some(occurrences.len());
+                }
+                extract_references_java(&child, src, path, package, scope_tree, imports, occurrences);
+            }
+            if let (Some(variable), Some(start)) = (variable, body_start) {
+                let name = node_text(&variable, src);
+                let fqn = local_fqn(LOOP_SCOPE_PREFIX, package, scope_tree, node, name);
+                for occ in &mut occurrences[start..] {
+                    let is_unbound_use = occ.kind == SymbolKind::PropertyReference
+                        && occ.receiver_type.is_none()
+                        && !occ.fqn.as_deref().is_some_and(is_local);
+                    if is_unbound_use && occ.name == name {
+                        occ.fqn = Some(fqn.clone());
+                    }
+                }
+            }
+            return;
+        }
         "identifier" => {
             // Bare identifier as a value reference.
             // Skip if in a context already handled by other cases.
@@ -682,7 +1302,6 @@ fn extract_references_java(
                         | "annotation"
                         | "marker_annotation"
                         | "catch_formal_parameter"
-                        | "enhanced_for_statement"
                         | "local_variable_declaration"
                         | "label"
                         | "break_statement"
@@ -694,7 +1313,17 @@ fn extract_references_java(
                         && parent
                             .child_by_field_name("name")
                             .is_some_and(|n| n.id() == node.id());
-                    if !is_method_name {
+                    // The element name of `@Foo(bar = ...)`; the value is a reference
+                    let is_element_name = pk == "element_value_pair"
+                        && parent
+                            .child_by_field_name("key")
+                            .is_some_and(|n| n.id() == node.id());
+                    // `r` in `try (Reader r = open())`; a bare `try (reader)` is a reference
+                    let is_resource_name = pk == "resource"
+                        && parent
+                            .child_by_field_name("name")
+                            .is_some_and(|n| n.id() == node.id());
+                    if !is_method_name && !is_element_name && !is_resource_name {
                         let name = node_text(node, src).to_string();
                         if !name.is_empty() {
                             let fqn = resolve_reference(&name, package, imports);
@@ -707,6 +1336,10 @@ fn extract_references_java(
                                 column: node.start_position().column + 1,
                                 byte_range: node.byte_range(),
                                 receiver_type: None,
+                                enclosing_fqn: None,
+                                signature: None,
+                                platform_modifier: None,
+                                modifiers: None,
                             });
                         }
                     }
@@ -717,11 +1350,106 @@ fn extract_references_java(
         _ => {}
     }
 
-    // Recurse
+    // Recurse. Uses of a local variable after its declaration bind to it, like uses of an
+    // enhanced for statement's variable in its body.
+    let mut locals = Vec::new();
     let mut cursor = node.walk();
     for child in node.children(&mut cursor) {
+        let start = occurrences.len();
         extract_references_java(&child, src, path, package, scope_tree, imports, occurrences);
+        if child.kind() == "local_variable_declaration" {
+            for (declarator, name) in local_variables(&child, src) {
+                let fqn = local_fqn(LOCAL_SCOPE_PREFIX, package, scope_tree, &declarator, &name);
+                locals.push((name, fqn, start));
+            }
+        }
     }
+    for (name, fqn, start) in locals {
+        for occ in &mut occurrences[start..] {
+            let is_unbound_use = occ.kind == SymbolKind::PropertyReference
+                && occ.receiver_type.is_none()
+                && !occ.fqn.as_deref().is_some_and(is_local);
+            if is_unbound_use && occ.name == name {
+                occ.fqn = Some(fqn.clone());
+            }
+        }
+    }
+}
+
+/// The declarators of a `local_variable_declaration` with their names:
+/// `int a = 1, b` declares `a` and `b`.
+fn local_variables<'a>(
+    node: &tree_sitter::Node<'a>,
+    src: &[u8],
+) -> Vec<(tree_sitter::Node<'a>, String)> {
+    let mut cursor = node.walk();
+    node.children(&mut cursor)
+        .filter(|declarator| declarator.kind() == "variable_declarator")
+        .filter_map(|declarator| find_child_name(&declarator, src).map(|name| (declarator, name)))
+        .collect()
+}
+
+/// Emit a reference to a possibly qualified type (a pattern type, `Outer.Nested` in a
+/// declaration or `new Outer.Nested()`), resolving `Outer.Nested` through the outer type's
+/// import. The qualifier is kept as the receiver so `cross_reference` can resolve nested
+/// types whose outer type the parser can't see. The position is that of the last
+/// (simple name) segment.
+fn push_qualified_type(
+    type_node: &tree_sitter::Node,
+    kind: SymbolKind,
+    src: &[u8],
+    path: &Path,
+    package: Option<&str>,
+    imports: &[ImportInfo],
+    occurrences: &mut Vec<SymbolOccurrence>,
+) {
+    let type_name: String = node_text(type_node, src)
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    let (name, fqn) = resolve_qualified_type(&type_name, package, imports);
+    let qualifier = type_name.rsplit_once('.').map(|(qualifier, _)| qualifier.to_string());
+    let name_node = match type_node.named_child_count() {
+        0 => *type_node,
+        n => type_node.named_child(n - 1).unwrap_or(*type_node),
+    };
+    occurrences.push(SymbolOccurrence {
+        name,
+        fqn,
+        kind,
+        file: path.to_path_buf(),
+        line: name_node.start_position().row + 1,
+        column: name_node.start_position().column + 1,
+        byte_range: name_node.byte_range(),
+        receiver_type: qualifier,
+        enclosing_fqn: None,
+        signature: None,
+        platform_modifier: None,
+        modifiers: None,
+    });
+}
+
+/// Whether the field access `node` names a nested type used as a qualifier, like `Inner` in
+/// `Outer.Inner.CONSTANT` or `Outer.Inner.create()`: a capitalized, not all-caps name whose
+/// access is itself the object of another access.
+fn is_nested_type_qualifier(node: &tree_sitter::Node, name: &str) -> bool {
+    let looks_like_type = name.starts_with(|c: char| c.is_ascii_uppercase())
+        && name.chars().any(|c| c.is_ascii_lowercase());
+    looks_like_type
+        && node.parent().is_some_and(|parent| {
+            matches!(parent.kind(), "field_access" | "method_invocation")
+                && parent
+                    .child_by_field_name("object")
+                    .is_some_and(|object| object.id() == node.id())
+        })
+}
+
+/// The `Outer.Nested` type of `new Outer.Nested()` or `new Outer.Nested<T>()`.
+fn find_scoped_type_child<'a>(node: &tree_sitter::Node<'a>) -> Option<tree_sitter::Node<'a>> {
+    find_child_of_kind(node, "scoped_type_identifier").or_else(|| {
+        find_child_of_kind(node, "generic_type")
+            .and_then(|generic| find_child_of_kind(&generic, "scoped_type_identifier"))
+    })
 }
 
 fn find_java_body_range(node: &tree_sitter::Node) -> Option<std::ops::Range<usize>> {
@@ -786,7 +1514,7 @@ public class MyClass {
 }
 "#;
         let path = PathBuf::from("MyClass.java");
-        let (file_info, occurrences, _, _) = parse_java_file(&path, source);
+        let ParsedFile { file_info, occurrences, .. } = parse_java_file(&path, source);
 
         assert_eq!(file_info.package, Some("com.example".to_string()));
 
@@ -848,7 +1576,7 @@ public class Foo {
 }
 "#;
         let path = PathBuf::from("Foo.java");
-        let (_, occurrences, _, _) = parse_java_file(&path, source);
+        let occurrences = parse_java_file(&path, source).occurrences;
 
         let ctor = occurrences
             .iter()
@@ -868,7 +1596,7 @@ public interface MyInterface {
 }
 "#;
         let path = PathBuf::from("MyInterface.java");
-        let (_, occurrences, _, _) = parse_java_file(&path, source);
+        let occurrences = parse_java_file(&path, source).occurrences;
 
         let iface = occurrences
             .iter()
@@ -905,7 +1633,7 @@ public enum Color {
 }
 "#;
         let path = PathBuf::from("Color.java");
-        let (_, occurrences, _, _) = parse_java_file(&path, source);
+        let occurrences = parse_java_file(&path, source).occurrences;
 
         let enum_decl = occurrences
             .iter()
@@ -934,7 +1662,7 @@ import static java.util.Collections.emptyList;
 import java.io.*;
 "#;
         let path = PathBuf::from("Test.java");
-        let (file_info, _, _, _) = parse_java_file(&path, source);
+        let file_info = parse_java_file(&path, source).file_info;
 
         assert_eq!(file_info.imports.len(), 4);
 
@@ -948,6 +1676,8 @@ import java.io.*;
         let static_imp = &file_info.imports[2];
         assert_eq!(static_imp.path, "java.util.Collections.emptyList");
         assert!(!static_imp.is_wildcard);
+        assert!(static_imp.is_static);
+        assert!(!list_imp.is_static);
 
         let wildcard_imp = &file_info.imports[3];
         assert_eq!(wildcard_imp.path, "java.io");
@@ -970,7 +1700,7 @@ public class Caller {
 }
 "#;
         let path = PathBuf::from("Caller.java");
-        let (_, occurrences, _, _) = parse_java_file(&path, source);
+        let occurrences = parse_java_file(&path, source).occurrences;
 
         // Should have a CallSite for `new Helper()`
         let new_helper = occurrences
@@ -995,6 +1725,109 @@ public class Caller {
         assert!(call_sites.contains(&"getName"));
     }
 
+    #[test]
+    fn test_static_import_calls() {
+        let source = r#"
+package com.example;
+
+import static java.util.Collections.emptyList;
+import static org.junit.Assert.*;
+
+public class CallerTest {
+    public void run() {
+        assertEquals(0, emptyList().size());
+    }
+}
+"#;
+        let path = PathBuf::from("CallerTest.java");
+        let occurrences = parse_java_file(&path, source).occurrences;
+        let call_fqn = |name: &str| {
+            occurrences
+                .iter()
+                .find(|o| o.name == name && o.kind == SymbolKind::CallSite)
+                .and_then(|o| o.fqn.clone())
+        };
+
+        assert_eq!(call_fqn("emptyList").as_deref(), Some("java.util.Collections.emptyList"));
+        assert_eq!(call_fqn("assertEquals").as_deref(), Some("org.junit.Assert.assertEquals"));
+        // `size` has a receiver, so static imports don't apply
+        assert_eq!(call_fqn("size").as_deref(), Some("com.example.size"));
+    }
+
+    #[test]
+    fn test_method_references_and_lambdas() {
+        let source = r#"
+package com.example;
+
+public class Pipeline {
+    public void run(List<String> items) {
+        items.forEach(System.out::println);
+        items.stream().map(Helper::clean).map(this::wrap).map(Box::new);
+        items.removeIf(item -> item.isEmpty());
+    }
+}
+"#;
+        let path = PathBuf::from("Pipeline.java");
+        let occurrences = parse_java_file(&path, source).occurrences;
+        let call = |name: &str| {
+            occurrences
+                .iter()
+                .find(|o| o.name == name && o.kind == SymbolKind::CallSite)
+        };
+
+        let println = call("println").expect("println method reference");
+        assert_eq!(println.receiver_type.as_deref(), Some("System.out"));
+        assert_eq!((println.line, println.column), (6, 35));
+        assert_eq!(call("clean").and_then(|o| o.receiver_type.as_deref()), Some("Helper"));
+        assert_eq!(call("wrap").and_then(|o| o.receiver_type.as_deref()), Some("this"));
+        // `Box::new` calls the constructor
+        assert_eq!(call("Box").and_then(|o| o.fqn.as_deref()), Some("com.example.Box"));
+        assert!(call("new").is_none());
+        // The lambda body is traversed; its parameter name is not a reference
+        assert!(call("isEmpty").is_some());
+        assert_eq!(
+            occurrences
+                .iter()
+                .filter(|o| o.name == "item" && o.kind.is_reference())
+                .map(|o| o.column)
+                .collect::<Vec<_>>(),
+            vec![32]
+        );
+    }
+
+    #[test]
+    fn test_text_blocks() {
+        let source = r##"
+package com.example;
+
+public class Queries {
+    String find(String id) {
+        String sql = """
+            SELECT * FROM "users"
+            WHERE id = '%s' AND note = \"""quoted\"""
+            """ + TABLE;
+        return run(sql.formatted(id));
+    }
+}
+"##;
+        let path = PathBuf::from("Queries.java");
+        let ParsedFile { file_info, occurrences, .. } = parse_java_file(&path, source);
+        let position = |name: &str, kind: SymbolKind| {
+            occurrences
+                .iter()
+                .find(|o| o.name == name && o.kind == kind)
+                .map(|o| (o.line, o.column))
+        };
+
+        assert_eq!(file_info.parse_errors, 0);
+        // Nothing inside the text block is a reference
+        assert!(!occurrences.iter().any(|o| (7..=8).contains(&o.line)));
+        // Identifiers concatenated to it and positions after it are unaffected
+        assert_eq!(position("TABLE", SymbolKind::PropertyReference), Some((9, 19)));
+        assert_eq!(position("run", SymbolKind::CallSite), Some((10, 16)));
+        assert_eq!(position("id", SymbolKind::PropertyReference), Some((10, 34)));
+    }
+
     #[test]
     fn test_parse_lombok_data_class() {
         let source = r#"
@@ -1009,7 +1842,7 @@ public class User {
 }
 "#;
         let path = PathBuf::from("User.java");
-        let (_, occurrences, _, lombok_acc) = parse_java_file(&path, source);
+        let ParsedFile { occurrences, lombok_accessors: lombok_acc, .. } = parse_java_file(&path, source);
 
         let decl_names: Vec<&str> = occurrences
             .iter()
@@ -1053,7 +1886,7 @@ public class Entity {
 }
 "#;
         let path = PathBuf::from("Entity.java");
-        let (_, occurrences, _, _) = parse_java_file(&path, source);
+        let occurrences = parse_java_file(&path, source).occurrences;
 
         let decl_names: Vec<&str> = occurrences
             .iter()
@@ -1084,7 +1917,7 @@ public class Flags {
 }
 "#;
         let path = PathBuf::from("Flags.java");
-        let (_, occurrences, _, _) = parse_java_file(&path, source);
+        let occurrences = parse_java_file(&path, source).occurrences;
 
         let decl_names: Vec<&str> = occurrences
             .iter()
@@ -1117,7 +1950,7 @@ public class Config {
 }
 "#;
         let path = PathBuf::from("Config.java");
-        let (_, occurrences, _, _) = parse_java_file(&path, source);
+        let occurrences = parse_java_file(&path, source).occurrences;
 
         let decl_names: Vec<&str> = occurrences
             .iter()
@@ -1144,7 +1977,7 @@ public class ReadOnly {
 }
 "#;
         let path = PathBuf::from("ReadOnly.java");
-        let (_, occurrences, _, _) = parse_java_file(&path, source);
+        let occurrences = parse_java_file(&path, source).occurrences;
 
         let decl_names: Vec<&str> = occurrences
             .iter()
@@ -1170,7 +2003,7 @@ public class Selective {
 }
 "#;
         let path = PathBuf::from("Selective.java");
-        let (_, occurrences, _, _) = parse_java_file(&path, source);
+        let occurrences = parse_java_file(&path, source).occurrences;
 
         let decl_names: Vec<&str> = occurrences
             .iter()
@@ -1183,4 +2016,384 @@ public class Selective {
         // Field without annotation → no getter
         assert!(!decl_names.contains(&"getHidden"), "Should NOT have getHidden, got: {:?}", decl_names);
     }
+
+    #[test]
+    fn test_parse_lombok_constructor_signatures() {
+        let source = r#"
+package com.example;
+
+import lombok.*;
+
+@Data
+@AllArgsConstructor
+@RequiredArgsConstructor
+public class Order {
+    private final String id;
+    @NonNull private String customer;
+    private int quantity;
+    private final String currency = "EUR";
+    private static int created;
+}
+"#;
+        let tree = {
+            let mut parser = tree_sitter::Parser::new();
+            parser.set_language(&tree_sitter_java::LANGUAGE.into()).unwrap();
+            parser.parse(source, None).unwrap()
+        };
+        let root = tree.root_node();
+        let class_node = find_child_of_kind(&root, "class_declaration").unwrap();
+        let fields = lombok_fields(&class_node, source.as_bytes());
+        let signatures: Vec<Vec<String>> = lombok_constructors(&class_node, source.as_bytes(), &fields)
+            .into_iter()
+            .map(|(_, params)| params)
+            .collect();
+
+        // Initialized finals and statics are never parameters; @Data adds no constructor of its own
+        assert_eq!(signatures, vec![vec!["id", "customer"], vec!["id", "customer", "quantity"]]);
+    }
+
+    #[test]
+    fn test_parse_lombok_builder() {
+        let source = r#"
+package com.example;
+
+import lombok.Builder;
+
+@Builder
+public class Order {
+    private final String id;
+    private int quantity;
+}
+"#;
+        let path = PathBuf::from("Order.java");
+        let ParsedFile { occurrences, lombok_accessors: lombok_acc, .. } = parse_java_file(&path, source);
+
+        let decl_fqns: Vec<(&str, &SymbolKind)> = occurrences
+            .iter()
+            .filter(|o| o.kind.is_declaration())
+            .map(|o| (o.fqn.as_deref().unwrap(), &o.kind))
+            .collect();
+        for expected in [
+            ("com.example.Order.builder", &SymbolKind::FunctionDeclaration),
+            ("com.example.Order.OrderBuilder", &SymbolKind::ClassDeclaration),
+            ("com.example.Order.OrderBuilder.build", &SymbolKind::FunctionDeclaration),
+            ("com.example.Order.OrderBuilder.id", &SymbolKind::FunctionDeclaration),
+            ("com.example.Order.OrderBuilder.quantity", &SymbolKind::FunctionDeclaration),
+            // @Builder alone implies an all-args constructor
+            ("com.example.Order.Order", &SymbolKind::ConstructorDeclaration),
+        ] {
+            assert!(decl_fqns.contains(&expected), "Expected {:?}, got: {:?}", expected, decl_fqns);
+        }
+
+        // Synthesized members point at the @Builder annotation
+        let builder = occurrences.iter().find(|o| o.name == "builder").unwrap();
+        assert_eq!(builder.line, 6);
+
+        let quantity_accessors = lombok_acc.iter().find(|(fqn, _)| fqn == "com.example.Order.quantity").unwrap();
+        assert_eq!(quantity_accessors.1, vec!["com.example.Order.OrderBuilder.quantity".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_java_supertypes() {
+        let source = r#"
+package com.example;
+
+import com.other.Base;
+
+public class Child extends Base implements Comparable<Child>, java.io.Serializable {
+    interface Marker extends Runnable {}
+}
+
+enum Mode implements Marker {}
+"#;
+        let path = PathBuf::from("Child.java");
+        let supertypes = parse_java_file(&path, source).supertypes;
+
+        let parents_of = |fqn: &str| {
+            supertypes
+                .iter()
+                .find(|(child, _)| child == fqn)
+                .map(|(_, parents)| parents.clone())
+                .unwrap_or_else(|| panic!("Expected supertypes for {}, got: {:?}", fqn, supertypes))
+        };
+        assert_eq!(
+            parents_of("com.example.Child"),
+            vec!["com.other.Base", "com.example.Comparable", "java.io.Serializable"]
+        );
+        assert_eq!(parents_of("com.example.Child.Marker"), vec!["com.example.Runnable"]);
+        assert_eq!(parents_of("com.example.Mode"), vec!["com.example.Marker"]);
+    }
+
+    #[test]
+    fn test_parse_java_nested_generic_type_arguments() {
+        let source = r#"
+package com.example;
+
+import com.other.User;
+
+public class Registry {
+    private Map<String, List<User>> byName = new HashMap<String, List<User>>();
+}
+"#;
+        let path = PathBuf::from("Registry.java");
+        let occurrences = parse_java_file(&path, source).occurrences;
+
+        let user_refs: Vec<(usize, usize)> = occurrences
+            .iter()
+            .filter(|o| o.name == "User" && o.kind == SymbolKind::TypeReference)
+            .map(|o| (o.line, o.column))
+            .collect();
+        // Once in the field type, once in the type arguments of `new HashMap<...>()`
+        assert_eq!(user_refs, vec![(7, 30), (7, 71)], "got: {:?}", user_refs);
+        assert!(occurrences
+            .iter()
+            .filter(|o| o.name == "User")
+            .all(|o| o.fqn.as_deref() == Some("com.other.User")));
+
+        let list_refs = occurrences
+            .iter()
+            .filter(|o| o.name == "List" && o.kind == SymbolKind::TypeReference)
+            .count();
+        assert_eq!(list_refs, 2);
+    }
+
+    #[test]
+    fn test_parse_java_anonymous_class() {
+        let source = r#"
+package com.example;
+
+public class Scheduler {
+    public void run() {}
+
+    public Runnable task() {
+        return new Runnable() {
+            @Override
+            public void run() {
+                log();
+            }
+        };
+    }
+}
+"#;
+        let path = PathBuf::from("Scheduler.java");
+        let occurrences = parse_java_file(&path, source).occurrences;
+
+        let runnable = occurrences
+            .iter()
+            .find(|o| o.name == "Runnable" && o.line == 8)
+            .expect("Expected Runnable in the anonymous class creation");
+        assert_eq!(runnable.kind, SymbolKind::TypeReference);
+
+        let mut run_fqns: Vec<&str> = occurrences
+            .iter()
+            .filter(|o| o.name == "run" && o.kind.is_declaration())
+            .filter_map(|o| o.fqn.as_deref())
+            .collect();
+        run_fqns.sort();
+        assert_eq!(run_fqns, vec!["com.example.Scheduler.<anon@8:16>.run", "com.example.Scheduler.run"]);
+
+        // References inside the anonymous class body are indexed
+        assert!(occurrences
+            .iter()
+            .any(|o| o.name == "log" && o.kind == SymbolKind::CallSite && o.line == 11));
+    }
+
+    #[test]
+    fn test_parse_java_member_columns() {
+        let source = r#"
+package com.example;
+
+public class Main {
+    void run() {
+        int t = config.settings.timeout;
+        service.getRepository().save(t);
+    }
+}
+"#;
+        let path = PathBuf::from("Main.java");
+        let occurrences = parse_java_file(&path, source).occurrences;
+        let position = |name: &str, kind: SymbolKind| {
+            occurrences
+                .iter()
+                .find(|o| o.name == name && o.kind == kind)
+                .map(|o| (o.line, o.column, &source[o.byte_range.clone()]))
+                .unwrap_or_else(|| panic!("Expected {:?} `{}`", kind, name))
+        };
+
+        assert_eq!(position("settings", SymbolKind::PropertyReference), (6, 24, "settings"));
+        assert_eq!(position("timeout", SymbolKind::PropertyReference), (6, 33, "timeout"));
+        assert_eq!(position("getRepository", SymbolKind::CallSite), (7, 17, "getRepository"));
+        assert_eq!(position("save", SymbolKind::CallSite), (7, 33, "save"));
+    }
+
+    #[test]
+    fn test_parse_java_switch_patterns() {
+        let source = r#"
+package com.example;
+
+import com.example.shapes.Shape;
+import com.example.shapes.Shape.Circle;
+
+public class Describer {
+    String describe(Object shape) {
+        return switch (shape) {
+            case Circle c when c.radius() > limit -> "large";
+            case Shape.Square s -> "square";
+            case Rect(double w, Size(var h, var d)) -> "rect";
+            default -> "other";
+        };
+    }
+}
+"#;
+        let path = PathBuf::from("Describer.java");
+        let occurrences = parse_java_file(&path, source).occurrences;
+        let type_ref = |name: &str| {
+            occurrences
+                .iter()
+                .find(|o| o.name == name && o.kind == SymbolKind::TypeReference)
+                .map(|o| (o.line, o.column, o.fqn.as_deref()))
+                .unwrap_or_else(|| panic!("Expected TypeReference `{}`", name))
+        };
+
+        assert_eq!(type_ref("Circle"), (10, 18, Some("com.example.shapes.Shape.Circle")));
+        assert_eq!(type_ref("Square"), (11, 24, Some("com.example.shapes.Shape.Square")));
+        assert_eq!(type_ref("Rect"), (12, 18, Some("com.example.Rect")));
+        assert_eq!(type_ref("Size"), (12, 33, Some("com.example.Size")));
+
+        // The guard is still scanned for references
+        assert!(occurrences
+            .iter()
+            .any(|o| o.name == "radius" && o.kind == SymbolKind::CallSite && o.line == 10));
+        assert!(occurrences
+            .iter()
+            .any(|o| o.name == "limit" && o.kind == SymbolKind::PropertyReference));
+
+        // Binding names are not references
+        for binding in ["s", "w", "h", "d"] {
+            assert!(
+                !occurrences.iter().any(|o| o.name == binding && o.kind.is_reference()),
+                "binding `{}` reported as a reference",
+                binding
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_java_qualified_nested_types() {
+        let source = r#"
+package com.example;
+
+import com.example.paging.Pagination;
+
+public class Loader {
+    private Pagination.PageRequest current = new Pagination.PageRequest(1);
+
+    int size() {
+        return Pagination.PageRequest.DEFAULT_SIZE + Color.RED.ordinal();
+    }
+}
+"#;
+        let path = PathBuf::from("Loader.java");
+        let occurrences = parse_java_file(&path, source).occurrences;
+        let refs: Vec<_> = occurrences
+            .iter()
+            .filter(|o| o.name == "PageRequest")
+            .map(|o| (o.line, o.column, &o.kind, o.fqn.as_deref(), o.receiver_type.as_deref()))
+            .collect();
+
+        let nested = Some("com.example.paging.Pagination.PageRequest");
+        assert_eq!(
+            refs,
+            vec![
+                (7, 24, &SymbolKind::TypeReference, nested, Some("Pagination")),
+                (7, 61, &SymbolKind::CallSite, nested, Some("Pagination")),
+                // Resolved through the outer type by cross_reference
+                (10, 27, &SymbolKind::TypeReference, Some("com.example.PageRequest"), Some("Pagination")),
+            ]
+        );
+
+        // An all-caps qualifier is a constant, not a nested type
+        assert!(occurrences
+            .iter()
+            .any(|o| o.name == "RED" && o.kind == SymbolKind::PropertyReference));
+    }
+
+    #[test]
+    fn test_parse_java_type_parameter_bounds() {
+        let source = r#"
+package com.example;
+
+import com.example.model.Entity;
+
+public class Repo<T extends Entity> {
+    <C extends Comparable<C>> C max(C a, C b) {
+        return a;
+    }
+}
+"#;
+        let path = PathBuf::from("Repo.java");
+        let occurrences = parse_java_file(&path, source).occurrences;
+        let type_refs = |name: &str| {
+            occurrences
+                .iter()
+                .filter(|o| o.name == name && o.kind == SymbolKind::TypeReference)
+                .map(|o| (o.line, o.column))
+                .collect::<Vec<_>>()
+        };
+
+        let entity = occurrences
+            .iter()
+            .find(|o| o.name == "Entity" && o.kind == SymbolKind::TypeReference)
+            .expect("Expected the bound `Entity` as a TypeReference");
+        assert_eq!((entity.line, entity.column), (6, 29));
+        assert_eq!(entity.fqn.as_deref(), Some("com.example.model.Entity"));
+        assert_eq!(type_refs("Comparable"), vec![(7, 16)]);
+
+        // `T` is only declared; `C` is referenced by the return and parameter types only
+        assert!(type_refs("T").is_empty());
+        assert_eq!(type_refs("C"), vec![(7, 31), (7, 37), (7, 42)]);
+    }
+
+    #[test]
+    fn test_parse_java_enclosing_fqn() {
+        let source = r#"
+package com.example;
+
+import lombok.Getter;
+
+public class Account {
+    @Getter
+    private Owner owner = Owner.anonymous();
+
+    public Account() {
+        audit("created");
+    }
+
+    public void close() {
+        audit("closed");
+    }
+}
+"#;
+        let path = PathBuf::from("Account.java");
+        let occurrences = parse_java_file(&path, source).occurrences;
+        let enclosing = |name: &str, line: usize| {
+            occurrences
+                .iter()
+                .find(|o| o.name == name && o.kind.is_reference() && o.line == line)
+                .unwrap_or_else(|| panic!("Expected a reference to `{}` on line {}", name, line))
+                .enclosing_fqn
+                .as_deref()
+        };
+
+        // The synthesized getOwner() shares the field's range but doesn't enclose it
+        assert_eq!(enclosing("anonymous", 8), Some("com.example.Account.owner"));
+        assert_eq!(enclosing("audit", 11), Some("com.example.Account.Account"));
+        assert_eq!(enclosing("audit", 15), Some("com.example.Account.close"));
+
+        let import = occurrences
+            .iter()
+            .find(|o| o.kind == SymbolKind::Import)
+            .expect("Expected the lombok import");
+        assert_eq!(import.enclosing_fqn, None);
+    }
 }
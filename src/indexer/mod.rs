@@ -1,16 +1,24 @@
+pub mod filter;
 pub mod java_parser;
 pub mod parser;
 pub mod scope;
 pub mod symbols;
 
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::Serialize;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum SymbolKind {
     // Declarations
     ClassDeclaration,
+    SealedClassDeclaration,
     InterfaceDeclaration,
+    /// Kotlin `sealed interface`, whose implementers form a closed hierarchy
+    SealedInterfaceDeclaration,
     ObjectDeclaration,
     CompanionObjectDeclaration,
     FunctionDeclaration,
@@ -19,11 +27,19 @@ pub enum SymbolKind {
     TypeAliasDeclaration,
     ParameterDeclaration,
     ExtensionFunctionDeclaration,
+    /// `val Foo.bar: T get() = ...`; the receiver type is in `receiver_type`
+    ExtensionPropertyDeclaration,
     ConstructorDeclaration,
+    InitializerDeclaration,
+    /// Custom `get()`/`set()` of a Kotlin property; shares the property's FQN
+    PropertyAccessorDeclaration,
     RecordDeclaration,
     AnnotationTypeDeclaration,
+    /// Kotlin `annotation class`, the counterpart of Java's `@interface`
+    AnnotationClassDeclaration,
     // References
     TypeReference,
+    AnnotationReference,
     CallSite,
     PropertyReference,
     Import,
@@ -32,11 +48,43 @@ pub enum SymbolKind {
 }
 
 impl SymbolKind {
+    /// Every kind, in declaration order.
+    pub const ALL: &'static [SymbolKind] = &[
+        SymbolKind::ClassDeclaration,
+        SymbolKind::SealedClassDeclaration,
+        SymbolKind::InterfaceDeclaration,
+        SymbolKind::SealedInterfaceDeclaration,
+        SymbolKind::ObjectDeclaration,
+        SymbolKind::CompanionObjectDeclaration,
+        SymbolKind::FunctionDeclaration,
+        SymbolKind::PropertyDeclaration,
+        SymbolKind::EnumEntryDeclaration,
+        SymbolKind::TypeAliasDeclaration,
+        SymbolKind::ParameterDeclaration,
+        SymbolKind::ExtensionFunctionDeclaration,
+        SymbolKind::ExtensionPropertyDeclaration,
+        SymbolKind::ConstructorDeclaration,
+        SymbolKind::InitializerDeclaration,
+        SymbolKind::PropertyAccessorDeclaration,
+        SymbolKind::RecordDeclaration,
+        SymbolKind::AnnotationTypeDeclaration,
+        SymbolKind::AnnotationClassDeclaration,
+        SymbolKind::TypeReference,
+        SymbolKind::AnnotationReference,
+        SymbolKind::CallSite,
+        SymbolKind::PropertyReference,
+        SymbolKind::Import,
+        SymbolKind::ExtensionFunctionCall,
+        SymbolKind::PackageDeclaration,
+    ];
+
     pub fn is_declaration(&self) -> bool {
         matches!(
             self,
             SymbolKind::ClassDeclaration
+                | SymbolKind::SealedClassDeclaration
                 | SymbolKind::InterfaceDeclaration
+                | SymbolKind::SealedInterfaceDeclaration
                 | SymbolKind::ObjectDeclaration
                 | SymbolKind::CompanionObjectDeclaration
                 | SymbolKind::FunctionDeclaration
@@ -45,9 +93,40 @@ impl SymbolKind {
                 | SymbolKind::TypeAliasDeclaration
                 | SymbolKind::ParameterDeclaration
                 | SymbolKind::ExtensionFunctionDeclaration
+                | SymbolKind::ExtensionPropertyDeclaration
                 | SymbolKind::ConstructorDeclaration
+                | SymbolKind::InitializerDeclaration
+                | SymbolKind::PropertyAccessorDeclaration
                 | SymbolKind::RecordDeclaration
                 | SymbolKind::AnnotationTypeDeclaration
+                | SymbolKind::AnnotationClassDeclaration
+        )
+    }
+
+    /// Declarations with a body of code: functions, constructors, initializers and accessors.
+    pub fn is_function_like(&self) -> bool {
+        matches!(
+            self,
+            SymbolKind::FunctionDeclaration
+                | SymbolKind::ExtensionFunctionDeclaration
+                | SymbolKind::ConstructorDeclaration
+                | SymbolKind::InitializerDeclaration
+                | SymbolKind::PropertyAccessorDeclaration
+        )
+    }
+
+    pub fn is_type_declaration(&self) -> bool {
+        matches!(
+            self,
+            SymbolKind::ClassDeclaration
+                | SymbolKind::SealedClassDeclaration
+                | SymbolKind::InterfaceDeclaration
+                | SymbolKind::SealedInterfaceDeclaration
+                | SymbolKind::ObjectDeclaration
+                | SymbolKind::CompanionObjectDeclaration
+                | SymbolKind::RecordDeclaration
+                | SymbolKind::AnnotationTypeDeclaration
+                | SymbolKind::AnnotationClassDeclaration
         )
     }
 
@@ -56,6 +135,22 @@ impl SymbolKind {
     }
 }
 
+/// Parse a kind from its variant name, case-insensitively (e.g., "CallSite" or "callsite").
+impl std::str::FromStr for SymbolKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SymbolKind::ALL
+            .iter()
+            .find(|kind| format!("{:?}", kind).eq_ignore_ascii_case(s))
+            .cloned()
+            .ok_or_else(|| {
+                let valid: Vec<String> = SymbolKind::ALL.iter().map(|k| format!("{:?}", k)).collect();
+                format!("Unknown symbol kind '{}'. Valid kinds: {}", s, valid.join(", "))
+            })
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SymbolOccurrence {
     pub name: String,
@@ -66,6 +161,75 @@ pub struct SymbolOccurrence {
     pub column: usize,
     pub byte_range: std::ops::Range<usize>,
     pub receiver_type: Option<String>,
+    /// FQN of the innermost declaration containing this occurrence: the function,
+    /// constructor or accessor a call sits in, or the property or class for occurrences
+    /// in initializers and class headers. None at the top level (imports, top-level declarations).
+    pub enclosing_fqn: Option<String>,
+    /// Parameter types of a Kotlin function declaration as written, e.g. `(Int, String)`,
+    /// which tells overloads sharing an FQN apart. None for other occurrences.
+    pub signature: Option<String>,
+    /// `expect` or `actual` on a Kotlin Multiplatform declaration. None for other occurrences.
+    pub platform_modifier: Option<PlatformModifier>,
+    /// Modifiers of a declaration, read from its source at parse time. None for references,
+    /// Java local variables and declarations synthesized by the indexer.
+    pub modifiers: Option<DeclarationModifiers>,
+}
+
+/// What a declaration's modifiers and keywords say about it, recorded while parsing so
+/// that queries don't go back to the (possibly since edited) source.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DeclarationModifiers {
+    /// A `var` property or constructor parameter, which has a setter as well as a getter
+    pub is_var: bool,
+    /// The visibility modifier as written. None when there is none and the language
+    /// default applies.
+    pub visibility: Option<Visibility>,
+    /// `override` in Kotlin, `@Override` in Java
+    pub is_override: bool,
+}
+
+/// Visibility of a declaration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Visibility {
+    Private,
+    /// Java members without a modifier
+    PackagePrivate,
+    Internal,
+    Protected,
+    Public,
+}
+
+impl fmt::Display for Visibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Visibility::Private => "private",
+            Visibility::PackagePrivate => "package-private",
+            Visibility::Internal => "internal",
+            Visibility::Protected => "protected",
+            Visibility::Public => "public",
+        };
+        f.write_str(name)
+    }
+}
+
+/// The Kotlin Multiplatform modifier pairing a common declaration with its platform
+/// implementations. Both sides share an FQN, so a definition lookup returns them together.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PlatformModifier {
+    /// `expect class Foo` in a common source set
+    Expect,
+    /// `actual class Foo` in a platform source set such as `jvmMain`
+    Actual,
+}
+
+impl PlatformModifier {
+    /// The modifier keyword as written in source
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PlatformModifier::Expect => "expect",
+            PlatformModifier::Actual => "actual",
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -73,6 +237,8 @@ pub struct ImportInfo {
     pub path: String,
     pub alias: Option<String>,
     pub is_wildcard: bool,
+    /// Java `import static`: `path` names a member, or a class for a wildcard import
+    pub is_static: bool,
     pub line: usize,
     pub column: usize,
     pub byte_range: std::ops::Range<usize>,
@@ -83,6 +249,8 @@ pub struct FileInfo {
     pub path: PathBuf,
     pub package: Option<String>,
     pub imports: Vec<ImportInfo>,
+    /// ERROR and MISSING nodes in the file's syntax tree; symbols inside them may be missing
+    pub parse_errors: usize,
 }
 
 #[derive(Debug, Default)]
@@ -93,6 +261,22 @@ pub struct SymbolIndex {
     pub type_aliases: HashMap<String, String>,
     /// Lombok accessor mappings: field FQN → [getter FQN, setter FQN, ...]
     pub lombok_accessors: HashMap<String, Vec<String>>,
+    /// Supertype mappings: class FQN → [direct supertype FQN, ...]
+    pub supertypes: HashMap<String, Vec<String>>,
+    /// Inverse of `supertypes`: type FQN → [direct subtype FQN, ...], derived by `cross_reference`
+    pub subtypes: HashMap<String, Vec<String>>,
+    /// Annotation mappings: annotated declaration FQN → [annotation FQN, ...]
+    pub annotations: HashMap<String, Vec<String>>,
+    /// Data class property mappings: property FQN → [componentN FQN], so destructuring
+    /// declarations (`val (a, b) = x`) count as property usages
+    pub data_class_members: HashMap<String, Vec<String>>,
+    /// Source files left out because they exceed the size cap of the file filter
+    pub skipped_files: Vec<PathBuf>,
+    /// Files whose syntax tree has ERROR or MISSING nodes → the number of such nodes.
+    /// They are still indexed, but possibly incompletely.
+    pub parse_errors: HashMap<PathBuf, usize>,
+    /// When each indexed file was read, to tell whether it changed on disk since
+    pub indexed_at: HashMap<PathBuf, SystemTime>,
 }
 
 impl SymbolIndex {
@@ -112,12 +296,87 @@ impl SymbolIndex {
         self.files.insert(info.path.clone(), info);
     }
 
+    /// Remove everything `path` contributed: its occurrences (including companion aliases),
+    /// its file info, and the alias, Lombok, supertype, annotation and data class mappings
+    /// keyed by the declarations it contains. `subtypes` is rebuilt by `cross_reference`.
+    pub fn remove_file(&mut self, path: &Path) {
+        let declared: HashSet<String> = self
+            .by_name
+            .values()
+            .flatten()
+            .filter(|o| o.file == path && o.kind.is_declaration())
+            .filter_map(|o| o.fqn.clone())
+            .collect();
+
+        for occs in [&mut self.by_name, &mut self.by_fqn] {
+            occs.retain(|_, list| {
+                list.retain(|o| o.file != path);
+                !list.is_empty()
+            });
+        }
+        self.files.remove(path);
+        self.skipped_files.retain(|skipped| skipped != path);
+        self.parse_errors.remove(path);
+        self.indexed_at.remove(path);
+
+        // Data class properties come from constructor parameters, which aren't declarations
+        // of their own, so mappings keyed by a member of a declared class go too
+        let contributed = |fqn: &String| {
+            declared.contains(fqn)
+                || fqn
+                    .rsplit_once('.')
+                    .is_some_and(|(owner, _)| declared.contains(owner))
+        };
+        self.type_aliases.retain(|fqn, _| !contributed(fqn));
+        self.lombok_accessors.retain(|fqn, _| !contributed(fqn));
+        self.supertypes.retain(|fqn, _| !contributed(fqn));
+        self.annotations.retain(|fqn, _| !contributed(fqn));
+        self.data_class_members.retain(|fqn, _| !contributed(fqn));
+    }
+
     pub fn clear(&mut self) {
         self.by_name.clear();
         self.by_fqn.clear();
         self.files.clear();
         self.type_aliases.clear();
         self.lombok_accessors.clear();
+        self.supertypes.clear();
+        self.subtypes.clear();
+        self.annotations.clear();
+        self.data_class_members.clear();
+        self.skipped_files.clear();
+        self.parse_errors.clear();
+        self.indexed_at.clear();
+    }
+
+    /// The indexed files among `files` that were modified or deleted after they were read
+    /// for indexing, in path order. Each file is stat'ed once; files that aren't indexed
+    /// are ignored.
+    pub fn changed_since_indexing<'a>(
+        &self,
+        files: impl IntoIterator<Item = &'a Path>,
+    ) -> Vec<PathBuf> {
+        let files: HashSet<&Path> = files.into_iter().collect();
+        let mut changed: Vec<PathBuf> = files
+            .into_iter()
+            .filter(|file| {
+                let indexed_at = match self.indexed_at.get(*file) {
+                    Some(indexed_at) => indexed_at,
+                    None => return false,
+                };
+                match std::fs::metadata(file) {
+                    // Platforms without modification times never report a change
+                    Ok(metadata) => metadata
+                        .modified()
+                        .is_ok_and(|modified| modified > *indexed_at),
+                    // Deleted since
+                    Err(_) => true,
+                }
+            })
+            .map(Path::to_path_buf)
+            .collect();
+        changed.sort();
+        changed
     }
 
     pub fn stats(&self) -> IndexStats {
@@ -128,11 +387,45 @@ impl SymbolIndex {
             total_occurrences: self.by_name.values().map(|v| v.len()).sum(),
             type_aliases: self.type_aliases.len(),
             lombok_accessors: self.lombok_accessors.len(),
+            skipped_files: self.skipped_files.len(),
+            files_with_parse_errors: self.parse_errors.len(),
+        }
+    }
+
+    /// [`stats`](Self::stats) plus breakdowns for diagnosing why a symbol isn't found:
+    /// occurrences per kind and per file (relative to `project_root`), the number of
+    /// references left without an FQN, and the files that failed to parse cleanly.
+    pub fn detailed_stats(&self, project_root: &Path) -> DetailedStats {
+        let mut occurrences_by_kind: BTreeMap<String, usize> = BTreeMap::new();
+        let mut occurrences_by_file: BTreeMap<String, usize> = BTreeMap::new();
+        let mut unresolved_references = 0;
+        for occ in self.by_name.values().flatten() {
+            *occurrences_by_kind.entry(format!("{:?}", occ.kind)).or_default() += 1;
+            let rel_path = occ.file.strip_prefix(project_root).unwrap_or(&occ.file);
+            *occurrences_by_file.entry(rel_path.display().to_string()).or_default() += 1;
+            if occ.kind.is_reference() && occ.fqn.is_none() {
+                unresolved_references += 1;
+            }
+        }
+        let parse_errors = self
+            .parse_errors
+            .iter()
+            .map(|(path, count)| {
+                let rel_path = path.strip_prefix(project_root).unwrap_or(path);
+                (rel_path.display().to_string(), *count)
+            })
+            .collect();
+        DetailedStats {
+            summary: self.stats(),
+            occurrences_by_kind,
+            unresolved_references,
+            occurrences_by_file,
+            parse_errors,
         }
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct IndexStats {
     pub files: usize,
     pub symbols_by_name: usize,
@@ -140,6 +433,23 @@ pub struct IndexStats {
     pub total_occurrences: usize,
     pub type_aliases: usize,
     pub lombok_accessors: usize,
+    pub skipped_files: usize,
+    pub files_with_parse_errors: usize,
+}
+
+/// Index metrics with per-kind and per-file breakdowns, serialized as one flat JSON object.
+#[derive(Debug, Serialize)]
+pub struct DetailedStats {
+    #[serde(flatten)]
+    pub summary: IndexStats,
+    /// Occurrence count per `SymbolKind` name; kinds without occurrences are left out
+    pub occurrences_by_kind: BTreeMap<String, usize>,
+    /// References whose FQN could not be resolved, so FQN lookups never find them
+    pub unresolved_references: usize,
+    /// Occurrence count per file path
+    pub occurrences_by_file: BTreeMap<String, usize>,
+    /// ERROR and MISSING node count per file path, for files that indexed incompletely
+    pub parse_errors: BTreeMap<String, usize>,
 }
 
 impl std::fmt::Display for IndexStats {
@@ -148,6 +458,13 @@ impl std::fmt::Display for IndexStats {
             f,
             "Indexed {} files: {} unique names, {} FQNs, {} total occurrences, {} type aliases, {} lombok accessors",
             self.files, self.symbols_by_name, self.symbols_by_fqn, self.total_occurrences, self.type_aliases, self.lombok_accessors
-        )
+        )?;
+        if self.skipped_files > 0 {
+            write!(f, " ({} files skipped as too large)", self.skipped_files)?;
+        }
+        if self.files_with_parse_errors > 0 {
+            write!(f, " ({} files with parse errors)", self.files_with_parse_errors)?;
+        }
+        Ok(())
     }
 }
@@ -1,95 +1,375 @@
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 use rayon::prelude::*;
 use tracing::{debug, warn};
 use walkdir::WalkDir;
 
-use super::scope::ScopeTree;
-use super::{FileInfo, ImportInfo, SymbolIndex, SymbolKind, SymbolOccurrence};
-
-/// Discover all .kt and .java files under the given root, skipping build dirs and hidden dirs.
+use super::filter::FileFilter;
+use super::scope::{
+    default_package, is_local, ScopeTree, ANONYMOUS_SCOPE_PREFIX, LAMBDA_SCOPE_PREFIX, LOOP_SCOPE_PREFIX,
+};
+use super::{
+    DeclarationModifiers, FileInfo, ImportInfo, PlatformModifier, SymbolIndex, SymbolKind,
+    SymbolOccurrence, Visibility,
+};
+
+/// Discover all .kt, .kts and .java files under the given root, skipping build dirs and hidden dirs.
+/// Kotlin scripts (Gradle build scripts, `*.main.kts`) are parsed like regular Kotlin files.
 pub fn discover_source_files(root: &Path) -> Vec<PathBuf> {
+    discover_source_files_with_filter(root, &FileFilter::default())
+}
+
+/// Like [`discover_source_files`], additionally honoring the include/exclude globs of
+/// `filter`. Excluded directories are pruned without being descended into.
+pub fn discover_source_files_with_filter(root: &Path, filter: &FileFilter) -> Vec<PathBuf> {
     WalkDir::new(root)
         .into_iter()
         .filter_entry(|e| {
-            let name = e.file_name().to_string_lossy();
-            // Skip hidden dirs, build dirs, gradle cache dirs
+            let rel = e.path().strip_prefix(root).unwrap_or(e.path());
             if e.file_type().is_dir() {
-                return !name.starts_with('.')
-                    && name != "build"
-                    && name != ".gradle"
-                    && name != "node_modules";
+                if e.depth() > 0 && is_skipped_dir(&e.file_name().to_string_lossy()) {
+                    return false;
+                }
+                return filter.allows_dir(rel);
             }
-            true
+            filter.allows_file(rel)
         })
         .filter_map(|e| e.ok())
-        .filter(|e| {
-            e.file_type().is_file()
-                && e.path()
-                    .extension()
-                    .is_some_and(|ext| ext == "kt" || ext == "java")
-        })
+        .filter(|e| e.file_type().is_file() && is_source_file(e.path()))
         .map(|e| e.into_path())
         .collect()
 }
 
-/// Discover only .kt files (backward compat for tests).
+/// Whether discovery would pick up `path` (which need not exist any more): a source file
+/// under `root` outside hidden and build directories, let through by `filter`.
+pub fn is_indexable_source(root: &Path, path: &Path, filter: &FileFilter) -> bool {
+    let rel = match path.strip_prefix(root) {
+        Ok(rel) => rel,
+        Err(_) => return false,
+    };
+    if !is_source_file(path) {
+        return false;
+    }
+    let dirs_allowed = rel.ancestors().skip(1).all(|dir| {
+        let skipped = dir
+            .file_name()
+            .is_some_and(|name| is_skipped_dir(&name.to_string_lossy()));
+        !skipped && filter.allows_dir(dir)
+    });
+    dirs_allowed && filter.allows_file(rel)
+}
+
+/// Hidden dirs, build dirs, gradle cache dirs
+fn is_skipped_dir(name: &str) -> bool {
+    name.starts_with('.') || name == "build" || name == ".gradle" || name == "node_modules"
+}
+
+fn is_source_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext == "kt" || ext == "kts" || ext == "java")
+}
+
+/// Discover only Kotlin (.kt and .kts) files (backward compat for tests).
 pub fn discover_kotlin_files(root: &Path) -> Vec<PathBuf> {
     discover_source_files(root)
         .into_iter()
-        .filter(|p| p.extension().is_some_and(|ext| ext == "kt"))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "kt" || ext == "kts"))
         .collect()
 }
 
 /// Parse all discovered files in parallel and build a SymbolIndex.
 pub fn index_files(root: &Path) -> SymbolIndex {
-    let files = discover_source_files(root);
+    index_files_with_filter(root, &FileFilter::default())
+}
+
+/// Number of files parsed in parallel before their results are merged into the index,
+/// which bounds how many parse results are held in memory at once.
+const PARSE_CHUNK_SIZE: usize = 512;
+
+/// Like [`index_files`], indexing only the files `filter` lets through. Files over its
+/// size cap are skipped with a warning and listed in `SymbolIndex::skipped_files`.
+/// Parsing runs on the global rayon pool, so its size sets the parser concurrency.
+pub fn index_files_with_filter(root: &Path, filter: &FileFilter) -> SymbolIndex {
+    let files = discover_source_files_with_filter(root, filter);
     debug!("Discovered {} source files", files.len());
 
-    let file_results: Vec<(FileInfo, Vec<SymbolOccurrence>, Vec<(String, String)>, Vec<(String, Vec<String>)>)> = files
-        .par_iter()
-        .filter_map(|path| {
-            let source = match std::fs::read_to_string(path) {
-                Ok(s) => s,
-                Err(e) => {
-                    warn!("Failed to read {}: {}", path.display(), e);
-                    return None;
-                }
-            };
-            match path.extension().and_then(|e| e.to_str()) {
-                Some("kt") => {
-                    let (fi, occs, ta) = parse_file(path, &source);
-                    Some((fi, occs, ta, vec![]))
-                }
-                Some("java") => Some(super::java_parser::parse_java_file(path, &source)),
-                _ => None,
+    let mut index = SymbolIndex::new();
+    let (files, oversized): (Vec<PathBuf>, Vec<PathBuf>) =
+        files.into_iter().partition(|path| !is_oversized(path, filter));
+    index.skipped_files = oversized;
+
+    for chunk in files.chunks(PARSE_CHUNK_SIZE) {
+        let read_at = SystemTime::now();
+        let file_results: Vec<ParsedFile> = chunk.par_iter().filter_map(|path| parse_source_file(root, path)).collect();
+        for parsed in file_results {
+            add_parsed_file(&mut index, parsed, read_at);
+        }
+    }
+
+    debug!("{}", index.stats());
+    index
+}
+
+/// Bring the index up to date with a single file that was created, modified or deleted:
+/// drop whatever it contributed before and, if it still exists and is within the size cap
+/// of `filter`, parse it again. FQN resolution spans files, so callers re-run
+/// `cross_reference` and `register_companion_aliases` once a batch of files has been
+/// re-indexed. `root` is the project root the index was built from.
+pub fn reindex_file(index: &mut SymbolIndex, root: &Path, path: &Path, filter: &FileFilter) {
+    index.remove_file(path);
+    if path.is_file() {
+        if is_oversized(path, filter) {
+            index.skipped_files.push(path.to_path_buf());
+        } else {
+            let read_at = SystemTime::now();
+            if let Some(parsed) = parse_source_file(root, path) {
+                add_parsed_file(index, parsed, read_at);
             }
-        })
+        }
+    }
+}
+
+/// Whether `path` is larger than the size cap of `filter`, logging a warning if so.
+fn is_oversized(path: &Path, filter: &FileFilter) -> bool {
+    let size = match std::fs::metadata(path) {
+        Ok(metadata) => metadata.len(),
+        // Unreadable files are reported when parsing fails to read them
+        Err(_) => return false,
+    };
+    if filter.allows_size(size) {
+        return false;
+    }
+    warn!(
+        "Skipping {}: {} bytes exceeds the {} byte limit",
+        path.display(),
+        size,
+        filter.max_file_size().unwrap_or_default()
+    );
+    true
+}
+
+/// Everything parsed from one source file, ready to be added to the index.
+#[derive(Debug)]
+pub struct ParsedFile {
+    pub file_info: FileInfo,
+    pub occurrences: Vec<SymbolOccurrence>,
+    /// (alias FQN, target FQN) of each type alias
+    pub type_aliases: Vec<(String, String)>,
+    /// (field FQN, accessor FQNs) of each field with Lombok-generated accessors (Java only)
+    pub lombok_accessors: Vec<(String, Vec<String>)>,
+    /// (type FQN, direct supertype FQNs) of each class, interface and object
+    pub supertypes: Vec<(String, Vec<String>)>,
+    /// (declaration FQN, annotation FQNs) of each annotated declaration (Kotlin only)
+    pub annotations: Vec<(String, Vec<String>)>,
+    /// (property FQN, `componentN` FQNs) of each data class property (Kotlin only)
+    pub data_class_members: Vec<(String, Vec<String>)>,
+}
+
+impl ParsedFile {
+    /// A file with no symbols, e.g. one tree-sitter failed to parse.
+    pub(super) fn empty(path: &Path) -> Self {
+        ParsedFile {
+            file_info: FileInfo {
+                path: path.to_path_buf(),
+                package: None,
+                imports: vec![],
+                parse_errors: 0,
+            },
+            occurrences: vec![],
+            type_aliases: vec![],
+            lombok_accessors: vec![],
+            supertypes: vec![],
+            annotations: vec![],
+            data_class_members: vec![],
+        }
+    }
+}
+
+/// Parse the file at `path` under the project `root`. Declarations of a file without a
+/// package header are placed in its [`default_package`].
+fn parse_source_file(root: &Path, path: &Path) -> Option<ParsedFile> {
+    let source = match std::fs::read_to_string(path) {
+        Ok(s) => s,
+        Err(e) => {
+            warn!("Failed to read {}: {}", path.display(), e);
+            return None;
+        }
+    };
+    let mut parsed = match path.extension().and_then(|e| e.to_str()) {
+        // Scripts usually have no package header
+        Some("kt") | Some("kts") => parse_file(path, &source),
+        Some("java") => super::java_parser::parse_java_file(path, &source),
+        _ => return None,
+    };
+    if parsed.file_info.package.is_none() {
+        let package = default_package(path.strip_prefix(root).unwrap_or(path));
+        qualify_default_package(&mut parsed, &package);
+    }
+    Some(parsed)
+}
+
+/// Move the declarations of a file without a package header into `package`: every FQN of
+/// the file starting with one of its top-level declarations (or a local scope of its
+/// top-level code) gets `package` in front, so the file's own references follow. FQNs of
+/// imported and unresolved names are left alone.
+fn qualify_default_package(parsed: &mut ParsedFile, package: &str) {
+    let ParsedFile {
+        occurrences,
+        type_aliases,
+        lombok_accessors,
+        supertypes,
+        annotations,
+        data_class_members,
+        ..
+    } = parsed;
+    let top_level: std::collections::HashSet<String> = occurrences
+        .iter()
+        .filter(|o| o.kind.is_declaration())
+        .filter_map(|o| o.fqn.as_deref())
+        .filter(|fqn| !fqn.contains('.'))
+        .map(str::to_string)
         .collect();
+    let qualify = |fqn: &mut String| {
+        let first = fqn.split('.').next().unwrap_or_default();
+        if top_level.contains(first) || first.starts_with('<') {
+            *fqn = format!("{}.{}", package, fqn);
+        }
+    };
 
-    let mut index = SymbolIndex::new();
-    for (file_info, occurrences, type_aliases, lombok_acc) in file_results {
-        index.add_file_info(file_info);
-        for occ in occurrences {
-            index.add_occurrence(occ);
+    for occ in occurrences.iter_mut() {
+        if occ.kind == SymbolKind::Import {
+            continue;
         }
-        for (alias_fqn, target_fqn) in type_aliases {
-            index.type_aliases.insert(alias_fqn, target_fqn);
+        if let Some(fqn) = occ.fqn.as_mut() {
+            qualify(fqn);
         }
-        for (field_fqn, accessor_fqns) in lombok_acc {
-            index.lombok_accessors.insert(field_fqn, accessor_fqns);
+        if let Some(fqn) = occ.enclosing_fqn.as_mut() {
+            qualify(fqn);
         }
     }
+    for (alias_fqn, target_fqn) in type_aliases.iter_mut() {
+        qualify(alias_fqn);
+        qualify(target_fqn);
+    }
+    for (fqn, fqns) in lombok_accessors
+        .iter_mut()
+        .chain(supertypes.iter_mut())
+        .chain(annotations.iter_mut())
+        .chain(data_class_members.iter_mut())
+    {
+        qualify(fqn);
+        fqns.iter_mut().for_each(qualify);
+    }
+}
 
-    debug!("{}", index.stats());
-    index
+/// Add a parsed file to the index, recording `read_at` (taken before the file was read) as
+/// the time it was indexed.
+fn add_parsed_file(index: &mut SymbolIndex, parsed: ParsedFile, read_at: SystemTime) {
+    let ParsedFile {
+        file_info,
+        occurrences,
+        type_aliases,
+        lombok_accessors,
+        supertypes,
+        annotations,
+        data_class_members,
+    } = parsed;
+    // Whatever parsed is still indexed; the count tells users the file may be incomplete
+    if file_info.parse_errors > 0 {
+        warn!(
+            "{} has {} parse errors; it may be indexed incompletely",
+            file_info.path.display(),
+            file_info.parse_errors
+        );
+        index.parse_errors.insert(file_info.path.clone(), file_info.parse_errors);
+    }
+    index.indexed_at.insert(file_info.path.clone(), read_at);
+    index.add_file_info(file_info);
+    for occ in occurrences {
+        index.add_occurrence(occ);
+    }
+    for (alias_fqn, target_fqn) in type_aliases {
+        index.type_aliases.insert(alias_fqn, target_fqn);
+    }
+    for (field_fqn, accessor_fqns) in lombok_accessors {
+        index.lombok_accessors.entry(field_fqn).or_default().extend(accessor_fqns);
+    }
+    for (class_fqn, parent_fqns) in supertypes {
+        index.supertypes.insert(class_fqn, parent_fqns);
+    }
+    for (decl_fqn, annotation_fqns) in annotations {
+        index.annotations.entry(decl_fqn).or_default().extend(annotation_fqns);
+    }
+    for (property_fqn, component_fqns) in data_class_members {
+        index.data_class_members.insert(property_fqn, component_fqns);
+    }
+}
+
+/// Count the ERROR and MISSING nodes under `node`, only descending into subtrees that
+/// contain one.
+pub(super) fn count_parse_errors(node: &tree_sitter::Node) -> usize {
+    if !node.has_error() {
+        return 0;
+    }
+    let own = usize::from(node.is_error() || node.is_missing());
+    let mut cursor = node.walk();
+    own + node
+        .children(&mut cursor)
+        .map(|child| count_parse_errors(&child))
+        .sum::<usize>()
+}
+
+/// Set each occurrence's `enclosing_fqn` from the declarations of the same file: the
+/// innermost function-like declaration whose range contains it, or failing that the
+/// innermost property or type declaration. Parameters never enclose anything, and
+/// synthesized functions that share the range of the declaration they're generated from
+/// (Lombok accessors of a field) are skipped.
+pub(super) fn assign_enclosing_fqns(occurrences: &mut [SymbolOccurrence]) {
+    let containers = enclosing_containers(occurrences);
+    for occ in occurrences.iter_mut() {
+        occ.enclosing_fqn = innermost_container(&containers, &occ.byte_range);
+    }
+}
+
+/// (range, is function-like, FQN) of every declaration that can enclose other occurrences.
+fn enclosing_containers(
+    occurrences: &[SymbolOccurrence],
+) -> Vec<(std::ops::Range<usize>, bool, String)> {
+    let source_ranges: std::collections::HashSet<std::ops::Range<usize>> = occurrences
+        .iter()
+        .filter(|o| o.kind.is_declaration() && !o.kind.is_function_like())
+        .map(|o| o.byte_range.clone())
+        .collect();
+    occurrences
+        .iter()
+        .filter(|o| o.kind.is_declaration() && o.kind != SymbolKind::ParameterDeclaration)
+        .filter(|o| !(o.kind.is_function_like() && source_ranges.contains(&o.byte_range)))
+        .filter_map(|o| Some((o.byte_range.clone(), o.kind.is_function_like(), o.fqn.clone()?)))
+        .collect()
+}
+
+fn innermost_container(
+    containers: &[(std::ops::Range<usize>, bool, String)],
+    range: &std::ops::Range<usize>,
+) -> Option<String> {
+    let innermost = |function_like: bool| {
+        containers
+            .iter()
+            .filter(|(r, is_fn, _)| {
+                *is_fn == function_like
+                    && r != range
+                    && r.start <= range.start
+                    && range.end <= r.end
+            })
+            .min_by_key(|(r, _, _)| r.end - r.start)
+            .map(|(_, _, fqn)| fqn.clone())
+    };
+    innermost(true).or_else(|| innermost(false))
 }
 
 /// Parse a single Kotlin file and extract symbols.
-fn parse_file(
-    path: &Path,
-    source: &str,
-) -> (FileInfo, Vec<SymbolOccurrence>, Vec<(String, String)>) {
+fn parse_file(path: &Path, source: &str) -> ParsedFile {
     let mut parser = tree_sitter::Parser::new();
     let language = tree_sitter_kotlin_ng::LANGUAGE;
     parser
@@ -100,15 +380,7 @@ fn parse_file(
         Some(t) => t,
         None => {
             warn!("Failed to parse {}", path.display());
-            return (
-                FileInfo {
-                    path: path.to_path_buf(),
-                    package: None,
-                    imports: vec![],
-                },
-                vec![],
-                vec![],
-            );
+            return ParsedFile::empty(path);
         }
     };
 
@@ -138,7 +410,26 @@ fn parse_file(
         &mut type_aliases,
     );
 
+    let mut data_class_members = Vec::new();
+    let mut data_class_occurrences = Vec::new();
+    extract_data_class_members(
+        &root,
+        src,
+        path,
+        package.as_deref(),
+        &scope_tree,
+        &mut data_class_occurrences,
+        &mut data_class_members,
+    );
+
     extract_references(&root, src, path, package.as_deref(), &scope_tree, &imports, &mut occurrences);
+    extract_context_receivers(&root, src, path, package.as_deref(), &imports, &mut occurrences);
+
+    let mut supertypes = Vec::new();
+    extract_supertypes(&root, src, package.as_deref(), &scope_tree, &imports, &mut supertypes);
+
+    let mut annotations = Vec::new();
+    extract_annotations(&root, src, package.as_deref(), &scope_tree, &imports, &mut annotations);
 
     // Add import occurrences
     for imp in &imports {
@@ -158,16 +449,39 @@ fn parse_file(
             column: imp.column,
             byte_range: imp.byte_range.clone(),
             receiver_type: None,
+            enclosing_fqn: None,
+            signature: None,
+            platform_modifier: None,
+            modifiers: None,
         });
     }
 
+    assign_enclosing_fqns(&mut occurrences);
+
+    // Synthesized `componentN()` are anchored at the constructor parameters, so they're
+    // placed after the pass rather than enclosing the parameters' type references
+    let containers = enclosing_containers(&occurrences);
+    for mut occ in data_class_occurrences {
+        occ.enclosing_fqn = innermost_container(&containers, &occ.byte_range);
+        occurrences.push(occ);
+    }
+
     let file_info = FileInfo {
         path: path.to_path_buf(),
         package: package.clone(),
         imports,
+        parse_errors: count_parse_errors(&root),
     };
 
-    (file_info, occurrences, type_aliases)
+    ParsedFile {
+        file_info,
+        occurrences,
+        type_aliases,
+        lombok_accessors: vec![],
+        supertypes,
+        annotations,
+        data_class_members,
+    }
 }
 
 fn extract_package(root: &tree_sitter::Node, src: &[u8]) -> Option<String> {
@@ -261,6 +575,7 @@ fn parse_import_node(node: &tree_sitter::Node, src: &[u8]) -> Option<ImportInfo>
         path,
         alias,
         is_wildcard,
+        is_static: false,
         line: node.start_position().row + 1,
         column: node.start_position().column + 1,
         byte_range: node.byte_range(),
@@ -288,7 +603,23 @@ fn collect_scopes(node: &tree_sitter::Node, src: &[u8], tree: &mut ScopeTree) {
         }
         "companion_object" => {
             if let Some(range) = find_body_range(node) {
-                tree.add_scope("Companion".to_string(), range);
+                // `companion object Factory { ... }` scopes its members under its own name
+                let name = find_child_name(node, src).unwrap_or_else(|| "Companion".to_string());
+                tree.add_scope(name, range);
+            }
+        }
+        "enum_entry" => {
+            // `RED { override fun hex() = "#f00" }`: members become `pkg.Color.RED.hex`
+            if let Some(name) = find_child_name(node, src) {
+                if let Some(range) = find_body_range(node) {
+                    tree.add_scope(name, range);
+                }
+            }
+        }
+        "object_literal" => {
+            // `object : Runnable { ... }` has no name; members become `pkg.Outer.<anon@l:c>.run`
+            if let Some(range) = find_body_range(node) {
+                tree.add_scope(positioned_scope(ANONYMOUS_SCOPE_PREFIX, node), range);
             }
         }
         _ => {}
@@ -316,7 +647,15 @@ fn extract_declarations(
                 // tree-sitter-kotlin-ng uses class_declaration for both classes and interfaces.
                 // Check for the "interface" keyword child to distinguish them.
                 let kind = if has_keyword_child(node, "interface") {
-                    SymbolKind::InterfaceDeclaration
+                    if has_modifier(node, src, "sealed") {
+                        SymbolKind::SealedInterfaceDeclaration
+                    } else {
+                        SymbolKind::InterfaceDeclaration
+                    }
+                } else if has_modifier(node, src, "sealed") {
+                    SymbolKind::SealedClassDeclaration
+                } else if has_modifier(node, src, "annotation") {
+                    SymbolKind::AnnotationClassDeclaration
                 } else {
                     SymbolKind::ClassDeclaration
                 };
@@ -329,6 +668,10 @@ fn extract_declarations(
                     column: node.start_position().column + 1,
                     byte_range: node.byte_range(),
                     receiver_type: None,
+                    enclosing_fqn: None,
+                    signature: None,
+                    platform_modifier: platform_modifier(node, src),
+                    modifiers: Some(declaration_modifiers(node, src)),
                 });
             }
         }
@@ -344,6 +687,10 @@ fn extract_declarations(
                     column: node.start_position().column + 1,
                     byte_range: node.byte_range(),
                     receiver_type: None,
+                    enclosing_fqn: None,
+                    signature: None,
+                    platform_modifier: platform_modifier(node, src),
+                    modifiers: Some(declaration_modifiers(node, src)),
                 });
             }
         }
@@ -359,6 +706,10 @@ fn extract_declarations(
                 column: node.start_position().column + 1,
                 byte_range: node.byte_range(),
                 receiver_type: None,
+                enclosing_fqn: None,
+                signature: None,
+                platform_modifier: None,
+                modifiers: Some(declaration_modifiers(node, src)),
             });
         }
         "function_declaration" => {
@@ -380,24 +731,157 @@ fn extract_declarations(
                     column: node.start_position().column + 1,
                     byte_range: node.byte_range(),
                     receiver_type: receiver,
+                    enclosing_fqn: None,
+                    signature: function_signature(node, src),
+                    platform_modifier: platform_modifier(node, src),
+                    modifiers: Some(declaration_modifiers(node, src)),
+                });
+            }
+        }
+        "property_declaration"
+            if find_child_of_kind(node, "multi_variable_declaration").is_some() =>
+        {
+            // `val (id, name) = user` declares `id` and `name`; `_` declares nothing
+            let mut variables = Vec::new();
+            collect_variable_declarations(node, &mut variables);
+            for variable in variables {
+                let name = match find_child_name(&variable, src) {
+                    Some(name) if name != "_" => name,
+                    _ => continue,
+                };
+                let fqn = build_fqn(package, scope_tree, node.start_byte(), &name);
+                occurrences.push(SymbolOccurrence {
+                    name,
+                    fqn: Some(fqn),
+                    kind: SymbolKind::PropertyDeclaration,
+                    file: path.to_path_buf(),
+                    line: variable.start_position().row + 1,
+                    column: variable.start_position().column + 1,
+                    byte_range: variable.byte_range(),
+                    receiver_type: None,
+                    enclosing_fqn: None,
+                    signature: None,
+                    platform_modifier: None,
+                    modifiers: Some(declaration_modifiers(node, src)),
                 });
             }
         }
         "property_declaration" => {
             if let Some(name) = find_property_name(node, src) {
+                // Extension property: `val Foo.bar: Int get() = ...`
+                let receiver = find_property_receiver_type(node, src);
+                let kind = if receiver.is_some() {
+                    SymbolKind::ExtensionPropertyDeclaration
+                } else {
+                    SymbolKind::PropertyDeclaration
+                };
                 let fqn = build_fqn(package, scope_tree, node.start_byte(), &name);
                 occurrences.push(SymbolOccurrence {
                     name: name.clone(),
                     fqn: Some(fqn),
+                    kind,
+                    file: path.to_path_buf(),
+                    line: node.start_position().row + 1,
+                    column: node.start_position().column + 1,
+                    byte_range: node.byte_range(),
+                    receiver_type: receiver,
+                    enclosing_fqn: None,
+                    signature: None,
+                    platform_modifier: platform_modifier(node, src),
+                    modifiers: Some(declaration_modifiers(node, src)),
+                });
+            }
+        }
+        "class_parameter" if has_keyword_child(node, "val") || has_keyword_child(node, "var") => {
+            // `class User(val name: String)`: a constructor parameter declaring a property.
+            // The parameter list is outside the class body, so the FQN is built from the class.
+            let class = node.parent().and_then(|params| params.parent()).and_then(|ctor| ctor.parent());
+            let class_fqn = class.and_then(|class| {
+                let class_name = find_child_name(&class, src)?;
+                Some(build_fqn(package, scope_tree, class.start_byte(), &class_name))
+            });
+            if let (Some(class_fqn), Some(name)) = (class_fqn, find_child_name(node, src)) {
+                occurrences.push(SymbolOccurrence {
+                    fqn: Some(format!("{}.{}", class_fqn, name)),
+                    name,
                     kind: SymbolKind::PropertyDeclaration,
                     file: path.to_path_buf(),
                     line: node.start_position().row + 1,
                     column: node.start_position().column + 1,
                     byte_range: node.byte_range(),
                     receiver_type: None,
+                    enclosing_fqn: None,
+                    signature: None,
+                    platform_modifier: None,
+                    modifiers: Some(declaration_modifiers(node, src)),
+                });
+            }
+        }
+        "getter" | "setter" => {
+            // Custom accessors (`get() = ...`, `set(value) { ... }`) share the property's FQN,
+            // so a definition lookup on the property also finds them
+            if let Some(property) = node.parent().filter(|p| p.kind() == "property_declaration") {
+                if let Some(name) = find_property_name(&property, src) {
+                    let fqn = build_fqn(package, scope_tree, property.start_byte(), &name);
+                    occurrences.push(SymbolOccurrence {
+                        name,
+                        fqn: Some(fqn),
+                        kind: SymbolKind::PropertyAccessorDeclaration,
+                        file: path.to_path_buf(),
+                        line: node.start_position().row + 1,
+                        column: node.start_position().column + 1,
+                        byte_range: node.byte_range(),
+                        receiver_type: find_property_receiver_type(&property, src),
+                        enclosing_fqn: None,
+                        signature: None,
+                        platform_modifier: None,
+                        modifiers: Some(declaration_modifiers(node, src)),
+                    });
+                }
+            }
+        }
+        "secondary_constructor" => {
+            // Named after the enclosing class (`pkg.Foo.Foo`), mirroring Java constructors
+            if let Some(class_name) = scope_tree
+                .scope_chain_at(node.start_byte())
+                .last()
+                .map(|s| s.to_string())
+            {
+                let fqn = build_fqn(package, scope_tree, node.start_byte(), &class_name);
+                occurrences.push(SymbolOccurrence {
+                    name: class_name,
+                    fqn: Some(fqn),
+                    kind: SymbolKind::ConstructorDeclaration,
+                    file: path.to_path_buf(),
+                    line: node.start_position().row + 1,
+                    column: node.start_position().column + 1,
+                    byte_range: node.byte_range(),
+                    receiver_type: None,
+                    enclosing_fqn: None,
+                    signature: None,
+                    platform_modifier: platform_modifier(node, src),
+                    modifiers: Some(declaration_modifiers(node, src)),
                 });
             }
         }
+        // `init { ... }` blocks have no name of their own: `pkg.Foo.<init>`
+        "anonymous_initializer" if !scope_tree.scope_chain_at(node.start_byte()).is_empty() => {
+            let fqn = build_fqn(package, scope_tree, node.start_byte(), "<init>");
+            occurrences.push(SymbolOccurrence {
+                name: "init".to_string(),
+                fqn: Some(fqn),
+                kind: SymbolKind::InitializerDeclaration,
+                file: path.to_path_buf(),
+                line: node.start_position().row + 1,
+                column: node.start_position().column + 1,
+                byte_range: node.byte_range(),
+                receiver_type: None,
+                enclosing_fqn: None,
+                signature: None,
+                platform_modifier: None,
+                modifiers: Some(declaration_modifiers(node, src)),
+            });
+        }
         "enum_entry" => {
             if let Some(name) = find_child_name(node, src) {
                 let fqn = build_fqn(package, scope_tree, node.start_byte(), &name);
@@ -410,9 +894,56 @@ fn extract_declarations(
                     column: node.start_position().column + 1,
                     byte_range: node.byte_range(),
                     receiver_type: None,
+                    enclosing_fqn: None,
+                    signature: None,
+                    platform_modifier: None,
+                    modifiers: Some(declaration_modifiers(node, src)),
                 });
             }
         }
+        "lambda_literal" => {
+            // `{ user -> ... }` and `{ (key, value) -> ... }` declare parameters local to the lambda
+            for param in lambda_parameters(node) {
+                if let Some(name) = find_child_name(&param, src).filter(|n| n != "_") {
+                    occurrences.push(SymbolOccurrence {
+                        fqn: Some(local_fqn(LAMBDA_SCOPE_PREFIX, package, scope_tree, node, &name)),
+                        name,
+                        kind: SymbolKind::ParameterDeclaration,
+                        file: path.to_path_buf(),
+                        line: param.start_position().row + 1,
+                        column: param.start_position().column + 1,
+                        byte_range: param.byte_range(),
+                        receiver_type: None,
+                        enclosing_fqn: None,
+                        signature: None,
+                        platform_modifier: None,
+                        modifiers: Some(declaration_modifiers(&param, src)),
+                    });
+                }
+            }
+        }
+        "for_statement" => {
+            // `for (item in items)` and `for ((key, value) in map)` declare variables local to
+            // the loop
+            for variable in loop_variables(node) {
+                if let Some(name) = find_child_name(&variable, src).filter(|n| n != "_") {
+                    occurrences.push(SymbolOccurrence {
+                        fqn: Some(local_fqn(LOOP_SCOPE_PREFIX, package, scope_tree, node, &name)),
+                        name,
+                        kind: SymbolKind::ParameterDeclaration,
+                        file: path.to_path_buf(),
+                        line: variable.start_position().row + 1,
+                        column: variable.start_position().column + 1,
+                        byte_range: variable.byte_range(),
+                        receiver_type: None,
+                        enclosing_fqn: None,
+                        signature: None,
+                        platform_modifier: None,
+                        modifiers: Some(declaration_modifiers(&variable, src)),
+                    });
+                }
+            }
+        }
         "type_alias" => {
             if let Some(name) = find_child_name(node, src) {
                 let fqn = build_fqn(package, scope_tree, node.start_byte(), &name);
@@ -429,6 +960,10 @@ fn extract_declarations(
                     column: node.start_position().column + 1,
                     byte_range: node.byte_range(),
                     receiver_type: None,
+                    enclosing_fqn: None,
+                    signature: None,
+                    platform_modifier: platform_modifier(node, src),
+                    modifiers: Some(declaration_modifiers(node, src)),
                 });
             }
         }
@@ -452,6 +987,8 @@ fn extract_references(
     occurrences: &mut Vec<SymbolOccurrence>,
 ) {
     match node.kind() {
+        // Names in `//`, `/* */` and KDoc comments are prose, never references
+        "line_comment" | "block_comment" | "multiline_comment" | "comment" => return,
         "call_expression" => {
             // Extract the function name from the call
             if let Some(name_node) = node.child(0) {
@@ -468,16 +1005,23 @@ fn extract_references(
                         }
                     }) {
                         let member_name = node_text(&member, src).to_string();
-                        let fqn = resolve_reference(&member_name, package, imports);
+                        let fqn = this_or_super_member_fqn(&name_node, &member_name, src, package, scope_tree)
+                            .or_else(|| resolve_reference(&member_name, package, imports));
+                        // Position of the member, not the receiver: `a.b.c()` reports `c` at its own column
+                        let position = member_position_node(&member);
                         occurrences.push(SymbolOccurrence {
                             name: member_name,
                             fqn,
                             kind: SymbolKind::CallSite,
                             file: path.to_path_buf(),
-                            line: node.start_position().row + 1,
-                            column: node.start_position().column + 1,
-                            byte_range: node.byte_range(),
+                            line: position.start_position().row + 1,
+                            column: position.start_position().column + 1,
+                            byte_range: position.byte_range(),
                             receiver_type: extract_receiver_from_nav(&name_node, src),
+                            enclosing_fqn: None,
+                            signature: None,
+                            platform_modifier: None,
+                            modifiers: None,
                         });
                         // Process the receiver of the navigation expression
                         extract_nav_receiver(&name_node, src, path, package, scope_tree, imports, occurrences);
@@ -502,6 +1046,10 @@ fn extract_references(
                         column: node.start_position().column + 1,
                         byte_range: node.byte_range(),
                         receiver_type: None,
+                        enclosing_fqn: None,
+                        signature: None,
+                        platform_modifier: None,
+                        modifiers: None,
                     });
                     // Recurse into arguments only
                     let mut cursor = node.walk();
@@ -514,6 +1062,29 @@ fn extract_references(
                 }
             }
         }
+        "navigation_expression" if class_literal_type(node, src).is_some() => {
+            // `User::class` and `Result.Success::class` are class literals of a type.
+            // Other `::` references (`User::name`, `this::class`) are navigations.
+            let type_name = class_literal_type(node, src).unwrap_or_default();
+            if let Some(receiver) = node.child(0) {
+                let (name, fqn) = resolve_qualified_type(type_name, package, imports);
+                occurrences.push(SymbolOccurrence {
+                    name,
+                    fqn,
+                    kind: SymbolKind::TypeReference,
+                    file: path.to_path_buf(),
+                    line: receiver.start_position().row + 1,
+                    column: receiver.start_position().column + 1,
+                    byte_range: receiver.byte_range(),
+                    receiver_type: None,
+                    enclosing_fqn: None,
+                    signature: None,
+                    platform_modifier: None,
+                    modifiers: None,
+                });
+            }
+            return;
+        }
         "navigation_expression" => {
             // Only handle if not already handled by parent call_expression
             if let Some(parent) = node.parent() {
@@ -528,16 +1099,22 @@ fn extract_references(
                 if let Some(member) = node.child(count - 1) {
                     if member.kind() == "simple_identifier" || member.kind() == "identifier" || member.kind() == "navigation_suffix" {
                         let member_name = node_text(&member, src).to_string();
-                        let fqn = resolve_reference(&member_name, package, imports);
+                        let fqn = this_or_super_member_fqn(node, &member_name, src, package, scope_tree)
+                            .or_else(|| resolve_reference(&member_name, package, imports));
+                        let position = member_position_node(&member);
                         occurrences.push(SymbolOccurrence {
                             name: member_name,
                             fqn,
                             kind: SymbolKind::PropertyReference,
                             file: path.to_path_buf(),
-                            line: node.start_position().row + 1,
-                            column: node.start_position().column + 1,
-                            byte_range: node.byte_range(),
+                            line: position.start_position().row + 1,
+                            column: position.start_position().column + 1,
+                            byte_range: position.byte_range(),
                             receiver_type: extract_receiver_from_nav(node, src),
+                            enclosing_fqn: None,
+                            signature: None,
+                            platform_modifier: None,
+                            modifiers: None,
                         });
                     }
                 }
@@ -546,65 +1123,445 @@ fn extract_references(
             }
             return;
         }
-        "user_type" => {
-            // Type references like `: Foo` or `Foo<Bar>`
+        "this_expression" => {
+            // `this@Outer` refers to the enclosing class `Outer`; a label naming a function
+            // or lambda (`this@apply`) refers to a receiver, which isn't a declaration
             let text = node_text(node, src);
-            // Get the simple type name (first identifier)
-            let type_name = text.split('<').next().unwrap_or(&text).trim().to_string();
-            if !type_name.is_empty() && type_name.chars().next().is_some_and(|c| c.is_uppercase()) {
-                let fqn = resolve_reference(&type_name, package, imports);
+            if let (Some(label), Some(at)) = (expression_label(text), text.find('@')) {
+                if let Some(class_fqn) =
+                    enclosing_class_fqn(package, scope_tree, node.start_byte(), Some(label))
+                {
+                    let start = node.start_byte() + at + 1;
+                    occurrences.push(SymbolOccurrence {
+                        name: label.to_string(),
+                        fqn: Some(class_fqn),
+                        kind: SymbolKind::TypeReference,
+                        file: path.to_path_buf(),
+                        line: node.start_position().row + 1,
+                        column: node.start_position().column + at + 2,
+                        byte_range: start..start + label.len(),
+                        receiver_type: None,
+                        enclosing_fqn: None,
+                        signature: None,
+                        platform_modifier: None,
+                        modifiers: None,
+                    });
+                }
+            }
+            return;
+        }
+        "super_expression" => {
+            // `super<Foo>` names a supertype
+            if let Some(type_node) = find_first_descendant(node, "user_type") {
+                extract_references(&type_node, src, path, package, scope_tree, imports, occurrences);
+            }
+            return;
+        }
+        "annotation" => {
+            // `@Foo`, `@Foo(args)`, `@field:Foo`, `@com.example.Foo`
+            if let Some(type_name) = annotation_type_name(node, src) {
+                let (name, fqn) = match type_name.rsplit_once('.') {
+                    Some((_, simple)) => (simple.to_string(), Some(type_name.clone())),
+                    None => {
+                        let fqn = resolve_reference(&type_name, package, imports);
+                        (type_name, fqn)
+                    }
+                };
                 occurrences.push(SymbolOccurrence {
-                    name: type_name,
+                    name,
                     fqn,
-                    kind: SymbolKind::TypeReference,
+                    kind: SymbolKind::AnnotationReference,
                     file: path.to_path_buf(),
                     line: node.start_position().row + 1,
                     column: node.start_position().column + 1,
                     byte_range: node.byte_range(),
                     receiver_type: None,
+                    enclosing_fqn: None,
+                    signature: None,
+                    platform_modifier: None,
+                    modifiers: None,
                 });
             }
-            // Don't recurse into type parameters - they'll be handled separately
+            // Annotation arguments may reference other symbols (constants, classes)
+            if let Some(args) = find_first_descendant(node, "value_arguments") {
+                extract_annotation_arguments(&args, src, path, package, scope_tree, imports, occurrences);
+            }
             return;
         }
-        "simple_identifier" | "identifier" => {
-            // Bare identifier used as a value reference (e.g., passed as argument,
-            // assigned to variable). Only capture if not already handled by another case.
-            if let Some(parent) = node.parent() {
-                let pk = parent.kind();
-                let dominated = matches!(
-                    pk,
-                    // Declaration names
-                    "class_declaration"
-                        | "object_declaration"
-                        | "function_declaration"
-                        | "variable_declaration"
-                        | "parameter"
-                        | "companion_object"
-                        | "enum_entry"
-                        | "type_alias"
-                        // Import / package path components
-                        | "import"
-                        | "import_header"
-                        | "import_alias"
-                        | "import_list"
-                        | "package_header"
-                        | "qualified_identifier"
-                        // Already handled by navigation_expression / user_type
-                        | "navigation_expression"
-                        | "navigation_suffix"
-                        | "user_type"
-                        // Type parameters and annotations
-                        | "type_parameter"
-                        | "type_constraint"
-                        | "annotation"
-                        // Labels
-                        | "label"
-                );
-                if !dominated {
-                    // Also skip if this is child(0) of a call_expression (callee, already handled)
-                    let is_callee = pk == "call_expression"
-                        && parent.child(0).is_some_and(|c| c.id() == node.id());
+        "explicit_delegation" | "property_delegate" => {
+            // Interface delegation `Bar by impl` and property delegation `val x by lazy { ... }`.
+            // The delegated type (if any) is a user_type; the delegate expression follows `by`.
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                if child.kind() == "simple_identifier" || child.kind() == "identifier" {
+                    // Bare delegate target, e.g. `by impl`
+                    let name = node_text(&child, src).to_string();
+                    let fqn = resolve_reference(&name, package, imports);
+                    occurrences.push(SymbolOccurrence {
+                        name,
+                        fqn,
+                        kind: SymbolKind::PropertyReference,
+                        file: path.to_path_buf(),
+                        line: child.start_position().row + 1,
+                        column: child.start_position().column + 1,
+                        byte_range: child.byte_range(),
+                        receiver_type: None,
+                        enclosing_fqn: None,
+                        signature: None,
+                        platform_modifier: None,
+                        modifiers: None,
+                    });
+                } else {
+                    // user_type, call_expression (`lazy { ... }`), navigation_expression, ...
+                    extract_references(&child, src, path, package, scope_tree, imports, occurrences);
+                }
+            }
+            if node.kind() == "property_delegate" {
+                push_delegate_operator_calls(node, src, path, package, imports, occurrences);
+            }
+            return;
+        }
+        "getter" | "setter" => {
+            let start = occurrences.len();
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                extract_references(&child, src, path, package, scope_tree, imports, occurrences);
+            }
+            qualify_accessor_references(node, src, package, scope_tree, &mut occurrences[start..]);
+            return;
+        }
+        "multi_variable_declaration" => {
+            // `val (a, b) = point` calls `point.component1()` and `point.component2()`
+            let type_name = node.parent().and_then(|decl| destructured_type_name(&decl, src));
+            let class_fqn = type_name
+                .as_deref()
+                .and_then(|t| resolve_reference(t, package, imports));
+            let mut position = 0;
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                if child.kind() != "variable_declaration" {
+                    continue;
+                }
+                position += 1;
+                // `_` skips a component without calling it
+                if find_child_name(&child, src).as_deref() == Some("_") {
+                    continue;
+                }
+                let name = format!("component{}", position);
+                occurrences.push(SymbolOccurrence {
+                    fqn: class_fqn.as_ref().map(|c| format!("{}.{}", c, name)),
+                    name,
+                    kind: SymbolKind::CallSite,
+                    file: path.to_path_buf(),
+                    line: child.start_position().row + 1,
+                    column: child.start_position().column + 1,
+                    byte_range: child.byte_range(),
+                    receiver_type: type_name.clone(),
+                    enclosing_fqn: None,
+                    signature: None,
+                    platform_modifier: None,
+                    modifiers: None,
+                });
+            }
+        }
+        "type_test" => {
+            // `is Foo` / `!is Foo` in `when` branches (smart casts), including nested
+            // sealed subtypes like `is Result.Success`. `in range` conditions, the
+            // `when (subject)` expression and the boolean conditions of a `when` without a
+            // subject are plain expressions handled by recursion.
+            if let Some(type_node) = find_first_descendant(node, "user_type") {
+                let text = node_text(&type_node, src);
+                let type_name = text.split('<').next().unwrap_or(text).trim();
+                if !type_name.is_empty() {
+                    let (name, fqn) = resolve_qualified_type(type_name, package, imports);
+                    occurrences.push(SymbolOccurrence {
+                        name,
+                        fqn,
+                        kind: SymbolKind::TypeReference,
+                        file: path.to_path_buf(),
+                        line: type_node.start_position().row + 1,
+                        column: type_node.start_position().column + 1,
+                        byte_range: type_node.byte_range(),
+                        receiver_type: None,
+                        enclosing_fqn: None,
+                        signature: None,
+                        platform_modifier: None,
+                        modifiers: None,
+                    });
+                }
+                let mut cursor = type_node.walk();
+                for child in type_node.children(&mut cursor) {
+                    if child.kind() == "type_arguments" {
+                        extract_references(&child, src, path, package, scope_tree, imports, occurrences);
+                    }
+                }
+            }
+            return;
+        }
+        "type_alias" => {
+            // `typealias Handler<T> = (User, T) -> Unit`: the types in the target are references,
+            // except the alias's own type parameters
+            let names = find_child_of_kind(node, "type_parameters")
+                .map(|list| type_parameter_names(&list, src))
+                .unwrap_or_default();
+            let start = occurrences.len();
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                extract_references(&child, src, path, package, scope_tree, imports, occurrences);
+            }
+            drop_type_parameter_references(occurrences, start, &names);
+            return;
+        }
+        "type_parameter" | "type_constraint" => {
+            // `class Repo<T : Entity>`, `fun <T : Comparable<T>> sort()`, `where T : Entity`.
+            // The bound is a type reference; the parameter name declares a type variable,
+            // and uses of it inside the bound (`Comparable<T>`) are not project types.
+            let type_parameters = if node.kind() == "type_parameter" {
+                node.parent()
+            } else {
+                // type_constraint → type_constraints → declaration
+                node.parent()
+                    .and_then(|constraints| constraints.parent())
+                    .and_then(|decl| find_child_of_kind(&decl, "type_parameters"))
+            };
+            let mut names = type_parameters
+                .map(|list| type_parameter_names(&list, src))
+                .unwrap_or_default();
+            names.extend(find_child_name(node, src));
+
+            let start = occurrences.len();
+            let mut cursor = node.walk();
+            for child in node.named_children(&mut cursor) {
+                if !matches!(child.kind(), "identifier" | "simple_identifier") {
+                    extract_references(&child, src, path, package, scope_tree, imports, occurrences);
+                }
+            }
+            drop_type_parameter_references(occurrences, start, &names);
+            return;
+        }
+        "string_literal" => {
+            // `"Hello $name, ${user.count} new"`: only the `$name` and `${...}` interpolations
+            // are code. The literal text between them and escapes like `\$name` are skipped.
+            // The grammar leaves a simple `$name` as literal text: a `$` followed by content
+            // that starts with the name.
+            let mut after_dollar = false;
+            let mut cursor = node.walk();
+            for child in node.named_children(&mut cursor) {
+                match child.kind() {
+                    "string_content" => {
+                        if after_dollar {
+                            push_template_identifier(&child, src, path, package, imports, occurrences);
+                        }
+                        after_dollar = node_text(&child, src).ends_with('$');
+                    }
+                    "escape_sequence" => after_dollar = false,
+                    _ => {
+                        after_dollar = false;
+                        extract_references(&child, src, path, package, scope_tree, imports, occurrences);
+                    }
+                }
+            }
+            return;
+        }
+        "lambda_literal" => {
+            // Uses of the lambda's parameters inside its body refer to them, not to
+            // same-named properties. Inner lambdas bind their own parameters first, so a
+            // shadowing parameter keeps its uses.
+            let names: Vec<String> = lambda_parameters(node)
+                .iter()
+                .filter_map(|param| find_child_name(param, src))
+                .collect();
+            let start = occurrences.len();
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                extract_references(&child, src, path, package, scope_tree, imports, occurrences);
+            }
+            let is_unbound_use = |occ: &SymbolOccurrence, name: &str| {
+                occ.kind == SymbolKind::PropertyReference
+                    && occ.receiver_type.is_none()
+                    && occ.name == name
+                    && !occ.fqn.as_deref().is_some_and(is_local)
+            };
+            if names.is_empty() {
+                // The implicit `it` is not a project symbol
+                let body = occurrences.split_off(start);
+                occurrences.extend(body.into_iter().filter(|occ| !is_unbound_use(occ, "it")));
+            } else {
+                for occ in &mut occurrences[start..] {
+                    if let Some(name) = names.iter().find(|name| is_unbound_use(&*occ, name.as_str())) {
+                        occ.fqn = Some(local_fqn(LAMBDA_SCOPE_PREFIX, package, scope_tree, node, name));
+                    }
+                }
+            }
+            return;
+        }
+        "for_statement" => {
+            // Uses of the loop variables in the body refer to them, like lambda parameters.
+            // The iterated expression comes before the body and is outside their scope.
+            let names: Vec<String> = loop_variables(node)
+                .iter()
+                .filter_map(|variable| find_child_name(variable, src))
+                .collect();
+            let mut body_start = None;
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                if child.kind() == ")" && body_start.is_none() {
+                    body_start = Some(occurrences.len());
+                }
+                extract_references(&child, src, path, package, scope_tree, imports, occurrences);
+            }
+            let start = body_start.unwrap_or(occurrences.len());
+            for occ in &mut occurrences[start..] {
+                let is_unbound_use = occ.kind == SymbolKind::PropertyReference
+                    && occ.receiver_type.is_none()
+                    && !occ.fqn.as_deref().is_some_and(is_local);
+                if !is_unbound_use {
+                    continue;
+                }
+                if let Some(name) = names.iter().find(|name| **name == occ.name) {
+                    occ.fqn = Some(local_fqn(LOOP_SCOPE_PREFIX, package, scope_tree, node, name));
+                }
+            }
+            return;
+        }
+        "binary_expression"
+        | "additive_expression"
+        | "multiplicative_expression"
+        | "comparison_expression"
+        | "check_expression"
+        | "in_expression"
+        | "indexing_expression"
+        | "index_expression" => {
+            // `a + b` calls `a.plus(b)`, `a[i]` calls `a.get(i)`, `x in xs` calls `xs.contains(x)`.
+            // The operands are scanned by the recursion below.
+            push_operator_call(node, src, path, package, imports, occurrences);
+        }
+        "infix_expression" => {
+            // `a dot b` calls `a.dot(b)`: a CallSite at the function name with the left operand
+            // as receiver. When the operand's type is known (a constructor call, or a typed
+            // parameter or local), the call is resolved as a member of that type first; cross-
+            // referencing falls back to the imported or same-package function (e.g., an
+            // extension) when the type declares no such member.
+            let operands: Vec<tree_sitter::Node> = {
+                let mut cursor = node.walk();
+                node.named_children(&mut cursor).collect()
+            };
+            if let [left, function, right] = operands.as_slice() {
+                if matches!(function.kind(), "identifier" | "simple_identifier") {
+                    let name = node_text(function, src).to_string();
+                    let fqn = expression_type_name(left, src)
+                        .and_then(|type_name| resolve_reference(&type_name, package, imports))
+                        .map(|type_fqn| format!("{}.{}", type_fqn, name))
+                        .or_else(|| resolve_reference(&name, package, imports));
+                    occurrences.push(SymbolOccurrence {
+                        name,
+                        fqn,
+                        kind: SymbolKind::CallSite,
+                        file: path.to_path_buf(),
+                        line: function.start_position().row + 1,
+                        column: function.start_position().column + 1,
+                        byte_range: function.byte_range(),
+                        receiver_type: Some(node_text(left, src).to_string()),
+                        enclosing_fqn: None,
+                        signature: None,
+                        platform_modifier: None,
+                        modifiers: None,
+                    });
+                    extract_references(left, src, path, package, scope_tree, imports, occurrences);
+                    extract_references(right, src, path, package, scope_tree, imports, occurrences);
+                    return;
+                }
+            }
+        }
+        "type_arguments" => {
+            // `<Foo, out Bar, *>` on a type (`List<Foo>`) or a call (`lookup<Foo>()`,
+            // `emptyList<Foo>()`): each projected type is a reference, star projections
+            // and variance modifiers are not
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                if child.kind() == "type_projection" {
+                    let mut inner = child.walk();
+                    for part in child.children(&mut inner) {
+                        if part.kind() != "type_projection_modifiers" {
+                            extract_references(&part, src, path, package, scope_tree, imports, occurrences);
+                        }
+                    }
+                } else if child.is_named() {
+                    extract_references(&child, src, path, package, scope_tree, imports, occurrences);
+                }
+            }
+            return;
+        }
+        "user_type" => {
+            // Type references like `: Foo` or `Foo<Bar>`
+            let text = node_text(node, src);
+            // Get the simple type name (first identifier)
+            let type_name = text.split('<').next().unwrap_or(&text).trim().to_string();
+            if !type_name.is_empty() && type_name.chars().next().is_some_and(|c| c.is_uppercase()) {
+                let fqn = resolve_reference(&type_name, package, imports);
+                occurrences.push(SymbolOccurrence {
+                    name: type_name,
+                    fqn,
+                    kind: SymbolKind::TypeReference,
+                    file: path.to_path_buf(),
+                    line: node.start_position().row + 1,
+                    column: node.start_position().column + 1,
+                    byte_range: node.byte_range(),
+                    receiver_type: None,
+                    enclosing_fqn: None,
+                    signature: None,
+                    platform_modifier: None,
+                    modifiers: None,
+                });
+            }
+            // Recurse only into type arguments (`List<Map<String, User>>`), not the name segments
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                if child.kind() == "type_arguments" {
+                    extract_references(&child, src, path, package, scope_tree, imports, occurrences);
+                }
+            }
+            return;
+        }
+        "simple_identifier" | "identifier" => {
+            // Bare identifier used as a value reference (e.g., passed as argument,
+            // assigned to variable). Only capture if not already handled by another case.
+            if let Some(parent) = node.parent() {
+                let pk = parent.kind();
+                let dominated = matches!(
+                    pk,
+                    // Declaration names
+                    "class_declaration"
+                        | "object_declaration"
+                        | "function_declaration"
+                        | "variable_declaration"
+                        | "parameter"
+                        | "class_parameter"
+                        | "companion_object"
+                        | "enum_entry"
+                        | "type_alias"
+                        // Import / package path components
+                        | "import"
+                        | "import_header"
+                        | "import_alias"
+                        | "import_list"
+                        | "package_header"
+                        | "qualified_identifier"
+                        // Already handled by navigation_expression / user_type
+                        | "navigation_expression"
+                        | "navigation_suffix"
+                        | "user_type"
+                        // Type parameters and annotations
+                        | "type_parameter"
+                        | "type_constraint"
+                        | "annotation"
+                        // Labels
+                        | "label"
+                );
+                if !dominated {
+                    // Also skip if this is child(0) of a call_expression (callee, already handled)
+                    let is_callee = pk == "call_expression"
+                        && parent.child(0).is_some_and(|c| c.id() == node.id());
                     if !is_callee {
                         let name = node_text(node, src).to_string();
                         if !name.is_empty() {
@@ -618,6 +1575,10 @@ fn extract_references(
                                 column: node.start_position().column + 1,
                                 byte_range: node.byte_range(),
                                 receiver_type: None,
+                                enclosing_fqn: None,
+                                signature: None,
+                                platform_modifier: None,
+                                modifiers: None,
                             });
                         }
                     }
@@ -635,286 +1596,2483 @@ fn extract_references(
     }
 }
 
-pub(super) fn resolve_reference(name: &str, package: Option<&str>, imports: &[ImportInfo]) -> Option<String> {
-    // Check explicit imports first
-    for imp in imports {
-        if imp.is_wildcard {
+/// Record the name at the start of template text following a `$` (`"Hi $name!"`) as a
+/// property reference.
+fn push_template_identifier(
+    content: &tree_sitter::Node,
+    src: &[u8],
+    path: &Path,
+    package: Option<&str>,
+    imports: &[ImportInfo],
+    occurrences: &mut Vec<SymbolOccurrence>,
+) {
+    let text = node_text(content, src);
+    if !text.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+        return;
+    }
+    let len = text
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(text.len());
+    let name = text[..len].to_string();
+    let start = content.start_byte();
+    let fqn = resolve_reference(&name, package, imports);
+    occurrences.push(SymbolOccurrence {
+        name,
+        fqn,
+        kind: SymbolKind::PropertyReference,
+        file: path.to_path_buf(),
+        line: content.start_position().row + 1,
+        column: content.start_position().column + 1,
+        byte_range: start..start + len,
+        receiver_type: None,
+        enclosing_fqn: None,
+        signature: None,
+        platform_modifier: None,
+        modifiers: None,
+    });
+}
+
+/// Keywords and modifiers that can follow a `context(...)` clause.
+const CONTEXT_CLAUSE_FOLLOWERS: &[&str] = &[
+    "fun", "val", "var", "class", "interface", "object", "context", "public", "private",
+    "internal", "protected", "override", "open", "abstract", "final", "suspend", "inline",
+    "operator", "infix", "tailrec", "external", "data", "sealed", "enum", "value", "expect",
+    "actual",
+];
+
+/// Record the types of context receivers and context parameters, `context(Logger)` and
+/// `context(logger: Logger)`, as type references. Grammar support for context clauses
+/// varies (error recovery may parse one as a call to `context`), so clauses are found in
+/// the source: a `context(...)` starting a line and followed by a declaration or modifier,
+/// outside comments and strings. A reference already recorded at a type's position is kept,
+/// and retyped as a TypeReference if error recovery took it for a value.
+fn extract_context_receivers(
+    root: &tree_sitter::Node,
+    src: &[u8],
+    path: &Path,
+    package: Option<&str>,
+    imports: &[ImportInfo],
+    occurrences: &mut Vec<SymbolOccurrence>,
+) {
+    let text = match std::str::from_utf8(src) {
+        Ok(text) => text,
+        Err(_) => return,
+    };
+    let mut search_from = 0;
+    while let Some(found) = text[search_from..].find("context") {
+        let keyword = search_from + found;
+        search_from = keyword + "context".len();
+
+        let line_start = text[..keyword].rfind('\n').map_or(0, |i| i + 1);
+        if !text[line_start..keyword].trim().is_empty() {
             continue;
         }
-        let imported_name = if let Some(ref alias) = imp.alias {
-            alias.as_str()
-        } else {
-            imp.path.rsplit('.').next().unwrap_or(&imp.path)
+        let rest = &text[search_from..];
+        let open = search_from + rest.len() - rest.trim_start_matches([' ', '\t']).len();
+        if !text[open..].starts_with('(') {
+            continue;
+        }
+        let close = match matching_paren(text, open) {
+            Some(close) => close,
+            None => continue,
         };
-        if imported_name == name {
-            if imp.alias.is_some() {
-                // Aliased import: the FQN is the original path
-                return Some(imp.path.clone());
+        let after = text[close + 1..].trim_start();
+        let declares = after.starts_with('@')
+            || CONTEXT_CLAUSE_FOLLOWERS.iter().any(|word| {
+                after.starts_with(word)
+                    && !after[word.len()..].starts_with(|c: char| c.is_alphanumeric() || c == '_')
+            });
+        if !declares || in_comment_or_string(root, keyword) {
+            continue;
+        }
+
+        // Error recovery may have recorded the clause as a call to `context`
+        occurrences.retain(|o| {
+            !(o.kind.is_reference() && o.name == "context" && o.byte_range.start == keyword)
+        });
+        for (start, type_name) in context_clause_types(text, open + 1, close) {
+            let fqn = resolve_reference(type_name, package, imports);
+            let existing = occurrences
+                .iter_mut()
+                .find(|o| o.kind.is_reference() && o.byte_range.start == start);
+            if let Some(existing) = existing {
+                if existing.kind != SymbolKind::TypeReference {
+                    existing.kind = SymbolKind::TypeReference;
+                    existing.name = type_name.to_string();
+                    existing.fqn = fqn;
+                    existing.byte_range = start..start + type_name.len();
+                }
+                continue;
             }
-            return Some(imp.path.clone());
+            let line_start = text[..start].rfind('\n').map_or(0, |i| i + 1);
+            occurrences.push(SymbolOccurrence {
+                name: type_name.to_string(),
+                fqn,
+                kind: SymbolKind::TypeReference,
+                file: path.to_path_buf(),
+                line: text[..start].matches('\n').count() + 1,
+                column: start - line_start + 1,
+                byte_range: start..start + type_name.len(),
+                receiver_type: None,
+                enclosing_fqn: None,
+                signature: None,
+                platform_modifier: None,
+                modifiers: None,
+            });
         }
+        search_from = close + 1;
     }
+}
 
-    // Check wildcard imports - we can't fully resolve without the full index,
-    // so we return None and let cross-referencing handle it
-    // But we note the package for same-package resolution
-    if let Some(pkg) = package {
-        return Some(format!("{}.{}", pkg, name));
+/// Byte offset of the `)` closing the `(` at `open`.
+fn matching_paren(text: &str, open: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, c) in text[open..].char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open + i);
+                }
+            }
+            _ => {}
+        }
     }
-
     None
 }
 
-/// Process the receiver (child 0) of a navigation_expression, capturing it as a reference.
-/// For a leaf identifier receiver (e.g., `Config` in `Config.foo`), emit it directly.
-/// For a complex receiver (e.g., another navigation in `a.b.c`), recurse into it.
-fn extract_nav_receiver(
-    nav_node: &tree_sitter::Node,
+/// The type names in the context clause between byte offsets `start` and `end`, with their
+/// offsets: every capitalized, possibly qualified name in each entry's type (after the `:`
+/// of a context parameter), including type arguments.
+fn context_clause_types(text: &str, start: usize, end: usize) -> Vec<(usize, &str)> {
+    let mut types = Vec::new();
+    let mut depth = 0usize;
+    let mut in_type = !entry_has_name(&text[start..end]);
+    let mut token_start: Option<usize> = None;
+    for (i, c) in text[start..end].char_indices() {
+        let offset = start + i;
+        if c.is_alphanumeric() || c == '_' || c == '.' {
+            token_start.get_or_insert(offset);
+            continue;
+        }
+        if let Some(token) = token_start.take() {
+            if in_type && text[token..offset].starts_with(|c: char| c.is_uppercase()) {
+                types.push((token, &text[token..offset]));
+            }
+        }
+        match c {
+            '<' | '(' => depth += 1,
+            '>' | ')' => depth = depth.saturating_sub(1),
+            // `name: Type` declares a context parameter; the name isn't a type
+            ':' if depth == 0 => in_type = true,
+            ',' if depth == 0 => in_type = !entry_has_name(&text[offset + 1..end]),
+            _ => {}
+        }
+    }
+    if let Some(token) = token_start {
+        if in_type && text[token..end].starts_with(|c: char| c.is_uppercase()) {
+            types.push((token, &text[token..end]));
+        }
+    }
+    types
+}
+
+/// Whether the context clause entry starting `rest` is a named context parameter.
+fn entry_has_name(rest: &str) -> bool {
+    let entry = rest.split(',').next().unwrap_or(rest);
+    entry.contains(':')
+}
+
+fn in_comment_or_string(root: &tree_sitter::Node, byte: usize) -> bool {
+    let mut node = root.descendant_for_byte_range(byte, byte + 1);
+    while let Some(n) = node {
+        if n.kind().contains("comment") || n.kind().contains("string") {
+            return true;
+        }
+        node = n.parent();
+    }
+    false
+}
+
+/// Record the direct supertypes of every class and object declaration.
+/// `class Foo : Bar(), Baz` yields `(pkg.Foo, [pkg.Bar, pkg.Baz])`.
+fn extract_supertypes(
+    node: &tree_sitter::Node,
     src: &[u8],
-    path: &Path,
     package: Option<&str>,
     scope_tree: &ScopeTree,
     imports: &[ImportInfo],
-    occurrences: &mut Vec<SymbolOccurrence>,
+    supertypes: &mut Vec<(String, Vec<String>)>,
 ) {
-    if let Some(receiver) = nav_node.child(0) {
-        if receiver.kind() == "simple_identifier" || receiver.kind() == "identifier" {
-            // Leaf receiver — capture directly as a reference
-            let name = node_text(&receiver, src).to_string();
-            if !name.is_empty() {
-                let fqn = resolve_reference(&name, package, imports);
-                occurrences.push(SymbolOccurrence {
-                    name,
-                    fqn,
-                    kind: SymbolKind::PropertyReference,
-                    file: path.to_path_buf(),
-                    line: receiver.start_position().row + 1,
-                    column: receiver.start_position().column + 1,
-                    byte_range: receiver.byte_range(),
-                    receiver_type: None,
-                });
+    if matches!(node.kind(), "class_declaration" | "object_declaration") {
+        if let Some(name) = find_child_name(node, src) {
+            let fqn = build_fqn(package, scope_tree, node.start_byte(), &name);
+            let parents: Vec<String> = find_supertype_names(node, src)
+                .into_iter()
+                .filter_map(|type_name| {
+                    if type_name.contains('.') {
+                        Some(type_name)
+                    } else {
+                        resolve_reference(&type_name, package, imports)
+                    }
+                })
+                .collect();
+            if !parents.is_empty() {
+                supertypes.push((fqn, parents));
             }
-        } else {
-            // Complex receiver (e.g., nested navigation_expression, call_expression) — recurse
-            extract_references(&receiver, src, path, package, scope_tree, imports, occurrences);
         }
     }
-}
 
-fn extract_receiver_from_nav(nav_node: &tree_sitter::Node, src: &[u8]) -> Option<String> {
-    if nav_node.child_count() >= 2 {
-        if let Some(receiver) = nav_node.child(0) {
-            let text = node_text(&receiver, src).to_string();
-            if !text.is_empty() {
-                return Some(text);
+    // An entry with a body is an anonymous subclass of its enum, whose members it overrides
+    if node.kind() == "enum_entry" && find_body_range(node).is_some() {
+        if let Some(name) = find_child_name(node, src) {
+            let fqn = build_fqn(package, scope_tree, node.start_byte(), &name);
+            if let Some((enum_fqn, _)) = fqn.rsplit_once('.') {
+                supertypes.push((fqn.clone(), vec![enum_fqn.to_string()]));
             }
         }
     }
-    None
-}
 
-pub(super) fn build_fqn(
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        extract_supertypes(&child, src, package, scope_tree, imports, supertypes);
+    }
+}
+
+/// Qualify bare references inside a property's custom getter or setter: the backing
+/// `field` refers to the property itself, and in a class, `get() = celsius * 9 / 5 + 32`
+/// refers to `pkg.Temperature.celsius`. Other names in a top-level property's accessors
+/// keep the package-level resolution.
+fn qualify_accessor_references(
+    accessor: &tree_sitter::Node,
+    src: &[u8],
     package: Option<&str>,
     scope_tree: &ScopeTree,
-    byte_offset: usize,
+    occurrences: &mut [SymbolOccurrence],
+) {
+    let property = match accessor.parent() {
+        Some(p) if p.kind() == "property_declaration" => p,
+        _ => return,
+    };
+    let property_fqn = find_property_name(&property, src)
+        .map(|name| build_fqn(package, scope_tree, property.start_byte(), &name));
+    let members = class_member_names(&property, src);
+
+    for occ in occurrences {
+        if !matches!(occ.kind, SymbolKind::PropertyReference | SymbolKind::CallSite) {
+            continue;
+        }
+        if !matches!(occ.receiver_type.as_deref(), None | Some("this")) {
+            continue;
+        }
+        if occ.name == "field" && occ.receiver_type.is_none() {
+            occ.fqn = property_fqn.clone();
+        } else if members.contains(&occ.name) {
+            occ.fqn = Some(build_fqn(package, scope_tree, property.start_byte(), &occ.name));
+        }
+    }
+}
+
+/// Names of the properties and functions declared alongside `member` in its class body.
+fn class_member_names(member: &tree_sitter::Node, src: &[u8]) -> Vec<String> {
+    let body = match member.parent() {
+        Some(b) if b.kind() == "class_body" => b,
+        _ => return Vec::new(),
+    };
+    let mut names = Vec::new();
+    let mut cursor = body.walk();
+    for child in body.children(&mut cursor) {
+        let name = match child.kind() {
+            "property_declaration" => find_property_name(&child, src),
+            "function_declaration" => find_child_name(&child, src),
+            _ => None,
+        };
+        names.extend(name);
+    }
+    names
+}
+
+/// Synthesize the `componentN()` and `copy()` functions generated for each `data class`.
+/// `data class Point(val x: Int, val y: Int)` declares `pkg.Point.component1` and
+/// `pkg.Point.component2` at the constructor parameters, and yields
+/// `(pkg.Point.x, [pkg.Point.component1])` and `(pkg.Point.y, [pkg.Point.component2])`.
+fn extract_data_class_members(
+    node: &tree_sitter::Node,
+    src: &[u8],
+    path: &Path,
+    package: Option<&str>,
+    scope_tree: &ScopeTree,
+    occurrences: &mut Vec<SymbolOccurrence>,
+    data_class_members: &mut Vec<(String, Vec<String>)>,
+) {
+    if node.kind() == "class_declaration" && has_modifier(node, src, "data") {
+        if let Some(name) = find_child_name(node, src) {
+            let class_fqn = build_fqn(package, scope_tree, node.start_byte(), &name);
+
+            let mut position = 0;
+            let mut cursor = node.walk();
+            let params = node
+                .children(&mut cursor)
+                .find(|c| c.kind() == "primary_constructor")
+                .and_then(|ctor| find_first_descendant(&ctor, "class_parameters"));
+            if let Some(params) = params {
+                let mut cursor = params.walk();
+                for param in params.children(&mut cursor) {
+                    if param.kind() != "class_parameter" {
+                        continue;
+                    }
+                    position += 1;
+                    let component = format!("component{}", position);
+                    let component_fqn = format!("{}.{}", class_fqn, component);
+                    occurrences.push(SymbolOccurrence {
+                        name: component,
+                        fqn: Some(component_fqn.clone()),
+                        kind: SymbolKind::FunctionDeclaration,
+                        file: path.to_path_buf(),
+                        line: param.start_position().row + 1,
+                        column: param.start_position().column + 1,
+                        byte_range: param.byte_range(),
+                        receiver_type: None,
+                        enclosing_fqn: None,
+                        signature: None,
+                        platform_modifier: None,
+                        modifiers: None,
+                    });
+                    if let Some(property) = find_child_name(&param, src) {
+                        data_class_members
+                            .push((format!("{}.{}", class_fqn, property), vec![component_fqn]));
+                    }
+                }
+            }
+
+            // `copy` is anchored at the class name so it doesn't enclose the components
+            let mut cursor = node.walk();
+            let name_node = node
+                .children(&mut cursor)
+                .find(|c| matches!(c.kind(), "identifier" | "type_identifier" | "simple_identifier"));
+            if let Some(name_node) = name_node {
+                occurrences.push(SymbolOccurrence {
+                    name: "copy".to_string(),
+                    fqn: Some(format!("{}.copy", class_fqn)),
+                    kind: SymbolKind::FunctionDeclaration,
+                    file: path.to_path_buf(),
+                    line: name_node.start_position().row + 1,
+                    column: name_node.start_position().column + 1,
+                    byte_range: name_node.byte_range(),
+                    receiver_type: None,
+                    enclosing_fqn: None,
+                    signature: None,
+                    platform_modifier: None,
+                    modifiers: None,
+                });
+            }
+        }
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        extract_data_class_members(&child, src, path, package, scope_tree, occurrences, data_class_members);
+    }
+}
+
+/// Infer the type destructured by `val (a, b) = <initializer>`: a constructor call
+/// (`Point(1, 2)`) or a parameter of the enclosing function (`fun f(p: Point)`).
+/// Returns the simple type name as written.
+fn destructured_type_name(decl: &tree_sitter::Node, src: &[u8]) -> Option<String> {
+    let mut cursor = decl.walk();
+    let initializer = decl
+        .children(&mut cursor)
+        .skip_while(|c| c.kind() != "=")
+        .nth(1)?;
+    match initializer.kind() {
+        "call_expression" => {
+            let callee = initializer.child(0)?;
+            let name = node_text(&callee, src);
+            let is_constructor = matches!(callee.kind(), "identifier" | "simple_identifier")
+                && name.chars().next().is_some_and(|c| c.is_uppercase());
+            if is_constructor {
+                Some(name.to_string())
+            } else {
+                None
+            }
+        }
+        "identifier" | "simple_identifier" => {
+            parameter_type_name(decl, node_text(&initializer, src), src)
+        }
+        _ => None,
+    }
+}
+
+/// `val x by Preference("key")` calls `Preference.getValue` on every read of `x`, and a `var`
+/// also calls `setValue` on writes. Both are recorded as call sites at `by` when the
+/// delegate's type can be read off the source (see [`expression_type_name`]); factory calls
+/// like `lazy { ... }` return a type that isn't, and only count as calls of the factory.
+fn push_delegate_operator_calls(
+    delegate: &tree_sitter::Node,
+    src: &[u8],
+    path: &Path,
+    package: Option<&str>,
+    imports: &[ImportInfo],
+    occurrences: &mut Vec<SymbolOccurrence>,
+) {
+    let expression = match delegate.named_child(delegate.named_child_count().saturating_sub(1)) {
+        Some(expression) => expression,
+        None => return,
+    };
+    let type_name = match expression_type_name(&expression, src) {
+        Some(type_name) => type_name,
+        None => return,
+    };
+    let type_fqn = resolve_reference(&type_name, package, imports);
+    let mut is_var = false;
+    if let Some(declaration) = delegate.parent() {
+        let mut cursor = declaration.walk();
+        is_var = declaration.children(&mut cursor).any(|c| node_text(&c, src) == "var");
+    }
+    let keyword = find_child_of_kind(delegate, "by").unwrap_or(*delegate);
+
+    let operators: &[&str] = if is_var { &["getValue", "setValue"] } else { &["getValue"] };
+    for operator in operators {
+        occurrences.push(SymbolOccurrence {
+            name: operator.to_string(),
+            fqn: type_fqn.as_ref().map(|t| format!("{}.{}", t, operator)),
+            kind: SymbolKind::CallSite,
+            file: path.to_path_buf(),
+            line: keyword.start_position().row + 1,
+            column: keyword.start_position().column + 1,
+            byte_range: keyword.byte_range(),
+            receiver_type: Some(type_name.clone()),
+            enclosing_fqn: None,
+            signature: None,
+            platform_modifier: None,
+            modifiers: None,
+        });
+    }
+}
+
+/// The type an expression evaluates to, when it can be read off the source: a constructor
+/// call (`Vector(1, 2)`), or a parameter or preceding local of the enclosing function
+/// declared with a type or initialized by a constructor call.
+fn expression_type_name(expr: &tree_sitter::Node, src: &[u8]) -> Option<String> {
+    match expr.kind() {
+        "call_expression" => {
+            let callee = expr.child(0)?;
+            let name = node_text(&callee, src);
+            if !matches!(callee.kind(), "identifier" | "simple_identifier") {
+                return None;
+            }
+            if name.chars().next().is_some_and(|c| c.is_uppercase()) {
+                Some(name.to_string())
+            } else {
+                function_return_type_name(expr, name, src)
+            }
+        }
+        "identifier" | "simple_identifier" => {
+            let name = node_text(expr, src);
+            local_type_name(expr, name, src).or_else(|| parameter_type_name(expr, name, src))
+        }
+        "parenthesized_expression" => {
+            let mut cursor = expr.walk();
+            let inner = expr.named_children(&mut cursor).next()?;
+            expression_type_name(&inner, src)
+        }
+        _ => None,
+    }
+}
+
+/// The type of local `name` declared before `node` in a block enclosing it, from its
+/// declared type (`val v: Vector = ...`) or constructor initializer (`val v = Vector(...)`).
+/// The nearest declaration wins, so a shadowing local hides the outer one.
+fn local_type_name(node: &tree_sitter::Node, name: &str, src: &[u8]) -> Option<String> {
+    let mut current = node.parent();
+    while let Some(n) = current {
+        if matches!(n.kind(), "function_declaration" | "class_body" | "source_file") {
+            return None;
+        }
+        let mut cursor = n.walk();
+        let declaration = n
+            .children(&mut cursor)
+            .filter(|c| c.kind() == "property_declaration" && c.end_byte() <= node.start_byte())
+            .filter(|c| find_property_name(c, src).as_deref() == Some(name))
+            .last();
+        if let Some(declaration) = declaration {
+            let declared = find_child_of_kind(&declaration, "variable_declaration")
+                .and_then(|variable| find_first_descendant(&variable, "user_type"))
+                .map(|user_type| {
+                    let text = node_text(&user_type, src);
+                    text.split('<').next().unwrap_or(text).trim().to_string()
+                });
+            return declared.or_else(|| destructured_type_name(&declaration, src));
+        }
+        current = n.parent();
+    }
+    None
+}
+
+/// The `variable_declaration`s of a lambda's parameters, including the components of
+/// destructured ones (`{ (key, value) -> ... }`).
+fn lambda_parameters<'a>(lambda: &tree_sitter::Node<'a>) -> Vec<tree_sitter::Node<'a>> {
+    let mut params = Vec::new();
+    if let Some(list) = find_child_of_kind(lambda, "lambda_parameters") {
+        collect_variable_declarations(&list, &mut params);
+    }
+    params
+}
+
+/// The `variable_declaration`s of a for-loop's variable, or the components of a
+/// destructured one (`for ((key, value) in map)`).
+fn loop_variables<'a>(for_statement: &tree_sitter::Node<'a>) -> Vec<tree_sitter::Node<'a>> {
+    let mut variables = Vec::new();
+    collect_variable_declarations(for_statement, &mut variables);
+    variables
+}
+
+fn collect_variable_declarations<'a>(
+    node: &tree_sitter::Node<'a>,
+    declarations: &mut Vec<tree_sitter::Node<'a>>,
+) {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        match child.kind() {
+            "variable_declaration" => declarations.push(child),
+            "multi_variable_declaration" => collect_variable_declarations(&child, declarations),
+            _ => {}
+        }
+    }
+}
+
+/// FQN of `name` declared by a lambda, for-loop or local variable `node`, unique to it:
+/// `prefix` is [`LAMBDA_SCOPE_PREFIX`], [`LOOP_SCOPE_PREFIX`] or
+/// [`LOCAL_SCOPE_PREFIX`](super::scope::LOCAL_SCOPE_PREFIX).
+pub(super) fn local_fqn(
+    prefix: &str,
+    package: Option<&str>,
+    scope_tree: &ScopeTree,
+    node: &tree_sitter::Node,
     name: &str,
 ) -> String {
-    let prefix = scope_tree.fqn_prefix_at(package, byte_offset);
-    if prefix.is_empty() {
-        name.to_string()
+    let local = format!("{}.{}", positioned_scope(prefix, node), name);
+    build_fqn(package, scope_tree, node.start_byte(), &local)
+}
+
+/// Scope segment named after where `node` starts, e.g. `<for@7:9>` for `prefix` `<for@`.
+pub(super) fn positioned_scope(prefix: &str, node: &tree_sitter::Node) -> String {
+    let position = node.start_position();
+    format!("{}{}:{}>", prefix, position.row + 1, position.column + 1)
+}
+
+/// The parameter types of a function declaration, e.g. `(Int, List<String>)` for
+/// `fun f(x: Int, ys: List<String>)`. Types are kept as written, with whitespace collapsed.
+fn function_signature(node: &tree_sitter::Node, src: &[u8]) -> Option<String> {
+    let params = find_child_of_kind(node, "function_value_parameters")?;
+    let mut cursor = params.walk();
+    let types: Vec<String> = params
+        .children(&mut cursor)
+        .filter(|param| param.kind() == "parameter")
+        .map(|param| {
+            let mut param_cursor = param.walk();
+            let param_type = param
+                .children(&mut param_cursor)
+                .skip_while(|c| c.kind() != ":")
+                .nth(1);
+            param_type
+                .map(|t| node_text(&t, src).split_whitespace().collect::<Vec<_>>().join(" "))
+                .unwrap_or_default()
+        })
+        .collect();
+    Some(format!("({})", types.join(", ")))
+}
+
+/// Find the declared type of parameter `name` in the function enclosing `node`.
+fn parameter_type_name(node: &tree_sitter::Node, name: &str, src: &[u8]) -> Option<String> {
+    let mut current = node.parent();
+    while let Some(n) = current {
+        if n.kind() == "function_declaration" {
+            let params = find_first_descendant(&n, "function_value_parameters")?;
+            let mut cursor = params.walk();
+            for param in params.children(&mut cursor) {
+                if param.kind() == "parameter" && find_child_name(&param, src).as_deref() == Some(name) {
+                    let user_type = find_first_descendant(&param, "user_type")?;
+                    let text = node_text(&user_type, src);
+                    return Some(text.split('<').next().unwrap_or(text).trim().to_string());
+                }
+            }
+            return None;
+        }
+        current = n.parent();
+    }
+    None
+}
+
+/// The declared return type of function `name` called at `node`: the nearest class body or
+/// file enclosing the call that declares a non-extension `fun name(...): Type`.
+fn function_return_type_name(node: &tree_sitter::Node, name: &str, src: &[u8]) -> Option<String> {
+    let mut current = node.parent();
+    while let Some(n) = current {
+        if matches!(n.kind(), "class_body" | "source_file") {
+            let mut cursor = n.walk();
+            let declaration = n.children(&mut cursor).find(|c| {
+                c.kind() == "function_declaration" && find_child_name(c, src).as_deref() == Some(name)
+            });
+            if let Some(declaration) = declaration {
+                // The return type follows the parameters; a type before them is a receiver
+                let mut inner = declaration.walk();
+                let return_type = declaration
+                    .children(&mut inner)
+                    .skip_while(|c| c.kind() != "function_value_parameters")
+                    .find(|c| c.kind() == "user_type")?;
+                let text = node_text(&return_type, src);
+                return Some(text.split('<').next().unwrap_or(text).trim().to_string());
+            }
+        }
+        current = n.parent();
+    }
+    None
+}
+
+/// Record the annotations applied to each class, object, function and property declaration.
+/// `@Foo class Bar` yields `(pkg.Bar, [pkg.Foo])`.
+fn extract_annotations(
+    node: &tree_sitter::Node,
+    src: &[u8],
+    package: Option<&str>,
+    scope_tree: &ScopeTree,
+    imports: &[ImportInfo],
+    annotations: &mut Vec<(String, Vec<String>)>,
+) {
+    let name = match node.kind() {
+        "class_declaration" | "object_declaration" | "function_declaration" => {
+            find_child_name(node, src)
+        }
+        "property_declaration" => find_property_name(node, src),
+        _ => None,
+    };
+    if let Some(name) = name {
+        let mut annotation_fqns = Vec::new();
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            if child.kind() != "modifiers" {
+                continue;
+            }
+            let mut inner = child.walk();
+            for modifier in child.children(&mut inner) {
+                if modifier.kind() != "annotation" {
+                    continue;
+                }
+                if let Some(type_name) = annotation_type_name(&modifier, src) {
+                    if type_name.contains('.') {
+                        annotation_fqns.push(type_name);
+                    } else if let Some(fqn) = resolve_reference(&type_name, package, imports) {
+                        annotation_fqns.push(fqn);
+                    }
+                }
+            }
+        }
+        if !annotation_fqns.is_empty() {
+            let fqn = build_fqn(package, scope_tree, node.start_byte(), &name);
+            annotations.push((fqn, annotation_fqns));
+        }
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        extract_annotations(&child, src, package, scope_tree, imports, annotations);
+    }
+}
+
+/// Get the annotation class name from an `annotation` node, skipping any use-site
+/// target (`@field:Foo`) and arguments (`@Foo(bar)`).
+fn annotation_type_name(node: &tree_sitter::Node, src: &[u8]) -> Option<String> {
+    let user_type = find_first_descendant(node, "user_type")?;
+    let text = node_text(&user_type, src);
+    let type_name = text.split('<').next().unwrap_or(text).trim();
+    if type_name.is_empty() {
+        None
     } else {
-        format!("{}.{}", prefix, name)
+        Some(type_name.to_string())
+    }
+}
+
+/// Collect the type names listed after `:` in a class or object header.
+/// Handles constructor invocations (`Parent()`), plain types (`Iface`) and
+/// explicit delegations (`Iface by impl`). Type arguments are stripped.
+fn find_supertype_names(node: &tree_sitter::Node, src: &[u8]) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.kind() != "delegation_specifiers" {
+            continue;
+        }
+        let mut inner = child.walk();
+        for spec in child.children(&mut inner) {
+            if spec.kind() != "delegation_specifier" {
+                continue;
+            }
+            if let Some(user_type) = find_first_descendant(&spec, "user_type") {
+                let text = node_text(&user_type, src);
+                let type_name = text.split('<').next().unwrap_or(text).trim();
+                if !type_name.is_empty() {
+                    names.push(type_name.to_string());
+                }
+            }
+        }
+    }
+    names
+}
+
+pub(super) fn find_child_of_kind<'a>(node: &tree_sitter::Node<'a>, kind: &str) -> Option<tree_sitter::Node<'a>> {
+    let mut cursor = node.walk();
+    let child = node.children(&mut cursor).find(|c| c.kind() == kind);
+    child
+}
+
+/// Names declared by a `type_parameters` list (`<K, V : Comparable<V>>` → `K`, `V`).
+pub(super) fn type_parameter_names(type_parameters: &tree_sitter::Node, src: &[u8]) -> Vec<String> {
+    let mut cursor = type_parameters.walk();
+    type_parameters
+        .named_children(&mut cursor)
+        .filter(|child| child.kind() == "type_parameter")
+        .filter_map(|param| find_child_name(&param, src))
+        .collect()
+}
+
+/// Remove TypeReferences to type variables pushed since `start`, e.g. the `T` in `Comparable<T>`.
+pub(super) fn drop_type_parameter_references(
+    occurrences: &mut Vec<SymbolOccurrence>,
+    start: usize,
+    names: &[String],
+) {
+    let pushed = occurrences.split_off(start);
+    occurrences.extend(
+        pushed
+            .into_iter()
+            .filter(|o| !(o.kind == SymbolKind::TypeReference && names.contains(&o.name))),
+    );
+}
+
+/// The type named by a class literal (`User::class` → `User`, `Result.Success::class` →
+/// `Result.Success`), or None for other expressions and for `this::class`.
+fn class_literal_type<'a>(node: &tree_sitter::Node, src: &'a [u8]) -> Option<&'a str> {
+    let type_name = node_text(node, src).strip_suffix("::class")?.trim();
+    let is_type = type_name
+        .rsplit('.')
+        .next()
+        .and_then(|simple| simple.chars().next())
+        .is_some_and(|c| c.is_uppercase());
+    if is_type {
+        Some(type_name)
+    } else {
+        None
+    }
+}
+
+/// References in an annotation's arguments. Nested annotations are written without `@`
+/// (`@JsonSubTypes(Type(User::class), JsonSubTypes.Type(Admin::class))`), so a call to a
+/// capitalized name is an annotation reference rather than a call site, and its arguments
+/// are scanned the same way, also inside array literals (`[Type(User::class)]`). Anything
+/// else, such as `User::class` or a constant, is an ordinary expression.
+fn extract_annotation_arguments(
+    node: &tree_sitter::Node,
+    src: &[u8],
+    path: &Path,
+    package: Option<&str>,
+    scope_tree: &ScopeTree,
+    imports: &[ImportInfo],
+    occurrences: &mut Vec<SymbolOccurrence>,
+) {
+    match node.kind() {
+        "value_arguments" | "value_argument" | "collection_literal" | "call_suffix" => {
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                extract_annotation_arguments(&child, src, path, package, scope_tree, imports, occurrences);
+            }
+        }
+        "call_expression" => {
+            let callee = match node.child(0) {
+                Some(callee) => callee,
+                None => return,
+            };
+            let type_name = node_text(&callee, src);
+            let is_annotation = matches!(
+                callee.kind(),
+                "simple_identifier" | "identifier" | "navigation_expression"
+            ) && type_name
+                .rsplit('.')
+                .next()
+                .and_then(|simple| simple.trim().chars().next())
+                .is_some_and(|c| c.is_uppercase());
+            if !is_annotation {
+                extract_references(node, src, path, package, scope_tree, imports, occurrences);
+                return;
+            }
+            let (name, fqn) = resolve_qualified_type(type_name, package, imports);
+            occurrences.push(SymbolOccurrence {
+                name,
+                fqn,
+                kind: SymbolKind::AnnotationReference,
+                file: path.to_path_buf(),
+                line: callee.start_position().row + 1,
+                column: callee.start_position().column + 1,
+                byte_range: callee.byte_range(),
+                receiver_type: None,
+                enclosing_fqn: None,
+                signature: None,
+                platform_modifier: None,
+                modifiers: None,
+            });
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                if child.id() != callee.id() {
+                    extract_annotation_arguments(&child, src, path, package, scope_tree, imports, occurrences);
+                }
+            }
+        }
+        _ => extract_references(node, src, path, package, scope_tree, imports, occurrences),
+    }
+}
+
+fn find_first_descendant<'a>(
+    node: &tree_sitter::Node<'a>,
+    kind: &str,
+) -> Option<tree_sitter::Node<'a>> {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.kind() == kind {
+            return Some(child);
+        }
+        if let Some(found) = find_first_descendant(&child, kind) {
+            return Some(found);
+        }
+    }
+    None
+}
+
+pub(super) fn resolve_reference(name: &str, package: Option<&str>, imports: &[ImportInfo]) -> Option<String> {
+    // Check explicit imports first
+    for imp in imports {
+        if imp.is_wildcard {
+            continue;
+        }
+        let imported_name = if let Some(ref alias) = imp.alias {
+            alias.as_str()
+        } else {
+            imp.path.rsplit('.').next().unwrap_or(&imp.path)
+        };
+        if imported_name == name {
+            if imp.alias.is_some() {
+                // Aliased import: the FQN is the original path
+                return Some(imp.path.clone());
+            }
+            return Some(imp.path.clone());
+        }
+    }
+
+    // Check wildcard imports - we can't fully resolve without the full index,
+    // so we return None and let cross-referencing handle it
+    // But we note the package for same-package resolution
+    if let Some(pkg) = package {
+        return Some(format!("{}.{}", pkg, name));
+    }
+
+    None
+}
+
+/// Resolve a possibly qualified type name to (simple name, FQN).
+/// `Result.Success` resolves its outer class through the imports (`com.example.core.Result.Success`);
+/// a lowercase first segment means the name is already package-qualified.
+pub(super) fn resolve_qualified_type(
+    type_name: &str,
+    package: Option<&str>,
+    imports: &[ImportInfo],
+) -> (String, Option<String>) {
+    let simple = type_name.rsplit('.').next().unwrap_or(type_name).to_string();
+    match type_name.split_once('.') {
+        Some((outer, nested)) if outer.chars().next().is_some_and(|c| c.is_uppercase()) => {
+            let fqn = resolve_reference(outer, package, imports).map(|o| format!("{}.{}", o, nested));
+            (simple, fqn)
+        }
+        Some(_) => (simple, Some(type_name.to_string())),
+        None => (simple, resolve_reference(type_name, package, imports)),
+    }
+}
+
+/// Process the receiver (child 0) of a navigation_expression, capturing it as a reference.
+/// For a leaf identifier receiver (e.g., `Config` in `Config.foo`), emit it directly.
+/// For a complex receiver (e.g., another navigation in `a.b.c`), recurse into it.
+fn extract_nav_receiver(
+    nav_node: &tree_sitter::Node,
+    src: &[u8],
+    path: &Path,
+    package: Option<&str>,
+    scope_tree: &ScopeTree,
+    imports: &[ImportInfo],
+    occurrences: &mut Vec<SymbolOccurrence>,
+) {
+    if let Some(receiver) = nav_node.child(0) {
+        if receiver.kind() == "simple_identifier" || receiver.kind() == "identifier" {
+            // Leaf receiver — capture directly as a reference
+            let name = node_text(&receiver, src).to_string();
+            if !name.is_empty() {
+                let fqn = resolve_reference(&name, package, imports);
+                occurrences.push(SymbolOccurrence {
+                    name,
+                    fqn,
+                    kind: SymbolKind::PropertyReference,
+                    file: path.to_path_buf(),
+                    line: receiver.start_position().row + 1,
+                    column: receiver.start_position().column + 1,
+                    byte_range: receiver.byte_range(),
+                    receiver_type: None,
+                    enclosing_fqn: None,
+                    signature: None,
+                    platform_modifier: None,
+                    modifiers: None,
+                });
+            }
+        } else {
+            // Complex receiver (e.g., nested navigation_expression, call_expression) — recurse
+            extract_references(&receiver, src, path, package, scope_tree, imports, occurrences);
+        }
+    }
+}
+
+/// The operator function a binary operator token desugars to, and whether the right
+/// operand is the receiver (`x in xs` is `xs.contains(x)`).
+fn binary_operator_function(token: &str) -> Option<(&'static str, bool)> {
+    match token {
+        "+" => Some(("plus", false)),
+        "-" => Some(("minus", false)),
+        "*" => Some(("times", false)),
+        "/" => Some(("div", false)),
+        "%" => Some(("rem", false)),
+        "<" | ">" | "<=" | ">=" => Some(("compareTo", false)),
+        "in" | "!in" => Some(("contains", true)),
+        _ => None,
+    }
+}
+
+/// Emit a CallSite named after the operator function an operator expression invokes,
+/// positioned at the operator token (`+`, `in`, or the `[` of an index) with the operand
+/// it is called on as the receiver. An index that is assigned to (`a[i] = v`) calls `set`.
+fn push_operator_call(
+    node: &tree_sitter::Node,
+    src: &[u8],
+    path: &Path,
+    package: Option<&str>,
+    imports: &[ImportInfo],
+    occurrences: &mut Vec<SymbolOccurrence>,
+) {
+    let operands: Vec<tree_sitter::Node> = {
+        let mut cursor = node.walk();
+        node.named_children(&mut cursor).collect()
+    };
+    let first_operand = match operands.first() {
+        Some(operand) => *operand,
+        None => return,
+    };
+
+    let call = if matches!(node.kind(), "indexing_expression" | "index_expression") {
+        // The `[` is a direct child, or inside an `indexing_suffix`
+        let bracket = find_child_of_kind(node, "[").or_else(|| {
+            find_child_of_kind(node, "indexing_suffix")
+                .and_then(|suffix| find_child_of_kind(&suffix, "["))
+        });
+        let is_assigned = node.parent().is_some_and(|parent| {
+            parent.kind() == "assignment"
+                && parent.child(0).is_some_and(|target| target.id() == node.id())
+                && parent.child(1).is_some_and(|op| op.kind() == "=")
+        });
+        let name = if is_assigned { "set" } else { "get" };
+        bracket.map(|bracket| (name, bracket, first_operand))
+    } else {
+        let mut cursor = node.walk();
+        let children: Vec<tree_sitter::Node> = node.children(&mut cursor).collect();
+        children.into_iter().filter(|c| !c.is_named()).find_map(|token| {
+            let (name, right_is_receiver) = binary_operator_function(token.kind())?;
+            let receiver = if right_is_receiver {
+                *operands.last()?
+            } else {
+                first_operand
+            };
+            Some((name, token, receiver))
+        })
+    };
+
+    if let Some((name, position, receiver)) = call {
+        // Resolved as a member of the receiver's type when that is known, like infix calls
+        let fqn = expression_type_name(&receiver, src)
+            .and_then(|type_name| resolve_reference(&type_name, package, imports))
+            .map(|type_fqn| format!("{}.{}", type_fqn, name))
+            .or_else(|| resolve_reference(name, package, imports));
+        occurrences.push(SymbolOccurrence {
+            name: name.to_string(),
+            fqn,
+            kind: SymbolKind::CallSite,
+            file: path.to_path_buf(),
+            line: position.start_position().row + 1,
+            column: position.start_position().column + 1,
+            byte_range: position.byte_range(),
+            receiver_type: Some(node_text(&receiver, src).to_string()),
+            enclosing_fqn: None,
+            signature: None,
+            platform_modifier: None,
+            modifiers: None,
+        });
+    }
+}
+
+/// The node whose position a navigation member reports: the identifier itself, or the
+/// identifier inside a `navigation_suffix` (skipping the `.`/`?.`/`::` token).
+fn member_position_node<'a>(member: &tree_sitter::Node<'a>) -> tree_sitter::Node<'a> {
+    if member.kind() == "navigation_suffix" {
+        let mut cursor = member.walk();
+        let identifier = member
+            .children(&mut cursor)
+            .find(|c| matches!(c.kind(), "identifier" | "simple_identifier"));
+        if let Some(identifier) = identifier {
+            return identifier;
+        }
+    }
+    *member
+}
+
+/// The label of a qualified `this@Outer` or `super@Outer` expression.
+fn expression_label(text: &str) -> Option<&str> {
+    text.rsplit_once('@')
+        .map(|(_, label)| label.trim())
+        .filter(|label| !label.is_empty())
+}
+
+/// FQN of the class a `this` or `super` expression at `byte_offset` refers to: the enclosing
+/// class named by `label`, or the innermost enclosing class. None outside any class, or when
+/// the label names a function or lambda rather than a class.
+fn enclosing_class_fqn(
+    package: Option<&str>,
+    scope_tree: &ScopeTree,
+    byte_offset: usize,
+    label: Option<&str>,
+) -> Option<String> {
+    let chain = scope_tree.scope_chain_at(byte_offset);
+    let end = match label {
+        Some(label) => chain.iter().rposition(|scope| *scope == label)? + 1,
+        None => chain.len(),
+    };
+    if end == 0 {
+        return None;
+    }
+    let parts: Vec<&str> = package.into_iter().chain(chain[..end].iter().copied()).collect();
+    Some(parts.join("."))
+}
+
+/// FQN of `member` accessed through the receiver of `nav_node` when that is a qualified
+/// `this@Outer` (a member of `Outer`) or `super`. A `super` member is recorded under the
+/// class making the call; [`cross_reference`](super::symbols::cross_reference) moves it to
+/// the supertype declaring it once supertypes are resolved.
+fn this_or_super_member_fqn(
+    nav_node: &tree_sitter::Node,
+    member: &str,
+    src: &[u8],
+    package: Option<&str>,
+    scope_tree: &ScopeTree,
+) -> Option<String> {
+    if nav_node.child_count() < 2 {
+        return None;
+    }
+    let receiver = nav_node.child(0)?;
+    let label = expression_label(node_text(&receiver, src));
+    match receiver.kind() {
+        "this_expression" if label.is_some() => {}
+        "super_expression" => {}
+        _ => return None,
+    }
+    let class_fqn = enclosing_class_fqn(package, scope_tree, receiver.start_byte(), label)?;
+    Some(format!("{}.{}", class_fqn, member))
+}
+
+fn extract_receiver_from_nav(nav_node: &tree_sitter::Node, src: &[u8]) -> Option<String> {
+    if nav_node.child_count() >= 2 {
+        if let Some(receiver) = nav_node.child(0) {
+            let text = node_text(&receiver, src).to_string();
+            if !text.is_empty() {
+                return Some(text);
+            }
+        }
+    }
+    None
+}
+
+pub(super) fn build_fqn(
+    package: Option<&str>,
+    scope_tree: &ScopeTree,
+    byte_offset: usize,
+    name: &str,
+) -> String {
+    let prefix = scope_tree.fqn_prefix_at(package, byte_offset);
+    if prefix.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", prefix, name)
+    }
+}
+
+fn find_body_range(node: &tree_sitter::Node) -> Option<std::ops::Range<usize>> {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.kind() == "class_body"
+            || child.kind() == "enum_class_body"
+            || child.kind() == "object_body"
+        {
+            return Some(child.byte_range());
+        }
+    }
+    None
+}
+
+pub(super) fn find_child_name(node: &tree_sitter::Node, src: &[u8]) -> Option<String> {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.kind() == "identifier"
+            || child.kind() == "type_identifier"
+            || child.kind() == "simple_identifier"
+        {
+            return Some(node_text(&child, src).to_string());
+        }
+    }
+    None
+}
+
+fn find_property_name(node: &tree_sitter::Node, src: &[u8]) -> Option<String> {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.kind() == "variable_declaration" {
+            let mut inner = child.walk();
+            for c in child.children(&mut inner) {
+                if c.kind() == "identifier" || c.kind() == "simple_identifier" {
+                    return Some(node_text(&c, src).to_string());
+                }
+            }
+        }
+        // Also check for direct identifier (e.g., top-level val)
+        if child.kind() == "identifier" || child.kind() == "simple_identifier" {
+            return Some(node_text(&child, src).to_string());
+        }
+    }
+    None
+}
+
+fn find_receiver_type(func_node: &tree_sitter::Node, src: &[u8]) -> Option<String> {
+    // Extension functions have a receiver type before the function name
+    // In the AST: function_declaration -> user_type (receiver) -> simple_identifier (name)
+    let mut cursor = func_node.walk();
+    for child in func_node.children(&mut cursor) {
+        if child.kind() == "user_type" {
+            // This is the receiver type (appears before the function name)
+            return Some(node_text(&child, src).to_string());
+        }
+        if child.kind() == "identifier" || child.kind() == "simple_identifier" {
+            // No receiver type before the name
+            return None;
+        }
+    }
+    None
+}
+
+fn find_property_receiver_type(prop_node: &tree_sitter::Node, src: &[u8]) -> Option<String> {
+    // Extension properties have a receiver type before the property name:
+    // property_declaration -> user_type (receiver) -> "." -> variable_declaration (name)
+    let mut cursor = prop_node.walk();
+    for child in prop_node.children(&mut cursor) {
+        match child.kind() {
+            "user_type" | "nullable_type" => return Some(node_text(&child, src).to_string()),
+            "variable_declaration"
+            | "multi_variable_declaration"
+            | "identifier"
+            | "simple_identifier" => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The type a `typealias` stands for, as written: `User` for `= User?`, `Map` for
+/// `= Map<K, User>`, `Result.Success` for a nested class. Cross-referencing resolves it to
+/// a declaration FQN. Function types have no declaration in the project and are recorded
+/// as the `kotlin.FunctionN` interface they compile to (`(User, T) -> Unit` → `kotlin.Function2`).
+fn find_type_alias_target(node: &tree_sitter::Node, src: &[u8]) -> Option<String> {
+    let mut cursor = node.walk();
+    let mut found_eq = false;
+    for child in node.children(&mut cursor) {
+        if child.kind() == "=" {
+            found_eq = true;
+            continue;
+        }
+        if found_eq {
+            if let Some(target) = alias_target_of_type(&child, src) {
+                return Some(target);
+            }
+        }
+    }
+    None
+}
+
+fn alias_target_of_type(type_node: &tree_sitter::Node, src: &[u8]) -> Option<String> {
+    match type_node.kind() {
+        "user_type" | "type_identifier" | "identifier" => {
+            let text = node_text(type_node, src);
+            // Drop type arguments, including those of an outer segment (`Outer<A>.Inner`)
+            let mut name = String::new();
+            let mut depth = 0usize;
+            for c in text.chars() {
+                match c {
+                    '<' => depth += 1,
+                    '>' => depth = depth.saturating_sub(1),
+                    c if depth == 0 && !c.is_whitespace() => name.push(c),
+                    _ => {}
+                }
+            }
+            Some(name).filter(|n| !n.is_empty())
+        }
+        "nullable_type" | "parenthesized_type" => {
+            let mut cursor = type_node.walk();
+            let inner = type_node
+                .named_children(&mut cursor)
+                .find_map(|child| alias_target_of_type(&child, src));
+            inner
+        }
+        "function_type" => {
+            let mut arity = 0;
+            let mut cursor = type_node.walk();
+            for child in type_node.named_children(&mut cursor) {
+                match child.kind() {
+                    "function_type_parameters" => {
+                        let mut params = child.walk();
+                        arity += child.named_children(&mut params).count();
+                        break;
+                    }
+                    "type_modifiers" => {}
+                    // `User.() -> Unit`: the receiver is the first parameter
+                    _ => arity += 1,
+                }
+            }
+            Some(format!("kotlin.Function{}", arity))
+        }
+        _ => None,
+    }
+}
+
+fn has_keyword_child(node: &tree_sitter::Node, keyword: &str) -> bool {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.kind() == keyword {
+            return true;
+        }
+    }
+    false
+}
+
+/// Check if a declaration's `modifiers` child contains the given modifier (e.g., "sealed").
+fn has_modifier(node: &tree_sitter::Node, src: &[u8], modifier: &str) -> bool {
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.kind() == "modifiers" {
+            let mut inner = child.walk();
+            for m in child.children(&mut inner) {
+                if node_text(&m, src) == modifier {
+                    return true;
+                }
+            }
+        }
+    }
+    false
+}
+
+/// The modifiers of a Kotlin declaration recorded on its occurrence.
+fn declaration_modifiers(node: &tree_sitter::Node, src: &[u8]) -> DeclarationModifiers {
+    let visibility = [
+        ("private", Visibility::Private),
+        ("internal", Visibility::Internal),
+        ("protected", Visibility::Protected),
+        ("public", Visibility::Public),
+    ]
+    .into_iter()
+    .find(|(modifier, _)| has_modifier(node, src, modifier))
+    .map(|(_, visibility)| visibility);
+    DeclarationModifiers {
+        is_var: has_keyword_child(node, "var"),
+        visibility,
+        is_override: has_modifier(node, src, "override"),
+    }
+}
+
+/// The `expect`/`actual` modifier of a Kotlin Multiplatform declaration, if any.
+fn platform_modifier(node: &tree_sitter::Node, src: &[u8]) -> Option<PlatformModifier> {
+    if has_modifier(node, src, "expect") {
+        Some(PlatformModifier::Expect)
+    } else if has_modifier(node, src, "actual") {
+        Some(PlatformModifier::Actual)
+    } else {
+        None
+    }
+}
+
+pub(super) fn node_text<'a>(node: &tree_sitter::Node, src: &'a [u8]) -> &'a str {
+    node.utf8_text(src).unwrap_or("")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interface_parsing() {
+        let source = "package com.example\n\ninterface Repository<T> {\n    fun findById(id: String): T?\n}\n";
+        let file_path = std::path::PathBuf::from("Test.kt");
+        let occurrences = parse_file(&file_path, source).occurrences;
+        let repo = occurrences
+            .iter()
+            .find(|o| o.name == "Repository")
+            .expect("Expected Repository in occurrences");
+        assert!(
+            matches!(repo.kind, super::SymbolKind::InterfaceDeclaration),
+            "Expected InterfaceDeclaration, got {:?}",
+            repo.kind
+        );
+        assert_eq!(repo.fqn.as_deref(), Some("com.example.Repository"));
+    }
+
+    #[test]
+    fn test_annotation_class_parsing() {
+        let source = "package com.example\n\n@Target(AnnotationTarget.CLASS)\nannotation class Marker(val value: String)\n";
+        let file_path = std::path::PathBuf::from("Test.kt");
+        let occurrences = parse_file(&file_path, source).occurrences;
+        let marker = occurrences
+            .iter()
+            .find(|o| o.name == "Marker" && o.kind.is_declaration())
+            .expect("Expected Marker in occurrences");
+        assert_eq!(marker.kind, super::SymbolKind::AnnotationClassDeclaration);
+        assert!(marker.kind.is_type_declaration());
+        assert_eq!(marker.fqn.as_deref(), Some("com.example.Marker"));
+    }
+
+    #[test]
+    fn test_sealed_class_parsing() {
+        let source = "package com.example\n\nimport com.other.Base\n\nsealed class Result : Base() {\n    class Ok : Result()\n    object Pending : Result()\n}\n";
+        let file_path = std::path::PathBuf::from("Test.kt");
+        let ParsedFile { occurrences, supertypes, .. } = parse_file(&file_path, source);
+        let result = occurrences
+            .iter()
+            .find(|o| o.name == "Result" && o.kind.is_declaration())
+            .expect("Expected Result in occurrences");
+        assert!(
+            matches!(result.kind, super::SymbolKind::SealedClassDeclaration),
+            "Expected SealedClassDeclaration, got {:?}",
+            result.kind
+        );
+
+        let parents_of = |fqn: &str| {
+            supertypes
+                .iter()
+                .find(|(child, _)| child == fqn)
+                .map(|(_, parents)| parents.clone())
+                .unwrap_or_default()
+        };
+        assert_eq!(parents_of("com.example.Result"), vec!["com.other.Base".to_string()]);
+        assert_eq!(parents_of("com.example.Result.Ok"), vec!["com.example.Result".to_string()]);
+        assert_eq!(parents_of("com.example.Result.Pending"), vec!["com.example.Result".to_string()]);
+    }
+
+    #[test]
+    fn test_sealed_interface_parsing() {
+        let source = "package com.example\n\nsealed interface Event {\n    data class Click(val x: Int) : Event\n    object Close : Event\n}\n\nclass Scroll : Event\n\ninterface Listener\n";
+        let file_path = std::path::PathBuf::from("Test.kt");
+        let ParsedFile { occurrences, supertypes, .. } = parse_file(&file_path, source);
+        let kind_of = |name: &str| {
+            occurrences
+                .iter()
+                .find(|o| o.name == name && o.kind.is_declaration())
+                .map(|o| o.kind.clone())
+                .unwrap_or_else(|| panic!("Expected {} in occurrences", name))
+        };
+        assert_eq!(kind_of("Event"), super::SymbolKind::SealedInterfaceDeclaration);
+        assert!(kind_of("Event").is_type_declaration());
+        assert_eq!(kind_of("Listener"), super::SymbolKind::InterfaceDeclaration);
+
+        let parents_of = |fqn: &str| {
+            supertypes
+                .iter()
+                .find(|(child, _)| child == fqn)
+                .map(|(_, parents)| parents.clone())
+                .unwrap_or_default()
+        };
+        for implementer in ["com.example.Event.Click", "com.example.Event.Close", "com.example.Scroll"] {
+            assert_eq!(parents_of(implementer), vec!["com.example.Event".to_string()], "{}", implementer);
+        }
+    }
+
+    #[test]
+    fn test_secondary_constructors_and_init_blocks() {
+        let source = r#"
+package com.example
+
+class Point(val x: Int, val y: Int) {
+    init {
+        require(x >= 0)
+    }
+
+    constructor(x: Int) : this(x, 0)
+
+    constructor() : this(0, 0) {
+        val origin = true
+    }
+}
+"#;
+        let file_path = std::path::PathBuf::from("Point.kt");
+        let occurrences = parse_file(&file_path, source).occurrences;
+
+        let ctors: Vec<_> = occurrences
+            .iter()
+            .filter(|o| matches!(o.kind, super::SymbolKind::ConstructorDeclaration))
+            .collect();
+        assert_eq!(ctors.len(), 2, "Expected two secondary constructors, got {:?}", ctors);
+        assert!(ctors.iter().all(|c| c.name == "Point"));
+        assert!(ctors.iter().all(|c| c.fqn.as_deref() == Some("com.example.Point.Point")));
+        assert_eq!(ctors[0].line, 9);
+        assert_eq!(ctors[1].line, 11);
+
+        let init = occurrences
+            .iter()
+            .find(|o| matches!(o.kind, super::SymbolKind::InitializerDeclaration))
+            .expect("Expected an init block occurrence");
+        assert_eq!(init.fqn.as_deref(), Some("com.example.Point.<init>"));
+        assert_eq!(init.line, 5);
+
+        // Constructor bodies are traversed
+        let origin = occurrences
+            .iter()
+            .find(|o| o.name == "origin" && o.kind.is_declaration())
+            .expect("Expected origin local declaration");
+        assert_eq!(origin.line, 12);
+    }
+
+    #[test]
+    fn test_discover_files() {
+        // Just test the function doesn't panic with a temp dir
+        let dir = tempfile::tempdir().unwrap();
+        let files = discover_kotlin_files(dir.path());
+        assert!(files.is_empty());
+    }
+
+    #[test]
+    fn test_is_indexable_source() {
+        let root = Path::new("/project");
+        let filter = FileFilter::new(&[], &["**/generated/**".to_string()]).unwrap();
+        let indexable = |rel: &str| is_indexable_source(root, &root.join(rel), &filter);
+
+        assert!(indexable("app/src/main/kotlin/App.kt"));
+        assert!(indexable("build.gradle.kts"));
+        assert!(indexable("core/src/main/java/Helper.java"));
+        assert!(!indexable("app/src/main/kotlin/App.txt"));
+        assert!(!indexable("app/build/tmp/Generated.kt"));
+        assert!(!indexable(".git/hooks/Hook.kt"));
+        assert!(!indexable("app/generated/Api.kt"));
+        assert!(!is_indexable_source(root, Path::new("/elsewhere/App.kt"), &filter));
+    }
+
+    #[test]
+    fn test_reindex_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let greeter = dir.path().join("Greeter.kt");
+        let app = dir.path().join("App.kt");
+        std::fs::write(&greeter, "package demo\n\nclass Greeter\n").unwrap();
+        std::fs::write(&app, "package demo\n\nfun main() {\n    Greeter()\n}\n").unwrap();
+        let mut index = index_files(dir.path());
+        let calls = |index: &SymbolIndex| {
+            index
+                .by_fqn
+                .get("demo.Greeter")
+                .map_or(0, |occs| occs.iter().filter(|o| o.kind == SymbolKind::CallSite).count())
+        };
+        assert_eq!(calls(&index), 1);
+
+        // Modified: the old occurrences are replaced, not duplicated
+        std::fs::write(&app, "package demo\n\nfun main() {\n    Greeter()\n    Greeter()\n}\n").unwrap();
+        reindex_file(&mut index, dir.path(), &app, &FileFilter::default());
+        assert_eq!(calls(&index), 2);
+
+        // Deleted: nothing of the file is left in any map
+        std::fs::remove_file(&greeter).unwrap();
+        reindex_file(&mut index, dir.path(), &greeter, &FileFilter::default());
+        assert!(!index.files.contains_key(&greeter));
+        assert!(index.by_name.values().flatten().all(|o| o.file != greeter));
+        assert!(index.by_fqn.values().flatten().all(|o| o.file != greeter));
+        assert!(index.by_name.contains_key("main"));
+    }
+
+    #[test]
+    fn test_index_files_skips_oversized_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let small = dir.path().join("Small.kt");
+        let generated = dir.path().join("Generated.kt");
+        std::fs::write(&small, "package demo\n\nclass Small\n").unwrap();
+        let big_source = format!("package demo\n\nclass Generated\n{}", "// padding\n".repeat(200));
+        std::fs::write(&generated, big_source).unwrap();
+        let filter = FileFilter::default().with_max_file_size(Some(1024));
+
+        let mut index = index_files_with_filter(dir.path(), &filter);
+        assert!(index.by_fqn.contains_key("demo.Small"));
+        assert!(!index.by_fqn.contains_key("demo.Generated"));
+        assert_eq!(index.skipped_files, vec![generated.clone()]);
+        assert!(index.stats().to_string().ends_with("(1 files skipped as too large)"));
+
+        // A file that shrinks below the cap is picked up again
+        std::fs::write(&generated, "package demo\n\nclass Generated\n").unwrap();
+        reindex_file(&mut index, dir.path(), &generated, &filter);
+        assert!(index.by_fqn.contains_key("demo.Generated"));
+        assert!(index.skipped_files.is_empty());
+    }
+
+    #[test]
+    fn test_index_files_reports_parse_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let clean = dir.path().join("Clean.kt");
+        let broken = dir.path().join("Broken.kt");
+        let broken_java = dir.path().join("Broken.java");
+        std::fs::write(&clean, "package demo\n\nclass Clean\n").unwrap();
+        std::fs::write(
+            &broken,
+            "package demo\n\nclass BeforeBroken\n\nfun broken(: Int {\n}\n",
+        )
+        .unwrap();
+        std::fs::write(&broken_java, "package demo;\n\nclass BrokenJava {\n    void run( {\n}\n").unwrap();
+
+        let mut index = index_files(dir.path());
+        assert!(index.parse_errors[&broken] > 0, "{:?}", index.parse_errors);
+        assert!(index.parse_errors[&broken_java] > 0, "{:?}", index.parse_errors);
+        assert!(!index.parse_errors.contains_key(&clean));
+        // The rest of the broken file is still indexed
+        assert!(index.by_fqn.contains_key("demo.BeforeBroken"));
+        assert!(index.stats().to_string().ends_with("(2 files with parse errors)"));
+        let stats = index.detailed_stats(dir.path());
+        assert!(stats.parse_errors["Broken.kt"] > 0);
+
+        // Fixing the file clears its count
+        std::fs::write(&broken, "package demo\n\nclass Broken\n").unwrap();
+        reindex_file(&mut index, dir.path(), &broken, &FileFilter::default());
+        assert!(!index.parse_errors.contains_key(&broken));
+    }
+
+    #[test]
+    fn test_parse_simple_file() {
+        let source = r#"
+package com.example
+
+import java.util.List
+
+class MyClass {
+    fun myMethod(): String {
+        return "hello"
+    }
+
+    val myProperty: Int = 42
+}
+
+fun topLevelFunction() {}
+"#;
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("Test.kt");
+        std::fs::write(&file_path, source).unwrap();
+
+        let ParsedFile { file_info, occurrences, .. } = parse_file(&file_path, source);
+        assert_eq!(file_info.package, Some("com.example".to_string()));
+        assert_eq!(file_info.imports.len(), 1);
+        assert_eq!(file_info.imports[0].path, "java.util.List");
+
+        // Check that we have class, method, property, and top-level function declarations
+        let decl_names: Vec<&str> = occurrences
+            .iter()
+            .filter(|o| o.kind.is_declaration())
+            .map(|o| o.name.as_str())
+            .collect();
+        assert!(decl_names.contains(&"MyClass"), "Expected MyClass declaration, got: {:?}", decl_names);
+        assert!(decl_names.contains(&"myMethod"), "Expected myMethod declaration, got: {:?}", decl_names);
+        assert!(decl_names.contains(&"myProperty"), "Expected myProperty declaration, got: {:?}", decl_names);
+        assert!(decl_names.contains(&"topLevelFunction"), "Expected topLevelFunction, got: {:?}", decl_names);
+    }
+
+    #[test]
+    fn test_parse_imports() {
+        let source = r#"
+package com.example
+
+import com.other.Foo
+import com.other.Bar as Baz
+import com.util.*
+"#;
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("Test.kt");
+
+        let file_info = parse_file(&file_path, source).file_info;
+        assert_eq!(file_info.imports.len(), 3);
+
+        let foo = &file_info.imports[0];
+        assert_eq!(foo.path, "com.other.Foo");
+        assert!(!foo.is_wildcard);
+        assert!(foo.alias.is_none());
+
+        let baz = &file_info.imports[1];
+        assert_eq!(baz.path, "com.other.Bar");
+        assert_eq!(baz.alias, Some("Baz".to_string()));
+
+        let wildcard = &file_info.imports[2];
+        assert!(wildcard.is_wildcard);
+    }
+
+    #[test]
+    fn test_nested_generic_type_arguments() {
+        let source = r#"
+package com.example
+
+import com.other.User
+
+class Registry {
+    val users: Map<String, List<Set<User>>> = emptyMap()
+}
+"#;
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("Registry.kt");
+        std::fs::write(&file_path, source).unwrap();
+
+        let occurrences = parse_file(&file_path, source).occurrences;
+        let type_refs: Vec<(&str, usize, usize)> = occurrences
+            .iter()
+            .filter(|o| o.kind == SymbolKind::TypeReference)
+            .map(|o| (o.name.as_str(), o.line, o.column))
+            .collect();
+
+        for expected in [("Map", 7, 16), ("String", 7, 20), ("List", 7, 28), ("Set", 7, 33), ("User", 7, 37)] {
+            assert!(type_refs.contains(&expected), "Expected {:?}, got: {:?}", expected, type_refs);
+        }
+        let user = occurrences
+            .iter()
+            .find(|o| o.name == "User" && o.kind == SymbolKind::TypeReference)
+            .unwrap();
+        assert_eq!(user.fqn.as_deref(), Some("com.other.User"));
+    }
+
+    #[test]
+    fn test_data_class_destructuring() {
+        let source = r#"
+package com.example
+
+data class Point(val x: Int, val y: Int, val z: Int)
+
+fun sum(p: Point): Int {
+    val (x, _, z) = p
+    return x + z
+}
+
+fun origin(): Int {
+    val (a, b) = Point(1, 2, 3)
+    return a + b
+}
+"#;
+        let file_path = std::path::PathBuf::from("Point.kt");
+        let ParsedFile { occurrences, data_class_members, .. } = parse_file(&file_path, source);
+
+        let component2 = occurrences
+            .iter()
+            .find(|o| o.name == "component2" && o.kind.is_declaration())
+            .expect("Expected synthesized component2");
+        assert_eq!(component2.fqn.as_deref(), Some("com.example.Point.component2"));
+        assert_eq!(component2.line, 4);
+        assert!(occurrences
+            .iter()
+            .any(|o| o.fqn.as_deref() == Some("com.example.Point.copy") && o.kind.is_declaration()));
+        assert!(data_class_members.contains(&(
+            "com.example.Point.y".to_string(),
+            vec!["com.example.Point.component2".to_string()]
+        )));
+
+        let calls: Vec<(&str, Option<&str>, usize)> = occurrences
+            .iter()
+            .filter(|o| o.kind == SymbolKind::CallSite && o.name.starts_with("component"))
+            .map(|o| (o.name.as_str(), o.fqn.as_deref(), o.line))
+            .collect();
+        assert_eq!(
+            calls.len(),
+            4,
+            "Expected component calls for x, z, a and b, got: {:?}",
+            calls
+        );
+        // Type inferred from the enclosing function's parameter; `_` is skipped
+        assert!(calls.contains(&("component1", Some("com.example.Point.component1"), 7)));
+        assert!(calls.contains(&("component3", Some("com.example.Point.component3"), 7)));
+        // Type inferred from the constructor call
+        assert!(calls.contains(&("component2", Some("com.example.Point.component2"), 12)));
+
+        // Each destructured variable is declared; the initializer is not
+        let declared: Vec<(&str, usize, usize)> = occurrences
+            .iter()
+            .filter(|o| o.kind == SymbolKind::PropertyDeclaration && o.line > 4)
+            .map(|o| (o.name.as_str(), o.line, o.column))
+            .collect();
+        assert_eq!(declared, vec![("x", 7, 10), ("z", 7, 16), ("a", 12, 10), ("b", 12, 13)]);
+    }
+
+    #[test]
+    fn test_object_expression() {
+        let source = r#"
+package com.example
+
+import com.other.Listener
+
+class Button {
+    fun onClick() {}
+
+    val listener = object : Listener {
+        override fun onClick() {
+            println("clicked")
+        }
+    }
+}
+"#;
+        let file_path = std::path::PathBuf::from("Button.kt");
+        let occurrences = parse_file(&file_path, source).occurrences;
+
+        let listener = occurrences
+            .iter()
+            .find(|o| o.name == "Listener" && o.kind == SymbolKind::TypeReference)
+            .expect("Expected a type reference to the implemented interface");
+        assert_eq!(listener.fqn.as_deref(), Some("com.other.Listener"));
+        assert_eq!(listener.line, 9);
+
+        let mut on_click_fqns: Vec<&str> = occurrences
+            .iter()
+            .filter(|o| o.name == "onClick" && o.kind.is_declaration())
+            .filter_map(|o| o.fqn.as_deref())
+            .collect();
+        on_click_fqns.sort();
+        assert_eq!(on_click_fqns, vec!["com.example.Button.<anon@9:20>.onClick", "com.example.Button.onClick"]);
+    }
+
+    #[test]
+    fn test_object_expressions_in_one_class_have_distinct_scopes() {
+        let source = r#"
+package demo
+
+class Screen {
+    val first = object : Runnable {
+        override fun run() {}
+    }
+    val second = object : Runnable {
+        override fun run() {}
+    }
+}
+"#;
+        let file_path = std::path::PathBuf::from("Screen.kt");
+        let occurrences = parse_file(&file_path, source).occurrences;
+
+        let run_fqns: Vec<&str> = occurrences
+            .iter()
+            .filter(|o| o.name == "run" && o.kind.is_declaration())
+            .filter_map(|o| o.fqn.as_deref())
+            .collect();
+        assert_eq!(run_fqns, vec!["demo.Screen.<anon@5:17>.run", "demo.Screen.<anon@8:18>.run"]);
+    }
+
+    #[test]
+    fn test_enum_entry_bodies() {
+        let source = r##"
+package com.example
+
+enum class Color {
+    RED {
+        override fun hex() = "#f00"
+    },
+    GREEN;
+
+    open fun hex() = "#000"
+}
+"##;
+        let file_path = std::path::PathBuf::from("Color.kt");
+        let ParsedFile { occurrences, supertypes, .. } = parse_file(&file_path, source);
+
+        let mut hex_fqns: Vec<&str> = occurrences
+            .iter()
+            .filter(|o| o.name == "hex" && o.kind == SymbolKind::FunctionDeclaration)
+            .filter_map(|o| o.fqn.as_deref())
+            .collect();
+        hex_fqns.sort();
+        assert_eq!(hex_fqns, vec!["com.example.Color.RED.hex", "com.example.Color.hex"]);
+
+        // Only the entry with a body subclasses the enum
+        assert!(supertypes.contains(&(
+            "com.example.Color.RED".to_string(),
+            vec!["com.example.Color".to_string()]
+        )));
+        assert!(!supertypes.iter().any(|(fqn, _)| fqn == "com.example.Color.GREEN"));
+    }
+
+    #[test]
+    fn test_custom_property_accessors() {
+        let source = r#"
+package com.example
+
+class Temperature {
+    var celsius: Double = 0.0
+
+    val fahrenheit: Double
+        get() = celsius * 9 / 5 + 32
+
+    var label: String = ""
+        set(value) {
+            field = value.trim()
+        }
+}
+"#;
+        let file_path = std::path::PathBuf::from("Temperature.kt");
+        let occurrences = parse_file(&file_path, source).occurrences;
+
+        let getter = occurrences
+            .iter()
+            .find(|o| o.kind == SymbolKind::PropertyAccessorDeclaration && o.name == "fahrenheit")
+            .expect("Expected the custom getter of fahrenheit");
+        assert_eq!(getter.fqn.as_deref(), Some("com.example.Temperature.fahrenheit"));
+        assert_eq!(getter.line, 8);
+
+        let setter = occurrences
+            .iter()
+            .find(|o| o.kind == SymbolKind::PropertyAccessorDeclaration && o.name == "label")
+            .expect("Expected the custom setter of label");
+        assert_eq!(setter.fqn.as_deref(), Some("com.example.Temperature.label"));
+        assert_eq!(setter.line, 11);
+
+        // Member references inside the getter carry the class scope
+        let celsius = occurrences
+            .iter()
+            .find(|o| o.name == "celsius" && o.kind == SymbolKind::PropertyReference)
+            .expect("Expected a reference to celsius in the getter");
+        assert_eq!(celsius.line, 8);
+        assert_eq!(celsius.fqn.as_deref(), Some("com.example.Temperature.celsius"));
+
+        // The backing field is the property itself
+        let field = occurrences
+            .iter()
+            .find(|o| o.name == "field" && o.kind.is_reference())
+            .expect("Expected a reference to the backing field");
+        assert_eq!(field.fqn.as_deref(), Some("com.example.Temperature.label"));
+    }
+
+    #[test]
+    fn test_discover_source_files_with_filter() {
+        use crate::indexer::filter::FileFilter;
+
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for file in [
+            "app/src/main/kotlin/App.kt",
+            "app/src/main/generated/Generated.kt",
+            "app/src/test/kotlin/AppTest.kt",
+            "core/src/main/java/Core.java",
+            "legacy/src/Old.kt",
+        ] {
+            let path = root.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, "").unwrap();
+        }
+        let discover = |include: &[&str], exclude: &[&str]| {
+            let to_strings = |globs: &[&str]| globs.iter().map(|g| g.to_string()).collect::<Vec<_>>();
+            let filter = FileFilter::new(&to_strings(include), &to_strings(exclude)).unwrap();
+            let mut found: Vec<String> = discover_source_files_with_filter(root, &filter)
+                .iter()
+                .map(|p| p.strip_prefix(root).unwrap().to_string_lossy().replace('\\', "/"))
+                .collect();
+            found.sort();
+            found
+        };
+
+        assert_eq!(discover(&[], &[]).len(), 5);
+        assert_eq!(
+            discover(&[], &["**/generated/**", "legacy", "**/*Test.kt"]),
+            vec!["app/src/main/kotlin/App.kt", "core/src/main/java/Core.java"]
+        );
+        assert_eq!(
+            discover(&["app/src/main"], &["**/generated"]),
+            vec!["app/src/main/kotlin/App.kt"]
+        );
+        assert_eq!(discover(&["core/**/*.java"], &[]), vec!["core/src/main/java/Core.java"]);
+    }
+
+    #[test]
+    fn test_discover_source_files_includes_scripts() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for file in [
+            "src/Main.kt",
+            "src/Helper.java",
+            "build.gradle.kts",
+            "scripts/report.main.kts",
+            "build/generated/Generated.kts",
+            ".gradle/cache/Cached.kt",
+            "notes.txt",
+        ] {
+            let path = root.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, "").unwrap();
+        }
+
+        let mut found: Vec<String> = discover_source_files(root)
+            .iter()
+            .map(|p| p.strip_prefix(root).unwrap().to_string_lossy().replace('\\', "/"))
+            .collect();
+        found.sort();
+        assert_eq!(
+            found,
+            vec!["build.gradle.kts", "scripts/report.main.kts", "src/Helper.java", "src/Main.kt"]
+        );
+
+        let kotlin = discover_kotlin_files(root);
+        assert_eq!(kotlin.len(), 3);
     }
-}
 
-fn find_body_range(node: &tree_sitter::Node) -> Option<std::ops::Range<usize>> {
-    let mut cursor = node.walk();
-    for child in node.children(&mut cursor) {
-        if child.kind() == "class_body"
-            || child.kind() == "enum_class_body"
-            || child.kind() == "object_body"
-        {
-            return Some(child.byte_range());
-        }
+    #[test]
+    fn test_script_without_package() {
+        let source = "import com.example.core.User\n\nfun summarize(users: List<User>): String = users.joinToString()\n\nprintln(summarize(emptyList()))\n";
+        let file_path = std::path::PathBuf::from("report.main.kts");
+        let ParsedFile { file_info, occurrences, .. } = parse_file(&file_path, source);
+
+        assert_eq!(file_info.package, None);
+        let summarize = occurrences
+            .iter()
+            .find(|o| o.name == "summarize" && o.kind.is_declaration())
+            .expect("Expected the script's top-level function");
+        assert_eq!(summarize.fqn.as_deref(), Some("summarize"));
+        assert!(occurrences
+            .iter()
+            .any(|o| o.name == "summarize" && o.kind == SymbolKind::CallSite && o.line == 5));
+        assert!(occurrences
+            .iter()
+            .any(|o| o.name == "User" && o.fqn.as_deref() == Some("com.example.core.User")));
     }
-    None
+
+    #[test]
+    fn test_navigation_member_columns() {
+        let source = r#"
+package com.example
+
+fun main() {
+    val x = config.settings.timeout
+    service.repository.save(x)
 }
+"#;
+        let file_path = std::path::PathBuf::from("Main.kt");
+        let occurrences = parse_file(&file_path, source).occurrences;
+        let position = |name: &str, kind: SymbolKind| {
+            occurrences
+                .iter()
+                .find(|o| o.name == name && o.kind == kind)
+                .map(|o| (o.line, o.column, &source[o.byte_range.clone()]))
+                .unwrap_or_else(|| panic!("Expected {:?} `{}`", kind, name))
+        };
 
-pub(super) fn find_child_name(node: &tree_sitter::Node, src: &[u8]) -> Option<String> {
-    let mut cursor = node.walk();
-    for child in node.children(&mut cursor) {
-        if child.kind() == "identifier"
-            || child.kind() == "type_identifier"
-            || child.kind() == "simple_identifier"
-        {
-            return Some(node_text(&child, src).to_string());
-        }
+        assert_eq!(position("config", SymbolKind::PropertyReference), (5, 13, "config"));
+        assert_eq!(position("settings", SymbolKind::PropertyReference), (5, 20, "settings"));
+        assert_eq!(position("timeout", SymbolKind::PropertyReference), (5, 29, "timeout"));
+        assert_eq!(position("repository", SymbolKind::PropertyReference), (6, 13, "repository"));
+        assert_eq!(position("save", SymbolKind::CallSite), (6, 24, "save"));
     }
-    None
+
+    #[test]
+    fn test_string_template_references() {
+        let source = r#"
+package com.example
+
+fun greet(name: String, user: Inbox): String {
+    return "Hello $name, you have ${user.count} messages (${format(user)}) \$unread"
 }
+"#;
+        let file_path = std::path::PathBuf::from("Greeter.kt");
+        let occurrences = parse_file(&file_path, source).occurrences;
+        let references: Vec<(&str, &SymbolKind, usize)> = occurrences
+            .iter()
+            .filter(|o| o.line == 5 && o.kind.is_reference())
+            .map(|o| (o.name.as_str(), &o.kind, o.column))
+            .collect();
 
-fn find_property_name(node: &tree_sitter::Node, src: &[u8]) -> Option<String> {
-    let mut cursor = node.walk();
-    for child in node.children(&mut cursor) {
-        if child.kind() == "variable_declaration" {
-            let mut inner = child.walk();
-            for c in child.children(&mut inner) {
-                if c.kind() == "identifier" || c.kind() == "simple_identifier" {
-                    return Some(node_text(&c, src).to_string());
-                }
-            }
+        for expected in [
+            ("name", &SymbolKind::PropertyReference, 20),
+            ("user", &SymbolKind::PropertyReference, 37),
+            ("count", &SymbolKind::PropertyReference, 42),
+            ("format", &SymbolKind::CallSite, 61),
+        ] {
+            assert!(references.contains(&expected), "Expected {:?}, got: {:?}", expected, references);
         }
-        // Also check for direct identifier (e.g., top-level val)
-        if child.kind() == "identifier" || child.kind() == "simple_identifier" {
-            return Some(node_text(&child, src).to_string());
+        let count = occurrences.iter().find(|o| o.name == "count").unwrap();
+        assert_eq!(count.receiver_type.as_deref(), Some("user"));
+
+        // Literal text and escaped dollars are not code
+        for text in ["Hello", "you", "messages", "unread"] {
+            assert!(
+                references.iter().all(|(name, _, _)| *name != text),
+                "Did not expect `{}` as a reference, got: {:?}",
+                text,
+                references
+            );
         }
     }
-    None
+
+    #[test]
+    fn test_operator_call_sites() {
+        let source = r#"
+package com.example
+
+fun tally(a: Money, b: Money, items: Map<String, Money>, keys: Set<String>): Boolean {
+    val sum = a + b * 2
+    val first = items["x"]
+    return a < b && "y" in keys
 }
+"#;
+        let file_path = std::path::PathBuf::from("Tally.kt");
+        let occurrences = parse_file(&file_path, source).occurrences;
+        let calls: Vec<(&str, usize, usize, Option<&str>)> = occurrences
+            .iter()
+            .filter(|o| o.kind == SymbolKind::CallSite)
+            .map(|o| (o.name.as_str(), o.line, o.column, o.receiver_type.as_deref()))
+            .collect();
 
-fn find_receiver_type(func_node: &tree_sitter::Node, src: &[u8]) -> Option<String> {
-    // Extension functions have a receiver type before the function name
-    // In the AST: function_declaration -> user_type (receiver) -> simple_identifier (name)
-    let mut cursor = func_node.walk();
-    for child in func_node.children(&mut cursor) {
-        if child.kind() == "user_type" {
-            // This is the receiver type (appears before the function name)
-            return Some(node_text(&child, src).to_string());
-        }
-        if child.kind() == "identifier" || child.kind() == "simple_identifier" {
-            // No receiver type before the name
-            return None;
+        for expected in [
+            ("plus", 5, 17, Some("a")),
+            ("times", 5, 21, Some("b")),
+            ("get", 6, 22, Some("items")),
+            ("compareTo", 7, 14, Some("a")),
+            // `x in xs` calls `xs.contains(x)`
+            ("contains", 7, 25, Some("keys")),
+        ] {
+            assert!(calls.contains(&expected), "Expected {:?}, got: {:?}", expected, calls);
         }
+        // The angle brackets of `Map<String, Money>` are not comparisons
+        assert!(!calls.iter().any(|(name, line, _, _)| *name == "compareTo" && *line == 4));
+        // The operands are still references
+        assert!(occurrences
+            .iter()
+            .any(|o| o.name == "keys" && o.kind == SymbolKind::PropertyReference && o.line == 7));
     }
-    None
-}
 
-fn find_type_alias_target(node: &tree_sitter::Node, src: &[u8]) -> Option<String> {
-    let mut cursor = node.walk();
-    let mut found_eq = false;
-    for child in node.children(&mut cursor) {
-        if child.kind() == "=" {
-            found_eq = true;
-            continue;
-        }
-        if found_eq
-            && (child.kind() == "user_type"
-                || child.kind() == "type_identifier"
-                || child.kind() == "identifier")
-        {
-            return Some(node_text(&child, src).to_string());
-        }
+    #[test]
+    fn test_extension_property_receiver() {
+        let source = r#"
+package com.example
+
+val User.isAdmin: Boolean
+    get() = role == Role.ADMIN
+
+val String?.orBlank: String get() = this ?: ""
+
+val plain: Int = 1
+"#;
+        let file_path = std::path::PathBuf::from("Extensions.kt");
+        let occurrences = parse_file(&file_path, source).occurrences;
+        let declaration = |name: &str| {
+            occurrences
+                .iter()
+                .find(|o| {
+                    o.name == name
+                        && o.kind.is_declaration()
+                        && o.kind != SymbolKind::PropertyAccessorDeclaration
+                })
+                .unwrap_or_else(|| panic!("Expected a declaration of `{}`", name))
+        };
+
+        let is_admin = declaration("isAdmin");
+        assert_eq!(is_admin.kind, SymbolKind::ExtensionPropertyDeclaration);
+        assert_eq!(is_admin.receiver_type.as_deref(), Some("User"));
+        assert_eq!(is_admin.fqn.as_deref(), Some("com.example.isAdmin"));
+
+        let or_blank = declaration("orBlank");
+        assert_eq!(or_blank.kind, SymbolKind::ExtensionPropertyDeclaration);
+        assert_eq!(or_blank.receiver_type.as_deref(), Some("String?"));
+
+        let plain = declaration("plain");
+        assert_eq!(plain.kind, SymbolKind::PropertyDeclaration);
+        assert_eq!(plain.receiver_type, None);
+
+        // The custom getter keeps the receiver too
+        let getter = occurrences
+            .iter()
+            .find(|o| o.kind == SymbolKind::PropertyAccessorDeclaration && o.name == "isAdmin")
+            .expect("Expected the isAdmin getter");
+        assert_eq!(getter.receiver_type.as_deref(), Some("User"));
     }
-    None
-}
 
-fn has_keyword_child(node: &tree_sitter::Node, keyword: &str) -> bool {
-    let mut cursor = node.walk();
-    for child in node.children(&mut cursor) {
-        if child.kind() == keyword {
-            return true;
-        }
+    #[test]
+    fn test_type_parameter_bounds() {
+        let source = r#"
+package com.example
+
+import com.example.model.Entity
+
+class Repo<T : Entity>
+
+fun <T : Comparable<T>> sort(items: List<T>): List<T> = items.sorted()
+
+fun <K, V> index(map: Map<K, V>) where V : Entity, K : Comparable<K> {}
+"#;
+        let file_path = std::path::PathBuf::from("Bounds.kt");
+        let occurrences = parse_file(&file_path, source).occurrences;
+        let type_refs = |name: &str, line: usize| {
+            occurrences
+                .iter()
+                .filter(|o| o.name == name && o.kind == SymbolKind::TypeReference && o.line == line)
+                .map(|o| o.column)
+                .collect::<Vec<_>>()
+        };
+
+        let entity_fqns: Vec<_> = occurrences
+            .iter()
+            .filter(|o| o.name == "Entity" && o.kind == SymbolKind::TypeReference)
+            .map(|o| (o.line, o.fqn.as_deref()))
+            .collect();
+        assert_eq!(
+            entity_fqns,
+            vec![(6, Some("com.example.model.Entity")), (10, Some("com.example.model.Entity"))]
+        );
+        assert_eq!(type_refs("Comparable", 8), vec![10]);
+        assert_eq!(type_refs("Comparable", 10), vec![56]);
+
+        // Neither the parameter names nor their uses inside a bound are type references;
+        // uses in the signature (`List<T>`, `Map<K, V>`) still are
+        assert!(type_refs("T", 6).is_empty());
+        assert_eq!(type_refs("T", 8), vec![42, 52]);
+        assert_eq!(type_refs("K", 10), vec![27]);
+        assert!(!occurrences
+            .iter()
+            .any(|o| matches!(o.name.as_str(), "T" | "K" | "V") && o.kind == SymbolKind::PropertyReference));
     }
-    false
-}
 
-pub(super) fn node_text<'a>(node: &tree_sitter::Node, src: &'a [u8]) -> &'a str {
-    node.utf8_text(src).unwrap_or("")
+    #[test]
+    fn test_enclosing_fqn() {
+        let source = r#"
+package com.example
+
+data class Point(val x: Coord, val y: Coord)
+
+val origin = Point(zero(), zero())
+
+class Shapes {
+    val unit: Point get() = Point(one(), one())
+
+    fun draw() {
+        val p = origin.copy()
+        render(p)
+    }
 }
+"#;
+        let file_path = std::path::PathBuf::from("Shapes.kt");
+        let occurrences = parse_file(&file_path, source).occurrences;
+        let enclosing = |name: &str, kind: SymbolKind, line: usize| {
+            occurrences
+                .iter()
+                .find(|o| o.name == name && o.kind == kind && o.line == line)
+                .unwrap_or_else(|| panic!("Expected {:?} `{}` on line {}", kind, name, line))
+                .enclosing_fqn
+                .as_deref()
+        };
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        // A constructor property encloses its type. The synthesized component1() shares the
+        // parameter's range but neither encloses it nor is enclosed by it.
+        assert_eq!(enclosing("Coord", SymbolKind::TypeReference, 4), Some("com.example.Point.x"));
+        assert_eq!(enclosing("component1", SymbolKind::FunctionDeclaration, 4), Some("com.example.Point"));
+        assert_eq!(enclosing("zero", SymbolKind::CallSite, 6), Some("com.example.origin"));
+        assert_eq!(enclosing("one", SymbolKind::CallSite, 9), Some("com.example.Shapes.unit"));
+        assert_eq!(enclosing("render", SymbolKind::CallSite, 13), Some("com.example.Shapes.draw"));
+        assert_eq!(enclosing("draw", SymbolKind::FunctionDeclaration, 11), Some("com.example.Shapes"));
+        assert_eq!(enclosing("Shapes", SymbolKind::ClassDeclaration, 8), None);
+    }
 
     #[test]
-    fn test_interface_parsing() {
-        let source = "package com.example\n\ninterface Repository<T> {\n    fun findById(id: String): T?\n}\n";
-        let file_path = std::path::PathBuf::from("Test.kt");
-        let (_, occurrences, _) = parse_file(&file_path, source);
-        let repo = occurrences
+    fn test_constructor_properties() {
+        let source = "package com.example\n\nclass User(val name: String, var age: Int, label: String) {\n    var nickname: String = \"\"\n}\n";
+        let file_path = std::path::PathBuf::from("User.kt");
+        let occurrences = parse_file(&file_path, source).occurrences;
+        let properties: Vec<(&str, bool)> = occurrences
             .iter()
-            .find(|o| o.name == "Repository")
-            .expect("Expected Repository in occurrences");
-        assert!(
-            matches!(repo.kind, super::SymbolKind::InterfaceDeclaration),
-            "Expected InterfaceDeclaration, got {:?}",
-            repo.kind
+            .filter(|o| o.kind == SymbolKind::PropertyDeclaration)
+            .map(|o| (o.fqn.as_deref().unwrap(), o.modifiers.unwrap().is_var))
+            .collect();
+        // `label` is a plain constructor parameter, not a property
+        assert_eq!(
+            properties,
+            vec![
+                ("com.example.User.name", false),
+                ("com.example.User.age", true),
+                ("com.example.User.nickname", true),
+            ]
         );
-        assert_eq!(repo.fqn.as_deref(), Some("com.example.Repository"));
     }
 
     #[test]
-    fn test_discover_files() {
-        // Just test the function doesn't panic with a temp dir
-        let dir = tempfile::tempdir().unwrap();
-        let files = discover_kotlin_files(dir.path());
-        assert!(files.is_empty());
+    fn test_context_clause_types() {
+        let text = "context(Logger, scope: Scope<User>, clock: com.example.Clock)";
+        let close = text.len() - 1;
+        let types: Vec<&str> = context_clause_types(text, 8, close)
+            .into_iter()
+            .map(|(start, name)| {
+                assert_eq!(&text[start..start + name.len()], name);
+                name
+            })
+            .collect();
+        assert_eq!(types, vec!["Logger", "Scope", "User"]);
+        assert_eq!(matching_paren(text, 7), Some(close));
     }
 
     #[test]
-    fn test_parse_simple_file() {
+    fn test_context_receivers_are_type_references() {
         let source = r#"
 package com.example
 
-import java.util.List
+// context(Ignored) fun inComment() {}
+context(Logger)
+fun log() {}
 
-class MyClass {
-    fun myMethod(): String {
-        return "hello"
+context(logger: Logger, clock: Clock)
+private fun stamp() {}
+
+fun call() = context(notAClause)
+"#;
+        let path = PathBuf::from("Context.kt");
+        let occurrences = parse_file(&path, source).occurrences;
+        let mut refs: Vec<(&str, usize, usize)> = occurrences
+            .iter()
+            .filter(|o| o.kind == SymbolKind::TypeReference)
+            .map(|o| (o.name.as_str(), o.line, o.column))
+            .collect();
+        refs.sort();
+        assert_eq!(refs, vec![("Clock", 8, 32), ("Logger", 5, 9), ("Logger", 8, 17)]);
+        assert!(occurrences
+            .iter()
+            .all(|o| o.fqn.as_deref() != Some("com.example.Ignored")));
     }
 
-    val myProperty: Int = 42
+    #[test]
+    fn test_lambda_parameters() {
+        let source = r#"
+package com.example
+
+fun totals(prices: Map<String, Money>) {
+    prices.forEach { (key, value) -> record(key, value) }
+    prices.keys.filter { k: String -> k.isNotEmpty() }
 }
+"#;
+        let file_path = std::path::PathBuf::from("Totals.kt");
+        let occurrences = parse_file(&file_path, source).occurrences;
+        let fqns = |kind: SymbolKind| -> Vec<(&str, Option<&str>)> {
+            occurrences
+                .iter()
+                .filter(|o| o.kind == kind && matches!(o.name.as_str(), "key" | "value" | "k"))
+                .map(|o| (o.name.as_str(), o.fqn.as_deref()))
+                .collect()
+        };
 
-fun topLevelFunction() {}
+        let declared = vec![
+            ("key", Some("com.example.<lambda@5:20>.key")),
+            ("value", Some("com.example.<lambda@5:20>.value")),
+            ("k", Some("com.example.<lambda@6:24>.k")),
+        ];
+        assert_eq!(fqns(SymbolKind::ParameterDeclaration), declared);
+        // Every use in the bodies is bound to its parameter
+        let mut used = fqns(SymbolKind::PropertyReference);
+        used.sort();
+        let mut expected = declared.clone();
+        expected.sort();
+        assert_eq!(used, expected);
+        // The parameter's type is still a reference
+        assert!(occurrences
+            .iter()
+            .any(|o| o.name == "String" && o.kind == SymbolKind::TypeReference && o.line == 6));
+    }
+
+    #[test]
+    fn test_for_loop_variables() {
+        let source = r#"
+package com.example
+
+fun totals(prices: Map<String, Money>, items: List<String>) {
+    for ((key, value) in prices) {
+        record(key, value)
+    }
+    for (item in items) println(item)
+    println(item)
+}
 "#;
-        let dir = tempfile::tempdir().unwrap();
-        let file_path = dir.path().join("Test.kt");
-        std::fs::write(&file_path, source).unwrap();
+        let file_path = std::path::PathBuf::from("Totals.kt");
+        let occurrences = parse_file(&file_path, source).occurrences;
+        let fqns = |kind: SymbolKind| -> Vec<(&str, usize, Option<&str>)> {
+            occurrences
+                .iter()
+                .filter(|o| o.kind == kind && matches!(o.name.as_str(), "key" | "value" | "item"))
+                .map(|o| (o.name.as_str(), o.line, o.fqn.as_deref()))
+                .collect()
+        };
 
-        let (file_info, occurrences, _) = parse_file(&file_path, source);
-        assert_eq!(file_info.package, Some("com.example".to_string()));
-        assert_eq!(file_info.imports.len(), 1);
-        assert_eq!(file_info.imports[0].path, "java.util.List");
+        assert_eq!(
+            fqns(SymbolKind::ParameterDeclaration),
+            vec![
+                ("key", 5, Some("com.example.<for@5:5>.key")),
+                ("value", 5, Some("com.example.<for@5:5>.value")),
+                ("item", 8, Some("com.example.<for@8:5>.item")),
+            ]
+        );
+        // Uses in the loop bodies are bound; after the loop, `item` is something else
+        let mut used = fqns(SymbolKind::PropertyReference);
+        used.sort();
+        assert_eq!(
+            used,
+            vec![
+                ("item", 8, Some("com.example.<for@8:5>.item")),
+                ("item", 9, Some("com.example.item")),
+                ("key", 6, Some("com.example.<for@5:5>.key")),
+                ("value", 6, Some("com.example.<for@5:5>.value")),
+            ]
+        );
+    }
 
-        // Check that we have class, method, property, and top-level function declarations
-        let decl_names: Vec<&str> = occurrences
+    #[test]
+    fn test_call_type_arguments() {
+        let source = r#"
+package com.example
+
+import com.other.User
+
+val registry = Registry.lookup<User>()
+val users = emptyList<User>()
+val pairs = mapOf<String, List<out User>>()
+val anything = lookup<Registry<*>>()
+"#;
+        let file_path = std::path::PathBuf::from("Lookups.kt");
+        let occurrences = parse_file(&file_path, source).occurrences;
+        let type_refs = |name: &str| -> Vec<(usize, usize, Option<&str>)> {
+            occurrences
+                .iter()
+                .filter(|o| o.name == name && o.kind == SymbolKind::TypeReference)
+                .map(|o| (o.line, o.column, o.fqn.as_deref()))
+                .collect()
+        };
+
+        // Type arguments of plain and qualified calls, and of types inside them
+        assert_eq!(
+            type_refs("User"),
+            vec![
+                (6, 32, Some("com.other.User")),
+                (7, 23, Some("com.other.User")),
+                (8, 36, Some("com.other.User")),
+            ]
+        );
+        assert_eq!(type_refs("List").len(), 1);
+        assert_eq!(type_refs("Registry").iter().map(|r| r.0).collect::<Vec<_>>(), vec![9]);
+        assert!(occurrences
             .iter()
-            .filter(|o| o.kind.is_declaration())
-            .map(|o| o.name.as_str())
-            .collect();
-        assert!(decl_names.contains(&"MyClass"), "Expected MyClass declaration, got: {:?}", decl_names);
-        assert!(decl_names.contains(&"myMethod"), "Expected myMethod declaration, got: {:?}", decl_names);
-        assert!(decl_names.contains(&"myProperty"), "Expected myProperty declaration, got: {:?}", decl_names);
-        assert!(decl_names.contains(&"topLevelFunction"), "Expected topLevelFunction, got: {:?}", decl_names);
+            .any(|o| o.name == "lookup" && o.kind == SymbolKind::CallSite && o.line == 6));
+        // Variance modifiers aren't references
+        assert!(!occurrences.iter().any(|o| o.name == "out"));
     }
 
     #[test]
-    fn test_parse_imports() {
+    fn test_annotation_class_literals() {
         let source = r#"
 package com.example
 
-import com.other.Foo
-import com.other.Bar as Baz
-import com.util.*
+import com.fasterxml.jackson.annotation.JsonSubTypes
+import com.other.User
+
+@JsonSubTypes(JsonSubTypes.Type(value = User::class, name = "user"), JsonSubTypes.Type(Admin::class))
+class Account {
+    val kind = User::class.simpleName
+    val self = this::class
+}
 "#;
-        let dir = tempfile::tempdir().unwrap();
-        let file_path = dir.path().join("Test.kt");
+        let file_path = std::path::PathBuf::from("Account.kt");
+        let occurrences = parse_file(&file_path, source).occurrences;
+        let found = |name: &str, kind: SymbolKind| -> Vec<(usize, Option<&str>)> {
+            occurrences
+                .iter()
+                .filter(|o| o.name == name && o.kind == kind)
+                .map(|o| (o.line, o.fqn.as_deref()))
+                .collect()
+        };
 
-        let (file_info, _, _) = parse_file(&file_path, source);
-        assert_eq!(file_info.imports.len(), 3);
+        // Class literals in nested annotation arguments and in expressions
+        assert_eq!(
+            found("User", SymbolKind::TypeReference),
+            vec![(7, Some("com.other.User")), (9, Some("com.other.User"))]
+        );
+        assert_eq!(found("Admin", SymbolKind::TypeReference), vec![(7, Some("com.example.Admin"))]);
+        // Nested annotations resolve through the outer annotation's import
+        let nested = Some("com.fasterxml.jackson.annotation.JsonSubTypes.Type");
+        assert_eq!(found("Type", SymbolKind::AnnotationReference), vec![(7, nested), (7, nested)]);
+        assert!(found("Type", SymbolKind::CallSite).is_empty());
+        // `this::class` names no type
+        assert!(!occurrences.iter().any(|o| o.line == 10 && o.kind == SymbolKind::TypeReference));
+    }
 
-        let foo = &file_info.imports[0];
-        assert_eq!(foo.path, "com.other.Foo");
-        assert!(!foo.is_wildcard);
-        assert!(foo.alias.is_none());
+    #[test]
+    fn test_platform_modifiers() {
+        let source = r#"
+package com.example
 
-        let baz = &file_info.imports[1];
-        assert_eq!(baz.path, "com.other.Bar");
-        assert_eq!(baz.alias, Some("Baz".to_string()));
+expect class Clipboard {
+    fun copy(text: String)
+}
 
-        let wildcard = &file_info.imports[2];
-        assert!(wildcard.is_wildcard);
+actual fun newClipboard(): Clipboard = TODO()
+
+actual typealias Timestamp = Long
+
+class Plain
+"#;
+        let file_path = std::path::PathBuf::from("Clipboard.kt");
+        let occurrences = parse_file(&file_path, source).occurrences;
+        let modifier = |name: &str| {
+            occurrences
+                .iter()
+                .find(|o| o.name == name && o.kind.is_declaration())
+                .unwrap_or_else(|| panic!("Expected a declaration of {}", name))
+                .platform_modifier
+        };
+
+        assert_eq!(modifier("Clipboard"), Some(PlatformModifier::Expect));
+        assert_eq!(modifier("newClipboard"), Some(PlatformModifier::Actual));
+        assert_eq!(modifier("Timestamp"), Some(PlatformModifier::Actual));
+        // Members of an `expect` class are implicitly expected but carry no modifier
+        assert_eq!(modifier("copy"), None);
+        assert_eq!(modifier("Plain"), None);
     }
 
+    #[test]
+    fn test_when_without_subject() {
+        let source = r#"
+package com.example
+
+fun label(user: User, limit: Int): String = when {
+    user.isActive && user.age > limit -> "active"
+    isBlocked(user) -> "blocked"
+    else -> "inactive"
+}
+"#;
+        let file_path = std::path::PathBuf::from("Label.kt");
+        let occurrences = parse_file(&file_path, source).occurrences;
+        let lines = |name: &str, kind: SymbolKind| -> Vec<usize> {
+            occurrences
+                .iter()
+                .filter(|o| o.name == name && o.kind == kind)
+                .map(|o| o.line)
+                .collect()
+        };
+
+        assert_eq!(lines("isActive", SymbolKind::PropertyReference), vec![5]);
+        assert_eq!(lines("age", SymbolKind::PropertyReference), vec![5]);
+        assert_eq!(lines("limit", SymbolKind::PropertyReference), vec![5]);
+        assert_eq!(lines("isBlocked", SymbolKind::CallSite), vec![6]);
+        assert_eq!(lines("user", SymbolKind::PropertyReference), vec![5, 5, 6]);
+    }
 }
@@ -1,5 +1,60 @@
-/// Scope tracking for Kotlin source files.
-/// Uses byte ranges from tree-sitter nodes to determine which scope a symbol belongs to.
+//! Scope tracking for Kotlin source files.
+//! Uses byte ranges from tree-sitter nodes to determine which scope a symbol belongs to.
+
+/// Start of the scope name given to anonymous objects and classes (`object : Foo { }`,
+/// `new Foo() { }`), e.g. `pkg.Foo.<anon@9:20>.run` for one at line 9, column 20, so their
+/// members don't share FQNs with the enclosing class's members or with each other.
+pub const ANONYMOUS_SCOPE_PREFIX: &str = "<anon@";
+
+/// Start of the scope segment of a lambda's parameters, e.g. `pkg.Foo.<lambda@12:20>.user`
+/// for `{ user -> ... }` at line 12, column 20. Nested lambdas reusing a parameter name
+/// get distinct FQNs.
+pub const LAMBDA_SCOPE_PREFIX: &str = "<lambda@";
+
+/// Start of the scope segment of a for-loop's variables, e.g. `pkg.Foo.<for@7:9>.key` for
+/// `for ((key, value) in map)` at line 7, column 9.
+pub const LOOP_SCOPE_PREFIX: &str = "<for@";
+
+/// Start of the scope segment of a local variable, e.g. `pkg.Foo.<local@9:13>.roster` for
+/// `var roster = ...` declared at line 9, column 13. Same-named locals of different methods,
+/// and fields they shadow, get distinct FQNs.
+pub const LOCAL_SCOPE_PREFIX: &str = "<local@";
+
+/// Whether `fqn` names a lambda parameter, loop variable or local variable, which is local
+/// to its lambda, loop or block.
+pub fn is_local(fqn: &str) -> bool {
+    fqn.contains(LAMBDA_SCOPE_PREFIX)
+        || fqn.contains(LOOP_SCOPE_PREFIX)
+        || fqn.contains(LOCAL_SCOPE_PREFIX)
+}
+
+/// Start of the package given to a file without a package header, e.g.
+/// `<default@scripts/report>.summarize` for `scripts/report.main.kts`. Such files share the
+/// default package, but bare simple-name FQNs would collide across them.
+pub const DEFAULT_PACKAGE_PREFIX: &str = "<default@";
+
+/// The synthetic package of a file without a package header, from its path relative to
+/// the project root: the directories and the file name up to its first dot. Dots in
+/// directory names become underscores so the package stays a single FQN segment.
+pub fn default_package(rel_path: &std::path::Path) -> String {
+    let mut segments: Vec<String> = rel_path
+        .parent()
+        .into_iter()
+        .flat_map(|dir| dir.components())
+        .map(|c| c.as_os_str().to_string_lossy().replace('.', "_"))
+        .collect();
+    let file_name = rel_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    segments.push(file_name.split('.').next().unwrap_or_default().to_string());
+    format!("{}{}>", DEFAULT_PACKAGE_PREFIX, segments.join("/"))
+}
+
+/// Whether `package` is the synthetic package of a file without a package header.
+pub fn is_default_package(package: &str) -> bool {
+    package.starts_with(DEFAULT_PACKAGE_PREFIX) && package.ends_with('>') && !package.contains(">.")
+}
 
 #[derive(Debug, Clone)]
 pub struct ScopeSegment {
@@ -89,4 +144,19 @@ mod tests {
         let fqn = tree.fqn_prefix_at(None, 50);
         assert_eq!(fqn, "MyClass");
     }
+
+    #[test]
+    fn test_default_package() {
+        let package = default_package(std::path::Path::new("scripts/report.main.kts"));
+        assert_eq!(package, "<default@scripts/report>");
+        assert!(is_default_package(&package));
+        assert_eq!(default_package(std::path::Path::new("Main.java")), "<default@Main>");
+        assert_eq!(
+            default_package(std::path::Path::new("tools/v1.2/Fix.kt")),
+            "<default@tools/v1_2/Fix>"
+        );
+
+        assert!(!is_default_package("com.example"));
+        assert!(!is_default_package("<default@Main>.Helper"));
+    }
 }
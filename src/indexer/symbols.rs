@@ -1,4 +1,5 @@
-use super::{SymbolIndex, SymbolOccurrence};
+use super::scope::{is_default_package, is_local};
+use super::{SymbolIndex, SymbolKind, SymbolOccurrence};
 
 /// Kotlin implicit imports that are available in every file.
 pub const KOTLIN_IMPLICIT_IMPORTS: &[&str] = &[
@@ -15,10 +16,53 @@ pub const KOTLIN_IMPLICIT_IMPORTS: &[&str] = &[
 /// Java implicit imports that are available in every file.
 pub const JAVA_IMPLICIT_IMPORTS: &[&str] = &["java.lang"];
 
+/// Kotlin standard library functions that resolve through the implicit imports without
+/// any declaration in the project. Deliberately limited to the `kotlin.collections`
+/// builders so common names elsewhere in a project aren't mistaken for stdlib calls.
+pub const KOTLIN_STDLIB_SYMBOLS: &[&str] = &[
+    "kotlin.collections.listOf",
+    "kotlin.collections.listOfNotNull",
+    "kotlin.collections.mutableListOf",
+    "kotlin.collections.arrayListOf",
+    "kotlin.collections.emptyList",
+    "kotlin.collections.buildList",
+    "kotlin.collections.setOf",
+    "kotlin.collections.mutableSetOf",
+    "kotlin.collections.hashSetOf",
+    "kotlin.collections.linkedSetOf",
+    "kotlin.collections.emptySet",
+    "kotlin.collections.buildSet",
+    "kotlin.collections.mapOf",
+    "kotlin.collections.mutableMapOf",
+    "kotlin.collections.hashMapOf",
+    "kotlin.collections.linkedMapOf",
+    "kotlin.collections.emptyMap",
+    "kotlin.collections.buildMap",
+];
+
+/// Look up a symbol in [`KOTLIN_STDLIB_SYMBOLS`] by simple name (`listOf`) or FQN
+/// (`kotlin.collections.listOf`).
+pub fn kotlin_stdlib_fqn(symbol: &str) -> Option<&'static str> {
+    KOTLIN_STDLIB_SYMBOLS.iter().copied().find(|fqn| {
+        *fqn == symbol || fqn.rsplit('.').next() == Some(symbol)
+    })
+}
+
 /// After initial indexing, cross-reference symbols:
 /// For each reference that only has a by-name entry, try to resolve its FQN
 /// using the full index.
 pub fn cross_reference(index: &mut SymbolIndex) {
+    cross_reference_files(index, None);
+}
+
+/// Like [`cross_reference`], re-resolving only the references and supertypes in `files`
+/// when given, e.g. the files just re-indexed. Declarations are still looked up across the
+/// whole index, but references elsewhere are left alone: if the changed files added or
+/// removed declarations that other files refer to, run the full [`cross_reference`].
+pub fn cross_reference_files(
+    index: &mut SymbolIndex,
+    files: Option<&std::collections::HashSet<std::path::PathBuf>>,
+) {
     // Collect all declarations by their simple name for resolution
     let declarations_by_name: std::collections::HashMap<String, Vec<(String, std::path::PathBuf)>> = {
         let mut map: std::collections::HashMap<String, Vec<(String, std::path::PathBuf)>> =
@@ -26,7 +70,8 @@ pub fn cross_reference(index: &mut SymbolIndex) {
         for (name, occs) in &index.by_name {
             for occ in occs {
                 if occ.kind.is_declaration() {
-                    if let Some(ref fqn) = occ.fqn {
+                    // Lambda parameters and loop variables are only visible inside their lambda or loop
+                    if let Some(fqn) = occ.fqn.as_ref().filter(|fqn| !is_local(fqn)) {
                         map.entry(name.clone())
                             .or_default()
                             .push((fqn.clone(), occ.file.clone()));
@@ -37,24 +82,91 @@ pub fn cross_reference(index: &mut SymbolIndex) {
         map
     };
 
-    // Collect file info for import resolution
-    let files = index.files.clone();
-    let type_aliases = index.type_aliases.clone();
+    // Companion objects by their containing class: class FQN → [companion FQN, ...]
+    let mut companions_by_class: std::collections::HashMap<String, Vec<String>> =
+        std::collections::HashMap::new();
+    for occs in index.by_name.values() {
+        for occ in occs {
+            if occ.kind != SymbolKind::CompanionObjectDeclaration {
+                continue;
+            }
+            if let Some(fqn) = occ.fqn.as_deref() {
+                if let Some((class_fqn, _)) = fqn.rsplit_once('.') {
+                    companions_by_class
+                        .entry(class_fqn.to_string())
+                        .or_default()
+                        .push(fqn.to_string());
+                }
+            }
+        }
+    }
+
+    resolve_type_alias_targets(index, &declarations_by_name);
 
     // Resolve references that need better FQN resolution
     let mut updates: Vec<(String, usize, String)> = Vec::new(); // (name, index_in_vec, new_fqn)
+    let mut super_members: Vec<(String, usize)> = Vec::new();
 
     for (name, occs) in &index.by_name {
         for (idx, occ) in occs.iter().enumerate() {
-            if !occ.kind.is_reference() {
+            // Uses of lambda parameters and loop variables were bound by the parser
+            if !occ.kind.is_reference()
+                || occ.fqn.as_deref().is_some_and(is_local)
+                || !in_scope(files, &occ.file)
+            {
+                continue;
+            }
+            // `super.bar()` needs the resolved supertypes, see below
+            if occ.receiver_type.as_deref().is_some_and(is_super_receiver) {
+                super_members.push((name.clone(), idx));
                 continue;
             }
 
             // Try to resolve to a better FQN
-            if let Some(file_info) = files.get(&occ.file) {
+            if let Some(file_info) = index.files.get(&occ.file) {
+                // `MyClass.member` where `member` lives in MyClass's companion object
+                if let Some(receiver) = occ.receiver_type.as_deref() {
+                    if let Some(companion_fqn) = resolve_companion_member(
+                        name,
+                        receiver,
+                        file_info,
+                        &declarations_by_name,
+                        &index.type_aliases,
+                        &companions_by_class,
+                    ) {
+                        if occ.fqn.as_deref() != Some(companion_fqn.as_str()) {
+                            updates.push((name.clone(), idx, companion_fqn));
+                        }
+                        continue;
+                    }
+                }
+
+                // `Outer.Inner` where `Inner` is declared inside `Outer`
+                if let Some(receiver) = occ.receiver_type.as_deref() {
+                    if let Some(nested_fqn) = resolve_nested_member(
+                        name,
+                        receiver,
+                        file_info,
+                        &declarations_by_name,
+                        &index.type_aliases,
+                    ) {
+                        if occ.fqn.as_deref() != Some(nested_fqn.as_str()) {
+                            updates.push((name.clone(), idx, nested_fqn));
+                        }
+                        continue;
+                    }
+                }
+
                 if let Some(resolved_fqn) =
-                    resolve_symbol_fqn(name, file_info, &declarations_by_name, &type_aliases)
+                    resolve_symbol_fqn(name, file_info, &declarations_by_name, &index.type_aliases)
                 {
+                    // `names().isEmpty()` calls a member of whatever `names()` returns, not a
+                    // same-named member of the class in scope
+                    if let Some(receiver) = occ.receiver_type.as_deref() {
+                        if !receiver_matches_owner(receiver, &resolved_fqn, &declarations_by_name) {
+                            continue;
+                        }
+                    }
                     if occ.fqn.as_deref() != Some(&resolved_fqn) {
                         // Don't override a FQN that already resolves to a known declaration.
                         // This prevents same-file class methods from shadowing a correct
@@ -64,7 +176,21 @@ pub fn cross_reference(index: &mut SymbolIndex) {
                                 decls.iter().any(|(fqn, _)| fqn == current_fqn)
                             })
                         });
-                        if !current_is_valid {
+                        // The parser guesses same-package for anything it can't resolve. An
+                        // explicit wildcard import that matches a real declaration wins over it.
+                        let replaces_package_guess = occ.fqn.as_ref().is_some_and(|current_fqn| {
+                            file_info.package.as_ref().is_some_and(|pkg| {
+                                *current_fqn == format!("{}.{}", pkg, name)
+                            })
+                        }) && resolve_wildcard_import(
+                            name,
+                            file_info,
+                            &declarations_by_name,
+                            &index.type_aliases,
+                        )
+                        .as_deref()
+                            == Some(resolved_fqn.as_str());
+                        if !current_is_valid || replaces_package_guess {
                             updates.push((name.clone(), idx, resolved_fqn));
                         }
                     }
@@ -73,7 +199,42 @@ pub fn cross_reference(index: &mut SymbolIndex) {
         }
     }
 
-    // Apply updates
+    apply_fqn_updates(index, updates);
+
+    resolve_supertypes(index, &declarations_by_name, files);
+
+    // The parser records `super.bar()` in `Child` as `Child.bar`; move it to the supertype
+    // declaring `bar`
+    let super_updates: Vec<(String, usize, String)> = super_members
+        .into_iter()
+        .filter_map(|(name, idx)| {
+            let occ = index.by_name.get(&name)?.get(idx)?;
+            let (class_fqn, _) = occ.fqn.as_deref()?.rsplit_once('.')?;
+            let qualifier = super_type_qualifier(occ.receiver_type.as_deref()?);
+            let fqn =
+                resolve_super_member(index, &declarations_by_name, class_fqn, &name, qualifier)?;
+            (occ.fqn.as_deref() != Some(fqn.as_str())).then_some((name, idx, fqn))
+        })
+        .collect();
+    apply_fqn_updates(index, super_updates);
+
+    build_subtypes(index);
+}
+
+/// Whether a [`cross_reference_files`] run limited to `files` re-resolves `file`.
+fn in_scope(
+    files: Option<&std::collections::HashSet<std::path::PathBuf>>,
+    file: &std::path::Path,
+) -> bool {
+    match files {
+        Some(files) => files.contains(file),
+        None => true,
+    }
+}
+
+/// Point the occurrences at `(name, index in by_name[name])` to new FQNs, moving them
+/// between `by_fqn` entries.
+fn apply_fqn_updates(index: &mut SymbolIndex, updates: Vec<(String, usize, String)>) {
     for (name, idx, new_fqn) in updates {
         if let Some(occs) = index.by_name.get_mut(&name) {
             if let Some(occ) = occs.get_mut(idx) {
@@ -98,6 +259,247 @@ pub fn cross_reference(index: &mut SymbolIndex) {
     }
 }
 
+/// Whether a member access through `receiver` can reach `member_fqn`: always for a top-level
+/// declaration (an extension, say), and for a class member only through `this` or the class
+/// itself (`Foo.bar`, `Foo().bar()`).
+fn receiver_matches_owner(
+    receiver: &str,
+    member_fqn: &str,
+    declarations_by_name: &std::collections::HashMap<String, Vec<(String, std::path::PathBuf)>>,
+) -> bool {
+    let owner = match member_fqn.rsplit_once('.') {
+        Some((owner, _)) => owner,
+        None => return true,
+    };
+    let owner_name = owner.rsplit('.').next().unwrap_or(owner);
+    let owner_is_type = declarations_by_name
+        .get(owner_name)
+        .is_some_and(|decls| decls.iter().any(|(fqn, _)| fqn == owner));
+    if !owner_is_type || receiver == "this" {
+        return true;
+    }
+    let head = receiver
+        .split(['(', '<', '.'])
+        .next()
+        .unwrap_or(receiver)
+        .trim();
+    head == owner_name
+}
+
+/// Whether a receiver as written is `super`, `super<Foo>` or `super@Outer`.
+fn is_super_receiver(receiver: &str) -> bool {
+    receiver == "super" || receiver.starts_with("super<") || receiver.starts_with("super@")
+}
+
+/// The supertype named in `super<Foo>`, as a simple name.
+fn super_type_qualifier(receiver: &str) -> Option<&str> {
+    let (qualifier, _) = receiver.strip_prefix("super<")?.split_once('>')?;
+    qualifier.trim().rsplit('.').next()
+}
+
+/// Resolve `member` called through `super` in `class_fqn` to the nearest declaration in
+/// its supertypes, searched breadth-first. `super<Foo>` starts at the direct supertype `Foo`.
+fn resolve_super_member(
+    index: &SymbolIndex,
+    declarations_by_name: &std::collections::HashMap<String, Vec<(String, std::path::PathBuf)>>,
+    class_fqn: &str,
+    member: &str,
+    qualifier: Option<&str>,
+) -> Option<String> {
+    let decls = declarations_by_name.get(member)?;
+    let mut queue: std::collections::VecDeque<&String> = index
+        .supertypes
+        .get(class_fqn)?
+        .iter()
+        .filter(|parent| qualifier.is_none() || parent.rsplit('.').next() == qualifier)
+        .collect();
+    let mut visited: std::collections::HashSet<&String> = std::collections::HashSet::new();
+    while let Some(parent) = queue.pop_front() {
+        if !visited.insert(parent) {
+            continue;
+        }
+        let candidate = format!("{}.{}", parent, member);
+        if decls.iter().any(|(fqn, _)| *fqn == candidate) {
+            return Some(candidate);
+        }
+        queue.extend(index.supertypes.get(parent).into_iter().flatten());
+    }
+    None
+}
+
+/// Resolve alias targets, recorded by the parser as written (`User`, `Result.Success`), to the
+/// FQN of the declaration they name, using the imports of the file declaring the alias.
+/// Targets the project doesn't declare (`String`, `kotlin.Function1`) are left as written.
+fn resolve_type_alias_targets(
+    index: &mut SymbolIndex,
+    declarations_by_name: &std::collections::HashMap<String, Vec<(String, std::path::PathBuf)>>,
+) {
+    let mut resolved: Vec<(String, String)> = Vec::new();
+    for (alias_fqn, target) in &index.type_aliases {
+        let (outer, nested) = match target.split_once('.') {
+            Some((outer, nested)) if outer.chars().next().is_some_and(|c| c.is_uppercase()) => {
+                (outer, Some(nested))
+            }
+            // Already package-qualified
+            Some(_) => continue,
+            None => (target.as_str(), None),
+        };
+        let file_info = match index
+            .by_fqn
+            .get(alias_fqn)
+            .and_then(|occs| occs.iter().find(|o| o.kind == SymbolKind::TypeAliasDeclaration))
+            .and_then(|decl| index.files.get(&decl.file))
+        {
+            Some(file_info) => file_info,
+            None => continue,
+        };
+        if let Some(outer_fqn) =
+            resolve_symbol_fqn(outer, file_info, declarations_by_name, &index.type_aliases)
+        {
+            let target_fqn = match nested {
+                Some(nested) => format!("{}.{}", outer_fqn, nested),
+                None => outer_fqn,
+            };
+            resolved.push((alias_fqn.clone(), target_fqn));
+        }
+    }
+    index.type_aliases.extend(resolved);
+}
+
+/// Derive `index.subtypes` by inverting the resolved `index.supertypes` map.
+fn build_subtypes(index: &mut SymbolIndex) {
+    index.subtypes.clear();
+    for (class_fqn, parents) in &index.supertypes {
+        for parent in parents {
+            index
+                .subtypes
+                .entry(parent.clone())
+                .or_default()
+                .push(class_fqn.clone());
+        }
+    }
+    for children in index.subtypes.values_mut() {
+        children.sort();
+        children.dedup();
+    }
+}
+
+/// Re-resolve supertype FQNs that don't point at a known declaration, e.g. a parent
+/// class brought in through a wildcard import that the parser could only guess at.
+fn resolve_supertypes(
+    index: &mut SymbolIndex,
+    declarations_by_name: &std::collections::HashMap<String, Vec<(String, std::path::PathBuf)>>,
+    files: Option<&std::collections::HashSet<std::path::PathBuf>>,
+) {
+    let known_fqns: std::collections::HashSet<&str> = declarations_by_name
+        .values()
+        .flatten()
+        .map(|(fqn, _)| fqn.as_str())
+        .collect();
+
+    // (class FQN, position in its supertypes, resolved supertype FQN)
+    let mut updates: Vec<(String, usize, String)> = Vec::new();
+    for (class_fqn, parents) in &index.supertypes {
+        let file_info = index
+            .by_fqn
+            .get(class_fqn)
+            .and_then(|occs| occs.iter().find(|o| o.kind.is_type_declaration()))
+            .filter(|decl| in_scope(files, &decl.file))
+            .and_then(|decl| index.files.get(&decl.file));
+        let file_info = match file_info {
+            Some(fi) => fi,
+            None => continue,
+        };
+        for (position, parent) in parents.iter().enumerate() {
+            if known_fqns.contains(parent.as_str()) {
+                continue;
+            }
+            let simple_name = parent.rsplit('.').next().unwrap_or(parent.as_str());
+            if let Some(resolved) = resolve_symbol_fqn(
+                simple_name,
+                file_info,
+                declarations_by_name,
+                &index.type_aliases,
+            ) {
+                updates.push((class_fqn.clone(), position, resolved));
+            }
+        }
+    }
+
+    for (class_fqn, position, resolved) in updates {
+        if let Some(parent) = index
+            .supertypes
+            .get_mut(&class_fqn)
+            .and_then(|parents| parents.get_mut(position))
+        {
+            *parent = resolved;
+        }
+    }
+}
+
+/// Resolve `member` accessed through `receiver` (e.g., `Config.load()`) to a declaration
+/// in the companion object of the receiver class, such as `com.example.Config.Companion.load`.
+/// Returns None when the receiver is not a known class or its companion has no such member.
+fn resolve_companion_member(
+    member: &str,
+    receiver: &str,
+    file_info: &super::FileInfo,
+    declarations_by_name: &std::collections::HashMap<String, Vec<(String, std::path::PathBuf)>>,
+    type_aliases: &std::collections::HashMap<String, String>,
+    companions_by_class: &std::collections::HashMap<String, Vec<String>>,
+) -> Option<String> {
+    // A dotted receiver is taken as a fully qualified class name; anything else
+    // (variables, calls) simply won't match a class with a companion.
+    let class_fqn = if receiver.contains('.') {
+        receiver.to_string()
+    } else {
+        resolve_symbol_fqn(receiver, file_info, declarations_by_name, type_aliases)?
+    };
+    let member_decls = declarations_by_name.get(member)?;
+    companions_by_class.get(&class_fqn)?.iter().find_map(|companion_fqn| {
+        let candidate = format!("{}.{}", companion_fqn, member);
+        member_decls
+            .iter()
+            .any(|(fqn, _)| *fqn == candidate)
+            .then_some(candidate)
+    })
+}
+
+/// Resolve `member` accessed through a type `receiver` (e.g., `Outer.Inner` or
+/// `Outer.Inner.CONSTANT`) to a declaration nested in that type, such as
+/// `com.example.Outer.Inner`. The receiver's first segment is resolved through the file's
+/// imports and package; a lowercase first segment is taken as a package, so the receiver
+/// is already fully qualified. Variables and calls won't match a declaration.
+fn resolve_nested_member(
+    member: &str,
+    receiver: &str,
+    file_info: &super::FileInfo,
+    declarations_by_name: &std::collections::HashMap<String, Vec<(String, std::path::PathBuf)>>,
+    type_aliases: &std::collections::HashMap<String, String>,
+) -> Option<String> {
+    let (head, rest) = match receiver.split_once('.') {
+        Some((head, rest)) => (head, Some(rest)),
+        None => (receiver, None),
+    };
+    let outer_fqn = if head.starts_with(|c: char| c.is_ascii_uppercase()) {
+        let head_fqn = resolve_symbol_fqn(head, file_info, declarations_by_name, type_aliases)?;
+        match rest {
+            Some(rest) => format!("{}.{}", head_fqn, rest),
+            None => head_fqn,
+        }
+    } else if rest.is_some() {
+        receiver.to_string()
+    } else {
+        return None;
+    };
+    let candidate = format!("{}.{}", outer_fqn, member);
+    declarations_by_name
+        .get(member)?
+        .iter()
+        .any(|(fqn, _)| *fqn == candidate)
+        .then_some(candidate)
+}
+
 /// Resolve a symbol name to its FQN using the import resolution order:
 /// 1. Same-file declarations
 /// 2. Explicit imports
@@ -138,29 +540,24 @@ fn resolve_symbol_fqn(
     }
 
     // 3. Wildcard imports
-    for imp in &file_info.imports {
-        if !imp.is_wildcard {
-            continue;
-        }
-        let candidate_fqn = format!("{}.{}", imp.path, name);
-        // Check if this FQN exists in declarations
-        if let Some(decls) = declarations_by_name.get(name) {
-            for (fqn, _) in decls {
-                if *fqn == candidate_fqn {
-                    return Some(follow_type_alias(&candidate_fqn, type_aliases));
-                }
-            }
-        }
+    if let Some(fqn) = resolve_wildcard_import(name, file_info, declarations_by_name, type_aliases) {
+        return Some(fqn);
     }
 
-    // 4. Same-package declarations
-    if let Some(ref pkg) = file_info.package {
-        let candidate_fqn = format!("{}.{}", pkg, name);
-        if let Some(decls) = declarations_by_name.get(name) {
-            for (fqn, _) in decls {
-                if *fqn == candidate_fqn {
-                    return Some(candidate_fqn);
-                }
+    // 4. Same-package declarations. Files without a package header share the default
+    // package, each under its own synthetic name
+    if let Some(decls) = declarations_by_name.get(name) {
+        for (fqn, _) in decls {
+            let package = fqn
+                .strip_suffix(name)
+                .and_then(|prefix| prefix.strip_suffix('.'));
+            let same_package = match (&file_info.package, package) {
+                (Some(pkg), Some(package)) => pkg == package,
+                (None, Some(package)) => is_default_package(package),
+                (_, None) => false,
+            };
+            if same_package {
+                return Some(fqn.clone());
             }
         }
     }
@@ -182,6 +579,47 @@ fn resolve_symbol_fqn(
     None
 }
 
+/// Resolve `name` as written in `file_info` to the FQN of a declaration, using the same
+/// import, same-file and same-package rules as [`cross_reference`].
+pub fn resolve_name_in_file(index: &SymbolIndex, name: &str, file_info: &super::FileInfo) -> Option<String> {
+    let declarations: Vec<(String, std::path::PathBuf)> = index
+        .by_name
+        .get(name)
+        .into_iter()
+        .flatten()
+        .filter(|occ| occ.kind.is_declaration())
+        .filter_map(|occ| occ.fqn.clone().map(|fqn| (fqn, occ.file.clone())))
+        .filter(|(fqn, _)| !is_local(fqn))
+        .collect();
+    let mut declarations_by_name = std::collections::HashMap::new();
+    declarations_by_name.insert(name.to_string(), declarations);
+    resolve_symbol_fqn(name, file_info, &declarations_by_name, &index.type_aliases)
+}
+
+/// Resolve a name through the file's wildcard imports by checking every declaration
+/// named `name` against each imported prefix: a package, or a type for member imports like
+/// `import com.example.Outer.*` (nested classes, enum entries, static members of a Java
+/// class). Kotlin rejects star imports from objects, so their members never come this way.
+fn resolve_wildcard_import(
+    name: &str,
+    file_info: &super::FileInfo,
+    declarations_by_name: &std::collections::HashMap<String, Vec<(String, std::path::PathBuf)>>,
+    type_aliases: &std::collections::HashMap<String, String>,
+) -> Option<String> {
+    let decls = declarations_by_name.get(name)?;
+    for imp in file_info.imports.iter().filter(|imp| imp.is_wildcard) {
+        let matched = decls.iter().find(|(fqn, _)| {
+            fqn.strip_prefix(imp.path.as_str())
+                .and_then(|rest| rest.strip_prefix('.'))
+                .is_some_and(|rest| rest == name)
+        });
+        if let Some((fqn, _)) = matched {
+            return Some(follow_type_alias(fqn, type_aliases));
+        }
+    }
+    None
+}
+
 fn follow_type_alias(fqn: &str, type_aliases: &std::collections::HashMap<String, String>) -> String {
     let mut current = fqn.to_string();
     let mut seen = std::collections::HashSet::new();
@@ -194,28 +632,65 @@ fn follow_type_alias(fqn: &str, type_aliases: &std::collections::HashMap<String,
     current
 }
 
-/// Register companion object members under both `MyClass.Companion.member` and `MyClass.member`.
+/// FQNs of every companion object in the index, named (`MyClass.Factory`) or not
+/// (`MyClass.Companion`).
+pub fn companion_fqns(index: &SymbolIndex) -> std::collections::HashSet<String> {
+    index
+        .by_name
+        .values()
+        .flatten()
+        .filter(|occ| occ.kind == SymbolKind::CompanionObjectDeclaration)
+        .filter_map(|occ| occ.fqn.clone())
+        .collect()
+}
+
+/// The `MyClass.member` form of a member declared in a companion object
+/// (`MyClass.Companion.member`, `MyClass.Factory.member`): every companion segment
+/// is dropped. None when `fqn` isn't inside a companion.
+pub fn strip_companion_segments(fqn: &str, companions: &std::collections::HashSet<String>) -> Option<String> {
+    let segments: Vec<&str> = fqn.split('.').collect();
+    let mut kept: Vec<&str> = Vec::with_capacity(segments.len());
+    let mut prefix = String::new();
+    for (i, segment) in segments.iter().enumerate() {
+        if !prefix.is_empty() {
+            prefix.push('.');
+        }
+        prefix.push_str(segment);
+        // The companion itself (the last segment) keeps its name
+        if i + 1 < segments.len() && companions.contains(&prefix) {
+            continue;
+        }
+        kept.push(segment);
+    }
+    (kept.len() < segments.len()).then(|| kept.join("."))
+}
+
+/// Register companion object members under both `MyClass.Companion.member` (or
+/// `MyClass.Factory.member` for `companion object Factory`) and `MyClass.member`.
 pub fn register_companion_aliases(index: &mut SymbolIndex) {
-    let mut new_entries: Vec<SymbolOccurrence> = Vec::new();
+    let companions = companion_fqns(index);
+    // (alias FQN, occurrence): the occurrence keeps its declared FQN, so either form of the
+    // name leads to `MyClass.Factory.member`
+    let mut new_entries: Vec<(String, SymbolOccurrence)> = Vec::new();
 
     for occs in index.by_fqn.values() {
         for occ in occs {
             if let Some(ref fqn) = occ.fqn {
-                // Check if this is inside a Companion object
-                if fqn.contains(".Companion.") {
-                    // Create an alias without .Companion.
-                    let alias_fqn = fqn.replace(".Companion.", ".");
-                    let mut alias_occ = occ.clone();
-                    alias_occ.fqn = Some(alias_fqn);
-                    new_entries.push(alias_occ);
+                if let Some(alias_fqn) = strip_companion_segments(fqn, &companions) {
+                    new_entries.push((alias_fqn, occ.clone()));
                 }
             }
         }
     }
 
-    for occ in new_entries {
-        if let Some(ref fqn) = occ.fqn {
-            index.by_fqn.entry(fqn.clone()).or_default().push(occ);
+    // Skip aliases registered by an earlier run, so the index can be refreshed in place
+    for (alias_fqn, occ) in new_entries {
+        let entries = index.by_fqn.entry(alias_fqn).or_default();
+        if !entries
+            .iter()
+            .any(|o| o.file == occ.file && o.byte_range == occ.byte_range && o.kind == occ.kind)
+        {
+            entries.push(occ);
         }
     }
 }
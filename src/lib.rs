@@ -1,5 +1,9 @@
 pub mod error;
 pub mod gradle;
 pub mod indexer;
+pub mod prompts;
+pub mod resources;
+pub mod rpc_log;
 pub mod server;
 pub mod tools;
+pub mod watcher;
@@ -1,6 +1,13 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicI8, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 
-use clap::{Parser, Subcommand};
+use clap::{ArgAction, Parser, Subcommand, ValueEnum};
+use kotlin_java_mcp::indexer::filter::FileFilter;
+use kotlin_java_mcp::indexer::SymbolKind;
+use kotlin_java_mcp::rpc_log::{LoggedReader, LoggedWriter, RpcLog};
+use kotlin_java_mcp::tools::{GroupBy, NameMatch};
 use rmcp::ServiceExt;
 use tracing_subscriber::EnvFilter;
 
@@ -11,13 +18,100 @@ struct Args {
     #[arg(short, long, default_value = ".")]
     project: PathBuf,
 
+    /// Index --project as given instead of walking up to the outermost settings.gradle(.kts)
+    /// or the repository root
+    #[arg(long)]
+    no_auto_root: bool,
+
+    /// Read Gradle dependencies from build scripts instead of invoking the Gradle wrapper
+    #[arg(long)]
+    offline: bool,
+
+    /// Seconds to reuse resolved Gradle dependencies from the on-disk cache (0 disables it)
+    #[arg(long, default_value_t = 600)]
+    gradle_cache_ttl: u64,
+
+    /// Only index files under paths matching this glob, relative to the project root (repeatable)
+    #[arg(long = "include", value_name = "GLOB")]
+    includes: Vec<String>,
+
+    /// Skip files and directories matching this glob, e.g. '**/generated/**' (repeatable)
+    #[arg(long = "exclude", value_name = "GLOB")]
+    excludes: Vec<String>,
+
+    /// Skip test sources (src/test, src/androidTest and other test directories)
+    #[arg(long)]
+    no_tests: bool,
+
+    /// Skip source files larger than this many KiB, e.g. giant generated files (0 disables the limit)
+    #[arg(long, value_name = "KIB", default_value_t = 1024)]
+    max_file_size_kb: u64,
+
+    /// Number of threads parsing files while indexing (default: one per CPU core)
+    #[arg(short, long, value_name = "N")]
+    jobs: Option<usize>,
+
+    /// Re-index changed files while the server runs (debounced; honors --include/--exclude)
+    #[arg(long)]
+    watch: bool,
+
+    /// MCP server transport
+    #[arg(long, value_enum, default_value_t = Transport::Stdio)]
+    transport: Transport,
+
+    /// Address to bind for the sse and tcp transports
+    #[arg(long, default_value = "127.0.0.1")]
+    host: String,
+
+    /// Port to bind for the sse and tcp transports
+    #[arg(long, default_value_t = 8080)]
+    port: u16,
+
+    /// Append every JSON-RPC message of the stdio and tcp transports, timestamped, to this file
+    /// (messages are also logged at debug level, e.g. with RUST_LOG=kotlin_java_mcp::rpc=debug).
+    /// Rejected with the sse transport
+    #[arg(long, value_name = "FILE")]
+    json_rpc_log: Option<PathBuf>,
+
+    /// Only print results: no indexing progress on stderr, and only errors are logged
+    #[arg(short, long, conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// Log more on stderr; repeat for even more (-vv)
+    #[arg(short, long, action = ArgAction::Count)]
+    verbose: u8,
+
     #[command(subcommand)]
     command: Option<Command>,
 }
 
+/// Log verbosity from `--quiet` (-1) and `-v` (one step each), set once at startup.
+static VERBOSITY: AtomicI8 = AtomicI8::new(0);
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Transport {
+    /// JSON-RPC over stdin/stdout
+    #[default]
+    Stdio,
+    /// Streamable HTTP with SSE responses, served at /mcp
+    Sse,
+    /// Newline-delimited JSON-RPC over raw TCP connections
+    Tcp,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default)]
+enum OutputFormat {
+    /// Human-readable listing
+    #[default]
+    Text,
+    /// JSON array of occurrences (dependency nodes for dependency-tree)
+    Json,
+}
+
 #[derive(Subcommand, Debug)]
 enum Command {
-    /// Start the MCP server (stdio transport) — this is the default when no subcommand is given
+    /// Start the MCP server over the transport chosen by --transport (stdio, sse or tcp) — this
+    /// is the default when no subcommand is given
     Serve,
 
     /// Find all usages/references of a symbol
@@ -36,6 +130,45 @@ enum Command {
         /// Include import statements in results
         #[arg(long)]
         include_imports: bool,
+
+        /// Show N lines of source around each usage (text output only)
+        #[arg(long, value_name = "N")]
+        context: Option<usize>,
+
+        /// Only return occurrences of this kind (e.g., TypeReference); repeatable
+        #[arg(long = "kind", value_name = "KIND", value_parser = parse_symbol_kind)]
+        kinds: Vec<SymbolKind>,
+
+        /// Return at most N usages; the output reports the total
+        #[arg(long, value_name = "N")]
+        limit: Option<usize>,
+
+        /// Skip the first N usages (for paging with --limit)
+        #[arg(long, value_name = "N", default_value_t = 0)]
+        offset: usize,
+
+        /// Only report usages in files under this directory, relative to the project root
+        /// (e.g., a Gradle module)
+        #[arg(long, value_name = "DIR")]
+        scope: Option<PathBuf>,
+
+        /// Group the text output by file or by kind, with a count per group: none, file or kind
+        #[arg(long, value_name = "GROUPING", default_value = "none", value_parser = parse_group_by)]
+        group_by: GroupBy,
+
+        /// Start with a summary of all usages by kind and file; JSON output becomes an object
+        /// with a `summary`
+        #[arg(long)]
+        summary: bool,
+
+        /// How the symbol is matched against indexed names: exact, prefix (`User` also
+        /// matches `UserService`) or ci (case-insensitive)
+        #[arg(long = "match", value_name = "MODE", default_value = "exact", value_parser = parse_name_match)]
+        name_match: NameMatch,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
     },
 
     /// Find the definition/declaration of a symbol
@@ -50,23 +183,254 @@ enum Command {
         /// Optional line number for precise resolution
         #[arg(short, long)]
         line: Option<usize>,
+
+        /// How the symbol is matched against indexed names: exact, prefix (`User` also
+        /// matches `UserService`) or ci (case-insensitive)
+        #[arg(long = "match", value_name = "MODE", default_value = "exact", value_parser = parse_name_match)]
+        name_match: NameMatch,
+
+        /// When several declarations match, keep those in the Gradle module of --file or in
+        /// modules it depends on (runs Gradle unless --offline)
+        #[arg(long, requires = "file")]
+        via_module_dependencies: bool,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+
+    /// Find all classes and objects that extend or implement a class/interface
+    FindImplementations {
+        /// Class or interface name (simple or fully-qualified)
+        symbol: String,
+    },
+
+    /// Show the package, imports and nested declarations of a single file
+    Outline {
+        /// Kotlin or Java file (absolute or relative to the project root)
+        file: PathBuf,
+    },
+
+    /// List the symbols a single file references, grouped by FQN with counts
+    FileReferences {
+        /// Kotlin or Java file (absolute or relative to the project root)
+        file: PathBuf,
+    },
+
+    /// List the symbols declared in a package, grouped by file
+    ListSymbolsInPackage {
+        /// Package name (e.g., com.example.core)
+        package: String,
+
+        /// Include nested declarations, members and subpackages
+        #[arg(short, long)]
+        recursive: bool,
+    },
+
+    /// Search declarations by fuzzy name match
+    SearchSymbols {
+        /// Fuzzy query (e.g., "UsrSvc" matches "UserService")
+        query: String,
+
+        /// Maximum number of results
+        #[arg(long, default_value_t = kotlin_java_mcp::tools::search_symbols::DEFAULT_SEARCH_LIMIT)]
+        limit: usize,
+
+        /// Only return declarations of this kind (e.g., ClassDeclaration)
+        #[arg(long)]
+        kind: Option<String>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+
+    /// List the most-referenced declarations with their reference and file counts
+    Hotspots {
+        /// Maximum number of declarations
+        #[arg(long, default_value_t = kotlin_java_mcp::tools::hotspots::DEFAULT_HOTSPOT_LIMIT)]
+        limit: usize,
+    },
+
+    /// Print index metrics as JSON: totals, occurrences per kind and per file, unresolved references
+    Stats,
+
+    /// Show a module's Gradle dependencies, or list the project modules without one
+    DependencyTree {
+        /// Gradle module path (e.g., :app)
+        module: Option<String>,
+
+        /// Gradle configuration to resolve, e.g. runtimeClasspath (default: compileClasspath)
+        #[arg(short, long)]
+        configuration: Option<String>,
+
+        /// Show at most N levels of the tree; 1 shows only the direct dependencies
+        #[arg(long, value_name = "N")]
+        max_depth: Option<usize>,
+
+        /// List every artifact once, sorted, with its resolved version instead of the tree
+        #[arg(long)]
+        flatten: bool,
+
+        /// Output format; json prints the nested dependency nodes
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
     },
 }
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let args = Args::parse();
+    let verbosity = if args.quiet { -1 } else { args.verbose.min(i8::MAX as u8) as i8 };
+    VERBOSITY.store(verbosity, Ordering::Relaxed);
     let project_root = args.project.canonicalize()?;
+    let project_root = if args.no_auto_root {
+        project_root
+    } else {
+        let detected = kotlin_java_mcp::gradle::detect_project_root(&project_root);
+        if detected != project_root && !is_quiet() {
+            eprintln!(
+                "Using project root {} (detected from {}; pass --no-auto-root to disable)",
+                detected.display(),
+                project_root.display()
+            );
+        }
+        detected
+    };
+    let max_file_size = match args.max_file_size_kb {
+        0 => None,
+        kib => Some(kib * 1024),
+    };
+    let filter = FileFilter::new(&args.includes, &args.excludes)?
+        .with_max_file_size(max_file_size)
+        .with_exclude_tests(args.no_tests);
+    if let Some(jobs) = args.jobs {
+        rayon::ThreadPoolBuilder::new().num_threads(jobs).build_global()?;
+    }
 
     match args.command {
-        None | Some(Command::Serve) => run_server(project_root).await,
-        Some(Command::FindUsages { symbol, file, line, include_imports }) => {
+        None | Some(Command::Serve) => {
+            let addr = format!("{}:{}", args.host, args.port);
+            let gradle_cache_ttl = Duration::from_secs(args.gradle_cache_ttl);
+            // The sse transport's messages travel through the HTTP server, which isn't hooked
+            // up to the log; rejecting the combination beats leaving an empty log file behind
+            if args.json_rpc_log.is_some() && matches!(args.transport, Transport::Sse) {
+                anyhow::bail!("--json-rpc-log is only supported with the stdio and tcp transports");
+            }
+            let rpc_log = match args.json_rpc_log {
+                Some(ref path) => RpcLog::to_file(path)?,
+                None => RpcLog::tracing_only(),
+            };
+            run_server(
+                project_root,
+                args.offline,
+                gradle_cache_ttl,
+                filter,
+                args.watch,
+                args.transport,
+                &addr,
+                rpc_log,
+            )
+            .await
+        }
+        Some(Command::FindUsages {
+            symbol,
+            file,
+            line,
+            include_imports,
+            context,
+            kinds,
+            limit,
+            offset,
+            scope,
+            group_by,
+            summary,
+            name_match,
+            format,
+        }) => {
+            init_cli_tracing();
+            run_find_usages(
+                project_root,
+                &filter,
+                &symbol,
+                file.as_deref(),
+                line,
+                include_imports,
+                context,
+                &kinds,
+                limit,
+                offset,
+                scope.as_deref(),
+                group_by,
+                summary,
+                name_match,
+                format,
+            )
+        }
+        Some(Command::FindDefinition {
+            symbol,
+            file,
+            line,
+            name_match,
+            via_module_dependencies,
+            format,
+        }) => {
+            init_cli_tracing();
+            let runner = via_module_dependencies.then(|| {
+                kotlin_java_mcp::gradle::GradleRunner::new(project_root.clone(), args.offline)
+                    .with_cache_ttl(Duration::from_secs(args.gradle_cache_ttl))
+            });
+            run_find_definition(
+                project_root,
+                &filter,
+                &symbol,
+                file.as_deref(),
+                line,
+                name_match,
+                runner.as_ref(),
+                format,
+            )
+        }
+        Some(Command::FindImplementations { symbol }) => {
+            init_cli_tracing();
+            run_find_implementations(project_root, &filter, &symbol)
+        }
+        Some(Command::Outline { file }) => {
+            init_cli_tracing();
+            run_outline(project_root, &filter, &file)
+        }
+        Some(Command::FileReferences { file }) => {
+            init_cli_tracing();
+            run_file_references(project_root, &filter, &file)
+        }
+        Some(Command::ListSymbolsInPackage { package, recursive }) => {
+            init_cli_tracing();
+            run_list_symbols_in_package(project_root, &filter, &package, recursive)
+        }
+        Some(Command::SearchSymbols { query, limit, kind, format }) => {
+            init_cli_tracing();
+            run_search_symbols(project_root, &filter, &query, limit, kind.as_deref(), format)
+        }
+        Some(Command::Hotspots { limit }) => {
             init_cli_tracing();
-            run_find_usages(project_root, &symbol, file.as_deref(), line, include_imports)
+            run_hotspots(project_root, &filter, limit)
         }
-        Some(Command::FindDefinition { symbol, file, line }) => {
+        Some(Command::Stats) => {
             init_cli_tracing();
-            run_find_definition(project_root, &symbol, file.as_deref(), line)
+            run_stats(project_root, &filter)
+        }
+        Some(Command::DependencyTree { module, configuration, max_depth, flatten, format }) => {
+            init_cli_tracing();
+            let runner = kotlin_java_mcp::gradle::GradleRunner::new(project_root, args.offline)
+                .with_cache_ttl(Duration::from_secs(args.gradle_cache_ttl));
+            run_dependency_tree(
+                &runner,
+                module.as_deref(),
+                configuration.as_deref(),
+                max_depth,
+                flatten,
+                format,
+            )
         }
     }
 }
@@ -74,40 +438,145 @@ async fn main() -> anyhow::Result<()> {
 fn init_cli_tracing() {
     tracing_subscriber::fmt()
         .with_writer(std::io::stderr)
-        .with_env_filter(
-            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("warn")),
-        )
+        .with_env_filter(log_filter("warn"))
         .with_ansi(true)
         .init();
 }
 
-async fn run_server(project_root: PathBuf) -> anyhow::Result<()> {
-    // MCP server logs to stderr, protocol uses stdout
+/// The tracing filter: RUST_LOG, else `default_level`. `--quiet` and `-v` override both,
+/// logging errors only or `default_level` raised by one level per `-v`.
+fn log_filter(default_level: &str) -> EnvFilter {
+    const LEVELS: [&str; 5] = ["error", "warn", "info", "debug", "trace"];
+    let verbosity = VERBOSITY.load(Ordering::Relaxed);
+    if verbosity < 0 {
+        return EnvFilter::new("error");
+    }
+    if verbosity == 0 {
+        return EnvFilter::try_from_default_env()
+            .unwrap_or_else(|_| EnvFilter::new(default_level));
+    }
+    let default_at = LEVELS.iter().position(|l| *l == default_level).unwrap_or(1);
+    EnvFilter::new(LEVELS[(default_at + verbosity as usize).min(LEVELS.len() - 1)])
+}
+
+fn is_quiet() -> bool {
+    VERBOSITY.load(Ordering::Relaxed) < 0
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn run_server(
+    project_root: PathBuf,
+    offline: bool,
+    gradle_cache_ttl: Duration,
+    file_filter: FileFilter,
+    watch: bool,
+    transport: Transport,
+    addr: &str,
+    rpc_log: Arc<RpcLog>,
+) -> anyhow::Result<()> {
+    // MCP server logs to stderr; with stdio transport, the protocol uses stdout
     tracing_subscriber::fmt()
         .with_writer(std::io::stderr)
-        .with_env_filter(
-            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
-        )
+        .with_env_filter(log_filter("info"))
         .with_ansi(false)
         .init();
 
     tracing::info!("Starting kotlin-java-mcp server for {}", project_root.display());
 
-    let server = kotlin_java_mcp::server::KotlinMcpServer::new(project_root);
-    let service = server.serve(rmcp::transport::stdio()).await?;
-    service.waiting().await?;
+    // The index is built once, in the background; clones of the server share it across
+    // connections
+    let server =
+        kotlin_java_mcp::server::KotlinMcpServer::new(project_root, offline, gradle_cache_ttl, file_filter);
+    // Runs on its own thread; kept alive for as long as the server is serving
+    let _watcher = if watch { Some(server.watch()?) } else { None };
+
+    match transport {
+        Transport::Stdio => {
+            // Only the protocol's own bytes reach stdout; the log goes to its file and stderr
+            let (stdin, stdout) = rmcp::transport::stdio();
+            let transport =
+                (LoggedReader::new(stdin, rpc_log.clone()), LoggedWriter::new(stdout, rpc_log));
+            let service = server.serve(transport).await?;
+            service.waiting().await?;
+        }
+        Transport::Sse => serve_http(server, addr).await?,
+        Transport::Tcp => serve_tcp(server, addr, rpc_log).await?,
+    }
 
     Ok(())
 }
 
+async fn serve_http(server: kotlin_java_mcp::server::KotlinMcpServer, addr: &str) -> anyhow::Result<()> {
+    use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+    use rmcp::transport::streamable_http_server::StreamableHttpService;
+
+    let service = StreamableHttpService::new(
+        move || Ok(server.clone()),
+        LocalSessionManager::default().into(),
+        Default::default(),
+    );
+    let router = axum::Router::new().nest_service("/mcp", service);
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    tracing::info!("Serving MCP over HTTP/SSE at http://{}/mcp", listener.local_addr()?);
+    axum::serve(listener, router).await?;
+    Ok(())
+}
+
+async fn serve_tcp(
+    server: kotlin_java_mcp::server::KotlinMcpServer,
+    addr: &str,
+    rpc_log: Arc<RpcLog>,
+) -> anyhow::Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    tracing::info!("Serving MCP over TCP at {}", listener.local_addr()?);
+
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        tracing::info!("Accepted connection from {}", peer);
+        let server = server.clone();
+        let (read, write) = tokio::io::split(stream);
+        let transport =
+            (LoggedReader::new(read, rpc_log.clone()), LoggedWriter::new(write, rpc_log.clone()));
+        tokio::spawn(async move {
+            match server.serve(transport).await {
+                Ok(service) => {
+                    if let Err(e) = service.waiting().await {
+                        tracing::warn!("Connection from {} ended with error: {}", peer, e);
+                    }
+                }
+                Err(e) => tracing::warn!("Failed to start MCP session for {}: {}", peer, e),
+            }
+            tracing::info!("Connection from {} closed", peer);
+        });
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn run_find_usages(
     project_root: PathBuf,
+    filter: &FileFilter,
     symbol: &str,
     file: Option<&str>,
     line: Option<usize>,
     include_imports: bool,
+    context: Option<usize>,
+    kinds: &[SymbolKind],
+    limit: Option<usize>,
+    offset: usize,
+    scope: Option<&Path>,
+    group_by: GroupBy,
+    summary: bool,
+    name_match: NameMatch,
+    format: OutputFormat,
 ) -> anyhow::Result<()> {
-    let index = build_index(&project_root);
+    let scope = scope.map(|s| project_root.join(s));
+    if let Some(ref scope) = scope {
+        if !scope.exists() {
+            anyhow::bail!("Scope path not found: {}", scope.display());
+        }
+    }
+    let index = build_index(&project_root, filter);
 
     let file_path = file.map(|f| {
         let p = PathBuf::from(f);
@@ -118,21 +587,53 @@ fn run_find_usages(
         }
     });
 
-    let results =
-        kotlin_java_mcp::tools::find_usages::find_usages(&index, symbol, file_path.as_deref(), line, include_imports);
+    // No --kind flags means no filtering
+    let kinds = if kinds.is_empty() { None } else { Some(kinds) };
+    let mut results = kotlin_java_mcp::tools::find_usages::find_usages_matching(
+        &index,
+        symbol,
+        file_path.as_deref(),
+        line,
+        include_imports,
+        kinds,
+        name_match,
+    );
+    if let Some(ref scope) = scope {
+        kotlin_java_mcp::tools::find_usages::retain_in_scope(&mut results, scope);
+    }
+    warn_if_stale(&index, &results, &project_root);
 
-    let output = kotlin_java_mcp::tools::format_occurrences(&results, &project_root);
+    let paginated = limit.is_some() || offset > 0;
+    let page = kotlin_java_mcp::tools::Page::new(results, offset, limit);
+    let output = match format {
+        OutputFormat::Text if summary => {
+            kotlin_java_mcp::tools::format_page_with_summary(&page, &project_root, context, group_by)
+        }
+        OutputFormat::Text => {
+            kotlin_java_mcp::tools::format_page_grouped(&page, &project_root, context, group_by)
+        }
+        // Without paging or summary flags the JSON stays a plain array
+        OutputFormat::Json if paginated || summary => {
+            kotlin_java_mcp::tools::format_page_json(&page, &project_root)
+        }
+        OutputFormat::Json => format_results(&page.items, &project_root, format),
+    };
     println!("{}", output);
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn run_find_definition(
     project_root: PathBuf,
+    filter: &FileFilter,
     symbol: &str,
     file: Option<&str>,
     line: Option<usize>,
+    name_match: NameMatch,
+    module_runner: Option<&kotlin_java_mcp::gradle::GradleRunner>,
+    format: OutputFormat,
 ) -> anyhow::Result<()> {
-    let index = build_index(&project_root);
+    let index = build_index(&project_root, filter);
 
     let file_path = file.map(|f| {
         let p = PathBuf::from(f);
@@ -143,26 +644,201 @@ fn run_find_definition(
         }
     });
 
-    let results = kotlin_java_mcp::tools::find_definition::find_definition(
+    let mut results = kotlin_java_mcp::tools::find_definition::find_definition_matching(
         &index,
         symbol,
         file_path.as_deref(),
         line,
+        name_match,
     );
+    // clap makes --via-module-dependencies require --file
+    if let (Some(runner), Some(file)) = (module_runner, file_path.as_deref()) {
+        kotlin_java_mcp::tools::symbol_module::retain_in_module_dependencies(
+            &mut results,
+            runner,
+            file,
+        )?;
+    }
+    warn_if_stale(&index, &results, &project_root);
+
+    // The stdlib fallback has no source location, so JSON output stays an empty array
+    if results.is_empty() && name_match == NameMatch::Exact && matches!(format, OutputFormat::Text) {
+        use kotlin_java_mcp::tools::find_definition::{find_stdlib_definition, format_stdlib_definition};
+        if let Some(fqn) = find_stdlib_definition(&index, symbol) {
+            println!("{}", format_stdlib_definition(symbol, fqn));
+            return Ok(());
+        }
+    }
+
+    println!("{}", format_results(&results, &project_root, format));
+    Ok(())
+}
+
+fn run_find_implementations(project_root: PathBuf, filter: &FileFilter, symbol: &str) -> anyhow::Result<()> {
+    let index = build_index(&project_root, filter);
+
+    let results = kotlin_java_mcp::tools::find_implementations::find_implementations(&index, symbol);
+    warn_if_stale(&index, &results, &project_root);
 
     let output = kotlin_java_mcp::tools::format_occurrences(&results, &project_root);
     println!("{}", output);
     Ok(())
 }
 
-fn build_index(project_root: &PathBuf) -> kotlin_java_mcp::indexer::SymbolIndex {
-    use kotlin_java_mcp::indexer::parser::index_files;
+fn run_outline(project_root: PathBuf, filter: &FileFilter, file: &Path) -> anyhow::Result<()> {
+    let index = build_index(&project_root, filter);
+
+    let file_path = if file.is_relative() {
+        project_root.join(file)
+    } else {
+        file.to_path_buf()
+    };
+
+    let output = kotlin_java_mcp::tools::outline::format_outline(&index, &file_path, &project_root);
+    println!("{}", output);
+    Ok(())
+}
+
+fn run_file_references(project_root: PathBuf, filter: &FileFilter, file: &Path) -> anyhow::Result<()> {
+    let index = build_index(&project_root, filter);
+
+    let file_path = if file.is_relative() {
+        project_root.join(file)
+    } else {
+        file.to_path_buf()
+    };
+
+    let output = kotlin_java_mcp::tools::file_references::format_file_references(
+        &index,
+        &file_path,
+        &project_root,
+    );
+    println!("{}", output);
+    Ok(())
+}
+
+fn run_list_symbols_in_package(
+    project_root: PathBuf,
+    filter: &FileFilter,
+    package: &str,
+    recursive: bool,
+) -> anyhow::Result<()> {
+    use kotlin_java_mcp::tools::list_symbols_in_package::{format_package_symbols, list_symbols_in_package};
+
+    let index = build_index(&project_root, filter);
+
+    let symbols = list_symbols_in_package(&index, package, recursive);
+
+    println!("{}", format_package_symbols(&symbols, package, &project_root));
+    Ok(())
+}
+
+fn run_search_symbols(
+    project_root: PathBuf,
+    filter: &FileFilter,
+    query: &str,
+    limit: usize,
+    kind: Option<&str>,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    let index = build_index(&project_root, filter);
+
+    let results = kotlin_java_mcp::tools::search_symbols::search_symbols(&index, query, limit, kind);
+
+    println!("{}", format_results(&results, &project_root, format));
+    Ok(())
+}
+
+fn run_hotspots(project_root: PathBuf, filter: &FileFilter, limit: usize) -> anyhow::Result<()> {
+    use kotlin_java_mcp::tools::hotspots::{format_hotspots, hotspots};
+
+    let index = build_index(&project_root, filter);
+
+    println!("{}", format_hotspots(&hotspots(&index, limit), &project_root));
+    Ok(())
+}
+
+fn run_stats(project_root: PathBuf, filter: &FileFilter) -> anyhow::Result<()> {
+    let index = build_index(&project_root, filter);
+
+    let stats = index.detailed_stats(&project_root);
+    println!("{}", serde_json::to_string_pretty(&stats)?);
+    Ok(())
+}
+
+fn run_dependency_tree(
+    runner: &kotlin_java_mcp::gradle::GradleRunner,
+    module: Option<&str>,
+    configuration: Option<&str>,
+    max_depth: Option<usize>,
+    flatten: bool,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    use kotlin_java_mcp::tools::dependency_tree::{dependency_tree, dependency_tree_json};
+
+    if max_depth == Some(0) {
+        anyhow::bail!("--max-depth must be at least 1");
+    }
+    match format {
+        OutputFormat::Text => {
+            print!("{}", dependency_tree(runner, module, configuration, max_depth, flatten)?)
+        }
+        OutputFormat::Json => println!(
+            "{}",
+            dependency_tree_json(runner, module, configuration, max_depth, flatten)?
+        ),
+    }
+    Ok(())
+}
+
+fn parse_symbol_kind(s: &str) -> Result<SymbolKind, String> {
+    s.parse()
+}
+
+fn parse_group_by(s: &str) -> Result<GroupBy, String> {
+    s.parse()
+}
+
+fn parse_name_match(s: &str) -> Result<NameMatch, String> {
+    s.parse()
+}
+
+fn format_results(
+    results: &[&kotlin_java_mcp::indexer::SymbolOccurrence],
+    project_root: &Path,
+    format: OutputFormat,
+) -> String {
+    match format {
+        OutputFormat::Text => kotlin_java_mcp::tools::format_occurrences(results, project_root),
+        OutputFormat::Json => kotlin_java_mcp::tools::format_occurrences_json(results, project_root),
+    }
+}
+
+/// Print a warning to stderr when files behind `results` changed since they were indexed.
+fn warn_if_stale(
+    index: &kotlin_java_mcp::indexer::SymbolIndex,
+    results: &[&kotlin_java_mcp::indexer::SymbolOccurrence],
+    project_root: &Path,
+) {
+    let changed = index.changed_since_indexing(results.iter().map(|o| o.file.as_path()));
+    if let Some(warning) = kotlin_java_mcp::tools::format_stale_warning(&changed, project_root) {
+        eprintln!("{}", warning);
+    }
+}
+
+fn build_index(project_root: &Path, filter: &FileFilter) -> kotlin_java_mcp::indexer::SymbolIndex {
+    use kotlin_java_mcp::indexer::parser::index_files_with_filter;
     use kotlin_java_mcp::indexer::symbols::{cross_reference, register_companion_aliases};
 
-    eprintln!("Indexing Kotlin and Java files in {} ...", project_root.display());
-    let mut index = index_files(project_root);
+    let quiet = is_quiet();
+    if !quiet {
+        eprintln!("Indexing Kotlin and Java files in {} ...", project_root.display());
+    }
+    let mut index = index_files_with_filter(project_root, filter);
     cross_reference(&mut index);
     register_companion_aliases(&mut index);
-    eprintln!("{}", index.stats());
+    if !quiet {
+        eprintln!("{}", index.stats());
+    }
     index
 }
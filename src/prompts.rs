@@ -0,0 +1,115 @@
+use rmcp::model::{
+    GetPromptResult, JsonObject, Prompt, PromptArgument, PromptMessage, PromptMessageRole,
+};
+use rmcp::ErrorData as McpError;
+use serde_json::json;
+
+/// Walk from a symbol's declaration to its usages and callers.
+pub const TRACE_SYMBOL: &str = "trace-symbol";
+/// Check what renaming a symbol would touch before doing it.
+pub const ASSESS_RENAME: &str = "assess-rename";
+
+/// The navigation workflows offered as prompts, in name order.
+pub fn list_prompts() -> Vec<Prompt> {
+    vec![
+        Prompt::new(
+            ASSESS_RENAME,
+            Some("Check the declarations, overrides and references a rename of a symbol touches"),
+            Some(vec![
+                argument("symbol", "Symbol to rename, a simple name or FQN", true),
+                argument("new_name", "The name it would get", false),
+            ]),
+        ),
+        Prompt::new(
+            TRACE_SYMBOL,
+            Some("Trace a symbol from its definition through its usages to its callers"),
+            Some(vec![argument("symbol", "Symbol to trace, a simple name or FQN", true)]),
+        ),
+    ]
+}
+
+fn argument(name: &str, description: &str, required: bool) -> PromptArgument {
+    PromptArgument {
+        name: name.to_string(),
+        title: None,
+        description: Some(description.to_string()),
+        required: Some(required),
+    }
+}
+
+/// The messages of prompt `name` with its `arguments` filled in. Unknown prompts and
+/// missing required arguments are invalid params.
+pub fn get_prompt(name: &str, arguments: Option<&JsonObject>) -> Result<GetPromptResult, McpError> {
+    let text = match name {
+        TRACE_SYMBOL => {
+            let symbol = required_argument(name, arguments, "symbol")?;
+            format!(
+                "Trace the Kotlin/Java symbol `{symbol}` through the project:\n\
+                 1. Call `find_definition` with symbol `{symbol}`. If it returns several \
+                 declarations, say which one you follow and pass its file and line to the \
+                 next calls.\n\
+                 2. Call `find_usages` for the same symbol and summarize where it is used, \
+                 grouped by file.\n\
+                 3. If it is a function, call `incoming_calls` to list its callers, and repeat \
+                 for the most relevant caller to show how execution reaches `{symbol}`.\n\
+                 Finish with a short explanation of the symbol's role.",
+                symbol = symbol
+            )
+        }
+        ASSESS_RENAME => {
+            let symbol = required_argument(name, arguments, "symbol")?;
+            let target = match optional_argument(arguments, "new_name") {
+                Some(new_name) => format!("`{}` to `{}`", symbol, new_name),
+                None => format!("`{}`", symbol),
+            };
+            format!(
+                "Assess renaming {target}:\n\
+                 1. Call `find_definition` with symbol `{symbol}` to confirm which declaration \
+                 is meant.\n\
+                 2. Call `rename_impact` for it and report the number of files and occurrences \
+                 the rename touches.\n\
+                 3. Call `find_implementations` and `find_super_definition` to find overrides \
+                 and overridden declarations that must be renamed together.\n\
+                 Point out references that aren't plain code, such as imports, type aliases and \
+                 Lombok accessors, and end with whether the rename looks safe.",
+                target = target,
+                symbol = symbol
+            )
+        }
+        _ => {
+            return Err(McpError::invalid_params(
+                format!("Unknown prompt: {}", name),
+                Some(json!({ "name": name })),
+            ))
+        }
+    };
+
+    Ok(GetPromptResult {
+        description: list_prompts()
+            .into_iter()
+            .find(|prompt| prompt.name == name)
+            .and_then(|prompt| prompt.description),
+        messages: vec![PromptMessage::new_text(PromptMessageRole::User, text)],
+    })
+}
+
+fn optional_argument<'a>(arguments: Option<&'a JsonObject>, key: &str) -> Option<&'a str> {
+    arguments
+        .and_then(|args| args.get(key))
+        .and_then(|value| value.as_str())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+fn required_argument<'a>(
+    prompt: &str,
+    arguments: Option<&'a JsonObject>,
+    key: &str,
+) -> Result<&'a str, McpError> {
+    optional_argument(arguments, key).ok_or_else(|| {
+        McpError::invalid_params(
+            format!("Prompt {} requires the `{}` argument", prompt, key),
+            Some(json!({ "prompt": prompt, "argument": key })),
+        )
+    })
+}
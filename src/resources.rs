@@ -0,0 +1,62 @@
+use std::path::{Path, PathBuf};
+
+use rmcp::model::{AnnotateAble, RawResource, ReadResourceResult, Resource, ResourceContents};
+use rmcp::ErrorData as McpError;
+use serde_json::json;
+
+use crate::indexer::SymbolIndex;
+
+const FILE_SCHEME: &str = "file://";
+
+/// Every indexed source file as a `file://` resource named by its path relative to
+/// `project_root`, in path order.
+pub fn list_file_resources(index: &SymbolIndex, project_root: &Path) -> Vec<Resource> {
+    let mut paths: Vec<&PathBuf> = index.files.keys().collect();
+    paths.sort();
+    paths
+        .into_iter()
+        .map(|path| {
+            let name = path.strip_prefix(project_root).unwrap_or(path);
+            let mut resource = RawResource::new(file_uri(path), name.display().to_string());
+            resource.mime_type = Some(mime_type(path).to_string());
+            resource.no_annotation()
+        })
+        .collect()
+}
+
+/// The resource URI of an indexed file: `file://` followed by its path.
+pub fn file_uri(path: &Path) -> String {
+    format!("{}{}", FILE_SCHEME, path.display())
+}
+
+/// Read the contents of the indexed file at `uri`. Anything that isn't an indexed file is
+/// not found, so resource reads can't reach other files on disk.
+pub fn read_file_resource(index: &SymbolIndex, uri: &str) -> Result<ReadResourceResult, McpError> {
+    let path = match uri.strip_prefix(FILE_SCHEME) {
+        Some(path) => Path::new(path),
+        None => return Err(not_found(uri)),
+    };
+    if !index.files.contains_key(path) {
+        return Err(not_found(uri));
+    }
+    let text = std::fs::read_to_string(path).map_err(|e| {
+        McpError::internal_error(format!("Failed to read {}: {}", path.display(), e), None)
+    })?;
+    Ok(ReadResourceResult {
+        contents: vec![ResourceContents::text(text, uri)],
+    })
+}
+
+fn mime_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("java") => "text/x-java",
+        _ => "text/x-kotlin",
+    }
+}
+
+fn not_found(uri: &str) -> McpError {
+    McpError::resource_not_found(
+        format!("No indexed source file for {}", uri),
+        Some(json!({ "uri": uri })),
+    )
+}
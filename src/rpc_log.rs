@@ -0,0 +1,191 @@
+//! Recording of the JSON-RPC messages a transport carries, for debugging MCP clients.
+//!
+//! [`LoggedReader`] and [`LoggedWriter`] wrap the two halves of a line-delimited transport
+//! (stdio, a TCP stream) and pass every byte through unchanged; each complete line is also
+//! handed to an [`RpcLog`], which logs it at debug level and appends it, timestamped, to the
+//! `--json-rpc-log` file. Nothing is ever written to the wrapped stream besides the
+//! protocol's own bytes, so the stdout of the stdio transport stays clean.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use parking_lot::Mutex;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// From the client to the server
+    Incoming,
+    /// From the server to the client
+    Outgoing,
+}
+
+impl Direction {
+    pub fn arrow(self) -> &'static str {
+        match self {
+            Direction::Incoming => "<-",
+            Direction::Outgoing => "->",
+        }
+    }
+}
+
+/// Where recorded messages go: always the `debug` tracing level, and the log file if one
+/// was given.
+pub struct RpcLog {
+    file: Option<Mutex<File>>,
+}
+
+impl RpcLog {
+    /// Log messages at debug level only.
+    pub fn tracing_only() -> Arc<Self> {
+        Arc::new(Self { file: None })
+    }
+
+    /// Also append messages to `path`, creating it if needed.
+    pub fn to_file(path: &Path) -> io::Result<Arc<Self>> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Arc::new(Self { file: Some(Mutex::new(file)) }))
+    }
+
+    /// Record one message, without its line terminator. Lines are written as
+    /// `<unix seconds>.<millis> <- {...}`, `<-` for incoming and `->` for outgoing.
+    pub fn record(&self, direction: Direction, message: &[u8]) {
+        let message = String::from_utf8_lossy(message);
+        let message = message.trim_end_matches('\r');
+        if message.is_empty() {
+            return;
+        }
+        tracing::debug!(target: "kotlin_java_mcp::rpc", "{} {}", direction.arrow(), message);
+
+        if let Some(ref file) = self.file {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+            let mut file = file.lock();
+            let written = writeln!(
+                file,
+                "{}.{:03} {} {}",
+                now.as_secs(),
+                now.subsec_millis(),
+                direction.arrow(),
+                message
+            )
+            .and_then(|_| file.flush());
+            if let Err(e) = written {
+                tracing::warn!("Failed to write to the JSON-RPC log: {}", e);
+            }
+        }
+    }
+}
+
+/// Split complete lines off the front of `pending` and record them.
+fn record_lines(log: &RpcLog, direction: Direction, pending: &mut Vec<u8>, bytes: &[u8]) {
+    pending.extend_from_slice(bytes);
+    while let Some(newline) = pending.iter().position(|&b| b == b'\n') {
+        let line: Vec<u8> = pending.drain(..=newline).collect();
+        log.record(direction, &line[..line.len() - 1]);
+    }
+}
+
+/// An [`AsyncRead`] that records each line it reads as an incoming message.
+pub struct LoggedReader<R> {
+    inner: R,
+    log: Arc<RpcLog>,
+    pending: Vec<u8>,
+}
+
+impl<R> LoggedReader<R> {
+    pub fn new(inner: R, log: Arc<RpcLog>) -> Self {
+        Self { inner, log, pending: Vec::new() }
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for LoggedReader<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let before = buf.filled().len();
+        let result = Pin::new(&mut this.inner).poll_read(cx, buf);
+        if let Poll::Ready(Ok(())) = result {
+            let read = &buf.filled()[before..];
+            record_lines(&this.log, Direction::Incoming, &mut this.pending, read);
+        }
+        result
+    }
+}
+
+/// An [`AsyncWrite`] that records each line it writes as an outgoing message.
+pub struct LoggedWriter<W> {
+    inner: W,
+    log: Arc<RpcLog>,
+    pending: Vec<u8>,
+}
+
+impl<W> LoggedWriter<W> {
+    pub fn new(inner: W, log: Arc<RpcLog>) -> Self {
+        Self { inner, log, pending: Vec::new() }
+    }
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWrite for LoggedWriter<W> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let result = Pin::new(&mut this.inner).poll_write(cx, buf);
+        if let Poll::Ready(Ok(written)) = result {
+            record_lines(&this.log, Direction::Outgoing, &mut this.pending, &buf[..written]);
+        }
+        result
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[tokio::test]
+    async fn test_messages_are_passed_through_and_logged() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rpc.log");
+        let log = RpcLog::to_file(&path).unwrap();
+
+        let input: &[u8] = b"{\"id\":1,\"method\":\"initialize\"}\n{\"method\":\"initialized\"}\n";
+        let mut reader = LoggedReader::new(input, log.clone());
+        let mut read = Vec::new();
+        reader.read_to_end(&mut read).await.unwrap();
+        assert_eq!(read, input);
+
+        let mut writer = LoggedWriter::new(Vec::new(), log);
+        // A message split across writes is logged once, when its line is complete
+        writer.write_all(b"{\"id\":1,").await.unwrap();
+        writer.write_all(b"\"result\":{}}\n").await.unwrap();
+        assert_eq!(writer.inner, b"{\"id\":1,\"result\":{}}\n");
+
+        let logged = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = logged.lines().collect();
+        assert_eq!(lines.len(), 3, "Expected one entry per message: {:?}", lines);
+        assert!(lines[0].ends_with(" <- {\"id\":1,\"method\":\"initialize\"}"));
+        assert!(lines[1].ends_with(" <- {\"method\":\"initialized\"}"));
+        assert!(lines[2].ends_with(" -> {\"id\":1,\"result\":{}}"));
+        let timestamp = lines[0].split(' ').next().unwrap();
+        assert!(timestamp.parse::<f64>().is_ok(), "Expected a timestamp, got {}", timestamp);
+    }
+}
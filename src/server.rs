@@ -1,25 +1,36 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 use parking_lot::RwLock;
 use rmcp::handler::server::tool::ToolRouter;
 use rmcp::handler::server::wrapper::Parameters;
 use rmcp::model::*;
-use rmcp::{tool, tool_handler, tool_router, ErrorData as McpError, ServerHandler};
+use rmcp::service::RequestContext;
+use rmcp::{tool, tool_handler, tool_router, ErrorData as McpError, RoleServer, ServerHandler};
 use schemars::JsonSchema;
 use serde::Deserialize;
-use tracing::info;
+use tokio::sync::watch;
+use tracing::{error, info};
 
 use crate::gradle::GradleRunner;
-use crate::indexer::parser::index_files;
+use crate::indexer::filter::FileFilter;
+use crate::indexer::parser::index_files_with_filter;
 use crate::indexer::symbols::{cross_reference, register_companion_aliases};
-use crate::indexer::SymbolIndex;
+use crate::indexer::{SymbolIndex, SymbolKind, SymbolOccurrence};
+use crate::watcher::IndexWatcher;
 
 #[derive(Clone)]
 pub struct KotlinMcpServer {
     project_root: PathBuf,
+    file_filter: Arc<FileFilter>,
     index: Arc<RwLock<SymbolIndex>>,
     gradle_runner: Arc<GradleRunner>,
+    /// Becomes true once the initial index, built in the background, is in place
+    indexed: watch::Receiver<bool>,
+    /// Set while `reindex` builds the replacement index
+    reindexing: Arc<AtomicBool>,
     tool_router: ToolRouter<Self>,
 }
 
@@ -33,6 +44,24 @@ pub struct FindUsagesParams {
     pub line: Option<usize>,
     #[schemars(description = "Include import statements in results (default: true)")]
     pub include_imports: Option<bool>,
+    #[schemars(description = "Optional number of source lines to show above and below each usage")]
+    pub context_lines: Option<usize>,
+    #[schemars(description = "Optional list of occurrence kinds to return (e.g., [\"TypeReference\"] or [\"CallSite\", \"PropertyReference\"])")]
+    pub kinds: Option<Vec<String>>,
+    #[schemars(description = "Maximum number of usages to return (default: all). The output reports the total so clients can page")]
+    pub limit: Option<usize>,
+    #[schemars(description = "Number of usages to skip before returning results, for paging (default: 0)")]
+    pub offset: Option<usize>,
+    #[schemars(description = "Optional directory relative to the project root (e.g., 'app' for a Gradle module); only usages in files under it are returned")]
+    pub scope_path: Option<String>,
+    #[schemars(description = "Leave out usages in test sources such as src/test and src/androidTest (default: false)")]
+    pub exclude_tests: Option<bool>,
+    #[schemars(description = "Group the usages into sections with counts: 'none' (default), 'file' or 'kind'")]
+    pub group_by: Option<String>,
+    #[schemars(description = "Start with a one-line summary of all usages by kind and file, e.g. '12 call sites, 3 imports across 6 files.' (default: false)")]
+    pub summary: Option<bool>,
+    #[schemars(description = "How the symbol is matched against names: 'exact' (default), 'prefix' (e.g. 'User' also matches 'UserService' and 'UserRole') or 'ci' (case-insensitive)")]
+    pub match_mode: Option<String>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -43,38 +72,330 @@ pub struct FindDefinitionParams {
     pub file: Option<String>,
     #[schemars(description = "Optional line number where the symbol is referenced, for precise resolution")]
     pub line: Option<usize>,
+    #[schemars(description = "How the symbol is matched against names: 'exact' (default), 'prefix' (e.g. 'User' also matches 'UserService' and 'UserRole') or 'ci' (case-insensitive)")]
+    pub match_mode: Option<String>,
+    #[schemars(description = "When several declarations match, keep those in the Gradle module of 'file' or in modules it depends on (required: 'file'). Reads the module graph from Gradle, or from build scripts when offline (default: false)")]
+    pub via_module_dependencies: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct FindDefinitionsBatchParams {
+    #[schemars(description = "The symbols to resolve, each with optional file and line context like find_definition")]
+    pub symbols: Vec<FindDefinitionParams>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetDefinitionSourceParams {
+    #[schemars(description = "The symbol whose declaration source to return (simple name or fully qualified name)")]
+    pub symbol: String,
+    #[schemars(description = "Optional file path where the symbol is referenced, for context")]
+    pub file: Option<String>,
+    #[schemars(description = "Optional line number where the symbol is referenced, for precise resolution")]
+    pub line: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct FindImplementationsParams {
+    #[schemars(description = "The class or interface to find subtypes of (simple name or fully qualified name)")]
+    pub symbol: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct FindSuperDefinitionParams {
+    #[schemars(description = "The overriding method as Class.method (e.g., 'com.example.InMemoryUserRepository.findById' or 'InMemoryUserRepository.findById')")]
+    pub symbol: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SearchSymbolsParams {
+    #[schemars(description = "Fuzzy query matched against declaration names and FQNs (e.g., 'UsrSvc' matches 'UserService')")]
+    pub query: String,
+    #[schemars(description = "Maximum number of results to return (default: 20)")]
+    pub limit: Option<usize>,
+    #[schemars(description = "Optional declaration kind filter (e.g., 'ClassDeclaration', 'FunctionDeclaration')")]
+    pub kind: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CallHierarchyParams {
+    #[schemars(description = "The function to inspect (simple name or fully qualified name)")]
+    pub symbol: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct TypeHierarchyParams {
+    #[schemars(description = "The class or interface to inspect (simple name or fully qualified name)")]
+    pub symbol: String,
+    #[schemars(description = "Return the hierarchy as JSON instead of an indented text tree (default: false)")]
+    pub json: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RenameImpactParams {
+    #[schemars(description = "The symbol to be renamed (simple name or fully qualified name)")]
+    pub symbol: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct FindUnusedDeclarationsParams {
+    #[schemars(description = "Also report public declarations, which may be used outside the project (default: false)")]
+    pub include_public: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct HotspotsParams {
+    #[schemars(description = "Maximum number of declarations to return (default: 20)")]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct OutlineParams {
+    #[schemars(description = "Path of the Kotlin or Java file to outline (absolute or relative to the project root)")]
+    pub file: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct FileReferencesParams {
+    #[schemars(description = "Path of the Kotlin or Java file whose references to list (absolute or relative to the project root)")]
+    pub file: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SymbolAtParams {
+    #[schemars(description = "Path of the Kotlin or Java file (absolute or relative to the project root)")]
+    pub file: String,
+    #[schemars(description = "1-based line of the cursor")]
+    pub line: usize,
+    #[schemars(description = "1-based column of the cursor, counted in bytes")]
+    pub column: usize,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ListSymbolsInPackageParams {
+    #[schemars(description = "Package name (e.g., 'com.example.core')")]
+    pub package: String,
+    #[schemars(description = "Include nested declarations, members and subpackages (default: false, top-level declarations only)")]
+    pub recursive: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct DependencyTreeParams {
     #[schemars(description = "Optional Gradle module path (e.g., ':app', ':core'). If omitted, lists all modules.")]
     pub module: Option<String>,
+    #[schemars(description = "Gradle configuration to resolve (e.g., 'runtimeClasspath', 'testCompileClasspath'; default: 'compileClasspath')")]
+    pub configuration: Option<String>,
+    #[schemars(description = "Show at most this many levels of the tree; 1 shows only the direct dependencies, and a cut-off dependency says how many it hides (default: unlimited)")]
+    pub max_depth: Option<usize>,
+    #[schemars(description = "List every artifact in the tree once, sorted, as group:artifact:version with the resolved version, instead of the tree (default: false)")]
+    pub flatten: Option<bool>,
+    #[schemars(description = "Return JSON instead of text: the dependency nodes (group, artifact, version, resolvedVersion, isProject, isTransitiveDuplicate, configuration, children), the flattened artifacts, or the modules (path, name) (default: false)")]
+    pub json: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct DependencyConflictsParams {
+    #[schemars(description = "Gradle module path (e.g., ':app')")]
+    pub module: String,
+    #[schemars(description = "Gradle configuration to resolve (e.g., 'runtimeClasspath'; default: 'compileClasspath')")]
+    pub configuration: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SymbolModuleParams {
+    #[schemars(description = "The symbol whose declaring module to find (simple name or fully qualified name)")]
+    pub symbol: String,
 }
 
 #[tool_router]
 impl KotlinMcpServer {
-    pub fn new(project_root: PathBuf) -> Self {
-        let gradle_runner = Arc::new(GradleRunner::new(project_root.clone()));
+    /// Start indexing `project_root` on a background task, discovering only the files
+    /// `file_filter` lets through (also on `reindex`). Returns right away so the MCP handshake
+    /// isn't held up by a large project; until the index is built, tools answer with a
+    /// "still indexing" message. Must be called from within a Tokio runtime.
+    pub fn new(
+        project_root: PathBuf,
+        offline: bool,
+        gradle_cache_ttl: Duration,
+        file_filter: FileFilter,
+    ) -> Self {
+        let gradle_runner = Arc::new(
+            GradleRunner::new(project_root.clone(), offline).with_cache_ttl(gradle_cache_ttl),
+        );
+        let file_filter = Arc::new(file_filter);
+        let index = Arc::new(RwLock::new(SymbolIndex::default()));
+        let (indexed_tx, indexed) = watch::channel(false);
 
-        info!("Indexing Kotlin and Java files in {}", project_root.display());
-        let mut index = index_files(&project_root);
-        cross_reference(&mut index);
-        register_companion_aliases(&mut index);
-        info!("{}", index.stats());
+        let (root, filter, shared) = (project_root.clone(), file_filter.clone(), index.clone());
+        tokio::spawn(async move {
+            info!("Indexing Kotlin and Java files in {}", root.display());
+            match tokio::task::spawn_blocking(move || build_index(&root, &filter)).await {
+                Ok(built) => {
+                    info!("{}", built.stats());
+                    *shared.write() = built;
+                }
+                // Leave the index empty rather than answering "still indexing" forever
+                Err(e) => error!("Indexing failed: {}", e),
+            }
+            let _ = indexed_tx.send(true);
+        });
 
         Self {
             project_root,
-            index: Arc::new(RwLock::new(index)),
+            file_filter,
+            index,
             gradle_runner,
+            indexed,
+            reindexing: Arc::new(AtomicBool::new(false)),
             tool_router: Self::tool_router(),
         }
     }
 
-    #[tool(description = "Find all usages/references of a Kotlin or Java symbol across the project. Returns file locations, symbol kinds (call site, type reference, property reference, import), and fully qualified names. Use 'file' and 'line' parameters for precise resolution when the symbol name is ambiguous.")]
+    /// Wait until the initial index is built.
+    pub async fn wait_until_indexed(&self) {
+        let mut indexed = self.indexed.clone();
+        // Only fails once the indexing task is gone, and it sends before finishing
+        let _ = indexed.wait_for(|indexed| *indexed).await;
+    }
+
+    /// The response for tools called before the initial index is built, or None once it is.
+    fn still_indexing(&self) -> Option<CallToolResult> {
+        self.still_indexing_message()
+            .map(|message| CallToolResult::error(vec![Content::text(message)]))
+    }
+
+    /// The error for resource requests made before the initial index is built, like
+    /// [`Self::still_indexing`] for tools.
+    fn resources_still_indexing(&self) -> Result<(), McpError> {
+        match self.still_indexing_message() {
+            Some(message) => Err(McpError::internal_error(message, None)),
+            None => Ok(()),
+        }
+    }
+
+    fn still_indexing_message(&self) -> Option<String> {
+        if *self.indexed.borrow() {
+            return None;
+        }
+        Some(format!(
+            "Still indexing {}; try again shortly. The health tool reports when indexing is done.",
+            self.project_root.display()
+        ))
+    }
+
+    /// Apply `via_module_dependencies` to the declarations found for a reference in `file`,
+    /// see [`crate::tools::symbol_module::retain_in_module_dependencies`]. Takes the
+    /// declarations by value so the index isn't locked while Gradle resolves the module
+    /// graph. The error result reports a missing `file` or a Gradle failure.
+    async fn retain_in_module_dependencies(
+        &self,
+        mut results: Vec<SymbolOccurrence>,
+        file: Option<&Path>,
+    ) -> Result<Vec<SymbolOccurrence>, CallToolResult> {
+        let file = match file {
+            Some(file) => file.to_path_buf(),
+            None => {
+                return Err(CallToolResult::error(vec![Content::text(
+                    "'via_module_dependencies' requires 'file', the file referencing the symbol",
+                )]))
+            }
+        };
+        self.run_gradle(move |runner| {
+            crate::tools::symbol_module::retain_in_module_dependencies(&mut results, runner, &file)
+                .map(|()| results)
+        })
+        .await
+    }
+
+    /// Keep the index in sync with the project as files are created, modified and deleted,
+    /// until the returned watcher is dropped. Changes made while the initial index is built
+    /// are applied once it is. Must be called from within a Tokio runtime.
+    pub fn watch(&self) -> notify::Result<IndexWatcher> {
+        let mut indexed = self.indexed.clone();
+        let runtime = tokio::runtime::Handle::current();
+        crate::watcher::watch(
+            self.project_root.clone(),
+            self.file_filter.clone(),
+            self.index.clone(),
+            move || {
+                let _ = runtime.block_on(indexed.wait_for(|indexed| *indexed));
+            },
+        )
+    }
+
+    /// Run `task` against the Gradle runner on a blocking thread, so a slow Gradle build
+    /// doesn't stall the async runtime. Errors come back as a tool result carrying the same
+    /// JSON block as `dependency_tree`.
+    async fn run_gradle<T: Send + 'static>(
+        &self,
+        task: impl FnOnce(&GradleRunner) -> Result<T, crate::error::GradleError> + Send + 'static,
+    ) -> Result<T, CallToolResult> {
+        let runner = self.gradle_runner.clone();
+        match tokio::task::spawn_blocking(move || task(&runner)).await {
+            Ok(Ok(value)) => Ok(value),
+            Ok(Err(e)) => {
+                // Human-readable message first, then a JSON payload with a stable error code
+                let error = crate::tools::dependency_tree::DependencyTreeError::from(&e);
+                Err(CallToolResult::error(vec![
+                    Content::text(format!("Gradle error: {}", e)),
+                    Content::text(error.to_json()),
+                ]))
+            }
+            Err(e) => Err(CallToolResult::error(vec![Content::text(format!(
+                "Gradle task failed: {}",
+                e
+            ))])),
+        }
+    }
+
+    /// A successful result with `output`, followed by a warning when any of the files it
+    /// draws on changed since they were indexed, so callers know to `reindex`.
+    fn with_stale_warning(&self, output: String, changed: &[PathBuf]) -> CallToolResult {
+        let mut contents = vec![Content::text(output)];
+        if let Some(warning) = crate::tools::format_stale_warning(changed, &self.project_root) {
+            contents.push(Content::text(format!(
+                "{}\nCall reindex to refresh the index.",
+                warning
+            )));
+        }
+        CallToolResult::success(contents)
+    }
+
+    #[tool(description = "Find all usages/references of a Kotlin or Java symbol across the project. Returns file locations, symbol kinds (call site, type reference, property reference, import), and fully qualified names. Use 'file' and 'line' parameters for precise resolution when the symbol name is ambiguous. Use 'limit' and 'offset' to page through large result sets, and 'scope_path' to report only the usages inside one module or directory.")]
     async fn find_usages(
         &self,
         Parameters(params): Parameters<FindUsagesParams>,
     ) -> Result<CallToolResult, McpError> {
+        if let Some(result) = self.still_indexing() {
+            return Ok(result);
+        }
+        let kinds = match params
+            .kinds
+            .as_ref()
+            .map(|kinds| kinds.iter().map(|k| k.parse::<SymbolKind>()).collect::<Result<Vec<_>, _>>())
+            .transpose()
+        {
+            Ok(kinds) => kinds,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+        let group_by = match params
+            .group_by
+            .as_deref()
+            .map(str::parse::<crate::tools::GroupBy>)
+            .transpose()
+        {
+            Ok(group_by) => group_by.unwrap_or_default(),
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+        let name_match = match params
+            .match_mode
+            .as_deref()
+            .map(str::parse::<crate::tools::NameMatch>)
+            .transpose()
+        {
+            Ok(name_match) => name_match.unwrap_or_default(),
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+
         let index = self.index.read();
         let file_path = params.file.as_ref().map(|f| {
             let p = PathBuf::from(f);
@@ -85,23 +406,218 @@ impl KotlinMcpServer {
             }
         });
 
-        let results = crate::tools::find_usages::find_usages(
+        let scope = params.scope_path.as_ref().map(|s| self.project_root.join(s));
+        if let Some(ref scope) = scope {
+            if !scope.exists() {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Scope path not found: {}",
+                    scope.display()
+                ))]));
+            }
+        }
+
+        let mut results = crate::tools::find_usages::find_usages_matching(
             &index,
             &params.symbol,
             file_path.as_deref(),
             params.line,
             params.include_imports.unwrap_or(true),
+            kinds.as_deref(),
+            name_match,
         );
+        if let Some(ref scope) = scope {
+            crate::tools::find_usages::retain_in_scope(&mut results, scope);
+        }
+        if params.exclude_tests.unwrap_or(false) {
+            crate::tools::find_usages::retain_outside_tests(&mut results, &self.project_root);
+        }
 
-        let output = crate::tools::format_occurrences(&results, &self.project_root);
-        Ok(CallToolResult::success(vec![Content::text(output)]))
+        let changed = index.changed_since_indexing(results.iter().map(|o| o.file.as_path()));
+        let page = crate::tools::Page::new(results, params.offset.unwrap_or(0), params.limit);
+        let output = if params.summary.unwrap_or(false) {
+            crate::tools::format_page_with_summary(
+                &page,
+                &self.project_root,
+                params.context_lines,
+                group_by,
+            )
+        } else {
+            crate::tools::format_page_grouped(
+                &page,
+                &self.project_root,
+                params.context_lines,
+                group_by,
+            )
+        };
+        Ok(self.with_stale_warning(output, &changed))
     }
 
-    #[tool(description = "Find the definition/declaration of a Kotlin or Java symbol. Returns the file location and declaration kind (class, interface, function, property, etc.). Use 'file' and 'line' parameters when calling from a specific reference location for precise resolution.")]
+    #[tool(description = "Find the definition/declaration of a Kotlin or Java symbol. Returns the file location and declaration kind (class, interface, function, property, etc.). Use 'file' and 'line' parameters when calling from a specific reference location for precise resolution. Common Kotlin collection builders with no project declaration (e.g., listOf, mapOf) report their standard library FQN.")]
     async fn find_definition(
         &self,
         Parameters(params): Parameters<FindDefinitionParams>,
     ) -> Result<CallToolResult, McpError> {
+        if let Some(result) = self.still_indexing() {
+            return Ok(result);
+        }
+        let name_match = match params
+            .match_mode
+            .as_deref()
+            .map(str::parse::<crate::tools::NameMatch>)
+            .transpose()
+        {
+            Ok(name_match) => name_match.unwrap_or_default(),
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+        let file_path = params.file.as_ref().map(|f| {
+            let p = PathBuf::from(f);
+            if p.is_relative() {
+                self.project_root.join(p)
+            } else {
+                p
+            }
+        });
+
+        let mut declarations: Vec<SymbolOccurrence> = {
+            let index = self.index.read();
+            crate::tools::find_definition::find_definition_matching(
+                &index,
+                &params.symbol,
+                file_path.as_deref(),
+                params.line,
+                name_match,
+            )
+            .into_iter()
+            .cloned()
+            .collect()
+        };
+        if params.via_module_dependencies.unwrap_or(false) {
+            declarations = match self
+                .retain_in_module_dependencies(declarations, file_path.as_deref())
+                .await
+            {
+                Ok(declarations) => declarations,
+                Err(result) => return Ok(result),
+            };
+        }
+        let results: Vec<&SymbolOccurrence> = declarations.iter().collect();
+
+        let index = self.index.read();
+        // Standard library names aren't enumerable, so only exact lookups fall back to them
+        let stdlib_fqn = if results.is_empty() && name_match == crate::tools::NameMatch::Exact {
+            crate::tools::find_definition::find_stdlib_definition(&index, &params.symbol)
+        } else {
+            None
+        };
+        let output = match stdlib_fqn {
+            Some(fqn) => {
+                crate::tools::find_definition::format_stdlib_definition(&params.symbol, fqn)
+            }
+            None => crate::tools::format_occurrences(&results, &self.project_root),
+        };
+        let changed = index.changed_since_indexing(results.iter().map(|o| o.file.as_path()));
+        Ok(self.with_stale_warning(output, &changed))
+    }
+
+    #[tool(description = "Find the definitions of several symbols in one call. Each entry is resolved like find_definition, with optional 'file' and 'line' context. Returns a JSON object mapping each input to an array of its declarations; the key is the symbol, followed by ' @ file:line' when context is given. Unresolved symbols map to an empty array.")]
+    async fn find_definitions_batch(
+        &self,
+        Parameters(params): Parameters<FindDefinitionsBatchParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if let Some(result) = self.still_indexing() {
+            return Ok(result);
+        }
+        let name_matches = match params
+            .symbols
+            .iter()
+            .map(|query| query.match_mode.as_deref().map(str::parse::<crate::tools::NameMatch>))
+            .map(|name_match| name_match.transpose().map(Option::unwrap_or_default))
+            .collect::<Result<Vec<_>, _>>()
+        {
+            Ok(name_matches) => name_matches,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+        let file_paths: Vec<Option<PathBuf>> = params
+            .symbols
+            .iter()
+            .map(|query| {
+                query.file.as_ref().map(|f| {
+                    let p = PathBuf::from(f);
+                    if p.is_relative() {
+                        self.project_root.join(p)
+                    } else {
+                        p
+                    }
+                })
+            })
+            .collect();
+        let queries: Vec<(&str, Option<&Path>, Option<usize>)> = params
+            .symbols
+            .iter()
+            .zip(&file_paths)
+            .map(|(query, file)| (query.symbol.as_str(), file.as_deref(), query.line))
+            .collect();
+
+        let candidates: Vec<Vec<SymbolOccurrence>> = {
+            let index = self.index.read();
+            queries
+                .iter()
+                .zip(&name_matches)
+                .map(|(&(symbol, file, line), &name_match)| {
+                    crate::tools::find_definition::find_definition_matching(
+                        &index, symbol, file, line, name_match,
+                    )
+                    .into_iter()
+                    .cloned()
+                    .collect()
+                })
+                .collect()
+        };
+        let mut declarations: Vec<Vec<SymbolOccurrence>> = Vec::new();
+        for ((candidates, &(_, file, _)), query) in
+            candidates.into_iter().zip(&queries).zip(&params.symbols)
+        {
+            if query.via_module_dependencies.unwrap_or(false) {
+                match self.retain_in_module_dependencies(candidates, file).await {
+                    Ok(retained) => declarations.push(retained),
+                    Err(result) => return Ok(result),
+                }
+            } else {
+                declarations.push(candidates);
+            }
+        }
+        let results: Vec<Vec<&SymbolOccurrence>> =
+            declarations.iter().map(|d| d.iter().collect()).collect();
+        let changed = self
+            .index
+            .read()
+            .changed_since_indexing(results.iter().flatten().map(|o| o.file.as_path()));
+        let keyed: Vec<(String, Vec<&SymbolOccurrence>)> = params
+            .symbols
+            .iter()
+            .map(|query| match (&query.file, query.line) {
+                (Some(file), Some(line)) => format!("{} @ {}:{}", query.symbol, file, line),
+                (Some(file), None) => format!("{} @ {}", query.symbol, file),
+                (None, _) => query.symbol.clone(),
+            })
+            .zip(results)
+            .collect();
+
+        let output = crate::tools::find_definition::format_definitions_batch_json(
+            &keyed,
+            &self.project_root,
+        );
+        Ok(self.with_stale_warning(output, &changed))
+    }
+
+    #[tool(description = "Return the full source text of a Kotlin or Java symbol's declaration: the whole class for a class, the signature and body for a function. The symbol is resolved like find_definition; when several declarations match, each is returned with its location. Use 'file' and 'line' parameters for precise resolution.")]
+    async fn get_definition_source(
+        &self,
+        Parameters(params): Parameters<GetDefinitionSourceParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if let Some(result) = self.still_indexing() {
+            return Ok(result);
+        }
         let index = self.index.read();
         let file_path = params.file.as_ref().map(|f| {
             let p = PathBuf::from(f);
@@ -112,52 +628,417 @@ impl KotlinMcpServer {
             }
         });
 
-        let results = crate::tools::find_definition::find_definition(
+        let sources = crate::tools::definition_source::definition_sources(
             &index,
             &params.symbol,
             file_path.as_deref(),
             params.line,
         );
 
+        let output =
+            crate::tools::definition_source::format_definition_sources(&sources, &self.project_root);
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(description = "Find all classes and objects that directly extend or implement a class or interface, such as the subtypes of a sealed class. Supertypes are resolved through imports, so subtypes in other files and packages are included.")]
+    async fn find_implementations(
+        &self,
+        Parameters(params): Parameters<FindImplementationsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if let Some(result) = self.still_indexing() {
+            return Ok(result);
+        }
+        let index = self.index.read();
+        let results =
+            crate::tools::find_implementations::find_implementations(&index, &params.symbol);
+
+        let output = crate::tools::format_occurrences(&results, &self.project_root);
+        let changed = index.changed_since_indexing(results.iter().map(|o| o.file.as_path()));
+        Ok(self.with_stale_warning(output, &changed))
+    }
+
+    #[tool(description = "Go to the super method: find the declaration an overriding method overrides. Walks the class's supertypes breadth-first and returns the nearest method with the same name, matched by parameter types or, failing that, parameter count.")]
+    async fn find_super_definition(
+        &self,
+        Parameters(params): Parameters<FindSuperDefinitionParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if let Some(result) = self.still_indexing() {
+            return Ok(result);
+        }
+        let index = self.index.read();
+        let results =
+            crate::tools::super_definition::find_super_definition(&index, &params.symbol);
+
+        let output = crate::tools::format_occurrences(&results, &self.project_root);
+        let changed = index.changed_since_indexing(results.iter().map(|o| o.file.as_path()));
+        Ok(self.with_stale_warning(output, &changed))
+    }
+
+    #[tool(description = "Search declarations across the project by fuzzy name match. The query is matched case-insensitively as a subsequence of each declaration's simple name and fully qualified name, so abbreviations like 'UsrSvc' find 'UserService'. Results are ranked best match first.")]
+    async fn search_symbols(
+        &self,
+        Parameters(params): Parameters<SearchSymbolsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if let Some(result) = self.still_indexing() {
+            return Ok(result);
+        }
+        let index = self.index.read();
+        let results = crate::tools::search_symbols::search_symbols(
+            &index,
+            &params.query,
+            params
+                .limit
+                .unwrap_or(crate::tools::search_symbols::DEFAULT_SEARCH_LIMIT),
+            params.kind.as_deref(),
+        );
+
+        let output = crate::tools::format_occurrences(&results, &self.project_root);
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(description = "Find the callers of a function. Returns every call site resolving to the function, grouped by the enclosing function, constructor, or initializer that contains it.")]
+    async fn incoming_calls(
+        &self,
+        Parameters(params): Parameters<CallHierarchyParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if let Some(result) = self.still_indexing() {
+            return Ok(result);
+        }
+        let index = self.index.read();
+        let groups = crate::tools::call_hierarchy::incoming_calls(&index, &params.symbol);
+
+        let output = crate::tools::call_hierarchy::format_incoming_calls(&groups, &self.project_root);
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(description = "Find the calls made by a function. Returns every call site inside the body of the function's declaration.")]
+    async fn outgoing_calls(
+        &self,
+        Parameters(params): Parameters<CallHierarchyParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if let Some(result) = self.still_indexing() {
+            return Ok(result);
+        }
+        let index = self.index.read();
+        let results = crate::tools::call_hierarchy::outgoing_calls(&index, &params.symbol);
+
         let output = crate::tools::format_occurrences(&results, &self.project_root);
         Ok(CallToolResult::success(vec![Content::text(output)]))
     }
 
-    #[tool(description = "Show the Gradle module dependency tree. Without a module parameter, lists all project modules. With a module path (e.g., ':app'), shows the compile classpath dependencies including transitive dependencies, version conflicts, and project references.")]
+    #[tool(description = "Show the type hierarchy of a class or interface: its supertypes (extended classes and implemented interfaces, transitively) and its subtypes, as a tree. Kotlin and Java types are combined, e.g. a Kotlin class extending a Java class.")]
+    async fn type_hierarchy(
+        &self,
+        Parameters(params): Parameters<TypeHierarchyParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if let Some(result) = self.still_indexing() {
+            return Ok(result);
+        }
+        let index = self.index.read();
+        let hierarchies =
+            crate::tools::type_hierarchy::type_hierarchy(&index, &params.symbol, &self.project_root);
+
+        let output = if params.json.unwrap_or(false) {
+            serde_json::to_string_pretty(&hierarchies).unwrap_or_else(|_| "[]".to_string())
+        } else {
+            crate::tools::type_hierarchy::format_type_hierarchies(&hierarchies)
+        };
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(description = "Estimate the blast radius of renaming a symbol: lists its declarations, references, and imports, grouped by file with counts. Includes occurrences reached through companion object aliases, Lombok getters/setters, and type aliases. Read-only; nothing is renamed.")]
+    async fn rename_impact(
+        &self,
+        Parameters(params): Parameters<RenameImpactParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if let Some(result) = self.still_indexing() {
+            return Ok(result);
+        }
+        let index = self.index.read();
+        let impacts = crate::tools::rename_impact::rename_impact(&index, &params.symbol);
+
+        let output = crate::tools::rename_impact::format_rename_impacts(&impacts, &self.project_root);
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(description = "Show the symbol outline of a single Kotlin or Java file: its package, imports, and the nested declaration tree (classes, their members, and nested classes) with each symbol's kind and line.")]
+    async fn outline(
+        &self,
+        Parameters(params): Parameters<OutlineParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if let Some(result) = self.still_indexing() {
+            return Ok(result);
+        }
+        let index = self.index.read();
+        let file_path = PathBuf::from(&params.file);
+        let file_path = if file_path.is_relative() {
+            self.project_root.join(file_path)
+        } else {
+            file_path
+        };
+
+        let output = crate::tools::outline::format_outline(&index, &file_path, &self.project_root);
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(description = "List everything a single Kotlin or Java file references, grouped by the resolved FQN of the target with a count, the kinds of reference and their lines; the most referenced symbols come first and unresolved references are listed by name. Complements 'outline', which lists the file's declarations, to show a file's dependencies at a glance.")]
+    async fn file_references(
+        &self,
+        Parameters(params): Parameters<FileReferencesParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if let Some(result) = self.still_indexing() {
+            return Ok(result);
+        }
+        let index = self.index.read();
+        let file_path = PathBuf::from(&params.file);
+        let file_path = if file_path.is_relative() {
+            self.project_root.join(file_path)
+        } else {
+            file_path
+        };
+
+        let output = crate::tools::file_references::format_file_references(
+            &index,
+            &file_path,
+            &self.project_root,
+        );
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(description = "Find declarations that nothing in the project references: classes, functions, properties and type aliases with no usages outside their own declaration and imports. Only private, internal, protected and package-private declarations are reported unless 'include_public' is set. Overrides are skipped. Code invoked through reflection or by a framework (DI, serialization, entry points) has no references and will be reported as unused.")]
+    async fn find_unused_declarations(
+        &self,
+        Parameters(params): Parameters<FindUnusedDeclarationsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if let Some(result) = self.still_indexing() {
+            return Ok(result);
+        }
+        let index = self.index.read();
+        let unused = crate::tools::unused::find_unused_declarations(
+            &index,
+            params.include_public.unwrap_or(false),
+        );
+        let output = crate::tools::unused::format_unused_declarations(&unused, &self.project_root);
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(description = "List the most-referenced declarations in the project, to find the central types and functions of an unfamiliar codebase. Ranks declarations by the number of references resolving to them, then by how many files those references are in; imports are counted separately.")]
+    async fn hotspots(
+        &self,
+        Parameters(params): Parameters<HotspotsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if let Some(result) = self.still_indexing() {
+            return Ok(result);
+        }
+        let index = self.index.read();
+        let hotspots = crate::tools::hotspots::hotspots(
+            &index,
+            params.limit.unwrap_or(crate::tools::hotspots::DEFAULT_HOTSPOT_LIMIT),
+        );
+        let output = crate::tools::hotspots::format_hotspots(&hotspots, &self.project_root);
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(description = "Identify the symbol under a cursor position (file, 1-based line and column), like an editor's hover. Returns the innermost occurrence containing the position: its name, kind, fully qualified name, and whether it declares or references the symbol.")]
+    async fn symbol_at(
+        &self,
+        Parameters(params): Parameters<SymbolAtParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if let Some(result) = self.still_indexing() {
+            return Ok(result);
+        }
+        let index = self.index.read();
+        let file_path = PathBuf::from(&params.file);
+        let file_path = if file_path.is_relative() {
+            self.project_root.join(file_path)
+        } else {
+            file_path
+        };
+
+        let occurrence =
+            crate::tools::symbol_at::symbol_at(&index, &file_path, params.line, params.column);
+        let output = crate::tools::symbol_at::format_symbol_at(occurrence, &self.project_root);
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(description = "List the symbols declared in a package across all files, grouped by file with each symbol's kind and line. By default only the package's top-level declarations are listed; set 'recursive' to include nested classes, members, and subpackages.")]
+    async fn list_symbols_in_package(
+        &self,
+        Parameters(params): Parameters<ListSymbolsInPackageParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if let Some(result) = self.still_indexing() {
+            return Ok(result);
+        }
+        let index = self.index.read();
+        let symbols = crate::tools::list_symbols_in_package::list_symbols_in_package(
+            &index,
+            &params.package,
+            params.recursive.unwrap_or(false),
+        );
+
+        let output = crate::tools::list_symbols_in_package::format_package_symbols(
+            &symbols,
+            &params.package,
+            &self.project_root,
+        );
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(description = "Show the Gradle module dependency tree. Without a module parameter, lists all project modules (read from the settings script when Gradle isn't run). With a module path (e.g., ':app'), shows the dependencies of a configuration (compileClasspath unless 'configuration' names another, e.g. runtimeClasspath or testCompileClasspath) including transitive dependencies, version conflicts, and project references. Use 'max_depth' to cut off deep transitive trees, or 'flatten' for a deduplicated, sorted list of every artifact with its resolved version. Set 'json' for a machine-readable result. On failure, the error result also carries a JSON block with a 'message' and a stable 'code': wrapper_not_found, build_file_not_found, invalid_configuration, command_failed, parse_error or io_error.")]
     async fn dependency_tree(
         &self,
         Parameters(params): Parameters<DependencyTreeParams>,
     ) -> Result<CallToolResult, McpError> {
-        match crate::tools::dependency_tree::dependency_tree(
-            &self.gradle_runner,
-            params.module.as_deref(),
-        ) {
-            Ok(output) => Ok(CallToolResult::success(vec![Content::text(output)])),
-            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
-                "Gradle error: {}",
-                e
-            ))])),
+        if params.max_depth == Some(0) {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "max_depth must be at least 1",
+            )]));
         }
+        let dependency_tree = if params.json.unwrap_or(false) {
+            crate::tools::dependency_tree::dependency_tree_json
+        } else {
+            crate::tools::dependency_tree::dependency_tree
+        };
+        let flatten = params.flatten.unwrap_or(false);
+        let result = self
+            .run_gradle(move |runner| {
+                dependency_tree(
+                    runner,
+                    params.module.as_deref(),
+                    params.configuration.as_deref(),
+                    params.max_depth,
+                    flatten,
+                )
+            })
+            .await;
+        Ok(match result {
+            Ok(output) => CallToolResult::success(vec![Content::text(output)]),
+            Err(result) => result,
+        })
     }
 
-    #[tool(description = "Re-index all Kotlin and Java files in the project. Use this after making changes to the codebase to update the symbol index. Also invalidates the Gradle cache.")]
-    async fn reindex(&self) -> Result<CallToolResult, McpError> {
-        info!("Re-indexing project at {}", self.project_root.display());
+    #[tool(description = "Report version conflicts among a Gradle module's dependencies (compileClasspath unless 'configuration' names another), including transitive ones: artifacts requested at several versions, or resolved by Gradle to a version other than the one requested. For each conflict, lists the selected version and every requested version with the dependency path that requested it. Errors carry the same JSON block as dependency_tree.")]
+    async fn dependency_conflicts(
+        &self,
+        Parameters(params): Parameters<DependencyConflictsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let result = self
+            .run_gradle(move |runner| {
+                crate::tools::dependency_tree::dependency_conflicts(
+                    runner,
+                    &params.module,
+                    params.configuration.as_deref(),
+                )
+            })
+            .await;
+        Ok(match result {
+            Ok(output) => CallToolResult::success(vec![Content::text(output)]),
+            Err(result) => result,
+        })
+    }
 
-        let mut new_index = index_files(&self.project_root);
-        cross_reference(&mut new_index);
-        register_companion_aliases(&mut new_index);
+    #[tool(description = "Find the Gradle module that owns a symbol's declaration, to see which side of a module boundary it lives on. The symbol is resolved like find_definition, and each declaration's file is mapped to the module with the nearest enclosing directory (':feature:login' lives in feature/login). Modules come from 'gradlew projects', or from the settings script when Gradle isn't run. Declarations outside every module are reported as such. Errors carry the same JSON block as dependency_tree.")]
+    async fn symbol_module(
+        &self,
+        Parameters(params): Parameters<SymbolModuleParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if let Some(result) = self.still_indexing() {
+            return Ok(result);
+        }
+        // Gradle can take minutes, so it runs without holding the index lock
+        let declarations: Vec<SymbolOccurrence> = {
+            let index = self.index.read();
+            crate::tools::find_definition::find_definition(&index, &params.symbol, None, None)
+                .into_iter()
+                .cloned()
+                .collect()
+        };
+        let modules = if declarations.is_empty() {
+            Vec::new()
+        } else {
+            let files: Vec<PathBuf> = declarations.iter().map(|d| d.file.clone()).collect();
+            match self
+                .run_gradle(move |runner| crate::tools::symbol_module::file_modules(runner, &files))
+                .await
+            {
+                Ok(modules) => modules,
+                Err(result) => return Ok(result),
+            }
+        };
+        let modules: Vec<crate::tools::symbol_module::SymbolModule> = declarations
+            .iter()
+            .zip(modules)
+            .map(|(declaration, module)| crate::tools::symbol_module::SymbolModule {
+                declaration,
+                module,
+            })
+            .collect();
+        let output =
+            crate::tools::symbol_module::format_symbol_modules(&modules, &self.project_root);
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
 
-        let stats = format!("{}", new_index.stats());
-        info!("{}", stats);
+    #[tool(description = "Report index metrics as JSON: file, name and occurrence totals, occurrence counts per symbol kind and per file, the number of references whose fully qualified name could not be resolved, and the files with syntax errors (ERROR or MISSING nodes) that may be indexed incompletely. Use this to diagnose why a symbol isn't found (e.g., its file was excluded, skipped or failed to parse, or its references are unresolved).")]
+    async fn index_stats(&self) -> Result<CallToolResult, McpError> {
+        if let Some(result) = self.still_indexing() {
+            return Ok(result);
+        }
+        let index = self.index.read();
+        let stats = index.detailed_stats(&self.project_root);
 
-        *self.index.write() = new_index;
-        self.gradle_runner.invalidate_cache();
+        let output = serde_json::to_string_pretty(&stats).unwrap_or_else(|_| "{}".to_string());
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(description = "Report whether the server is ready as JSON: 'indexed' (the index is built), 'reindex_in_progress', the number of indexed 'files' and 'total_occurrences', and 'last_indexed_at', when a file was last indexed in seconds since the Unix epoch. Use this to wait for the server before sending queries.")]
+    async fn health(&self) -> Result<CallToolResult, McpError> {
+        let indexed = *self.indexed.borrow();
+        let reindexing = self.reindexing.load(Ordering::SeqCst);
+        let index = self.index.read();
+        let health = crate::tools::health::health(&index, indexed, reindexing);
+
+        let output = crate::tools::health::format_health_json(&health);
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    #[tool(description = "Re-index all Kotlin and Java files in the project. Use this after making changes to the codebase to update the symbol index. Also invalidates the Gradle cache.")]
+    async fn reindex(&self) -> Result<CallToolResult, McpError> {
+        if let Some(result) = self.still_indexing() {
+            return Ok(result);
+        }
+        info!("Re-indexing project at {}", self.project_root.display());
+        let reindexing = ReindexingFlag::set(self.reindexing.clone());
 
-        Ok(CallToolResult::success(vec![Content::text(format!(
-            "Reindex complete. {}",
+        // Indexing is CPU-bound, so it runs on a blocking thread; the flag is cleared when
+        // the task ends, even if it panics or the request is cancelled
+        let (root, filter, index) =
+            (self.project_root.clone(), self.file_filter.clone(), self.index.clone());
+        let reindexed = tokio::task::spawn_blocking(move || {
+            let _reindexing = reindexing;
+            let new_index = build_index(&root, &filter);
+            let stats = format!("{}", new_index.stats());
+            *index.write() = new_index;
             stats
-        ))]))
+        })
+        .await;
+
+        match reindexed {
+            Ok(stats) => {
+                info!("{}", stats);
+                self.gradle_runner.invalidate_cache();
+                Ok(CallToolResult::success(vec![Content::text(format!(
+                    "Reindex complete. {}",
+                    stats
+                ))]))
+            }
+            Err(e) => {
+                error!("Re-indexing failed: {}", e);
+                Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Reindex failed: {}",
+                    e
+                ))]))
+            }
+        }
     }
 }
 
@@ -166,7 +1047,11 @@ impl ServerHandler for KotlinMcpServer {
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
             protocol_version: ProtocolVersion::V_2024_11_05,
-            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            capabilities: ServerCapabilities::builder()
+                .enable_tools()
+                .enable_prompts()
+                .enable_resources()
+                .build(),
             server_info: Implementation {
                 name: "kotlin-java-mcp".to_string(),
                 title: None,
@@ -177,9 +1062,125 @@ impl ServerHandler for KotlinMcpServer {
             },
             instructions: Some(
                 "Kotlin MCP server for code navigation. Indexes .kt and .java files using tree-sitter \
-                 and provides find_usages, find_definition, dependency_tree, and reindex tools."
+                 and provides find_usages, find_definition, find_definitions_batch, find_implementations, find_super_definition, type_hierarchy, search_symbols, incoming_calls, outgoing_calls, outline, file_references, rename_impact, find_unused_declarations, hotspots, list_symbols_in_package, dependency_tree, dependency_conflicts, symbol_module, get_definition_source, index_stats, health, symbol_at, and reindex tools. Indexed source files are also exposed as file:// resources, and the trace-symbol and assess-rename prompts walk through common navigation workflows."
                     .to_string(),
             ),
         }
     }
+
+    /// Every indexed `.kt`, `.kts` and `.java` file, as a `file://` URI.
+    async fn list_resources(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, McpError> {
+        self.resources_still_indexing()?;
+        let index = self.index.read();
+        let resources = crate::resources::list_file_resources(&index, &self.project_root);
+        Ok(ListResourcesResult::with_all_items(resources))
+    }
+
+    /// The navigation workflow prompts, see [`crate::prompts`].
+    async fn list_prompts(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListPromptsResult, McpError> {
+        Ok(ListPromptsResult::with_all_items(crate::prompts::list_prompts()))
+    }
+
+    /// A prompt listed by `list_prompts`, with its arguments filled in.
+    async fn get_prompt(
+        &self,
+        request: GetPromptRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<GetPromptResult, McpError> {
+        crate::prompts::get_prompt(&request.name, request.arguments.as_ref())
+    }
+
+    /// The contents of a file listed by `list_resources`.
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, McpError> {
+        self.resources_still_indexing()?;
+        let index = self.index.read();
+        crate::resources::read_file_resource(&index, &request.uri)
+    }
+}
+
+/// Marks a `reindex` as running until dropped, for the health tool.
+struct ReindexingFlag(Arc<AtomicBool>);
+
+impl ReindexingFlag {
+    fn set(flag: Arc<AtomicBool>) -> Self {
+        flag.store(true, Ordering::SeqCst);
+        Self(flag)
+    }
+}
+
+impl Drop for ReindexingFlag {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Index `project_root` and resolve cross-file references, for startup and `reindex`.
+fn build_index(project_root: &Path, file_filter: &FileFilter) -> SymbolIndex {
+    let mut index = index_files_with_filter(project_root, file_filter);
+    cross_reference(&mut index);
+    register_companion_aliases(&mut index);
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(result: &CallToolResult) -> String {
+        result
+            .content
+            .iter()
+            .filter_map(|content| content.as_text())
+            .map(|text| text.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[tokio::test]
+    async fn test_tools_answer_while_indexing() {
+        let root =
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/ambiguous-names");
+        let server = KotlinMcpServer::new(root, true, Duration::ZERO, FileFilter::default());
+        let query = || {
+            Parameters(FindDefinitionParams {
+                symbol: "User".to_string(),
+                file: None,
+                line: None,
+                match_mode: None,
+                via_module_dependencies: None,
+            })
+        };
+
+        // The single-threaded test runtime hasn't run the indexing task yet
+        let early = server.find_definition(query()).await.unwrap();
+        assert_eq!(early.is_error, Some(true));
+        assert!(text(&early).starts_with("Still indexing"), "{}", text(&early));
+        let health = text(&server.health().await.unwrap());
+        assert!(health.contains("\"indexed\": false"), "{}", health);
+
+        server.wait_until_indexed().await;
+        let late = text(&server.find_definition(query()).await.unwrap());
+        assert!(late.contains("com.example.accounts.User"), "{}", late);
+        assert!(late.contains("com.example.billing.User"), "{}", late);
+        let health = text(&server.health().await.unwrap());
+        assert!(health.contains("\"indexed\": true"), "{}", health);
+        assert!(health.contains("\"files\": 4"), "{}", health);
+
+        let reindexed = server.reindex().await.unwrap();
+        assert!(text(&reindexed).starts_with("Reindex complete"), "{}", text(&reindexed));
+        let health = text(&server.health().await.unwrap());
+        assert!(health.contains("\"reindex_in_progress\": false"), "{}", health);
+    }
 }
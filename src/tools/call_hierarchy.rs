@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::indexer::{SymbolIndex, SymbolKind, SymbolOccurrence};
+
+/// Call sites grouped under the declaration that contains them.
+pub struct CallerGroup<'a> {
+    /// Innermost function-like declaration containing the calls, or the innermost
+    /// declaration of any kind for calls in property initializers and class bodies.
+    /// None for calls outside every declaration (e.g., top-level script code).
+    pub caller: Option<&'a SymbolOccurrence>,
+    pub call_sites: Vec<&'a SymbolOccurrence>,
+}
+
+/// Find every call site of a function, grouped by the declaration the call appears in.
+/// Accepts a fully-qualified name or a simple name (matching all functions with that name).
+pub fn incoming_calls<'a>(index: &'a SymbolIndex, symbol: &str) -> Vec<CallerGroup<'a>> {
+    let target_fqns = resolve_function_fqns(index, symbol);
+    let declarations = declarations_by_file(index);
+
+    let mut groups: Vec<CallerGroup> = Vec::new();
+    for fqn in &target_fqns {
+        if let Some(occs) = index.by_fqn.get(fqn) {
+            for call in occs.iter().filter(|o| is_call(&o.kind)) {
+                let caller = enclosing_declaration(&declarations, call);
+                match groups.iter_mut().find(|g| same_occurrence(g.caller, caller)) {
+                    Some(group) => group.call_sites.push(call),
+                    None => groups.push(CallerGroup {
+                        caller,
+                        call_sites: vec![call],
+                    }),
+                }
+            }
+        }
+    }
+
+    for group in &mut groups {
+        group
+            .call_sites
+            .sort_by(|a, b| a.file.cmp(&b.file).then(a.line.cmp(&b.line)));
+    }
+    groups.sort_by(|a, b| {
+        let key = |g: &CallerGroup| g.call_sites.first().map(|o| (o.file.clone(), o.line));
+        key(a).cmp(&key(b))
+    });
+    groups
+}
+
+/// Find every call site inside the body of a function declaration.
+/// Accepts a fully-qualified name or a simple name (matching all functions with that name).
+pub fn outgoing_calls<'a>(index: &'a SymbolIndex, symbol: &str) -> Vec<&'a SymbolOccurrence> {
+    let function_decls: Vec<&SymbolOccurrence> = resolve_function_fqns(index, symbol)
+        .iter()
+        .filter_map(|fqn| index.by_fqn.get(fqn))
+        .flatten()
+        .filter(|o| o.kind.is_function_like())
+        .collect();
+
+    let mut results: Vec<&SymbolOccurrence> = Vec::new();
+    for occs in index.by_name.values() {
+        for occ in occs {
+            if !is_call(&occ.kind) {
+                continue;
+            }
+            let inside = function_decls.iter().any(|decl| {
+                decl.file == occ.file
+                    && decl.byte_range.start <= occ.byte_range.start
+                    && occ.byte_range.end <= decl.byte_range.end
+            });
+            if inside {
+                results.push(occ);
+            }
+        }
+    }
+    results.sort_by(|a, b| {
+        a.file
+            .cmp(&b.file)
+            .then(a.line.cmp(&b.line))
+            .then(a.column.cmp(&b.column))
+    });
+    results
+}
+
+/// Format grouped incoming calls into a human-readable string.
+pub fn format_incoming_calls(groups: &[CallerGroup], project_root: &Path) -> String {
+    if groups.is_empty() {
+        return "No results found.".to_string();
+    }
+
+    let total: usize = groups.iter().map(|g| g.call_sites.len()).sum();
+    let mut lines = Vec::new();
+    lines.push(format!(
+        "Found {} call site(s) in {} caller(s):\n",
+        total,
+        groups.len()
+    ));
+
+    for group in groups {
+        match group.caller {
+            Some(caller) => lines.push(format!(
+                "  {}",
+                caller.fqn.as_deref().unwrap_or(&caller.name)
+            )),
+            None => lines.push("  <top level>".to_string()),
+        }
+        for call in &group.call_sites {
+            lines.push(format!(
+                "    {}",
+                super::format_occurrence(call, project_root)
+            ));
+        }
+    }
+
+    lines.join("\n")
+}
+
+fn resolve_function_fqns(index: &SymbolIndex, symbol: &str) -> Vec<String> {
+    if symbol.contains('.') {
+        return vec![symbol.to_string()];
+    }
+    let mut fqns: Vec<String> = index
+        .by_name
+        .get(symbol)
+        .map(|occs| {
+            occs.iter()
+                .filter(|o| o.kind.is_function_like())
+                .filter_map(|o| o.fqn.clone())
+                .collect()
+        })
+        .unwrap_or_default();
+    fqns.sort();
+    fqns.dedup();
+    fqns
+}
+
+fn declarations_by_file(index: &SymbolIndex) -> HashMap<&PathBuf, Vec<&SymbolOccurrence>> {
+    let mut map: HashMap<&PathBuf, Vec<&SymbolOccurrence>> = HashMap::new();
+    for occs in index.by_name.values() {
+        for occ in occs {
+            if occ.kind.is_declaration() && !matches!(occ.kind, SymbolKind::ParameterDeclaration) {
+                map.entry(&occ.file).or_default().push(occ);
+            }
+        }
+    }
+    map
+}
+
+/// Find the declaration containing `occ`, preferring the innermost function-like declaration.
+fn enclosing_declaration<'a>(
+    declarations: &HashMap<&PathBuf, Vec<&'a SymbolOccurrence>>,
+    occ: &SymbolOccurrence,
+) -> Option<&'a SymbolOccurrence> {
+    let candidates: Vec<&SymbolOccurrence> = declarations
+        .get(&occ.file)?
+        .iter()
+        .copied()
+        .filter(|d| {
+            d.byte_range.start <= occ.byte_range.start && occ.byte_range.end <= d.byte_range.end
+        })
+        .collect();
+    let innermost = |decls: Vec<&'a SymbolOccurrence>| {
+        decls
+            .into_iter()
+            .min_by_key(|d| d.byte_range.end - d.byte_range.start)
+    };
+    let functions: Vec<&SymbolOccurrence> = candidates
+        .iter()
+        .copied()
+        .filter(|d| d.kind.is_function_like())
+        .collect();
+    innermost(functions).or_else(|| innermost(candidates))
+}
+
+fn same_occurrence(a: Option<&SymbolOccurrence>, b: Option<&SymbolOccurrence>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => std::ptr::eq(a, b),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+fn is_call(kind: &SymbolKind) -> bool {
+    matches!(kind, SymbolKind::CallSite | SymbolKind::ExtensionFunctionCall)
+}
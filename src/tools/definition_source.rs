@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::indexer::{SymbolIndex, SymbolOccurrence};
+
+use super::find_definition::find_definition;
+
+/// A declaration with the source text spanning its byte range: the whole class for a
+/// class, the signature and body for a function.
+pub struct DefinitionSource<'a> {
+    pub declaration: &'a SymbolOccurrence,
+    /// `None` when the file can no longer be read or has changed since it was indexed,
+    /// so the byte range no longer falls on character boundaries
+    pub source: Option<String>,
+}
+
+/// Resolve `symbol` like `find_definition` and read the source of every matching
+/// declaration from disk. Each file is read once.
+pub fn definition_sources<'a>(
+    index: &'a SymbolIndex,
+    symbol: &str,
+    file: Option<&Path>,
+    line: Option<usize>,
+) -> Vec<DefinitionSource<'a>> {
+    let mut contents: HashMap<&PathBuf, Option<String>> = HashMap::new();
+    find_definition(index, symbol, file, line)
+        .into_iter()
+        .map(|declaration| {
+            let content = contents
+                .entry(&declaration.file)
+                .or_insert_with(|| std::fs::read_to_string(&declaration.file).ok());
+            let source = content
+                .as_deref()
+                .and_then(|content| content.get(declaration.byte_range.clone()))
+                .map(|text| text.to_string());
+            DefinitionSource { declaration, source }
+        })
+        .collect()
+}
+
+/// Format each declaration's location line followed by its source in a fenced code block.
+pub fn format_definition_sources(sources: &[DefinitionSource], project_root: &Path) -> String {
+    if sources.is_empty() {
+        return "No definitions found.".to_string();
+    }
+
+    let mut sections = vec![format!("Found {} definition(s):", sources.len())];
+    for definition in sources {
+        let location = super::format_occurrence(definition.declaration, project_root);
+        let section = match definition.source {
+            Some(ref source) => format!(
+                "{}\n```{}\n{}\n```",
+                location,
+                fence_language(&definition.declaration.file),
+                source
+            ),
+            None => format!(
+                "{}\n(source unavailable: the file is unreadable or changed since indexing; reindex and retry)",
+                location
+            ),
+        };
+        sections.push(section);
+    }
+    sections.join("\n\n")
+}
+
+fn fence_language(file: &Path) -> &'static str {
+    match file.extension().and_then(|ext| ext.to_str()) {
+        Some("java") => "java",
+        _ => "kotlin",
+    }
+}
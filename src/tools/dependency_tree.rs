@@ -1,25 +1,107 @@
-use crate::gradle::{DependencyNode, GradleRunner};
+use std::collections::{BTreeMap, BTreeSet};
 
-/// Get the dependency tree for a module, formatted as text.
+use serde::Serialize;
+
+use crate::error::GradleError;
+use crate::gradle::{DependencyNode, GradleRunner, DEFAULT_CONFIGURATION};
+
+/// Stable, machine-readable code for each `GradleError` variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GradleErrorCode {
+    WrapperNotFound,
+    BuildFileNotFound,
+    InvalidConfiguration,
+    CommandFailed,
+    ParseError,
+    IoError,
+}
+
+impl From<&GradleError> for GradleErrorCode {
+    fn from(error: &GradleError) -> Self {
+        match error {
+            GradleError::WrapperNotFound(_) => GradleErrorCode::WrapperNotFound,
+            GradleError::BuildFileNotFound(_) => GradleErrorCode::BuildFileNotFound,
+            GradleError::InvalidConfiguration(_) => GradleErrorCode::InvalidConfiguration,
+            GradleError::CommandFailed(_) => GradleErrorCode::CommandFailed,
+            GradleError::ParseError(_) => GradleErrorCode::ParseError,
+            GradleError::IoError(_) => GradleErrorCode::IoError,
+        }
+    }
+}
+
+/// Structured error payload returned by the `dependency_tree` tool, e.g.
+/// `{"code":"wrapper_not_found","message":"Gradle wrapper not found at: /p/gradlew"}`.
+#[derive(Debug, Serialize)]
+pub struct DependencyTreeError {
+    pub code: GradleErrorCode,
+    pub message: String,
+}
+
+impl From<&GradleError> for DependencyTreeError {
+    fn from(error: &GradleError) -> Self {
+        Self {
+            code: error.into(),
+            message: error.to_string(),
+        }
+    }
+}
+
+impl DependencyTreeError {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| "{}".to_string())
+    }
+}
+
+/// Get the dependency tree for a module in `configuration` (default: `compileClasspath`),
+/// formatted as text. `max_depth` cuts the tree below that many levels (1 keeps only the
+/// direct dependencies); `flatten` lists every artifact in the tree once instead, see
+/// [`flatten_dependencies`]. Without a module, lists the project's modules, read from the
+/// settings script when Gradle isn't run (see [`GradleRunner::get_project_modules`]).
 pub fn dependency_tree(
     runner: &GradleRunner,
     module: Option<&str>,
-) -> Result<String, crate::error::GradleError> {
+    configuration: Option<&str>,
+    max_depth: Option<usize>,
+    flatten: bool,
+) -> Result<String, GradleError> {
     let mut output = String::new();
 
     if let Some(module) = module {
         // Get dependencies for a specific module
-        let deps = runner.get_dependencies(module)?;
-        output.push_str(&format!("Dependencies for module '{}':\n\n", module));
-        for dep in &deps {
-            format_dep_node(&mut output, dep, 0);
+        let deps = runner.get_dependencies(module, configuration)?;
+        if runner.uses_declared_dependencies() {
+            let on_configuration = configuration
+                .map(|c| format!(" on {}", c))
+                .unwrap_or_default();
+            output.push_str(&format!(
+                "Declared dependencies for module '{}'{} (from build script, unresolved):\n\n",
+                module, on_configuration
+            ));
+        } else {
+            output.push_str(&format!(
+                "Dependencies for module '{}' ({}):\n\n",
+                module,
+                configuration.unwrap_or(DEFAULT_CONFIGURATION)
+            ));
+        }
+        if flatten {
+            output.push_str(&format_flat_dependencies(&deps, max_depth));
+        } else {
+            output.push_str(&format_dependency_nodes(&deps, max_depth));
         }
     } else {
         // List all modules
-        let modules = runner.get_modules()?;
+        let modules = runner.get_project_modules()?;
+        let source = if runner.uses_declared_dependencies() {
+            ", from settings script"
+        } else {
+            ""
+        };
         output.push_str(&format!(
-            "Project modules ({} total):\n\n",
-            modules.len()
+            "Project modules ({} total{}):\n\n",
+            modules.len(),
+            source
         ));
         for m in &modules {
             output.push_str(&format!("  {} ({})\n", m.path, m.name));
@@ -29,12 +111,93 @@ pub fn dependency_tree(
     Ok(output)
 }
 
-fn format_dep_node(output: &mut String, node: &DependencyNode, depth: usize) {
+/// [`dependency_tree`] as JSON: the module's dependency nodes with their children, or the
+/// project modules (`path`, `name`) without a module. `flatten` gives the array of
+/// [`flatten_dependencies`] instead of the nodes.
+pub fn dependency_tree_json(
+    runner: &GradleRunner,
+    module: Option<&str>,
+    configuration: Option<&str>,
+    max_depth: Option<usize>,
+    flatten: bool,
+) -> Result<String, GradleError> {
+    let json = match module {
+        Some(module) => {
+            let deps = runner.get_dependencies(module, configuration)?;
+            if flatten {
+                serde_json::to_string_pretty(&flatten_dependencies(&deps, max_depth))
+                    .unwrap_or_else(|_| "[]".to_string())
+            } else {
+                format_dependency_nodes_json(&deps, max_depth)
+            }
+        }
+        None => serde_json::to_string_pretty(&runner.get_project_modules()?)
+            .unwrap_or_else(|_| "[]".to_string()),
+    };
+    Ok(json)
+}
+
+/// Format dependency trees as a JSON array of nodes. With `max_depth`, the children below
+/// that many levels are left out.
+pub fn format_dependency_nodes_json(deps: &[DependencyNode], max_depth: Option<usize>) -> String {
+    let deps = match max_depth {
+        Some(max) => truncate_dependency_nodes(deps, max),
+        None => deps.to_vec(),
+    };
+    serde_json::to_string_pretty(&deps).unwrap_or_else(|_| "[]".to_string())
+}
+
+fn truncate_dependency_nodes(deps: &[DependencyNode], max_depth: usize) -> Vec<DependencyNode> {
+    deps.iter()
+        .map(|dep| DependencyNode {
+            children: if max_depth > 1 {
+                truncate_dependency_nodes(&dep.children, max_depth - 1)
+            } else {
+                Vec::new()
+            },
+            ..dep.clone()
+        })
+        .collect()
+}
+
+/// Format dependency trees as indented text, one line per dependency. With `max_depth`, the
+/// dependencies below that many levels are left out and their parent says how many.
+pub fn format_dependency_nodes(deps: &[DependencyNode], max_depth: Option<usize>) -> String {
+    let mut output = String::new();
+    for dep in deps {
+        format_dep_node(&mut output, dep, 0, max_depth);
+    }
+    output
+}
+
+fn format_dep_node(
+    output: &mut String,
+    node: &DependencyNode,
+    depth: usize,
+    max_depth: Option<usize>,
+) {
     let indent = "  ".repeat(depth);
     let prefix = if depth == 0 { "" } else { "├── " };
 
+    let config_marker = node
+        .configuration
+        .as_deref()
+        .map(|c| format!(" [{}]", c))
+        .unwrap_or_default();
+
+    // Children past the depth limit are counted on their parent's line instead
+    let truncated = max_depth.is_some_and(|max| depth + 1 >= max) && !node.children.is_empty();
+    let hidden_marker = if truncated {
+        format!(" (+{} more)", count_descendants(node))
+    } else {
+        String::new()
+    };
+
     if node.is_project {
-        output.push_str(&format!("{}{}project :{}\n", indent, prefix, node.artifact));
+        output.push_str(&format!(
+            "{}{}project :{}{}{}\n",
+            indent, prefix, node.artifact, config_marker, hidden_marker
+        ));
     } else {
         let version_display = if let Some(ref resolved) = node.resolved_version {
             format!("{} -> {}", node.version, resolved)
@@ -49,13 +212,198 @@ fn format_dep_node(output: &mut String, node: &DependencyNode, depth: usize) {
         };
 
         output.push_str(&format!(
-            "{}{}{}:{}:{}{}",
-            indent, prefix, node.group, node.artifact, version_display, dup_marker
+            "{}{}{}:{}:{}{}{}{}",
+            indent,
+            prefix,
+            node.group,
+            node.artifact,
+            version_display,
+            dup_marker,
+            config_marker,
+            hidden_marker
         ));
         output.push('\n');
     }
 
+    if truncated {
+        return;
+    }
     for child in &node.children {
-        format_dep_node(output, child, depth + 1);
+        format_dep_node(output, child, depth + 1, max_depth);
     }
 }
+
+fn count_descendants(node: &DependencyNode) -> usize {
+    node.children.iter().map(|child| 1 + count_descendants(child)).sum()
+}
+
+/// Every artifact in the dependency trees as `group:artifact:version`, once each and sorted,
+/// with the version Gradle resolved it to. Repeats of an artifact already listed elsewhere
+/// in the tree (`(*)`) collapse into one entry, and projects are left out. With `max_depth`,
+/// only artifacts within that many levels are listed.
+pub fn flatten_dependencies(deps: &[DependencyNode], max_depth: Option<usize>) -> Vec<String> {
+    let mut artifacts = BTreeSet::new();
+    for dep in deps {
+        collect_artifacts(dep, 0, max_depth, &mut artifacts);
+    }
+    artifacts.into_iter().collect()
+}
+
+fn collect_artifacts(
+    node: &DependencyNode,
+    depth: usize,
+    max_depth: Option<usize>,
+    artifacts: &mut BTreeSet<String>,
+) {
+    if max_depth.is_some_and(|max| depth >= max) {
+        return;
+    }
+    if !node.is_project {
+        let version = node.resolved_version.as_deref().unwrap_or(&node.version);
+        // Versions managed by a BOM or platform are left blank in the tree
+        artifacts.insert(if version.is_empty() {
+            format!("{}:{}", node.group, node.artifact)
+        } else {
+            format!("{}:{}:{}", node.group, node.artifact, version)
+        });
+    }
+    for child in &node.children {
+        collect_artifacts(child, depth + 1, max_depth, artifacts);
+    }
+}
+
+/// Format [`flatten_dependencies`] as a counted list, one artifact per line.
+pub fn format_flat_dependencies(deps: &[DependencyNode], max_depth: Option<usize>) -> String {
+    let artifacts = flatten_dependencies(deps, max_depth);
+    let mut output = format!("{} unique artifact(s):\n", artifacts.len());
+    for artifact in &artifacts {
+        output.push_str(&format!("  {}\n", artifact));
+    }
+    output
+}
+
+/// A `group:artifact` requested at different versions, or resolved by Gradle to a version
+/// other than the one requested (`1.8.20 -> 1.9.22`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionConflict {
+    pub group: String,
+    pub artifact: String,
+    /// Version Gradle selected, when the tree reports one
+    pub resolved_version: Option<String>,
+    /// Every request for the artifact, in tree order
+    pub requests: Vec<VersionRequest>,
+}
+
+/// One place in the dependency tree that asks for a version of an artifact.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionRequest {
+    pub version: String,
+    /// The dependencies leading to the request, outermost first
+    /// (e.g. `["com.squareup.okhttp3:okhttp:4.12.0"]`); empty for a direct dependency
+    pub path: Vec<String>,
+}
+
+/// Walk the whole dependency tree and collect the artifacts with version conflicts,
+/// sorted by `group:artifact`.
+pub fn find_version_conflicts(deps: &[DependencyNode]) -> Vec<VersionConflict> {
+    let mut by_artifact: BTreeMap<(String, String), VersionConflict> = BTreeMap::new();
+    let mut path = Vec::new();
+    for dep in deps {
+        collect_version_requests(dep, &mut path, &mut by_artifact);
+    }
+
+    by_artifact
+        .into_values()
+        .filter(|conflict| {
+            let first = match conflict.requests.first() {
+                Some(request) => &request.version,
+                None => return false,
+            };
+            let resolved_elsewhere = conflict
+                .resolved_version
+                .as_ref()
+                .is_some_and(|resolved| conflict.requests.iter().any(|r| r.version != *resolved));
+            resolved_elsewhere || conflict.requests.iter().any(|r| r.version != *first)
+        })
+        .collect()
+}
+
+fn collect_version_requests(
+    node: &DependencyNode,
+    path: &mut Vec<String>,
+    by_artifact: &mut BTreeMap<(String, String), VersionConflict>,
+) {
+    let label = if node.is_project {
+        format!("project :{}", node.artifact)
+    } else {
+        let entry = by_artifact
+            .entry((node.group.clone(), node.artifact.clone()))
+            .or_insert_with(|| VersionConflict {
+                group: node.group.clone(),
+                artifact: node.artifact.clone(),
+                resolved_version: None,
+                requests: Vec::new(),
+            });
+        if entry.resolved_version.is_none() {
+            entry.resolved_version = node.resolved_version.clone();
+        }
+        let request = VersionRequest {
+            version: node.version.clone(),
+            path: path.clone(),
+        };
+        // The same dependency declared in several configurations is one request, and a
+        // dependency without a version (managed by a BOM or platform) requests none
+        if !request.version.is_empty() && !entry.requests.contains(&request) {
+            entry.requests.push(request);
+        }
+        format!("{}:{}:{}", node.group, node.artifact, node.version)
+    };
+
+    path.push(label);
+    for child in &node.children {
+        collect_version_requests(child, path, by_artifact);
+    }
+    path.pop();
+}
+
+/// Format a "version conflicts" report: each conflicting artifact with the version Gradle
+/// selected, then every requested version and the dependency path that requested it.
+pub fn format_version_conflicts(conflicts: &[VersionConflict], module: &str) -> String {
+    if conflicts.is_empty() {
+        return format!("No version conflicts in module '{}'.", module);
+    }
+
+    let mut output = format!(
+        "Version conflicts in module '{}' ({} total):\n",
+        module,
+        conflicts.len()
+    );
+    for conflict in conflicts {
+        let resolved = conflict
+            .resolved_version
+            .as_deref()
+            .map(|v| format!(" -> {}", v))
+            .unwrap_or_default();
+        output.push_str(&format!("\n  {}:{}{}\n", conflict.group, conflict.artifact, resolved));
+        for request in &conflict.requests {
+            let via = if request.path.is_empty() {
+                "direct".to_string()
+            } else {
+                format!("via {}", request.path.join(" > "))
+            };
+            output.push_str(&format!("    {} ({})\n", request.version, via));
+        }
+    }
+    output
+}
+
+/// Report the version conflicts among a module's dependencies in `configuration`
+/// (default: `compileClasspath`), including transitive ones.
+pub fn dependency_conflicts(
+    runner: &GradleRunner,
+    module: &str,
+    configuration: Option<&str>,
+) -> Result<String, GradleError> {
+    let deps = runner.get_dependencies(module, configuration)?;
+    Ok(format_version_conflicts(&find_version_conflicts(&deps), module))
+}
@@ -0,0 +1,118 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::indexer::{SymbolIndex, SymbolKind, SymbolOccurrence};
+
+use super::outline::find_file;
+
+/// The references in a file to one symbol.
+pub struct ReferenceGroup<'a> {
+    /// FQN the references resolved to; None for unresolved references, grouped by name
+    pub fqn: Option<&'a str>,
+    pub name: &'a str,
+    /// The references in source order
+    pub occurrences: Vec<&'a SymbolOccurrence>,
+}
+
+/// The references in a file grouped by the symbol they resolve to, most referenced first,
+/// then resolved before unresolved, then by FQN. Imports and declarations are left out; the outline lists those.
+pub fn file_references<'a>(index: &'a SymbolIndex, file: &Path) -> Vec<ReferenceGroup<'a>> {
+    let path = match find_file(index, file) {
+        Some(info) => &info.path,
+        None => return Vec::new(),
+    };
+
+    let mut references: Vec<&SymbolOccurrence> = index
+        .by_name
+        .values()
+        .flatten()
+        .filter(|o| &o.file == path && o.kind.is_reference())
+        .collect();
+    references.sort_by(|a, b| a.line.cmp(&b.line).then(a.column.cmp(&b.column)));
+
+    let mut groups: Vec<ReferenceGroup> = Vec::new();
+    let mut positions: HashMap<(Option<&str>, &str), usize> = HashMap::new();
+    for occ in references {
+        let fqn = occ.fqn.as_deref();
+        // Resolved references to one FQN may be written differently (aliases, `Foo.bar`)
+        let key = match fqn {
+            Some(fqn) => (Some(fqn), ""),
+            None => (None, occ.name.as_str()),
+        };
+        match positions.get(&key) {
+            Some(&position) => groups[position].occurrences.push(occ),
+            None => {
+                positions.insert(key, groups.len());
+                groups.push(ReferenceGroup {
+                    fqn,
+                    name: &occ.name,
+                    occurrences: vec![occ],
+                });
+            }
+        }
+    }
+
+    groups.sort_by(|a, b| {
+        Reverse(a.occurrences.len())
+            .cmp(&Reverse(b.occurrences.len()))
+            .then(a.fqn.is_none().cmp(&b.fqn.is_none()))
+            .then(a.fqn.cmp(&b.fqn))
+            .then(a.name.cmp(b.name))
+    });
+    groups
+}
+
+/// Format a file's references: one line per referenced symbol with its count, the kinds
+/// of reference and the lines they are on. Unresolved references are listed by name.
+pub fn format_file_references(index: &SymbolIndex, file: &Path, project_root: &Path) -> String {
+    let info = match find_file(index, file) {
+        Some(info) => info,
+        None => return format!("File not indexed: {}", file.display()),
+    };
+    let rel_path = info
+        .path
+        .strip_prefix(project_root)
+        .unwrap_or(&info.path)
+        .display();
+
+    let groups = file_references(index, &info.path);
+    if groups.is_empty() {
+        return format!("No references found in {}.", rel_path);
+    }
+    let total: usize = groups.iter().map(|g| g.occurrences.len()).sum();
+    let mut lines = vec![format!(
+        "{} reference(s) to {} symbol(s) in {}:\n",
+        total,
+        groups.len(),
+        rel_path
+    )];
+    for group in &groups {
+        let mut kinds: Vec<SymbolKind> = Vec::new();
+        for occ in &group.occurrences {
+            if !kinds.contains(&occ.kind) {
+                kinds.push(occ.kind.clone());
+            }
+        }
+        let kinds: Vec<String> = kinds.iter().map(|k| format!("{:?}", k)).collect();
+        let mut line_numbers: Vec<String> = Vec::new();
+        for occ in &group.occurrences {
+            let number = occ.line.to_string();
+            if !line_numbers.contains(&number) {
+                line_numbers.push(number);
+            }
+        }
+        let target = match group.fqn {
+            Some(fqn) => fqn.to_string(),
+            None => format!("`{}` (unresolved)", group.name),
+        };
+        lines.push(format!(
+            "  {} ({}) - {}; line(s) {}",
+            target,
+            group.occurrences.len(),
+            kinds.join(", "),
+            line_numbers.join(", ")
+        ));
+    }
+    lines.join("\n")
+}
@@ -1,15 +1,33 @@
+use std::collections::BTreeMap;
 use std::path::Path;
 
-use crate::indexer::{SymbolIndex, SymbolOccurrence};
+use crate::indexer::symbols::{kotlin_stdlib_fqn, resolve_name_in_file};
+use crate::indexer::{PlatformModifier, SymbolIndex, SymbolKind, SymbolOccurrence};
+
+use super::outline::find_file;
+use super::{matching_occurrences, NameMatch, OccurrenceJson};
 
 /// Find the definition(s) of a symbol.
-/// Returns only declaration-kind occurrences.
+/// Returns only declaration-kind occurrences, most likely definition first (see
+/// [`rank_definitions`]); the order is stable across runs. A symbol with parameter types,
+/// e.g. `process(String)` or `com.example.process(String)`, selects among overloads. A symbol
+/// qualified by a type instead of a package, `AppConfig.Network.TIMEOUT`, resolves through
+/// the declarations of that type.
 pub fn find_definition<'a>(
     index: &'a SymbolIndex,
     symbol: &str,
     file: Option<&Path>,
     line: Option<usize>,
 ) -> Vec<&'a SymbolOccurrence> {
+    if let Some((name, signature)) = split_signature(symbol) {
+        let signature = normalize_signature(signature);
+        let mut results = find_definition(index, name, file, line);
+        results.retain(|occ| {
+            occ.signature.as_deref().map(normalize_signature).as_ref() == Some(&signature)
+        });
+        return results;
+    }
+
     // If file and line are provided, try to resolve the exact FQN at that location
     let fqn = if let (Some(f), Some(l)) = (file, line) {
         find_reference_fqn_at(index, f, l, symbol)
@@ -42,11 +60,23 @@ pub fn find_definition<'a>(
             }
         }
         if !results.is_empty() {
-            results.sort_by(|a, b| a.file.cmp(&b.file).then(a.line.cmp(&b.line)));
+            rank_definitions(index, &mut results, symbol, file);
             return results;
         }
     }
 
+    // `AppConfig.Network.TIMEOUT`: qualified by a type rather than a package
+    let mut results: Vec<&SymbolOccurrence> = qualified_chain_fqns(index, symbol, file)
+        .iter()
+        .filter_map(|fqn| index.by_fqn.get(fqn))
+        .flatten()
+        .filter(|occ| occ.kind.is_declaration())
+        .collect();
+    if !results.is_empty() {
+        rank_definitions(index, &mut results, symbol, file);
+        return results;
+    }
+
     // Fall back to name-based lookup
     let mut results: Vec<&SymbolOccurrence> = Vec::new();
     if let Some(occs) = index.by_name.get(symbol) {
@@ -56,11 +86,274 @@ pub fn find_definition<'a>(
             }
         }
     }
+
+    // Java-style accessor call on a Kotlin property, e.g. `getName()` → `name`
+    if results.is_empty() {
+        let simple_name = symbol.rsplit('.').next().unwrap_or(symbol);
+        if let Some((properties, is_setter)) = accessor_property_names(simple_name) {
+            return find_accessor_property(
+                index,
+                &properties,
+                is_setter,
+                fqn.as_deref(),
+                !symbol.contains('.'),
+            );
+        }
+    }
+
+    rank_definitions(index, &mut results, symbol, file);
+    results
+}
+
+/// Find definitions like [`find_definition`], matching `symbol` against declared names
+/// under `name_match`. Exact matching is [`find_definition`] itself; the other modes return
+/// every declaration whose simple name (or FQN, for a qualified `symbol`) matches, ranked
+/// the same way.
+pub fn find_definition_matching<'a>(
+    index: &'a SymbolIndex,
+    symbol: &str,
+    file: Option<&Path>,
+    line: Option<usize>,
+    name_match: NameMatch,
+) -> Vec<&'a SymbolOccurrence> {
+    if name_match == NameMatch::Exact {
+        return find_definition(index, symbol, file, line);
+    }
+    let mut results: Vec<&SymbolOccurrence> = matching_occurrences(index, symbol, name_match)
+        .into_iter()
+        .filter(|occ| occ.kind.is_declaration())
+        .collect();
+    rank_definitions(index, &mut results, symbol, file);
+    results
+}
+
+/// The FQNs a chain starting with a type name, such as `AppConfig.Network.TIMEOUT`, can
+/// name: the head resolved through `file`'s imports and package when given, otherwise every
+/// type declared with that name (following type aliases), with the rest of the chain
+/// appended. Only FQNs the index knows are returned.
+fn qualified_chain_fqns(index: &SymbolIndex, symbol: &str, file: Option<&Path>) -> Vec<String> {
+    let (head, rest) = match symbol.split_once('.') {
+        Some((head, rest)) if head.starts_with(|c: char| c.is_ascii_uppercase()) => (head, rest),
+        _ => return Vec::new(),
+    };
+    let resolved = file
+        .and_then(|f| find_file(index, f))
+        .and_then(|info| resolve_name_in_file(index, head, info));
+    let heads: Vec<String> = match resolved {
+        Some(head_fqn) => vec![head_fqn],
+        None => index
+            .by_name
+            .get(head)
+            .into_iter()
+            .flatten()
+            .filter(|occ| {
+                occ.kind.is_type_declaration() || occ.kind == SymbolKind::TypeAliasDeclaration
+            })
+            .filter_map(|occ| occ.fqn.clone())
+            .collect(),
+    };
+
+    let mut fqns: Vec<String> = heads
+        .into_iter()
+        .map(|head_fqn| {
+            let head_fqn = index.type_aliases.get(&head_fqn).cloned().unwrap_or(head_fqn);
+            format!("{}.{}", head_fqn, rest)
+        })
+        .filter(|fqn| index.by_fqn.contains_key(fqn))
+        .collect();
+    fqns.sort();
+    fqns.dedup();
+    fqns
+}
+
+/// Split `process(Int, String)` into the name and the parenthesized parameter types.
+fn split_signature(symbol: &str) -> Option<(&str, &str)> {
+    if !symbol.ends_with(')') {
+        return None;
+    }
+    let open = symbol.find('(')?;
+    Some((&symbol[..open], &symbol[open..]))
+}
+
+/// Drop whitespace so `(Int,String)` matches the recorded `(Int, String)`.
+fn normalize_signature(signature: &str) -> String {
+    signature.chars().filter(|c| !c.is_whitespace()).collect()
+}
+
+/// Order declarations so the most likely definition comes first:
+/// 1. With a referencing `file`, the declaration `symbol` resolves to from that file
+///    (same file, imports, then same package)
+/// 2. Types before type aliases, functions and constructors, then properties and the rest
+/// 3. An `expect` declaration before its `actual` implementations
+/// 4. File path, then line, so the order is stable
+fn rank_definitions(
+    index: &SymbolIndex,
+    results: &mut [&SymbolOccurrence],
+    symbol: &str,
+    file: Option<&Path>,
+) {
+    let simple_name = symbol.rsplit('.').next().unwrap_or(symbol);
+    let resolved = file
+        .and_then(|f| find_file(index, f))
+        .and_then(|info| resolve_name_in_file(index, simple_name, info));
+    results.sort_by(|a, b| {
+        let a_unreachable = resolved.is_some() && a.fqn != resolved;
+        let b_unreachable = resolved.is_some() && b.fqn != resolved;
+        a_unreachable
+            .cmp(&b_unreachable)
+            .then(declaration_rank(&a.kind).cmp(&declaration_rank(&b.kind)))
+            .then(is_expect(b).cmp(&is_expect(a)))
+            .then(a.file.cmp(&b.file))
+            .then(a.line.cmp(&b.line))
+            .then(a.column.cmp(&b.column))
+    });
+}
+
+fn is_expect(occ: &SymbolOccurrence) -> bool {
+    occ.platform_modifier == Some(PlatformModifier::Expect)
+}
+
+/// How likely a declaration kind is what a lookup by name is after; lower is more likely.
+fn declaration_rank(kind: &SymbolKind) -> u8 {
+    if kind.is_type_declaration() {
+        0
+    } else if matches!(kind, SymbolKind::TypeAliasDeclaration) {
+        1
+    } else if kind.is_function_like() {
+        2
+    } else if matches!(kind, SymbolKind::ParameterDeclaration) {
+        4
+    } else {
+        3
+    }
+}
+
+/// Resolve several symbols against one index, each like [`find_definition`] with its own
+/// optional `(file, line)` context. Results are returned in query order.
+pub fn find_definitions_batch<'a>(
+    index: &'a SymbolIndex,
+    queries: &[(&str, Option<&Path>, Option<usize>)],
+) -> Vec<Vec<&'a SymbolOccurrence>> {
+    queries
+        .iter()
+        .map(|&(symbol, file, line)| find_definition(index, symbol, file, line))
+        .collect()
+}
+
+/// Format batch results as a JSON object mapping each input to its definitions, an array
+/// of [`OccurrenceJson`] that is empty when the input resolved to nothing.
+pub fn format_definitions_batch_json(
+    results: &[(String, Vec<&SymbolOccurrence>)],
+    project_root: &Path,
+) -> String {
+    let map: BTreeMap<&str, Vec<OccurrenceJson>> = results
+        .iter()
+        .map(|(input, occurrences)| {
+            let entries = occurrences
+                .iter()
+                .map(|occ| OccurrenceJson::from_occurrence(occ, project_root))
+                .collect();
+            (input.as_str(), entries)
+        })
+        .collect();
+    serde_json::to_string_pretty(&map).unwrap_or_else(|_| "{}".to_string())
+}
+
+/// Last resort when `find_definition` finds nothing: the canonical FQN of a Kotlin
+/// standard library function (e.g., `listOf` → `kotlin.collections.listOf`), whose
+/// declaration isn't part of the project sources. Project declarations always win.
+pub fn find_stdlib_definition(index: &SymbolIndex, symbol: &str) -> Option<&'static str> {
+    let fqn = kotlin_stdlib_fqn(symbol)?;
+    let simple_name = fqn.rsplit('.').next().unwrap_or(fqn);
+    let declared_in_project = index
+        .by_name
+        .get(simple_name)
+        .is_some_and(|occs| occs.iter().any(|o| o.kind.is_declaration()));
+    if declared_in_project {
+        return None;
+    }
+    Some(fqn)
+}
+
+/// Format a standard library fallback from [`find_stdlib_definition`].
+pub fn format_stdlib_definition(symbol: &str, fqn: &str) -> String {
+    format!(
+        "No declaration of `{}` in the project. It resolves to the Kotlin standard library: {}",
+        symbol, fqn
+    )
+}
+
+/// Map a `get<X>`/`set<X>`/`is<X>` accessor name to the names of the properties it can
+/// access and whether it's a setter: "getUserName" → (["userName"], false). The setter of
+/// a Kotlin property named `isX` is `setX`, so "setLocked" → (["locked", "isLocked"], true).
+/// Returns None if the name doesn't follow the accessor convention.
+fn accessor_property_names(name: &str) -> Option<(Vec<String>, bool)> {
+    let (rest, is_setter) = [("get", false), ("set", true), ("is", false)]
+        .iter()
+        .find_map(|(prefix, is_setter)| name.strip_prefix(prefix).map(|rest| (rest, *is_setter)))?;
+    let mut chars = rest.chars();
+    let first = chars.next()?;
+    if !first.is_uppercase() {
+        return None;
+    }
+    let mut properties = vec![first.to_lowercase().to_string() + chars.as_str()];
+    if is_setter {
+        properties.push(format!("is{}", rest));
+    }
+    Some((properties, is_setter))
+}
+
+/// Find the Kotlin property declarations behind a Java-style accessor, constructor
+/// properties included. When the accessor's FQN is known, only properties in the same
+/// class are considered; otherwise (and only if `allow_name_fallback` is set) any Kotlin
+/// property with one of the names matches. A setter only maps to a `var`. Java fields are
+/// left out: their accessors are real (or Lombok-generated) declarations, so a missing one
+/// means there is none.
+fn find_accessor_property<'a>(
+    index: &'a SymbolIndex,
+    properties: &[String],
+    is_setter: bool,
+    accessor_fqn: Option<&str>,
+    allow_name_fallback: bool,
+) -> Vec<&'a SymbolOccurrence> {
+    let is_match = |occ: &SymbolOccurrence| {
+        matches!(occ.kind, SymbolKind::PropertyDeclaration)
+            && is_kotlin_file(&occ.file)
+            && (!is_setter || occ.modifiers.is_some_and(|m| m.is_var))
+    };
+    let mut results: Vec<&SymbolOccurrence> = Vec::new();
+
+    if let Some(accessor_fqn) = accessor_fqn {
+        for property in properties {
+            let property_fqn = match accessor_fqn.rsplit_once('.') {
+                Some((class_fqn, _)) => format!("{}.{}", class_fqn, property),
+                None => property.to_string(),
+            };
+            if let Some(occs) = index.by_fqn.get(&property_fqn) {
+                results.extend(occs.iter().filter(|occ| is_match(occ)));
+            }
+        }
+    }
+
+    if results.is_empty() && allow_name_fallback {
+        for property in properties {
+            if let Some(occs) = index.by_name.get(property) {
+                results.extend(occs.iter().filter(|occ| is_match(occ)));
+            }
+        }
+    }
+
     results.sort_by(|a, b| a.file.cmp(&b.file).then(a.line.cmp(&b.line)));
     results
 }
 
-/// Find the FQN of a reference at a specific file and line.
+fn is_kotlin_file(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "kt" || ext == "kts")
+}
+
+/// Find the FQN of a reference at a specific file and line. When the occurrence there
+/// carries no FQN (or the line has none), the name is resolved the way the file sees it,
+/// through its imports and package, so one of several same-name declarations is picked.
 fn find_reference_fqn_at(
     index: &SymbolIndex,
     file: &Path,
@@ -69,10 +362,11 @@ fn find_reference_fqn_at(
 ) -> Option<String> {
     if let Some(occs) = index.by_name.get(name) {
         for occ in occs {
-            if occ.file == file && occ.line == line {
+            if occ.file == file && occ.line == line && occ.fqn.is_some() {
                 return occ.fqn.clone();
             }
         }
     }
-    None
+    let file_info = find_file(index, file)?;
+    resolve_name_in_file(index, name, file_info)
 }
@@ -0,0 +1,39 @@
+use std::collections::HashSet;
+
+use crate::indexer::{SymbolIndex, SymbolOccurrence};
+
+/// Find all declarations that directly extend or implement a class or interface.
+/// Accepts either a fully-qualified name or a simple name; a simple name matches
+/// every type declaration with that name.
+pub fn find_implementations<'a>(index: &'a SymbolIndex, symbol: &str) -> Vec<&'a SymbolOccurrence> {
+    let target_fqns: HashSet<&str> = if symbol.contains('.') {
+        std::iter::once(symbol).collect()
+    } else {
+        index
+            .by_name
+            .get(symbol)
+            .map(|occs| {
+                occs.iter()
+                    .filter(|o| o.kind.is_type_declaration())
+                    .filter_map(|o| o.fqn.as_deref())
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let mut results: Vec<&SymbolOccurrence> = Vec::new();
+    for (class_fqn, parents) in &index.supertypes {
+        if !parents.iter().any(|p| target_fqns.contains(p.as_str())) {
+            continue;
+        }
+        if let Some(occs) = index.by_fqn.get(class_fqn) {
+            for occ in occs {
+                if occ.kind.is_type_declaration() {
+                    results.push(occ);
+                }
+            }
+        }
+    }
+    results.sort_by(|a, b| a.file.cmp(&b.file).then(a.line.cmp(&b.line)));
+    results
+}
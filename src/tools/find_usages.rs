@@ -1,24 +1,96 @@
 use std::path::Path;
 
-use crate::indexer::{SymbolIndex, SymbolOccurrence};
+use crate::indexer::filter::is_test_source;
+use crate::indexer::{SymbolIndex, SymbolKind, SymbolOccurrence};
+
+use super::{matching_occurrences, NameMatch};
 
 /// Find all usages (references) of a symbol in the index.
 /// If `file` and `line` are provided, first find the symbol at that location
-/// to get its FQN for precise matching.
+/// to get its FQN for precise matching. An extension can be named by its receiver
+/// (`User.isAdmin`) to tell it apart from unrelated members with the same name.
+/// If `kinds` is provided, only occurrences of those kinds are returned
+/// (e.g., only `TypeReference`s of a class that is also called as a constructor).
 pub fn find_usages<'a>(
     index: &'a SymbolIndex,
     symbol: &str,
     file: Option<&Path>,
     line: Option<usize>,
     include_imports: bool,
+    kinds: Option<&[SymbolKind]>,
+) -> Vec<&'a SymbolOccurrence> {
+    let mut results = collect_usages(index, symbol, file, line, include_imports);
+    if let Some(kinds) = kinds {
+        results.retain(|occ| kinds.contains(&occ.kind));
+    }
+    collapse_overlapping(&mut results);
+    results
+}
+
+/// Find usages like [`find_usages`], matching `symbol` against names under `name_match`.
+/// Exact matching is [`find_usages`] itself, the only mode that resolves `symbol` through
+/// `file` and `line`; the other modes return the references filed under every matching
+/// simple name (or FQN, for a qualified `symbol`).
+pub fn find_usages_matching<'a>(
+    index: &'a SymbolIndex,
+    symbol: &str,
+    file: Option<&Path>,
+    line: Option<usize>,
+    include_imports: bool,
+    kinds: Option<&[SymbolKind]>,
+    name_match: NameMatch,
+) -> Vec<&'a SymbolOccurrence> {
+    if name_match == NameMatch::Exact {
+        return find_usages(index, symbol, file, line, include_imports, kinds);
+    }
+    let mut results: Vec<&SymbolOccurrence> = matching_occurrences(index, symbol, name_match)
+        .into_iter()
+        .filter(|occ| {
+            occ.kind.is_reference() || (include_imports && matches!(occ.kind, SymbolKind::Import))
+        })
+        .collect();
+    if let Some(kinds) = kinds {
+        results.retain(|occ| kinds.contains(&occ.kind));
+    }
+    collapse_overlapping(&mut results);
+    results
+}
+
+/// Keep only the occurrences in files under `scope`, a directory (e.g., a Gradle module) or
+/// file of the project. The symbol is still resolved against the whole index, so `scope`
+/// narrows where usages are reported, not what they refer to.
+pub fn retain_in_scope(results: &mut Vec<&SymbolOccurrence>, scope: &Path) {
+    results.retain(|occ| occ.file.starts_with(scope));
+}
+
+/// Drop the occurrences in test sources (see [`is_test_source`]), for when only
+/// production usages matter.
+pub fn retain_outside_tests(results: &mut Vec<&SymbolOccurrence>, project_root: &Path) {
+    results.retain(|occ| {
+        let rel = occ.file.strip_prefix(project_root).unwrap_or(&occ.file);
+        !is_test_source(rel)
+    });
+}
+
+fn collect_usages<'a>(
+    index: &'a SymbolIndex,
+    symbol: &str,
+    file: Option<&Path>,
+    line: Option<usize>,
+    include_imports: bool,
 ) -> Vec<&'a SymbolOccurrence> {
     // If file and line are provided, try to find the exact symbol first
     let fqn = if let (Some(f), Some(l)) = (file, line) {
         find_symbol_fqn_at(index, f, l, symbol)
     } else {
-        // Try to find by FQN if the symbol looks fully qualified
+        // Try to find by FQN if the symbol looks fully qualified, or as `Receiver.member`
+        // for an extension declared on `Receiver`
         if symbol.contains('.') {
-            Some(symbol.to_string())
+            if index.by_fqn.contains_key(symbol) {
+                Some(symbol.to_string())
+            } else {
+                find_extension_fqn(index, symbol).or_else(|| Some(symbol.to_string()))
+            }
         } else {
             find_unique_fqn(index, symbol)
         }
@@ -30,7 +102,7 @@ pub fn find_usages<'a>(
         if let Some(occs) = index.by_fqn.get(fqn) {
             for occ in occs {
                 if occ.kind.is_reference()
-                    || (include_imports && matches!(occ.kind, crate::indexer::SymbolKind::Import))
+                    || (include_imports && matches!(occ.kind, SymbolKind::Import))
                 {
                     results.push(occ);
                 }
@@ -62,8 +134,7 @@ pub fn find_usages<'a>(
             if let Some(occs) = index.by_name.get(field_simple_name) {
                 for occ in occs {
                     if occ.kind.is_reference()
-                        || (include_imports
-                            && matches!(occ.kind, crate::indexer::SymbolKind::Import))
+                        || (include_imports && matches!(occ.kind, SymbolKind::Import))
                     {
                         if occ.fqn.as_deref() != Some(fqn)
                             && file_references_class(index, &occ.file, class_fqn)
@@ -79,8 +150,7 @@ pub fn find_usages<'a>(
                 if let Some(occs) = index.by_fqn.get(acc_fqn) {
                     for occ in occs {
                         if occ.kind.is_reference()
-                            || (include_imports
-                                && matches!(occ.kind, crate::indexer::SymbolKind::Import))
+                            || (include_imports && matches!(occ.kind, SymbolKind::Import))
                         {
                             results.push(occ);
                         }
@@ -102,8 +172,15 @@ pub fn find_usages<'a>(
                 }
             }
         }
+        // Destructuring a data class (`val (a, b) = x`) reads its properties via componentN()
+        if let Some(component_fqns) = index.data_class_members.get(fqn) {
+            for component_fqn in component_fqns {
+                if let Some(occs) = index.by_fqn.get(component_fqn) {
+                    results.extend(occs.iter().filter(|occ| occ.kind.is_reference()));
+                }
+            }
+        }
         if !results.is_empty() {
-            results.sort_by(|a, b| a.file.cmp(&b.file).then(a.line.cmp(&b.line)));
             return results;
         }
     }
@@ -116,23 +193,66 @@ pub fn find_usages<'a>(
     } else {
         symbol
     };
+    // A declared FQN with no usages must not pick up references that resolved to another
+    // declaration of the same simple name
+    let declared_fqn = fqn.as_deref().filter(|fqn| is_declared(index, fqn));
     let mut results: Vec<&SymbolOccurrence> = Vec::new();
     if let Some(occs) = index.by_name.get(lookup_name) {
         for occ in occs {
+            if let (Some(declared), Some(resolved)) = (declared_fqn, occ.fqn.as_deref()) {
+                if declared != resolved && is_declared(index, resolved) {
+                    continue;
+                }
+            }
             if occ.kind.is_reference()
-                || (include_imports && matches!(occ.kind, crate::indexer::SymbolKind::Import))
+                || (include_imports && matches!(occ.kind, SymbolKind::Import))
             {
                 results.push(occ);
             }
         }
     }
-    results.sort_by(|a, b| a.file.cmp(&b.file).then(a.line.cmp(&b.line)));
     results
 }
 
+/// Check if the index has a declaration with the given FQN.
+fn is_declared(index: &SymbolIndex, fqn: &str) -> bool {
+    index
+        .by_fqn
+        .get(fqn)
+        .is_some_and(|occs| occs.iter().any(|occ| occ.kind.is_declaration()))
+}
+
+/// Order occurrences by location and collapse those in the same file whose byte ranges
+/// overlap at the same start, keeping the most specific kind. The same access can be reached
+/// more than once: by FQN and again by a Lombok accessor's simple name, through a type alias,
+/// or as both a navigation member and a bare identifier. Ranges that merely contain a later
+/// occurrence (`foo(foo(1))`, `Result<Result<T>>`) are separate accesses and are kept.
+fn collapse_overlapping(results: &mut Vec<&SymbolOccurrence>) {
+    results.sort_by(|a, b| {
+        a.file
+            .cmp(&b.file)
+            .then(a.byte_range.start.cmp(&b.byte_range.start))
+            .then(specificity(&b.kind).cmp(&specificity(&a.kind)))
+    });
+
+    // Sorted most specific first, so dedup_by keeps the occurrence with the best kind
+    results.dedup_by(|later, kept| later.file == kept.file && later.byte_range.start == kept.byte_range.start);
+}
+
+/// How much an occurrence kind says about the access: a call beats a type or annotation
+/// reference, which beats a plain property reference, which beats an import.
+fn specificity(kind: &SymbolKind) -> u8 {
+    match kind {
+        SymbolKind::CallSite | SymbolKind::ExtensionFunctionCall => 3,
+        SymbolKind::TypeReference | SymbolKind::AnnotationReference => 2,
+        SymbolKind::PropertyReference => 1,
+        _ => 0,
+    }
+}
+
 /// Check if a file could reference a given class: the file imports it explicitly,
 /// has a wildcard import covering its package, or is in the same package.
-fn file_references_class(index: &SymbolIndex, file: &Path, class_fqn: Option<&str>) -> bool {
+pub(crate) fn file_references_class(index: &SymbolIndex, file: &Path, class_fqn: Option<&str>) -> bool {
     let class_fqn = match class_fqn {
         Some(fqn) => fqn,
         None => return true, // Can't determine class, don't filter
@@ -186,12 +306,42 @@ fn find_symbol_fqn_at(
     None
 }
 
+/// Resolve `Foo.bar` to the FQN of the extension function or property `bar` declared
+/// with receiver `Foo`, if exactly one such declaration exists.
+fn find_extension_fqn(index: &SymbolIndex, symbol: &str) -> Option<String> {
+    let (receiver, name) = symbol.rsplit_once('.')?;
+    let receiver = receiver.rsplit('.').next().unwrap_or(receiver);
+    let occs = index.by_name.get(name)?;
+    let fqns: std::collections::HashSet<&str> = occs
+        .iter()
+        .filter(|o| {
+            matches!(
+                o.kind,
+                SymbolKind::ExtensionFunctionDeclaration | SymbolKind::ExtensionPropertyDeclaration
+            )
+        })
+        .filter(|o| {
+            o.receiver_type.as_deref().is_some_and(|r| {
+                // `User`, `com.example.User`, `User?` and `List<User>` all name the receiver class
+                let class = r.split('<').next().unwrap_or(r).trim_end_matches('?');
+                class.rsplit('.').next() == Some(receiver)
+            })
+        })
+        .filter_map(|o| o.fqn.as_deref())
+        .collect();
+    if fqns.len() == 1 {
+        return fqns.into_iter().next().map(|s| s.to_string());
+    }
+    None
+}
+
 /// If a symbol name maps to exactly one FQN, return it.
 fn find_unique_fqn(index: &SymbolIndex, name: &str) -> Option<String> {
     if let Some(occs) = index.by_name.get(name) {
         let fqns: std::collections::HashSet<&str> = occs
             .iter()
-            .filter(|o| o.kind.is_declaration())
+            // A lambda parameter named like a property doesn't make the property ambiguous
+            .filter(|o| o.kind.is_declaration() && o.kind != SymbolKind::ParameterDeclaration)
             .filter_map(|o| o.fqn.as_deref())
             .collect();
         if fqns.len() == 1 {
@@ -0,0 +1,42 @@
+use std::time::UNIX_EPOCH;
+
+use serde::Serialize;
+
+use crate::indexer::SymbolIndex;
+
+/// Whether the server is ready to answer queries, for automation that waits on it.
+#[derive(Debug, Serialize)]
+pub struct Health {
+    /// The initial index is built; until then, queries answer with a "still indexing"
+    /// message. They keep answering from the current index while a reindex runs.
+    pub indexed: bool,
+    pub reindex_in_progress: bool,
+    pub files: usize,
+    pub total_occurrences: usize,
+    /// When a file was last (re)indexed, in seconds since the Unix epoch. None when no file
+    /// has been indexed.
+    pub last_indexed_at: Option<u64>,
+}
+
+/// Report readiness from `index` and whether the initial index is built.
+pub fn health(index: &SymbolIndex, indexed: bool, reindex_in_progress: bool) -> Health {
+    let stats = index.stats();
+    let last_indexed_at = index
+        .indexed_at
+        .values()
+        .max()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|since_epoch| since_epoch.as_secs());
+
+    Health {
+        indexed,
+        reindex_in_progress,
+        files: stats.files,
+        total_occurrences: stats.total_occurrences,
+        last_indexed_at,
+    }
+}
+
+pub fn format_health_json(health: &Health) -> String {
+    serde_json::to_string_pretty(health).unwrap_or_else(|_| "{}".to_string())
+}
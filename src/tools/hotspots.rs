@@ -0,0 +1,77 @@
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use crate::indexer::{SymbolIndex, SymbolKind, SymbolOccurrence};
+
+/// Default number of declarations returned by `hotspots`.
+pub const DEFAULT_HOTSPOT_LIMIT: usize = 20;
+
+/// A declaration and how much of the project refers to it.
+pub struct Hotspot<'a> {
+    pub declaration: &'a SymbolOccurrence,
+    /// References resolving to the declaration's FQN, imports excluded
+    pub references: usize,
+    /// Distinct files containing those references
+    pub files: usize,
+    pub imports: usize,
+}
+
+/// The `limit` most-referenced declarations, by reference count, then file spread, then
+/// FQN. Counts what resolved to each declaration's FQN, so overloads and `expect`/`actual`
+/// declarations sharing one are counted together under the first declaration. Parameters
+/// and declarations without references are left out.
+pub fn hotspots(index: &SymbolIndex, limit: usize) -> Vec<Hotspot<'_>> {
+    let mut hotspots: Vec<Hotspot> = index
+        .by_fqn
+        .values()
+        .filter_map(|occs| {
+            let declaration = occs
+                .iter()
+                .find(|o| o.kind.is_declaration() && o.kind != SymbolKind::ParameterDeclaration)?;
+            let references: Vec<&SymbolOccurrence> =
+                occs.iter().filter(|o| o.kind.is_reference()).collect();
+            if references.is_empty() {
+                return None;
+            }
+            let files: HashSet<&PathBuf> = references.iter().map(|o| &o.file).collect();
+            Some(Hotspot {
+                declaration,
+                references: references.len(),
+                files: files.len(),
+                imports: occs.iter().filter(|o| o.kind == SymbolKind::Import).count(),
+            })
+        })
+        .collect();
+
+    hotspots.sort_by(|a, b| {
+        Reverse(a.references)
+            .cmp(&Reverse(b.references))
+            .then(Reverse(a.files).cmp(&Reverse(b.files)))
+            .then(a.declaration.fqn.cmp(&b.declaration.fqn))
+    });
+    hotspots.truncate(limit);
+    hotspots
+}
+
+/// Format hotspots as a ranked list, each with its counts and declaration location.
+pub fn format_hotspots(hotspots: &[Hotspot], project_root: &Path) -> String {
+    if hotspots.is_empty() {
+        return "No referenced declarations found.".to_string();
+    }
+    let mut lines = vec![format!("Top {} most-referenced declaration(s):\n", hotspots.len())];
+    for (rank, hotspot) in hotspots.iter().enumerate() {
+        lines.push(format!(
+            "  {}. {} reference(s) in {} file(s), {} import(s)",
+            rank + 1,
+            hotspot.references,
+            hotspot.files,
+            hotspot.imports
+        ));
+        lines.push(format!(
+            "     {}",
+            super::format_occurrence(hotspot.declaration, project_root)
+        ));
+    }
+    lines.join("\n")
+}
@@ -0,0 +1,80 @@
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+use crate::indexer::{SymbolIndex, SymbolKind, SymbolOccurrence};
+
+/// List the declarations whose FQN lies in `package`, sorted by file and line.
+/// Without `recursive`, only the package's top-level declarations are returned
+/// (`com.example.core.User`, not `com.example.core.Result.Success` or members).
+/// With `recursive`, nested declarations and subpackages are included.
+/// Packages match on segment boundaries: `com.example.core` does not include
+/// `com.example.coreutils`. Parameters are omitted.
+pub fn list_symbols_in_package<'a>(
+    index: &'a SymbolIndex,
+    package: &str,
+    recursive: bool,
+) -> Vec<&'a SymbolOccurrence> {
+    let package = package.trim_end_matches('.');
+    let mut results: Vec<&SymbolOccurrence> = Vec::new();
+
+    for (fqn, occs) in &index.by_fqn {
+        let rest = match fqn
+            .strip_prefix(package)
+            .and_then(|rest| rest.strip_prefix('.'))
+        {
+            Some(rest) => rest,
+            None => continue,
+        };
+        if !recursive && rest.contains('.') {
+            continue;
+        }
+        results.extend(
+            occs.iter()
+                .filter(|o| o.kind.is_declaration() && o.kind != SymbolKind::ParameterDeclaration),
+        );
+    }
+
+    // Companion members are indexed under both `Foo.Companion.bar` and `Foo.bar`
+    let mut seen = HashSet::new();
+    results.retain(|&o| seen.insert((&o.file, o.byte_range.clone(), &o.name, o.kind.clone())));
+    results.sort_by(|a, b| {
+        a.file
+            .cmp(&b.file)
+            .then(a.line.cmp(&b.line))
+            .then(a.column.cmp(&b.column))
+            .then(a.name.cmp(&b.name))
+    });
+    results
+}
+
+/// Format package declarations grouped by file, one `Kind `name` (line N)` entry per symbol.
+pub fn format_package_symbols(
+    symbols: &[&SymbolOccurrence],
+    package: &str,
+    project_root: &Path,
+) -> String {
+    if symbols.is_empty() {
+        return "No results found.".to_string();
+    }
+
+    let mut by_file: BTreeMap<&Path, Vec<&SymbolOccurrence>> = BTreeMap::new();
+    for &occ in symbols {
+        by_file.entry(occ.file.as_path()).or_default().push(occ);
+    }
+
+    let mut lines = vec![format!(
+        "Found {} symbol(s) in package {} across {} file(s):\n",
+        symbols.len(),
+        package.trim_end_matches('.'),
+        by_file.len()
+    )];
+    for (file, occs) in by_file {
+        let rel_path = file.strip_prefix(project_root).unwrap_or(file).display();
+        lines.push(format!("  {}:", rel_path));
+        for occ in occs {
+            lines.push(format!("    {:?} `{}` (line {})", occ.kind, occ.name, occ.line));
+        }
+    }
+
+    lines.join("\n")
+}
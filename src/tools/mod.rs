@@ -1,48 +1,713 @@
+pub mod call_hierarchy;
+pub mod definition_source;
 pub mod dependency_tree;
+pub mod file_references;
 pub mod find_definition;
+pub mod find_implementations;
 pub mod find_usages;
+pub mod health;
+pub mod hotspots;
+pub mod list_symbols_in_package;
+pub mod outline;
+pub mod rename_impact;
+pub mod search_symbols;
+pub mod super_definition;
+pub mod symbol_at;
+pub mod symbol_module;
+pub mod type_hierarchy;
+pub mod unused;
 
-use crate::indexer::SymbolOccurrence;
+use crate::indexer::{SymbolIndex, SymbolOccurrence};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
 use std::path::Path;
 
+/// Stable JSON representation of a symbol occurrence, used by `--format json`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OccurrenceJson {
+    pub name: String,
+    pub fqn: Option<String>,
+    pub kind: String,
+    /// File path relative to the project root
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub byte_start: usize,
+    pub byte_end: usize,
+    pub receiver_type: Option<String>,
+    /// FQN of the declaration the occurrence appears in
+    pub enclosing_fqn: Option<String>,
+    /// Parameter types of a function declaration, e.g. `(Int, String)`
+    pub signature: Option<String>,
+    /// `expect` or `actual` for Kotlin Multiplatform declarations
+    pub platform_modifier: Option<String>,
+}
+
+impl OccurrenceJson {
+    pub fn from_occurrence(occ: &SymbolOccurrence, project_root: &Path) -> Self {
+        Self {
+            name: occ.name.clone(),
+            fqn: occ.fqn.clone(),
+            kind: format!("{:?}", occ.kind),
+            file: occ
+                .file
+                .strip_prefix(project_root)
+                .unwrap_or(&occ.file)
+                .display()
+                .to_string(),
+            line: occ.line,
+            column: occ.column,
+            byte_start: occ.byte_range.start,
+            byte_end: occ.byte_range.end,
+            receiver_type: occ.receiver_type.clone(),
+            enclosing_fqn: occ.enclosing_fqn.clone(),
+            signature: occ.signature.clone(),
+            platform_modifier: occ.platform_modifier.map(|m| m.as_str().to_string()),
+        }
+    }
+}
+
+/// Format a list of symbol occurrences as a JSON array of [`OccurrenceJson`].
+pub fn format_occurrences_json(occurrences: &[&SymbolOccurrence], project_root: &Path) -> String {
+    let entries: Vec<OccurrenceJson> = occurrences
+        .iter()
+        .map(|occ| OccurrenceJson::from_occurrence(occ, project_root))
+        .collect();
+    serde_json::to_string_pretty(&entries).unwrap_or_else(|_| "[]".to_string())
+}
+
+/// Counts of a result set by occurrence kind and file. Displays as one line with the most
+/// frequent kinds first: "12 call sites, 4 type references, 3 imports across 6 files."
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsageSummary {
+    pub total: usize,
+    /// Number of occurrences of each kind, e.g. `"CallSite": 12`
+    pub kinds: BTreeMap<String, usize>,
+    /// Number of distinct files the occurrences are in
+    pub files: usize,
+}
+
+impl UsageSummary {
+    pub fn new(occurrences: &[&SymbolOccurrence]) -> Self {
+        let mut kinds: BTreeMap<String, usize> = BTreeMap::new();
+        for occ in occurrences {
+            *kinds.entry(format!("{:?}", occ.kind)).or_default() += 1;
+        }
+        let files: HashSet<&Path> = occurrences.iter().map(|occ| occ.file.as_path()).collect();
+        Self {
+            total: occurrences.len(),
+            kinds,
+            files: files.len(),
+        }
+    }
+}
+
+impl fmt::Display for UsageSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut kinds: Vec<(&String, &usize)> = self.kinds.iter().collect();
+        kinds.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+        let counts: Vec<String> = kinds
+            .into_iter()
+            .map(|(kind, &count)| format!("{} {}", count, kind_words(kind, count)))
+            .collect();
+        let files = if self.files == 1 { "file" } else { "files" };
+        write!(f, "{} across {} {}.", counts.join(", "), self.files, files)
+    }
+}
+
+/// `CallSite` as "call site", or "call sites" for any count but one.
+fn kind_words(kind: &str, count: usize) -> String {
+    let mut words = String::new();
+    for (i, c) in kind.chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            words.push(' ');
+        }
+        words.push(c.to_ascii_lowercase());
+    }
+    if count != 1 {
+        words.push('s');
+    }
+    words
+}
+
+/// A window of a sorted result list, along with the size of the whole list so
+/// clients can page through large result sets.
+#[derive(Debug)]
+pub struct Page<'a> {
+    pub items: Vec<&'a SymbolOccurrence>,
+    /// Number of results before paging
+    pub total: usize,
+    pub offset: usize,
+    /// Summary of all results, not just this page's
+    pub summary: UsageSummary,
+}
+
+impl<'a> Page<'a> {
+    /// Take up to `limit` results starting at `offset`; no limit means the rest of the list.
+    pub fn new(results: Vec<&'a SymbolOccurrence>, offset: usize, limit: Option<usize>) -> Self {
+        let total = results.len();
+        let summary = UsageSummary::new(&results);
+        let items = results
+            .into_iter()
+            .skip(offset)
+            .take(limit.unwrap_or(usize::MAX))
+            .collect();
+        Self { items, total, offset, summary }
+    }
+
+    /// Whether results exist past the end of this page.
+    pub fn has_more(&self) -> bool {
+        self.offset + self.items.len() < self.total
+    }
+
+    fn is_complete(&self) -> bool {
+        self.items.len() == self.total
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct PageJson<'a> {
+    total: usize,
+    offset: usize,
+    summary: &'a UsageSummary,
+    results: Vec<OccurrenceJson>,
+}
+
+/// Format a page of occurrences as a JSON object with `total`, `offset`, the
+/// [`UsageSummary`] of all results as `summary`, and the page's `results`.
+pub fn format_page_json(page: &Page, project_root: &Path) -> String {
+    let json = PageJson {
+        total: page.total,
+        offset: page.offset,
+        summary: &page.summary,
+        results: page
+            .items
+            .iter()
+            .map(|occ| OccurrenceJson::from_occurrence(occ, project_root))
+            .collect(),
+    };
+    serde_json::to_string_pretty(&json).unwrap_or_else(|_| "{}".to_string())
+}
+
 /// Format a list of symbol occurrences into a human-readable string.
 pub fn format_occurrences(occurrences: &[&SymbolOccurrence], project_root: &Path) -> String {
     if occurrences.is_empty() {
         return "No results found.".to_string();
     }
+    let header = format!("Found {} result(s):\n", occurrences.len());
+    format_listing(occurrences, header, project_root, None, GroupBy::None)
+}
+
+/// How a listing of occurrences is split into sections.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GroupBy {
+    /// One flat list
+    #[default]
+    None,
+    /// A section per file
+    File,
+    /// A section per occurrence kind
+    Kind,
+}
+
+impl std::str::FromStr for GroupBy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "none" => Ok(GroupBy::None),
+            "file" => Ok(GroupBy::File),
+            "kind" => Ok(GroupBy::Kind),
+            _ => Err(format!("Unknown grouping '{}'. Valid groupings: none, file, kind", s)),
+        }
+    }
+}
+
+/// How a symbol is matched against the names in the index.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NameMatch {
+    /// The name as given
+    #[default]
+    Exact,
+    /// Every name starting with the given one (`User` matches `UserService`)
+    Prefix,
+    /// The name in any casing (`userservice` matches `UserService`)
+    CaseInsensitive,
+}
+
+impl std::str::FromStr for NameMatch {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "exact" => Ok(NameMatch::Exact),
+            "prefix" => Ok(NameMatch::Prefix),
+            "ci" => Ok(NameMatch::CaseInsensitive),
+            _ => Err(format!("Unknown match mode '{}'. Valid modes: exact, prefix, ci", s)),
+        }
+    }
+}
+
+impl NameMatch {
+    /// Whether the indexed name `candidate` matches the requested `name`
+    pub fn matches(self, candidate: &str, name: &str) -> bool {
+        match self {
+            NameMatch::Exact => candidate == name,
+            NameMatch::Prefix => candidate.starts_with(name),
+            NameMatch::CaseInsensitive => candidate.to_lowercase() == name.to_lowercase(),
+        }
+    }
+}
+
+/// The occurrences of every name `symbol` matches, in name order. A qualified `symbol` is
+/// matched against FQNs, a simple one against simple names. Exact matches take a single
+/// lookup; the other modes scan the names.
+pub fn matching_occurrences<'a>(
+    index: &'a SymbolIndex,
+    symbol: &str,
+    name_match: NameMatch,
+) -> Vec<&'a SymbolOccurrence> {
+    let names = if symbol.contains('.') {
+        &index.by_fqn
+    } else {
+        &index.by_name
+    };
+    if name_match == NameMatch::Exact {
+        return names.get(symbol).into_iter().flatten().collect();
+    }
+    let mut matching: Vec<(&String, &Vec<SymbolOccurrence>)> = names
+        .iter()
+        .filter(|(name, _)| name_match.matches(name, symbol))
+        .collect();
+    matching.sort_by(|a, b| a.0.cmp(b.0));
+    matching.into_iter().flat_map(|(_, occs)| occs).collect()
+}
+
+/// Format a page of occurrences. A page holding every result reads exactly like
+/// [`format_occurrences`]; a partial one says "showing X of Y" and where the next page starts.
+pub fn format_page(page: &Page, project_root: &Path, context_lines: Option<usize>) -> String {
+    format_page_grouped(page, project_root, context_lines, GroupBy::None)
+}
+
+/// Format a page of occurrences like [`format_page`], split into sections by file or kind.
+/// Each section starts with a header giving its file or kind and how many of the page's
+/// occurrences it holds; sections are ordered by their first occurrence.
+pub fn format_page_grouped(
+    page: &Page,
+    project_root: &Path,
+    context_lines: Option<usize>,
+    group_by: GroupBy,
+) -> String {
+    if page.total == 0 {
+        return "No results found.".to_string();
+    }
+    if page.is_complete() {
+        let header = format!("Found {} result(s):\n", page.total);
+        return format_listing(&page.items, header, project_root, context_lines, group_by);
+    }
+    if page.items.is_empty() {
+        return format!("No results at offset {} ({} result(s) in total).", page.offset, page.total);
+    }
 
-    let mut lines = Vec::new();
-    lines.push(format!("Found {} result(s):\n", occurrences.len()));
-
-    for occ in occurrences {
-        let rel_path = occ
-            .file
-            .strip_prefix(project_root)
-            .unwrap_or(&occ.file)
-            .display();
-        let kind = format!("{:?}", occ.kind);
-        let fqn_display = occ
-            .fqn
-            .as_deref()
-            .map(|f| format!(" [{}]", f))
-            .unwrap_or_default();
-        let receiver_display = occ
-            .receiver_type
-            .as_deref()
-            .map(|r| format!(" (receiver: {})", r))
-            .unwrap_or_default();
-
-        lines.push(format!(
-            "  {}:{}:{} - {} `{}`{}{}",
-            rel_path,
-            occ.line,
-            occ.column,
-            kind,
-            occ.name,
-            fqn_display,
-            receiver_display,
+    let header = format!(
+        "Showing {} of {} result(s) (offset {}):\n",
+        page.items.len(),
+        page.total,
+        page.offset
+    );
+    let mut output = format_listing(&page.items, header, project_root, context_lines, group_by);
+    if page.has_more() {
+        output.push_str(&format!(
+            "\n\n{} more result(s); use offset {} for the next page.",
+            page.total - page.offset - page.items.len(),
+            page.offset + page.items.len()
         ));
     }
+    output
+}
+
+/// Format a page like [`format_page_grouped`], starting with a line summarizing all
+/// results by kind and file (see [`UsageSummary`]).
+pub fn format_page_with_summary(
+    page: &Page,
+    project_root: &Path,
+    context_lines: Option<usize>,
+    group_by: GroupBy,
+) -> String {
+    let listing = format_page_grouped(page, project_root, context_lines, group_by);
+    if page.total == 0 {
+        return listing;
+    }
+    format!("{}\n{}", page.summary, listing)
+}
+
+fn format_listing(
+    occurrences: &[&SymbolOccurrence],
+    header: String,
+    project_root: &Path,
+    context_lines: Option<usize>,
+    group_by: GroupBy,
+) -> String {
+    // Read each file at most once per call
+    let mut file_cache: HashMap<&Path, Option<Vec<String>>> = HashMap::new();
+
+    let mut lines = vec![header];
+    for (group, members) in group_occurrences(occurrences, group_by, project_root) {
+        let indent = match group {
+            Some(group) => {
+                lines.push(format!("  {} ({}):", group, members.len()));
+                "    "
+            }
+            None => "  ",
+        };
+        for occ in members {
+            lines.push(format!("{}{}", indent, format_occurrence(occ, project_root)));
+            if let Some(context_lines) = context_lines {
+                let source = file_cache.entry(occ.file.as_path()).or_insert_with(|| {
+                    std::fs::read_to_string(&occ.file)
+                        .ok()
+                        .map(|content| content.lines().map(str::to_string).collect())
+                });
+                if let Some(source) = source {
+                    lines.extend(format_snippet(source, occ.line, occ.column, context_lines));
+                    lines.push(String::new());
+                }
+            }
+        }
+    }
 
     lines.join("\n")
 }
+
+/// Split occurrences into sections named by `group_by`, ordered by first occurrence and
+/// keeping the order within each. Without grouping there is one unnamed section.
+fn group_occurrences<'a>(
+    occurrences: &[&'a SymbolOccurrence],
+    group_by: GroupBy,
+    project_root: &Path,
+) -> Vec<(Option<String>, Vec<&'a SymbolOccurrence>)> {
+    let key = |occ: &SymbolOccurrence| match group_by {
+        GroupBy::None => None,
+        GroupBy::File => Some(
+            occ.file
+                .strip_prefix(project_root)
+                .unwrap_or(&occ.file)
+                .display()
+                .to_string(),
+        ),
+        GroupBy::Kind => Some(format!("{:?}", occ.kind)),
+    };
+    let mut groups: Vec<(Option<String>, Vec<&SymbolOccurrence>)> = Vec::new();
+    for &occ in occurrences {
+        let group = key(occ);
+        match groups.iter_mut().find(|(existing, _)| *existing == group) {
+            Some((_, members)) => members.push(occ),
+            None => groups.push((group, vec![occ])),
+        }
+    }
+    groups
+}
+
+/// Render the source lines around 1-based `line`, clamped to the file, with a caret
+/// under 1-based byte `column` on the target line.
+fn format_snippet(source: &[String], line: usize, column: usize, context_lines: usize) -> Vec<String> {
+    if line == 0 || line > source.len() {
+        return Vec::new();
+    }
+    let first = line.saturating_sub(context_lines).max(1);
+    let last = (line + context_lines).min(source.len());
+    let width = last.to_string().len();
+
+    let mut out = Vec::new();
+    for number in first..=last {
+        let text = &source[number - 1];
+        out.push(format!("    {:>width$} | {}", number, text, width = width));
+        if number == line {
+            // Keep tabs so the caret lines up with the rendered source
+            let prefix = text.get(..column.saturating_sub(1)).unwrap_or(text);
+            let padding: String = prefix
+                .chars()
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect();
+            out.push(format!("    {:>width$} | {}^", "", padding, width = width));
+        }
+    }
+    out
+}
+
+/// Format a single occurrence as one line, which clients may parse:
+///
+/// `<path>:<line>:<column> (bytes <start>..<end>) - <Kind> `<name>` [<fqn>] (receiver: <receiver>) in <enclosing fqn>`
+///
+/// The path is relative to `project_root`, line and column are 1-based and the byte range
+/// is the occurrence's half-open range in the file. A function declaration's name carries
+/// its parameter types (`process(Int)`), so overloads can be told apart. The FQN, receiver
+/// and enclosing parts are left out when unknown.
+pub fn format_occurrence(occ: &SymbolOccurrence, project_root: &Path) -> String {
+    let rel_path = occ
+        .file
+        .strip_prefix(project_root)
+        .unwrap_or(&occ.file)
+        .display();
+    let kind = match occ.platform_modifier {
+        Some(modifier) => format!("{:?} ({})", occ.kind, modifier.as_str()),
+        None => format!("{:?}", occ.kind),
+    };
+    let fqn_display = occ
+        .fqn
+        .as_deref()
+        .map(|f| format!(" [{}]", f))
+        .unwrap_or_default();
+    let receiver_display = occ
+        .receiver_type
+        .as_deref()
+        .map(|r| format!(" (receiver: {})", r))
+        .unwrap_or_default();
+    let enclosing_display = occ
+        .enclosing_fqn
+        .as_deref()
+        .map(|e| format!(" in {}", e))
+        .unwrap_or_default();
+
+    format!(
+        "{}:{}:{} (bytes {}..{}) - {} `{}{}`{}{}{}",
+        rel_path,
+        occ.line,
+        occ.column,
+        occ.byte_range.start,
+        occ.byte_range.end,
+        kind,
+        occ.name,
+        occ.signature.as_deref().unwrap_or_default(),
+        fqn_display,
+        receiver_display,
+        enclosing_display,
+    )
+}
+
+/// A warning naming the `changed` files (relative to `project_root`) that were modified or
+/// deleted since they were indexed, or None when there are none.
+pub fn format_stale_warning(changed: &[std::path::PathBuf], project_root: &Path) -> Option<String> {
+    if changed.is_empty() {
+        return None;
+    }
+    let mut lines = vec![format!(
+        "Warning: {} file(s) changed since they were indexed; results may be out of date:",
+        changed.len()
+    )];
+    for file in changed {
+        lines.push(format!("  {}", file.strip_prefix(project_root).unwrap_or(file).display()));
+    }
+    Some(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|l| l.to_string()).collect()
+    }
+
+    fn occurrence(receiver_type: Option<&str>, enclosing_fqn: Option<&str>) -> SymbolOccurrence {
+        SymbolOccurrence {
+            name: "save".to_string(),
+            fqn: Some("com.example.Repository.save".to_string()),
+            kind: crate::indexer::SymbolKind::CallSite,
+            file: std::path::PathBuf::from("/project/app/src/Service.java"),
+            line: 12,
+            column: 20,
+            byte_range: 301..305,
+            receiver_type: receiver_type.map(str::to_string),
+            enclosing_fqn: enclosing_fqn.map(str::to_string),
+            signature: None,
+            platform_modifier: None,
+            modifiers: None,
+        }
+    }
+
+    #[test]
+    fn test_format_occurrence_layout() {
+        let occ = occurrence(Some("repository"), Some("com.example.Service.store"));
+        assert_eq!(
+            format_occurrence(&occ, Path::new("/project")),
+            "app/src/Service.java:12:20 (bytes 301..305) - CallSite `save` [com.example.Repository.save] \
+             (receiver: repository) in com.example.Service.store"
+        );
+    }
+
+    #[test]
+    fn test_format_occurrence_leaves_out_unknown_parts() {
+        let mut occ = occurrence(None, None);
+        occ.fqn = None;
+        assert_eq!(
+            format_occurrence(&occ, Path::new("/project")),
+            "app/src/Service.java:12:20 (bytes 301..305) - CallSite `save`"
+        );
+        // Outside the project root the path stays absolute
+        assert_eq!(
+            format_occurrence(&occ, Path::new("/elsewhere")),
+            "/project/app/src/Service.java:12:20 (bytes 301..305) - CallSite `save`"
+        );
+    }
+
+    #[test]
+    fn test_format_snippet_with_caret() {
+        let src = source(&["package a", "", "fun main() {", "    greet()", "}"]);
+        let snippet = format_snippet(&src, 4, 5, 1);
+        assert_eq!(
+            snippet,
+            vec![
+                "    3 | fun main() {",
+                "    4 |     greet()",
+                "      |     ^",
+                "    5 | }",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_format_snippet_clamped_to_file_bounds() {
+        let src = source(&["first()", "second()"]);
+        let snippet = format_snippet(&src, 1, 1, 3);
+        assert_eq!(snippet, vec!["    1 | first()", "      | ^", "    2 | second()"]);
+
+        assert!(format_snippet(&src, 3, 1, 1).is_empty());
+        assert!(format_snippet(&src, 0, 1, 1).is_empty());
+    }
+
+    #[test]
+    fn test_format_snippet_preserves_tabs() {
+        let src = source(&["\tcall()"]);
+        let snippet = format_snippet(&src, 1, 2, 0);
+        assert_eq!(snippet[1], "      | \t^");
+    }
+
+    fn occurrences(count: usize) -> Vec<SymbolOccurrence> {
+        (1..=count)
+            .map(|line| SymbolOccurrence {
+                name: "name".to_string(),
+                fqn: None,
+                kind: crate::indexer::SymbolKind::PropertyReference,
+                file: std::path::PathBuf::from("/project/A.kt"),
+                line,
+                column: 1,
+                byte_range: 0..4,
+                receiver_type: None,
+                enclosing_fqn: None,
+                signature: None,
+                platform_modifier: None,
+                modifiers: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_format_page() {
+        let occs = occurrences(5);
+        let refs: Vec<_> = occs.iter().collect();
+        let root = Path::new("/project");
+
+        let page = Page::new(refs.clone(), 1, Some(2));
+        assert_eq!(page.items.iter().map(|o| o.line).collect::<Vec<_>>(), vec![2, 3]);
+        assert!(page.has_more());
+        let output = format_page(&page, root, None);
+        assert!(output.starts_with("Showing 2 of 5 result(s) (offset 1):"), "{}", output);
+        assert!(output.ends_with("2 more result(s); use offset 3 for the next page."), "{}", output);
+
+        // The last page has no next-page hint; a full page reads like format_occurrences
+        let last = format_page(&Page::new(refs.clone(), 3, Some(10)), root, None);
+        assert!(last.starts_with("Showing 2 of 5 result(s) (offset 3):"), "{}", last);
+        assert!(!last.contains("next page"));
+        assert_eq!(format_page(&Page::new(refs.clone(), 0, None), root, None), format_occurrences(&refs, root));
+
+        assert_eq!(
+            format_page(&Page::new(refs, 9, Some(2)), root, None),
+            "No results at offset 9 (5 result(s) in total)."
+        );
+        assert_eq!(format_page(&Page::new(Vec::new(), 0, Some(2)), root, None), "No results found.");
+    }
+
+    #[test]
+    fn test_format_page_grouped() {
+        let mut occs = occurrences(4);
+        occs[1].file = std::path::PathBuf::from("/project/B.kt");
+        occs[3].kind = crate::indexer::SymbolKind::CallSite;
+        let refs: Vec<_> = occs.iter().collect();
+        let root = Path::new("/project");
+        let page = Page::new(refs.clone(), 0, None);
+
+        let by_file = format_page_grouped(&page, root, None, GroupBy::File);
+        let lines: Vec<&str> = by_file.lines().collect();
+        assert_eq!(lines[0], "Found 4 result(s):");
+        assert_eq!(lines[2], "  A.kt (3):");
+        assert!(lines[3].starts_with("    A.kt:1:1 "), "{}", by_file);
+        assert!(lines[4].starts_with("    A.kt:3:1 "), "{}", by_file);
+        assert!(lines[5].starts_with("    A.kt:4:1 "), "{}", by_file);
+        assert_eq!(lines[6], "  B.kt (1):");
+        assert!(lines[7].starts_with("    B.kt:2:1 "), "{}", by_file);
+
+        let by_kind = format_page_grouped(&page, root, None, GroupBy::Kind);
+        // Group headers are indented under the `Found N result(s):` line
+        let headers: Vec<&str> =
+            by_kind.lines().filter(|l| l.starts_with("  ") && l.ends_with("):")).collect();
+        assert_eq!(headers, vec!["  PropertyReference (3):", "  CallSite (1):"]);
+
+        // Counts are per page; no grouping reads like format_page
+        let partial = format_page_grouped(&Page::new(refs.clone(), 0, Some(2)), root, None, GroupBy::File);
+        assert!(partial.contains("  A.kt (1):") && partial.contains("  B.kt (1):"), "{}", partial);
+        assert_eq!(format_page_grouped(&page, root, None, GroupBy::None), format_page(&page, root, None));
+
+        assert_eq!("File".parse::<GroupBy>(), Ok(GroupBy::File));
+        assert!("module".parse::<GroupBy>().is_err());
+    }
+
+    #[test]
+    fn test_usage_summary() {
+        let mut occs = occurrences(5);
+        occs[1].file = std::path::PathBuf::from("/project/B.kt");
+        occs[3].kind = crate::indexer::SymbolKind::CallSite;
+        occs[4].kind = crate::indexer::SymbolKind::Import;
+        let refs: Vec<_> = occs.iter().collect();
+        let root = Path::new("/project");
+
+        let summary = UsageSummary::new(&refs);
+        assert_eq!(summary.total, 5);
+        assert_eq!(summary.files, 2);
+        assert_eq!(summary.kinds.get("PropertyReference"), Some(&3));
+        assert_eq!(
+            summary.to_string(),
+            "3 property references, 1 call site, 1 import across 2 files."
+        );
+
+        // The summary covers every result, not just the page
+        let page = Page::new(refs.clone(), 0, Some(1));
+        let output = format_page_with_summary(&page, root, None, GroupBy::None);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], summary.to_string());
+        assert_eq!(lines[1], "Showing 1 of 5 result(s) (offset 0):");
+
+        let json: serde_json::Value = serde_json::from_str(&format_page_json(&page, root)).unwrap();
+        assert_eq!(json["summary"]["total"], 5);
+        assert_eq!(json["summary"]["files"], 2);
+        assert_eq!(json["summary"]["kinds"]["CallSite"], 1);
+
+        let empty = Page::new(Vec::new(), 0, None);
+        assert_eq!(format_page_with_summary(&empty, root, None, GroupBy::None), "No results found.");
+    }
+
+    #[test]
+    fn test_name_match() {
+        assert_eq!("prefix".parse::<NameMatch>(), Ok(NameMatch::Prefix));
+        assert_eq!("CI".parse::<NameMatch>(), Ok(NameMatch::CaseInsensitive));
+        assert!("fuzzy".parse::<NameMatch>().is_err());
+
+        assert!(NameMatch::Exact.matches("User", "User"));
+        assert!(!NameMatch::Exact.matches("UserService", "User"));
+        assert!(NameMatch::Prefix.matches("UserService", "User"));
+        assert!(!NameMatch::Prefix.matches("User", "UserService"));
+        assert!(NameMatch::CaseInsensitive.matches("UserService", "userservice"));
+        assert!(!NameMatch::CaseInsensitive.matches("UserServices", "userservice"));
+    }
+}
@@ -0,0 +1,120 @@
+use std::path::{Path, PathBuf};
+
+use crate::indexer::{FileInfo, SymbolIndex, SymbolKind, SymbolOccurrence};
+
+/// A declaration in a file outline, with its nesting depth (0 = top level).
+pub struct OutlineEntry<'a> {
+    pub depth: usize,
+    pub symbol: &'a SymbolOccurrence,
+}
+
+/// Look up an indexed file by path, accepting either the indexed path or any
+/// path that canonicalizes to it.
+pub fn find_file<'a>(index: &'a SymbolIndex, file: &Path) -> Option<&'a FileInfo> {
+    index.files.get(file).or_else(|| {
+        file.canonicalize()
+            .ok()
+            .and_then(|canonical| index.files.get(&canonical))
+    })
+}
+
+/// Collect the declarations of a file in source order, with nesting reconstructed
+/// from byte range containment (class → members → nested classes).
+/// Parameters are omitted; declarations sharing a range (e.g., Lombok accessors
+/// synthesized from a field) are siblings.
+pub fn outline<'a>(index: &'a SymbolIndex, file: &Path) -> Vec<OutlineEntry<'a>> {
+    let path: &PathBuf = match find_file(index, file) {
+        Some(info) => &info.path,
+        None => return Vec::new(),
+    };
+
+    let mut decls: Vec<&SymbolOccurrence> = index
+        .by_name
+        .values()
+        .flatten()
+        .filter(|o| {
+            &o.file == path
+                && o.kind.is_declaration()
+                && !matches!(o.kind, SymbolKind::ParameterDeclaration)
+        })
+        .collect();
+    // Outer declarations first: by start, then widest range, then source position of the name
+    decls.sort_by(|a, b| {
+        a.byte_range
+            .start
+            .cmp(&b.byte_range.start)
+            .then(b.byte_range.end.cmp(&a.byte_range.end))
+            .then(a.line.cmp(&b.line))
+            .then(a.column.cmp(&b.column))
+            .then(a.name.cmp(&b.name))
+    });
+
+    let mut entries = Vec::new();
+    let mut stack: Vec<&SymbolOccurrence> = Vec::new();
+    for decl in decls {
+        while let Some(parent) = stack.last() {
+            let contains = parent.byte_range.start <= decl.byte_range.start
+                && decl.byte_range.end <= parent.byte_range.end
+                && parent.byte_range != decl.byte_range;
+            if contains {
+                break;
+            }
+            stack.pop();
+        }
+        entries.push(OutlineEntry {
+            depth: stack.len(),
+            symbol: decl,
+        });
+        stack.push(decl);
+    }
+    entries
+}
+
+/// Format a file outline: package, imports, and the indented declaration tree.
+pub fn format_outline(index: &SymbolIndex, file: &Path, project_root: &Path) -> String {
+    let info = match find_file(index, file) {
+        Some(info) => info,
+        None => return format!("File not indexed: {}", file.display()),
+    };
+
+    let rel_path = info
+        .path
+        .strip_prefix(project_root)
+        .unwrap_or(&info.path)
+        .display();
+    let mut lines = vec![format!("Outline of {}:\n", rel_path)];
+
+    if let Some(ref package) = info.package {
+        lines.push(format!("  package {}", package));
+    }
+    if !info.imports.is_empty() {
+        lines.push(format!("  imports ({}):", info.imports.len()));
+        for imp in &info.imports {
+            let wildcard = if imp.is_wildcard { ".*" } else { "" };
+            let alias = imp
+                .alias
+                .as_deref()
+                .map(|a| format!(" as {}", a))
+                .unwrap_or_default();
+            lines.push(format!("    {}{}{} (line {})", imp.path, wildcard, alias, imp.line));
+        }
+    }
+
+    let entries = outline(index, &info.path);
+    if entries.is_empty() {
+        lines.push("  No declarations found.".to_string());
+    } else {
+        lines.push(format!("  declarations ({}):", entries.len()));
+        for entry in &entries {
+            lines.push(format!(
+                "    {}{:?} `{}` (line {})",
+                "  ".repeat(entry.depth),
+                entry.symbol.kind,
+                entry.symbol.name,
+                entry.symbol.line
+            ));
+        }
+    }
+
+    lines.join("\n")
+}
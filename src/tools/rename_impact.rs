@@ -0,0 +1,193 @@
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+use crate::indexer::symbols::{companion_fqns, strip_companion_segments};
+use crate::indexer::{SymbolIndex, SymbolKind, SymbolOccurrence};
+
+use super::find_usages::file_references_class;
+
+/// Every occurrence of one resolved FQN that a rename would touch.
+pub struct RenameImpact<'a> {
+    pub fqn: String,
+    /// Declarations, references and imports, de-duplicated and sorted by file and position
+    pub occurrences: Vec<&'a SymbolOccurrence>,
+}
+
+/// Collect everything a rename of `symbol` would touch: its declaration(s), references
+/// and imports, plus occurrences reached through the index's alias maps:
+/// - companion members, indexed as both `Foo.Companion.bar` and `Foo.bar`
+/// - Lombok accessors of a field (or the field and sibling accessors of an accessor),
+///   including Kotlin property-style access (`user.username`)
+/// - type alias declarations whose target is the symbol
+///
+/// Accepts a fully-qualified name or a simple name; a simple name yields one impact
+/// per declared FQN with that name.
+pub fn rename_impact<'a>(index: &'a SymbolIndex, symbol: &str) -> Vec<RenameImpact<'a>> {
+    resolve_fqns(index, symbol)
+        .into_iter()
+        .map(|fqn| {
+            let occurrences = collect_occurrences(index, &fqn);
+            RenameImpact { fqn, occurrences }
+        })
+        .filter(|impact| !impact.occurrences.is_empty())
+        .collect()
+}
+
+/// Format rename impacts with totals per kind and occurrences grouped by file.
+pub fn format_rename_impacts(impacts: &[RenameImpact], project_root: &Path) -> String {
+    if impacts.is_empty() {
+        return "No results found.".to_string();
+    }
+
+    let mut lines = Vec::new();
+    for impact in impacts {
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+
+        let mut by_file: BTreeMap<&Path, Vec<&SymbolOccurrence>> = BTreeMap::new();
+        for &occ in &impact.occurrences {
+            by_file.entry(occ.file.as_path()).or_default().push(occ);
+        }
+        let declarations = impact.occurrences.iter().filter(|o| o.kind.is_declaration()).count();
+        let imports = impact
+            .occurrences
+            .iter()
+            .filter(|o| o.kind == SymbolKind::Import)
+            .count();
+        let references = impact.occurrences.len() - declarations - imports;
+
+        lines.push(format!(
+            "Rename impact of {}: {} occurrence(s) in {} file(s) ({} declaration(s), {} reference(s), {} import(s))\n",
+            impact.fqn,
+            impact.occurrences.len(),
+            by_file.len(),
+            declarations,
+            references,
+            imports
+        ));
+
+        for (file, occs) in by_file {
+            let rel_path = file.strip_prefix(project_root).unwrap_or(file).display();
+            lines.push(format!("  {} ({}):", rel_path, occs.len()));
+            for occ in occs {
+                // Occurrences reached through an alias carry their own FQN
+                let fqn_display = match occ.fqn.as_deref() {
+                    Some(fqn) if fqn != impact.fqn => format!(" [{}]", fqn),
+                    _ => String::new(),
+                };
+                lines.push(format!(
+                    "    {}:{} - {:?} `{}`{}",
+                    occ.line, occ.column, occ.kind, occ.name, fqn_display
+                ));
+            }
+        }
+    }
+
+    lines.join("\n")
+}
+
+fn collect_occurrences<'a>(index: &'a SymbolIndex, fqn: &str) -> Vec<&'a SymbolOccurrence> {
+    let mut results: Vec<&SymbolOccurrence> = Vec::new();
+
+    let fqns = expand_fqns(index, fqn);
+    for related in &fqns {
+        if let Some(occs) = index.by_fqn.get(related) {
+            results.extend(occs.iter().filter(|o| o.kind != SymbolKind::PackageDeclaration));
+        }
+    }
+
+    // `typealias Id = Target` mentions the target by name
+    for (alias_fqn, target_fqn) in &index.type_aliases {
+        if target_fqn == fqn {
+            if let Some(occs) = index.by_fqn.get(alias_fqn) {
+                results.extend(occs.iter().filter(|o| o.kind.is_declaration()));
+            }
+        }
+    }
+
+    // Kotlin reaches Lombok fields and accessors by simple name (`user.username`,
+    // `user.getUsername()`), which the parser can't tie to the Java class by FQN
+    if is_lombok_related(index, fqn) {
+        let class_fqn = fqn.rsplit_once('.').map(|(class, _)| class);
+        for related in &fqns {
+            let simple_name = related.rsplit('.').next().unwrap_or(related);
+            if let Some(occs) = index.by_name.get(simple_name) {
+                results.extend(occs.iter().filter(|o| {
+                    o.kind.is_reference() && file_references_class(index, &o.file, class_fqn)
+                }));
+            }
+        }
+    }
+
+    // Companion aliases and accessors sharing a field's range appear more than once
+    let mut seen = HashSet::new();
+    results.retain(|&o| seen.insert((&o.file, o.byte_range.clone(), &o.name, o.kind.clone())));
+    results.sort_by(|a, b| {
+        a.file
+            .cmp(&b.file)
+            .then(a.line.cmp(&b.line))
+            .then(a.column.cmp(&b.column))
+    });
+    results
+}
+
+/// The FQN itself plus every FQN that names the same symbol through an alias map.
+fn expand_fqns(index: &SymbolIndex, fqn: &str) -> Vec<String> {
+    let mut fqns = vec![fqn.to_string()];
+
+    // Companion members: `Foo.Companion.bar` (or `Foo.Factory.bar`) ⇄ `Foo.bar`
+    let companions = companion_fqns(index);
+    if let Some(alias_fqn) = strip_companion_segments(fqn, &companions) {
+        fqns.push(alias_fqn);
+    } else if let Some((owner, member)) = fqn.rsplit_once('.') {
+        for companion in &companions {
+            if companion.rsplit_once('.').map(|(class_fqn, _)| class_fqn) == Some(owner) {
+                let companion_fqn = format!("{}.{}", companion, member);
+                if index.by_fqn.contains_key(&companion_fqn) {
+                    fqns.push(companion_fqn);
+                }
+            }
+        }
+    }
+
+    // Lombok: a field renames with its accessors, and an accessor with its field
+    if let Some(accessors) = index.lombok_accessors.get(fqn) {
+        fqns.extend(accessors.iter().cloned());
+    }
+    for (field_fqn, accessors) in &index.lombok_accessors {
+        if accessors.iter().any(|a| a == fqn) {
+            fqns.push(field_fqn.clone());
+            fqns.extend(accessors.iter().filter(|a| *a != fqn).cloned());
+        }
+    }
+
+    fqns
+}
+
+fn is_lombok_related(index: &SymbolIndex, fqn: &str) -> bool {
+    index.lombok_accessors.contains_key(fqn)
+        || index
+            .lombok_accessors
+            .values()
+            .any(|accessors| accessors.iter().any(|a| a == fqn))
+}
+
+fn resolve_fqns(index: &SymbolIndex, symbol: &str) -> Vec<String> {
+    if symbol.contains('.') {
+        return vec![symbol.to_string()];
+    }
+    let mut fqns: Vec<String> = index
+        .by_name
+        .get(symbol)
+        .map(|occs| {
+            occs.iter()
+                .filter(|o| o.kind.is_declaration())
+                .filter_map(|o| o.fqn.clone())
+                .collect()
+        })
+        .unwrap_or_default();
+    fqns.sort();
+    fqns.dedup();
+    fqns
+}
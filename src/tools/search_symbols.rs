@@ -0,0 +1,124 @@
+use crate::indexer::{SymbolIndex, SymbolKind, SymbolOccurrence};
+
+/// Default number of results returned by `search_symbols`.
+pub const DEFAULT_SEARCH_LIMIT: usize = 20;
+
+/// Search declarations whose simple name or FQN fuzzily matches `query`.
+/// Results are sorted by descending score and truncated to `limit`.
+/// `kind` optionally restricts results to one declaration kind (e.g., "ClassDeclaration").
+pub fn search_symbols<'a>(
+    index: &'a SymbolIndex,
+    query: &str,
+    limit: usize,
+    kind: Option<&str>,
+) -> Vec<&'a SymbolOccurrence> {
+    let mut scored: Vec<(i64, &SymbolOccurrence)> = Vec::new();
+
+    for (name, occs) in &index.by_name {
+        for occ in occs {
+            if !occ.kind.is_declaration() {
+                continue;
+            }
+            // Parameters are local; list them only when asked for by kind
+            if kind.is_none() && occ.kind == SymbolKind::ParameterDeclaration {
+                continue;
+            }
+            if let Some(kind) = kind {
+                if !format!("{:?}", occ.kind).eq_ignore_ascii_case(kind) {
+                    continue;
+                }
+            }
+            // Both the simple name and the FQN contribute; the name match weighs more.
+            let name_score = fuzzy_score(query, name);
+            let fqn_score = occ.fqn.as_deref().and_then(|fqn| fuzzy_score(query, fqn));
+            if name_score.is_none() && fqn_score.is_none() {
+                continue;
+            }
+            let score = name_score.unwrap_or(0) + fqn_score.unwrap_or(0) / 2;
+            scored.push((score, occ));
+        }
+    }
+
+    scored.sort_by(|(sa, a), (sb, b)| {
+        sb.cmp(sa)
+            .then(a.name.cmp(&b.name))
+            .then(a.file.cmp(&b.file))
+            .then(a.line.cmp(&b.line))
+    });
+    scored.into_iter().take(limit).map(|(_, occ)| occ).collect()
+}
+
+/// Score `candidate` against `query` as a case-insensitive subsequence match.
+/// Returns None if `query` is not a subsequence of `candidate`. Consecutive matches and
+/// matches at word boundaries (start, after `.` or `_`, camel-case humps) score higher.
+fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let chars: Vec<char> = candidate.chars().collect();
+    let mut score: i64 = 0;
+    let mut pos = 0;
+    let mut prev_match: Option<usize> = None;
+
+    for qc in query.chars() {
+        while pos < chars.len() && !chars_eq_ignore_case(chars[pos], qc) {
+            pos += 1;
+        }
+        if pos == chars.len() {
+            return None;
+        }
+        score += 1;
+        if prev_match.is_some_and(|p| p + 1 == pos) {
+            score += 5;
+        }
+        if is_word_boundary(&chars, pos) {
+            score += 3;
+        }
+        prev_match = Some(pos);
+        pos += 1;
+    }
+
+    if candidate.eq_ignore_ascii_case(query) {
+        score += 100;
+    } else if candidate.to_lowercase().starts_with(&query.to_lowercase()) {
+        score += 20;
+    }
+    // Prefer tighter matches: penalize unmatched characters slightly
+    score -= (chars.len() - query.chars().count()) as i64 / 4;
+
+    Some(score)
+}
+
+fn chars_eq_ignore_case(a: char, b: char) -> bool {
+    a == b || a.to_lowercase().eq(b.to_lowercase())
+}
+
+fn is_word_boundary(chars: &[char], pos: usize) -> bool {
+    if pos == 0 {
+        return true;
+    }
+    let prev = chars[pos - 1];
+    matches!(prev, '.' | '_' | '$') || (chars[pos].is_uppercase() && prev.is_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_score_subsequence() {
+        assert!(fuzzy_score("UsrSvc", "UserService").is_some());
+        assert!(fuzzy_score("usrsvc", "UserService").is_some());
+        assert!(fuzzy_score("SvcUsr", "UserService").is_none());
+    }
+
+    #[test]
+    fn test_fuzzy_score_prefers_exact_and_contiguous() {
+        let exact = fuzzy_score("User", "User").unwrap();
+        let prefix = fuzzy_score("User", "UserService").unwrap();
+        let scattered = fuzzy_score("User", "UpdateServer").unwrap();
+        assert!(exact > prefix, "exact {} should beat prefix {}", exact, prefix);
+        assert!(prefix > scattered, "prefix {} should beat scattered {}", prefix, scattered);
+    }
+}
@@ -0,0 +1,145 @@
+use std::collections::{HashSet, VecDeque};
+
+use crate::indexer::{SymbolIndex, SymbolOccurrence};
+
+/// Find the declarations a method overrides (an `override fun` in Kotlin, an `@Override`
+/// method in Java). `symbol` is `Class.method`, with a fully-qualified or simple class name.
+/// The class's supertypes are searched breadth-first, and the nearest declaration of
+/// `method` on each path is returned, so an override of an override resolves to the method
+/// it directly overrides. Among overloads, see [`matching_methods`].
+pub fn find_super_definition<'a>(
+    index: &'a SymbolIndex,
+    symbol: &str,
+) -> Vec<&'a SymbolOccurrence> {
+    let (class, method) = match symbol.rsplit_once('.') {
+        Some(parts) => parts,
+        None => return Vec::new(),
+    };
+
+    let mut results: Vec<&SymbolOccurrence> = Vec::new();
+    for class_fqn in resolve_class_fqns(index, class) {
+        let own = function_declarations(index, &format!("{}.{}", class_fqn, method));
+        let signatures: Vec<&str> = own
+            .iter()
+            .filter_map(|o| o.signature.as_deref())
+            .collect();
+
+        let mut visited = HashSet::from([class_fqn.clone()]);
+        let mut queue: VecDeque<&String> =
+            index.supertypes.get(&class_fqn).into_iter().flatten().collect();
+        while let Some(super_fqn) = queue.pop_front() {
+            if !visited.insert(super_fqn.clone()) {
+                continue;
+            }
+            let matches =
+                matching_methods(index, &format!("{}.{}", super_fqn, method), &signatures);
+            if matches.is_empty() {
+                queue.extend(index.supertypes.get(super_fqn).into_iter().flatten());
+            } else {
+                for occ in matches {
+                    if !results.iter().any(|r| std::ptr::eq(*r, occ)) {
+                        results.push(occ);
+                    }
+                }
+            }
+        }
+    }
+    results
+}
+
+/// A fully-qualified class name as is, or every type declared with a simple name.
+fn resolve_class_fqns(index: &SymbolIndex, class: &str) -> Vec<String> {
+    if class.contains('.') {
+        return vec![class.to_string()];
+    }
+    let mut fqns: Vec<String> = index
+        .by_name
+        .get(class)
+        .into_iter()
+        .flatten()
+        .filter(|o| o.kind.is_type_declaration())
+        .filter_map(|o| o.fqn.clone())
+        .collect();
+    fqns.sort();
+    fqns.dedup();
+    fqns
+}
+
+fn function_declarations<'a>(index: &'a SymbolIndex, fqn: &str) -> Vec<&'a SymbolOccurrence> {
+    index
+        .by_fqn
+        .get(fqn)
+        .into_iter()
+        .flatten()
+        .filter(|o| o.kind.is_function_like())
+        .collect()
+}
+
+/// The declarations at `fqn` that the overriding method with one of `signatures` can
+/// override: the ones with the same parameter types, or failing that the same number of
+/// parameters (`save(entity: User)` overrides `save(entity: T)`). Without signatures on
+/// both sides (Java methods), every declaration matches.
+fn matching_methods<'a>(
+    index: &'a SymbolIndex,
+    fqn: &str,
+    signatures: &[&str],
+) -> Vec<&'a SymbolOccurrence> {
+    let candidates = function_declarations(index, fqn);
+    if signatures.is_empty() || candidates.iter().any(|c| c.signature.is_none()) {
+        return candidates;
+    }
+    let exact: Vec<&SymbolOccurrence> = candidates
+        .iter()
+        .copied()
+        .filter(|c| c.signature.as_deref().is_some_and(|s| signatures.contains(&s)))
+        .collect();
+    if !exact.is_empty() {
+        return exact;
+    }
+    let arities: Vec<usize> = signatures.iter().map(|s| arity(s)).collect();
+    candidates
+        .into_iter()
+        .filter(|c| c.signature.as_deref().is_some_and(|s| arities.contains(&arity(s))))
+        .collect()
+}
+
+/// Number of parameters in a signature like `(Map<String, Int>, (Int) -> Unit)`: commas
+/// nested in type arguments or function types don't separate parameters.
+fn arity(signature: &str) -> usize {
+    let inner = signature
+        .strip_prefix('(')
+        .and_then(|s| s.strip_suffix(')'))
+        .unwrap_or(signature);
+    if inner.trim().is_empty() {
+        return 0;
+    }
+    let mut depth = 0usize;
+    let mut count = 1;
+    let mut prev = ' ';
+    for c in inner.chars() {
+        match c {
+            '<' | '(' => depth += 1,
+            // The `>` of a function type's arrow closes nothing.
+            '>' if prev == '-' => {}
+            '>' | ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => count += 1,
+            _ => {}
+        }
+        prev = c;
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arity() {
+        assert_eq!(arity("()"), 0);
+        assert_eq!(arity("(Int)"), 1);
+        assert_eq!(arity("(Map<String, Int>, (Int, Int) -> Unit)"), 2);
+        assert_eq!(arity("(String, Int, Boolean)"), 3);
+        assert_eq!(arity("(Map<String, (Int) -> Unit>, Int)"), 2);
+    }
+}
@@ -0,0 +1,100 @@
+use std::path::Path;
+
+use crate::indexer::{SymbolIndex, SymbolOccurrence};
+
+use super::outline::find_file;
+
+/// Find the occurrence under a cursor at 1-based `line` and `column` of `file`, the way an
+/// editor reports positions. Columns count bytes, like the columns of indexed occurrences.
+/// The file is read from disk to turn the position into a byte offset.
+pub fn symbol_at<'a>(
+    index: &'a SymbolIndex,
+    file: &Path,
+    line: usize,
+    column: usize,
+) -> Option<&'a SymbolOccurrence> {
+    let info = find_file(index, file)?;
+    let content = std::fs::read_to_string(&info.path).ok()?;
+    let offset = byte_offset(&content, line, column)?;
+    symbol_at_offset(index, &info.path, offset)
+}
+
+/// Find the occurrence whose byte range contains `offset` in `file`. Ranges nest (a call
+/// inside a function inside a class), so the smallest containing range wins, like the
+/// innermost scope of a scope chain.
+pub fn symbol_at_offset<'a>(
+    index: &'a SymbolIndex,
+    file: &Path,
+    offset: usize,
+) -> Option<&'a SymbolOccurrence> {
+    let info = find_file(index, file)?;
+    index
+        .by_name
+        .values()
+        .flatten()
+        .filter(|o| o.file == info.path && o.byte_range.contains(&offset))
+        // Ties (e.g., Lombok accessors sharing a field's range) go to the first in source order
+        .min_by(|a, b| {
+            a.byte_range
+                .len()
+                .cmp(&b.byte_range.len())
+                .then(a.line.cmp(&b.line))
+                .then(a.column.cmp(&b.column))
+                .then(a.name.cmp(&b.name))
+        })
+}
+
+/// Byte offset of a 1-based line and column, or None past the end of the line or file.
+fn byte_offset(content: &str, line: usize, column: usize) -> Option<usize> {
+    if line == 0 || column == 0 {
+        return None;
+    }
+    let mut line_start = 0;
+    for text in content.split_inclusive('\n').take(line - 1) {
+        line_start += text.len();
+    }
+    let line_text = content.get(line_start..)?.lines().next()?;
+    (column <= line_text.len()).then_some(line_start + column - 1)
+}
+
+/// Describe the occurrence under the cursor: its name, kind, FQN and whether it declares
+/// or references the symbol.
+pub fn format_symbol_at(occurrence: Option<&SymbolOccurrence>, project_root: &Path) -> String {
+    let occ = match occurrence {
+        Some(occ) => occ,
+        None => return "No symbol at this position.".to_string(),
+    };
+    let role = if occ.kind.is_declaration() {
+        "declaration"
+    } else if occ.kind.is_reference() {
+        "reference"
+    } else {
+        "other"
+    };
+    format!(
+        "Symbol `{}`:\n  kind: {:?}\n  fqn: {}\n  role: {}\n  at {}",
+        occ.name,
+        occ.kind,
+        occ.fqn.as_deref().unwrap_or("(unresolved)"),
+        role,
+        super::format_occurrence(occ, project_root)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_byte_offset() {
+        let content = "package a\n\nclass Foo\n";
+        assert_eq!(byte_offset(content, 1, 1), Some(0));
+        assert_eq!(byte_offset(content, 3, 7), Some(17));
+        assert_eq!(byte_offset(content, 3, 9), Some(19));
+        // Past the end of the line, before the first line, past the end of the file
+        assert_eq!(byte_offset(content, 3, 10), None);
+        assert_eq!(byte_offset(content, 0, 1), None);
+        assert_eq!(byte_offset(content, 2, 1), None);
+        assert_eq!(byte_offset(content, 5, 1), None);
+    }
+}
@@ -0,0 +1,162 @@
+use std::borrow::Borrow;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use crate::error::GradleError;
+use crate::gradle::cache::normalize_module;
+use crate::gradle::{DependencyNode, GradleModule, GradleRunner};
+use crate::indexer::{SymbolIndex, SymbolOccurrence};
+
+use super::find_definition::find_definition;
+
+/// A declaration and the Gradle module whose directory contains it.
+pub struct SymbolModule<'a> {
+    pub declaration: &'a SymbolOccurrence,
+    /// `None` when the file is outside every module directory, e.g. in the root project
+    /// or a directory the build doesn't include
+    pub module: Option<GradleModule>,
+}
+
+/// Resolve `symbol` like `find_definition` and map each declaration's file to the module
+/// with the nearest enclosing directory. Modules come from `gradlew projects`, or from the
+/// settings script when dependencies are read from build scripts (see
+/// [`GradleRunner::uses_declared_dependencies`]). Gradle isn't consulted when nothing
+/// matches `symbol`.
+pub fn symbol_modules<'a>(
+    index: &'a SymbolIndex,
+    runner: &GradleRunner,
+    symbol: &str,
+) -> Result<Vec<SymbolModule<'a>>, GradleError> {
+    let declarations = find_definition(index, symbol, None, None);
+    if declarations.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let files: Vec<PathBuf> = declarations.iter().map(|d| d.file.clone()).collect();
+    let modules = file_modules(runner, &files)?;
+    Ok(declarations
+        .into_iter()
+        .zip(modules)
+        .map(|(declaration, module)| SymbolModule {
+            declaration,
+            module,
+        })
+        .collect())
+}
+
+/// The module with the nearest enclosing directory of each of `files`, in order. Takes
+/// paths rather than declarations so the index needn't stay locked while Gradle runs.
+pub fn file_modules(
+    runner: &GradleRunner,
+    files: &[PathBuf],
+) -> Result<Vec<Option<GradleModule>>, GradleError> {
+    let module_dirs = module_dirs(runner)?;
+    Ok(files
+        .iter()
+        .map(|file| enclosing_module(&module_dirs, file).cloned())
+        .collect())
+}
+
+/// Narrow ambiguous `results` to the declarations `file` can see through the module graph:
+/// those in `file`'s Gradle module or in a module it depends on, directly or transitively
+/// (`project(...)` dependencies). Gradle is only consulted when there are several results
+/// and `file` is in a module; when no result is reachable, all are kept.
+pub fn retain_in_module_dependencies<O: Borrow<SymbolOccurrence>>(
+    results: &mut Vec<O>,
+    runner: &GradleRunner,
+    file: &Path,
+) -> Result<(), GradleError> {
+    if results.len() < 2 {
+        return Ok(());
+    }
+    let module_dirs = module_dirs(runner)?;
+    let from = match enclosing_module(&module_dirs, file) {
+        Some(module) => module.path.clone(),
+        None => return Ok(()),
+    };
+    let reachable = module_dependency_closure(runner, &from)?;
+
+    let is_reachable = |occ: &O| {
+        enclosing_module(&module_dirs, &occ.borrow().file)
+            .is_some_and(|m| reachable.contains(&m.path))
+    };
+    if results.iter().any(is_reachable) {
+        results.retain(is_reachable);
+    }
+    Ok(())
+}
+
+/// `module` and every module it depends on through `project(...)` dependencies. Modules
+/// without a build script have no dependencies.
+fn module_dependency_closure(
+    runner: &GradleRunner,
+    module: &str,
+) -> Result<HashSet<String>, GradleError> {
+    let mut reachable = HashSet::from([normalize_module(module)]);
+    let mut pending = vec![normalize_module(module)];
+    while let Some(module) = pending.pop() {
+        let deps = match runner.get_dependencies(&module, None) {
+            Ok(deps) => deps,
+            Err(GradleError::BuildFileNotFound(_)) => Vec::new(),
+            Err(e) => return Err(e),
+        };
+        let mut projects = Vec::new();
+        collect_project_paths(&deps, &mut projects);
+        for project in projects {
+            if reachable.insert(project.clone()) {
+                pending.push(project);
+            }
+        }
+    }
+    Ok(reachable)
+}
+
+/// The module paths of the project dependencies in `deps` and their children.
+fn collect_project_paths(deps: &[DependencyNode], paths: &mut Vec<String>) {
+    for dep in deps {
+        if dep.is_project {
+            paths.push(normalize_module(&dep.artifact));
+        }
+        collect_project_paths(&dep.children, paths);
+    }
+}
+
+/// Each module with its directory. Modules come from `gradlew projects`, or from the
+/// settings script when dependencies are read from build scripts.
+fn module_dirs(runner: &GradleRunner) -> Result<Vec<(PathBuf, GradleModule)>, GradleError> {
+    Ok(runner
+        .get_project_modules()?
+        .into_iter()
+        .map(|module| (runner.module_dir(&module.path), module))
+        .collect())
+}
+
+/// The module whose directory is the longest ancestor of `file`, so a file of a nested
+/// module (`:feature:login`) isn't attributed to its parent directory's module.
+fn enclosing_module<'m>(
+    module_dirs: &'m [(PathBuf, GradleModule)],
+    file: &Path,
+) -> Option<&'m GradleModule> {
+    module_dirs
+        .iter()
+        .filter(|(dir, _)| file.starts_with(dir))
+        .max_by_key(|(dir, _)| dir.components().count())
+        .map(|(_, module)| module)
+}
+
+/// Format each declaration's location followed by its module's path and name.
+pub fn format_symbol_modules(modules: &[SymbolModule], project_root: &Path) -> String {
+    if modules.is_empty() {
+        return "No definitions found.".to_string();
+    }
+
+    let mut lines = vec![format!("Found {} definition(s):\n", modules.len())];
+    for entry in modules {
+        lines.push(format!("  {}", super::format_occurrence(entry.declaration, project_root)));
+        lines.push(match entry.module {
+            Some(ref module) => format!("    module: {} ({})", module.path, module.name),
+            None => "    module: none (outside every known Gradle module)".to_string(),
+        });
+    }
+    lines.join("\n")
+}
@@ -0,0 +1,174 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::indexer::{SymbolIndex, SymbolOccurrence};
+
+/// A type in a hierarchy tree. For the supertype tree, `children` are the node's own
+/// supertypes; for the subtype tree, they are its subtypes.
+#[derive(Debug, Serialize)]
+pub struct TypeHierarchyNode {
+    pub fqn: String,
+    /// Declaration kind, or None for types outside the project (e.g., `java.io.Serializable`)
+    pub kind: Option<String>,
+    /// File path relative to the project root
+    pub file: Option<String>,
+    pub line: Option<usize>,
+    /// True when this type already appears on the path from the root; its children are omitted
+    pub cycle: bool,
+    pub children: Vec<TypeHierarchyNode>,
+}
+
+/// Transitive supertypes and subtypes of one type.
+#[derive(Debug, Serialize)]
+pub struct TypeHierarchy {
+    pub root: TypeHierarchyNode,
+    pub supertypes: Vec<TypeHierarchyNode>,
+    pub subtypes: Vec<TypeHierarchyNode>,
+}
+
+/// Build the type hierarchy of a class or interface: its ancestors (extended classes and
+/// implemented interfaces, transitively) and its descendants. Kotlin and Java types are
+/// mixed freely, e.g. a Kotlin class extending a Java class that implements a Kotlin interface.
+/// Accepts a fully-qualified name or a simple name; a simple name yields one hierarchy
+/// per type declaration with that name.
+pub fn type_hierarchy(index: &SymbolIndex, symbol: &str, project_root: &Path) -> Vec<TypeHierarchy> {
+    resolve_type_fqns(index, symbol)
+        .into_iter()
+        .map(|fqn| {
+            let mut path = HashSet::from([fqn.clone()]);
+            TypeHierarchy {
+                root: make_node(index, &fqn, project_root, false, Vec::new()),
+                supertypes: build_tree(index, &index.supertypes, &fqn, project_root, &mut path),
+                subtypes: build_tree(index, &index.subtypes, &fqn, project_root, &mut path),
+            }
+        })
+        .collect()
+}
+
+/// Format type hierarchies as indented trees.
+pub fn format_type_hierarchies(hierarchies: &[TypeHierarchy]) -> String {
+    if hierarchies.is_empty() {
+        return "No results found.".to_string();
+    }
+
+    let mut lines = Vec::new();
+    for hierarchy in hierarchies {
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+        lines.push(format!("Type hierarchy of {}\n", format_node(&hierarchy.root)));
+        for (title, nodes) in [
+            ("Supertypes", &hierarchy.supertypes),
+            ("Subtypes", &hierarchy.subtypes),
+        ] {
+            lines.push(format!("  {}:", title));
+            if nodes.is_empty() {
+                lines.push("    (none)".to_string());
+            }
+            for node in nodes {
+                push_tree_lines(&mut lines, node, 2);
+            }
+        }
+    }
+
+    lines.join("\n")
+}
+
+fn push_tree_lines(lines: &mut Vec<String>, node: &TypeHierarchyNode, depth: usize) {
+    lines.push(format!("{}{}", "  ".repeat(depth), format_node(node)));
+    for child in &node.children {
+        push_tree_lines(lines, child, depth + 1);
+    }
+}
+
+fn format_node(node: &TypeHierarchyNode) -> String {
+    let kind = node.kind.as_deref().unwrap_or("external");
+    let location = match (&node.file, node.line) {
+        (Some(file), Some(line)) => format!(" {}:{}", file, line),
+        _ => String::new(),
+    };
+    let cycle = if node.cycle { " (cycle)" } else { "" };
+    format!("{} ({}){}{}", node.fqn, kind, location, cycle)
+}
+
+/// Follow `edges` (supertypes or subtypes) from `fqn`. `path` holds the types on the
+/// current branch, so diamonds are expanded on each branch while cycles stop.
+fn build_tree(
+    index: &SymbolIndex,
+    edges: &HashMap<String, Vec<String>>,
+    fqn: &str,
+    project_root: &Path,
+    path: &mut HashSet<String>,
+) -> Vec<TypeHierarchyNode> {
+    let mut related: Vec<&String> = edges.get(fqn).map(|v| v.iter().collect()).unwrap_or_default();
+    related.sort();
+    related.dedup();
+
+    related
+        .into_iter()
+        .map(|next| {
+            if !path.insert(next.clone()) {
+                return make_node(index, next, project_root, true, Vec::new());
+            }
+            let children = build_tree(index, edges, next, project_root, path);
+            path.remove(next);
+            make_node(index, next, project_root, false, children)
+        })
+        .collect()
+}
+
+fn make_node(
+    index: &SymbolIndex,
+    fqn: &str,
+    project_root: &Path,
+    cycle: bool,
+    children: Vec<TypeHierarchyNode>,
+) -> TypeHierarchyNode {
+    let decl = type_declaration(index, fqn);
+    TypeHierarchyNode {
+        fqn: fqn.to_string(),
+        kind: decl.map(|d| format!("{:?}", d.kind)),
+        file: decl.map(|d| {
+            d.file
+                .strip_prefix(project_root)
+                .unwrap_or(&d.file)
+                .display()
+                .to_string()
+        }),
+        line: decl.map(|d| d.line),
+        cycle,
+        children,
+    }
+}
+
+fn type_declaration<'a>(index: &'a SymbolIndex, fqn: &str) -> Option<&'a SymbolOccurrence> {
+    index
+        .by_fqn
+        .get(fqn)
+        .and_then(|occs| occs.iter().find(|o| o.kind.is_type_declaration()))
+}
+
+fn resolve_type_fqns(index: &SymbolIndex, symbol: &str) -> Vec<String> {
+    if symbol.contains('.') {
+        // External types are allowed as long as some project type extends them
+        let known = type_declaration(index, symbol).is_some()
+            || index.supertypes.contains_key(symbol)
+            || index.subtypes.contains_key(symbol);
+        return if known { vec![symbol.to_string()] } else { Vec::new() };
+    }
+    let mut fqns: Vec<String> = index
+        .by_name
+        .get(symbol)
+        .map(|occs| {
+            occs.iter()
+                .filter(|o| o.kind.is_type_declaration())
+                .filter_map(|o| o.fqn.clone())
+                .collect()
+        })
+        .unwrap_or_default();
+    fqns.sort();
+    fqns.dedup();
+    fqns
+}
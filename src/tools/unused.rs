@@ -0,0 +1,132 @@
+use std::path::Path;
+
+use crate::indexer::{SymbolIndex, SymbolKind, SymbolOccurrence, Visibility};
+
+/// A declaration nothing in the index refers to.
+pub struct UnusedDeclaration<'a> {
+    pub declaration: &'a SymbolOccurrence,
+    pub visibility: Visibility,
+}
+
+/// Find classes, functions, properties and type aliases that no reference in the index
+/// resolves to. Uses through Lombok accessors and data class `componentN()` calls count.
+/// Overrides are skipped (they're called through their supertype), as are synthesized
+/// declarations, parameters, constructors and enum entries.
+///
+/// Only non-public declarations are reported unless `include_public` is set: public API
+/// may be used outside the project. Code invoked through reflection or by a framework
+/// (DI, serialization, annotated entry points) has no references and shows up as unused.
+pub fn find_unused_declarations(
+    index: &SymbolIndex,
+    include_public: bool,
+) -> Vec<UnusedDeclaration<'_>> {
+    let mut unused = Vec::new();
+    for occs in index.by_fqn.values() {
+        for occ in occs.iter().filter(|o| is_candidate(&o.kind)) {
+            let fqn = match occ.fqn.as_deref() {
+                Some(fqn) => fqn,
+                None => continue,
+            };
+            if is_referenced(index, fqn) {
+                continue;
+            }
+            let modifiers = match occ.modifiers {
+                Some(modifiers) if !modifiers.is_override => modifiers,
+                _ => continue,
+            };
+            let visibility = modifiers.visibility.unwrap_or_else(|| default_visibility(index, occ));
+            if include_public || visibility != Visibility::Public {
+                unused.push(UnusedDeclaration { declaration: occ, visibility });
+            }
+        }
+    }
+    unused.sort_by(|a, b| {
+        a.declaration
+            .file
+            .cmp(&b.declaration.file)
+            .then(a.declaration.line.cmp(&b.declaration.line))
+            .then(a.declaration.column.cmp(&b.declaration.column))
+    });
+    unused
+}
+
+fn is_candidate(kind: &SymbolKind) -> bool {
+    matches!(
+        kind,
+        SymbolKind::ClassDeclaration
+            | SymbolKind::SealedClassDeclaration
+            | SymbolKind::InterfaceDeclaration
+            | SymbolKind::SealedInterfaceDeclaration
+            | SymbolKind::ObjectDeclaration
+            | SymbolKind::FunctionDeclaration
+            | SymbolKind::PropertyDeclaration
+            | SymbolKind::TypeAliasDeclaration
+            | SymbolKind::ExtensionFunctionDeclaration
+            | SymbolKind::ExtensionPropertyDeclaration
+            | SymbolKind::RecordDeclaration
+            | SymbolKind::AnnotationTypeDeclaration
+            | SymbolKind::AnnotationClassDeclaration
+    )
+}
+
+fn is_referenced(index: &SymbolIndex, fqn: &str) -> bool {
+    let has_reference = |fqn: &str| {
+        index
+            .by_fqn
+            .get(fqn)
+            .is_some_and(|occs| occs.iter().any(|o| o.kind.is_reference()))
+    };
+    has_reference(fqn)
+        || index
+            .lombok_accessors
+            .get(fqn)
+            .is_some_and(|accessors| accessors.iter().any(|a| has_reference(a)))
+        || index
+            .data_class_members
+            .get(fqn)
+            .is_some_and(|components| components.iter().any(|c| has_reference(c)))
+}
+
+/// Kotlin declarations are public without a modifier. Java members are package-private,
+/// except in interfaces where they are public.
+fn default_visibility(index: &SymbolIndex, occ: &SymbolOccurrence) -> Visibility {
+    let is_java = occ.file.extension().is_some_and(|ext| ext == "java");
+    if is_java && !in_interface(index, occ) {
+        Visibility::PackagePrivate
+    } else {
+        Visibility::Public
+    }
+}
+
+fn in_interface(index: &SymbolIndex, occ: &SymbolOccurrence) -> bool {
+    let parent = match occ.fqn.as_deref().and_then(|fqn| fqn.rsplit_once('.')) {
+        Some((parent, _)) => parent,
+        None => return false,
+    };
+    index.by_fqn.get(parent).is_some_and(|occs| {
+        occs.iter().any(|o| {
+            matches!(
+                o.kind,
+                SymbolKind::InterfaceDeclaration
+                    | SymbolKind::SealedInterfaceDeclaration
+                    | SymbolKind::AnnotationTypeDeclaration
+            )
+        })
+    })
+}
+
+/// Format unused declarations one per line, each followed by its visibility.
+pub fn format_unused_declarations(unused: &[UnusedDeclaration], project_root: &Path) -> String {
+    if unused.is_empty() {
+        return "No unused declarations found.".to_string();
+    }
+    let mut lines = vec![format!("Found {} unused declaration(s):\n", unused.len())];
+    for entry in unused {
+        lines.push(format!(
+            "  {} ({})",
+            super::format_occurrence(entry.declaration, project_root),
+            entry.visibility
+        ));
+    }
+    lines.join("\n")
+}
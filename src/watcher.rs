@@ -0,0 +1,117 @@
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::time::Duration;
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use parking_lot::RwLock;
+use tracing::{info, warn};
+
+use crate::indexer::filter::FileFilter;
+use crate::indexer::parser::{is_indexable_source, reindex_file};
+use crate::indexer::symbols::{cross_reference, register_companion_aliases};
+use crate::indexer::SymbolIndex;
+
+/// How long the project must stay quiet before a batch of changes is re-indexed, so
+/// a save that touches several files (or an editor's write-rename dance) costs one pass.
+pub const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Keeps the index in sync with the files under the project root. Watching stops when
+/// this is dropped.
+pub struct IndexWatcher {
+    _watcher: RecommendedWatcher,
+}
+
+/// Watch `project_root` recursively and re-index changed `.kt`, `.kts` and `.java` files
+/// that discovery would index, on a background thread. Watching starts right away, but
+/// changes are only applied once `until_ready` returns, so edits made while the initial
+/// index is built queue up instead of being overwritten by it.
+pub fn watch(
+    project_root: PathBuf,
+    filter: Arc<FileFilter>,
+    index: Arc<RwLock<SymbolIndex>>,
+    until_ready: impl FnOnce() + Send + 'static,
+) -> notify::Result<IndexWatcher> {
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)?;
+    watcher.watch(&project_root, RecursiveMode::Recursive)?;
+
+    // A plain thread, so waiting for events never ties up the async runtime serving MCP
+    std::thread::Builder::new()
+        .name("index-watcher".to_string())
+        .spawn(move || {
+            until_ready();
+            process_events(rx, &project_root, &filter, &index)
+        })
+        .map_err(notify::Error::io)?;
+
+    info!("Watching for file changes");
+    Ok(IndexWatcher { _watcher: watcher })
+}
+
+fn process_events(
+    rx: Receiver<notify::Result<Event>>,
+    project_root: &Path,
+    filter: &FileFilter,
+    index: &RwLock<SymbolIndex>,
+) {
+    while let Ok(first) = rx.recv() {
+        let mut changed = BTreeSet::new();
+        collect_changes(first, &mut changed);
+        while let Ok(event) = rx.recv_timeout(DEBOUNCE) {
+            collect_changes(event, &mut changed);
+        }
+        apply_changes(&changed, project_root, filter, index);
+    }
+}
+
+/// Record the paths of create, modify and remove events.
+fn collect_changes(event: notify::Result<Event>, changed: &mut BTreeSet<PathBuf>) {
+    match event {
+        Ok(event) => {
+            if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)) {
+                changed.extend(event.paths);
+            }
+        }
+        Err(e) => warn!("File watcher error: {}", e),
+    }
+}
+
+/// Re-index the changed paths that discovery would index, plus every indexed file under a
+/// removed directory, then redo the cross-file resolution once for the whole batch.
+fn apply_changes(
+    changed: &BTreeSet<PathBuf>,
+    project_root: &Path,
+    filter: &FileFilter,
+    index: &RwLock<SymbolIndex>,
+) {
+    let mut files = BTreeSet::new();
+    {
+        let index = index.read();
+        for path in changed {
+            if is_indexable_source(project_root, path, filter) {
+                files.insert(path.clone());
+            } else if !path.exists() {
+                files.extend(index.files.keys().filter(|file| file.starts_with(path)).cloned());
+            }
+        }
+    }
+    // Changes under .git, build output and excluded paths don't touch the index
+    if files.is_empty() {
+        return;
+    }
+
+    let mut index = index.write();
+    for file in &files {
+        if file.exists() {
+            info!("Re-indexing {}", file.display());
+        } else {
+            info!("Removing {} from the index", file.display());
+        }
+        reindex_file(&mut index, project_root, file, filter);
+    }
+    cross_reference(&mut index);
+    register_companion_aliases(&mut index);
+    info!("{}", index.stats());
+}
@@ -0,0 +1,172 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use kotlin_java_mcp::indexer::symbols::{cross_reference, cross_reference_files};
+use kotlin_java_mcp::indexer::{FileInfo, ImportInfo, SymbolIndex, SymbolKind, SymbolOccurrence};
+
+/// Counts allocations made on the current thread while counting is switched on, so tests
+/// running in parallel don't add to each other's counts.
+struct CountingAllocator;
+
+thread_local! {
+    static COUNTING: Cell<bool> = const { Cell::new(false) };
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if COUNTING.try_with(|c| c.get()).unwrap_or(false) {
+            let _ = ALLOCATIONS.try_with(|a| a.set(a.get() + 1));
+        }
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn count_allocations(f: impl FnOnce()) -> usize {
+    ALLOCATIONS.with(|a| a.set(0));
+    COUNTING.with(|c| c.set(true));
+    f();
+    COUNTING.with(|c| c.set(false));
+    ALLOCATIONS.with(|a| a.get())
+}
+
+const FILES: usize = 2000;
+const REFERENCES_PER_FILE: usize = 40;
+
+fn file_path(i: usize) -> PathBuf {
+    PathBuf::from(format!("src/pkg{i}/Service{i}.kt"))
+}
+
+fn occurrence(
+    name: String,
+    fqn: String,
+    kind: SymbolKind,
+    file: &Path,
+    line: usize,
+) -> SymbolOccurrence {
+    SymbolOccurrence {
+        name,
+        fqn: Some(fqn),
+        kind,
+        file: file.to_path_buf(),
+        line,
+        column: 1,
+        byte_range: 0..0,
+        receiver_type: None,
+        enclosing_fqn: None,
+        signature: None,
+        platform_modifier: None,
+        modifiers: None,
+    }
+}
+
+/// Add file `i` as the parser would: `class Service{i}` in package `pkg{i}`, importing and
+/// referring to the next files' services, with the same-package FQN guess for each reference.
+fn add_file(index: &mut SymbolIndex, i: usize) {
+    let path = file_path(i);
+    let mut imports = Vec::new();
+    index.add_occurrence(occurrence(
+        format!("Service{i}"),
+        format!("pkg{i}.Service{i}"),
+        SymbolKind::ClassDeclaration,
+        &path,
+        1,
+    ));
+    for k in 1..=REFERENCES_PER_FILE {
+        let j = (i + k) % FILES;
+        imports.push(ImportInfo {
+            path: format!("pkg{j}.Service{j}"),
+            alias: None,
+            is_wildcard: false,
+            is_static: false,
+            line: k,
+            column: 1,
+            byte_range: 0..0,
+        });
+        index.add_occurrence(occurrence(
+            format!("Service{j}"),
+            format!("pkg{i}.Service{j}"),
+            SymbolKind::TypeReference,
+            &path,
+            k + 1,
+        ));
+    }
+    index.add_file_info(FileInfo {
+        path,
+        package: Some(format!("pkg{i}")),
+        imports,
+        parse_errors: 0,
+    });
+}
+
+fn build_index() -> SymbolIndex {
+    let mut index = SymbolIndex::new();
+    for i in 0..FILES {
+        add_file(&mut index, i);
+    }
+    cross_reference(&mut index);
+    index
+}
+
+/// Re-add file 0 with its references unresolved again, as after re-parsing it.
+fn edit_first_file(index: &mut SymbolIndex) {
+    index.remove_file(&file_path(0));
+    add_file(index, 0);
+}
+
+fn resolved_references_from_first_file(index: &SymbolIndex) -> Vec<String> {
+    let mut fqns: Vec<String> = index
+        .by_name
+        .values()
+        .flatten()
+        .filter(|o| o.kind == SymbolKind::TypeReference && o.file == file_path(0))
+        .filter_map(|o| o.fqn.clone())
+        .collect();
+    fqns.sort();
+    fqns
+}
+
+#[test]
+fn test_cross_reference_files_only_resolves_given_files() {
+    let mut full = build_index();
+    let mut scoped = build_index();
+    edit_first_file(&mut full);
+    edit_first_file(&mut scoped);
+
+    let full_allocations = count_allocations(|| cross_reference(&mut full));
+    let changed = HashSet::from([file_path(0)]);
+    let scoped_allocations =
+        count_allocations(|| cross_reference_files(&mut scoped, Some(&changed)));
+
+    // Both resolve the edited file's references through its imports
+    let expected: Vec<String> = {
+        let mut fqns: Vec<String> = (1..=REFERENCES_PER_FILE)
+            .map(|j| format!("pkg{j}.Service{j}"))
+            .collect();
+        fqns.sort();
+        fqns
+    };
+    assert_eq!(resolved_references_from_first_file(&full), expected);
+    assert_eq!(resolved_references_from_first_file(&scoped), expected);
+    assert_eq!(
+        full.by_fqn.get("pkg1.Service1").map(Vec::len),
+        scoped.by_fqn.get("pkg1.Service1").map(Vec::len)
+    );
+
+    // Skipping the other 79,960 references leaves little beyond the declaration lookup
+    assert!(
+        scoped_allocations * 4 < full_allocations,
+        "Expected the scoped run to allocate far less: {} vs {} allocations",
+        scoped_allocations,
+        full_allocations
+    );
+}
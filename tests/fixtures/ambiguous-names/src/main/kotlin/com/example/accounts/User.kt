@@ -0,0 +1,3 @@
+package com.example.accounts
+
+class User(val login: String)
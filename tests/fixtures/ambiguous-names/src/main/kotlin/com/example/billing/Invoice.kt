@@ -0,0 +1,4 @@
+package com.example.billing
+
+// Invoices are addressed to the billing User, not the accounts one
+class Invoice(val recipient: User)
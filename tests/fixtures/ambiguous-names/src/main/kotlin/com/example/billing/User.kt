@@ -0,0 +1,3 @@
+package com.example.billing
+
+class User(val accountNumber: String)
@@ -0,0 +1,6 @@
+package com.example.reports
+
+import com.example.accounts.User
+
+// Lists the login of each accounts User
+fun loginReport(users: List<User>): List<String> = users.map { it.login }
@@ -0,0 +1,3 @@
+class Clock {
+    fun now(): Long = 0L
+}
@@ -0,0 +1 @@
+fun elapsed(watch: Stopwatch, now: Long): Long = now - watch.started
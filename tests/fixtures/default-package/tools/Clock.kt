@@ -0,0 +1,5 @@
+class Clock {
+    fun tick() {}
+}
+
+fun newClock(): Clock = Clock()
@@ -0,0 +1 @@
+class Stopwatch(val started: Long)
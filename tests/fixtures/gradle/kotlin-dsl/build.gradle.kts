@@ -0,0 +1,21 @@
+plugins {
+    kotlin("jvm") version "1.9.22"
+}
+
+buildscript {
+    dependencies {
+        classpath("org.jetbrains.kotlin:kotlin-gradle-plugin:1.9.22")
+    }
+}
+
+dependencies {
+    implementation(project(":core"))
+    implementation("com.google.code.gson:gson:2.10.1")
+    api(group = "org.apache.commons", name = "commons-lang3", version = "3.14.0")
+    implementation("com.squareup.okhttp3:okhttp:4.12.0") {
+        exclude(group = "org.jetbrains.kotlin")
+    }
+    implementation(libs.kotlinx.coroutines.core)
+    // testImplementation("org.mockito:mockito-core:5.8.0")
+    testImplementation("org.junit.jupiter:junit-jupiter:5.10.1")
+}
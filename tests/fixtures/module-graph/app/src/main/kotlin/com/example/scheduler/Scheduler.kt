@@ -0,0 +1,7 @@
+package com.example.scheduler
+
+import com.example.time.Clock
+
+class Scheduler(private val clock: Clock) {
+    fun isDue(at: Long): Boolean = clock.now() >= at
+}
@@ -0,0 +1,3 @@
+dependencies {
+    api(project(":realtime"))
+}
@@ -0,0 +1,7 @@
+package com.example.platform
+
+import com.example.time.Clock
+
+object Platform {
+    val clock = Clock()
+}
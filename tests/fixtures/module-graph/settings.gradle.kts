@@ -0,0 +1,6 @@
+rootProject.name = "module-graph"
+
+include(":app")
+include(":platform")
+include(":realtime")
+include(":simulated")
@@ -0,0 +1,3 @@
+dependencies {
+    testImplementation("junit:junit:4.13.2")
+}
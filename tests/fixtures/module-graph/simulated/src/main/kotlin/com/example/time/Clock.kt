@@ -0,0 +1,9 @@
+package com.example.time
+
+class Clock(private var millis: Long = 0) {
+    fun now(): Long = millis
+
+    fun advance(by: Long) {
+        millis += by
+    }
+}
@@ -0,0 +1,9 @@
+package com.example.kmp
+
+expect class Platform() {
+    val name: String
+}
+
+expect fun currentTimeMillis(): Long
+
+fun greeting(): String = "Hello from ${Platform().name} at ${currentTimeMillis()}"
@@ -0,0 +1,9 @@
+package com.example.kmp
+
+import platform.Foundation.NSDate
+
+actual class Platform actual constructor() {
+    actual val name: String = "iOS"
+}
+
+actual fun currentTimeMillis(): Long = (NSDate().timeIntervalSince1970 * 1000).toLong()
@@ -0,0 +1,7 @@
+package com.example.kmp
+
+actual class Platform actual constructor() {
+    actual val name: String = "JVM " + System.getProperty("java.version")
+}
+
+actual fun currentTimeMillis(): Long = System.currentTimeMillis()
@@ -0,0 +1,23 @@
+package com.example.app;
+
+import java.util.List;
+import java.util.stream.Collectors;
+
+public class MethodReferences {
+    public List<String> clean(List<String> raw) {
+        raw.forEach(System.out::println);
+        return raw.stream()
+                .map(MethodReferences::normalize)
+                .filter(line -> !line.isBlank())
+                .map(this::quote)
+                .collect(Collectors.toList());
+    }
+
+    private static String normalize(String value) {
+        return value.trim();
+    }
+
+    private String quote(String value) {
+        return "\"" + value + "\"";
+    }
+}
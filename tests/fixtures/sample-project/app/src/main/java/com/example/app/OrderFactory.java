@@ -0,0 +1,17 @@
+package com.example.app;
+
+import com.example.core.LombokOrder;
+
+public class OrderFactory {
+    public LombokOrder create(String customer) {
+        return LombokOrder.builder()
+            .id("order-1")
+            .customer(customer)
+            .quantity(2)
+            .build();
+    }
+
+    public LombokOrder reorder(String customer) {
+        return new LombokOrder("order-2", customer);
+    }
+}
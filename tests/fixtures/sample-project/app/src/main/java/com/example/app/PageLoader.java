@@ -0,0 +1,16 @@
+package com.example.app;
+
+import com.example.core.Pagination;
+
+public class PageLoader {
+    private Pagination.PageRequest current = new Pagination.PageRequest(1);
+
+    public int defaultSize() {
+        return Pagination.PageRequest.DEFAULT_SIZE;
+    }
+
+    public Pagination.PageRequest restart() {
+        current = Pagination.PageRequest.first();
+        return current;
+    }
+}
@@ -0,0 +1,17 @@
+package com.example.app;
+
+public class ReportQueries {
+    private static final String OWNER_TABLE = "reports";
+
+    public String byOwner(String owner) {
+        String query = """
+            SELECT id, "title" FROM %s
+            WHERE owner = '%s' -- owner.trim()
+            """ + OWNER_TABLE;
+        return withOwner(query, owner);
+    }
+
+    private String withOwner(String query, String owner) {
+        return query.formatted(OWNER_TABLE, owner);
+    }
+}
@@ -0,0 +1,23 @@
+package com.example.app;
+
+import com.example.core.User;
+import java.io.StringReader;
+import java.util.List;
+
+public class RosterExport {
+    public int countNamed(List<User> users) {
+        int named = 0;
+        for (User u : users) {
+            if (!u.getName().isEmpty()) {
+                named++;
+            }
+        }
+        return named;
+    }
+
+    public int firstChar(String text) throws java.io.IOException {
+        try (StringReader reader = new StringReader(text)) {
+            return reader.read();
+        }
+    }
+}
@@ -0,0 +1,25 @@
+package com.example.app;
+
+import com.example.core.shapes.Shape;
+import com.example.core.shapes.Shape.Circle;
+import com.example.core.shapes.Shape.Rectangle;
+
+public class ShapeDescriber {
+    public String describe(Shape shape) {
+        return switch (shape) {
+            case Circle c when c.radius() > 10 -> "large circle";
+            case Circle c -> "circle";
+            case Shape.Square s -> "square of " + s.side();
+            case Rectangle(double w, double h) -> "rectangle " + w + "x" + h;
+        };
+    }
+
+    public int corners(Shape shape) {
+        switch (shape) {
+            case Circle c:
+                return 0;
+            default:
+                return 4;
+        }
+    }
+}
@@ -0,0 +1,21 @@
+package com.example.app;
+
+import static java.util.Collections.emptyList;
+import static org.junit.Assert.*;
+
+import java.util.List;
+
+public class StaticImports {
+    public List<String> names() {
+        return emptyList();
+    }
+
+    public void check() {
+        assertEquals(0, names().size());
+        assertTrue(isEmpty());
+    }
+
+    private boolean isEmpty() {
+        return names().isEmpty();
+    }
+}
@@ -0,0 +1,15 @@
+package com.example.app;
+
+import com.example.core.Traced;
+import com.example.core.JavaHelper;
+
+@Traced
+public class TracedService {
+    @Traced("orders")
+    public void placeOrder() {
+    }
+
+    @Traced(by = JavaHelper.class)
+    public void refund() {
+    }
+}
@@ -0,0 +1,17 @@
+package com.example.app;
+
+import com.example.core.User;
+
+/**
+ * Captions a {@link User}; see {@link com.example.core.UserService#getUser}.
+ */
+public class UserCaptions {
+    // new User(id, name) would work too, but UserService.getUser() is cached
+    public String caption(User user) {
+        return user.getName(); /* not User.toString() */
+    }
+
+    /*
+     * UserService service = new UserService(); service.getUser("id");
+     */
+}
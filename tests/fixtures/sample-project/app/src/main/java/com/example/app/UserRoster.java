@@ -0,0 +1,22 @@
+package com.example.app;
+
+import com.example.core.User;
+import java.util.ArrayList;
+import java.util.List;
+
+public class UserRoster {
+    private final List<User> roster = new ArrayList<>();
+
+    public List<User> active(List<User> candidates) {
+        var roster = new ArrayList<User>();
+        for (var candidate : candidates) {
+            roster.add(candidate);
+        }
+        return roster;
+    }
+
+    public int size() {
+        int roster = this.roster.size();
+        return roster;
+    }
+}
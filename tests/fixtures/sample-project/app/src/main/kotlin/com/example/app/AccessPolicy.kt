@@ -0,0 +1,14 @@
+package com.example.app
+
+class Member(val isActive: Boolean, val strikes: Int)
+
+object AccessPolicy {
+    const val MAX_STRIKES = 3
+
+    fun describe(member: Member, grace: Int): String = when {
+        member.isActive && member.strikes == 0 -> "good standing"
+        member.strikes > MAX_STRIKES + grace -> "suspended"
+        !member.isActive -> "inactive"
+        else -> "on probation"
+    }
+}
@@ -0,0 +1,10 @@
+package com.example.app
+
+import com.example.core.Account
+
+class AccountUsage {
+    fun rename(account: Account) {
+        account.setUserName("renamed")
+        println(account.getBalance())
+    }
+}
@@ -0,0 +1,12 @@
+package com.example.app
+
+import com.example.core.Quaternion
+import com.example.core.Vector2
+
+fun alignment(a: Vector2, b: Vector2, rotation: Quaternion): Double {
+    val planar = a dot b
+    val identity = Quaternion(1.0, 0.0, 0.0, 0.0)
+    val spatial = rotation dot identity
+    val axis = Vector2(1, 0) dot a
+    return planar + spatial + (identity dot rotation) + axis
+}
@@ -0,0 +1,15 @@
+package com.example.app
+
+import com.example.core.Audited
+import com.example.core.UserRole
+
+const val AUDIT_REASON = "compliance"
+
+@Audited(AUDIT_REASON)
+class AuditedService {
+    @Audited
+    fun save(role: UserRole) {}
+
+    @field:Audited(reason = AUDIT_REASON)
+    val lastSaved: String = ""
+}
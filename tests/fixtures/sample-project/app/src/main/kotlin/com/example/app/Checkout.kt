@@ -0,0 +1,17 @@
+package com.example.app
+
+import com.example.core.Money
+
+class Checkout(private val prices: List<Money>) {
+    fun total(): Money {
+        var sum = Money(0)
+        for (price in prices) {
+            sum = sum + price
+        }
+        return sum
+    }
+
+    fun isFree(): Boolean = total() <= Money(0)
+
+    fun hasPrice(price: Money): Boolean = price in prices
+}
@@ -0,0 +1,9 @@
+package com.example.app
+
+import com.example.core.Connection
+
+fun openConnections(): List<Connection> {
+    val primary = Connection.create("db://primary")
+    val replica = Connection.Factory.create("db://replica")
+    return listOf(primary, replica)
+}
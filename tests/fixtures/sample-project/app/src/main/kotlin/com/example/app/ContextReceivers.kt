@@ -0,0 +1,19 @@
+package com.example.app
+
+class AuditLog {
+    fun record(message: String) = println(message)
+}
+
+class Clock {
+    fun now(): Long = 0L
+}
+
+context(AuditLog)
+fun audit(message: String) {
+    record(message)
+}
+
+context(log: AuditLog, clock: Clock)
+fun auditAt(message: String) {
+    log.record("${clock.now()}: $message")
+}
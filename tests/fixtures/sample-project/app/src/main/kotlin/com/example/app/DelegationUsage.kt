@@ -0,0 +1,12 @@
+package com.example.app
+
+import com.example.core.Greeter
+import com.example.core.defaultGreeter
+
+class LoudGreeter(private val impl: Greeter) : Greeter by impl
+
+class SharedGreeter : Greeter by defaultGreeter
+
+class LazyGreeting {
+    val greeting by lazy { defaultGreeter.greet("lazy") }
+}
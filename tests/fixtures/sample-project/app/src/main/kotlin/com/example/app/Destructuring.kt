@@ -0,0 +1,15 @@
+package com.example.app
+
+import com.example.core.User
+
+fun describeUser(user: User): String {
+    val (id, name) = user
+    return "$name <$id>"
+}
+
+data class Coordinates(val latitude: Double, val longitude: Double)
+
+fun formatCoordinates(): String {
+    val (lat, lon) = Coordinates(52.5, 13.4)
+    return "$lat,$lon"
+}
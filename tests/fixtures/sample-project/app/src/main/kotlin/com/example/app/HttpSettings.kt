@@ -0,0 +1,9 @@
+package com.example.app
+
+import com.example.core.AppConfig
+
+class HttpSettings {
+    val timeout = AppConfig.Network.TIMEOUT
+    val attempts = AppConfig.Network.Retry.MAX_ATTEMPTS
+    val label = "${AppConfig.NAME}: ${com.example.core.AppConfig.Network.TIMEOUT}"
+}
@@ -0,0 +1,21 @@
+package com.example.app
+
+import com.example.core.User
+
+class LambdaScopes {
+    val name: String = "directory"
+
+    fun labels(users: List<User>): List<String> =
+        users.map { user -> user.name }
+
+    fun nested(groups: List<List<User>>): List<String> =
+        groups.flatMap { user ->
+            user.map { user -> user.email }
+        }
+
+    fun implicit(users: List<User>): List<Int> =
+        users.map { it.name.length + name.length }
+
+    fun shadowsProperty(names: List<String>): List<Int> =
+        names.map { name -> name.length }
+}
@@ -0,0 +1,16 @@
+package com.example.app
+
+class LoopVariables {
+    val key: String = "default"
+
+    fun settingLines(settings: Map<String, Int>): List<String> {
+        val lines = mutableListOf<String>()
+        for ((key, value) in settings) {
+            lines.add("$key=$value")
+        }
+        for (entry in settings.entries) {
+            lines.add(entry.key + key)
+        }
+        return lines
+    }
+}
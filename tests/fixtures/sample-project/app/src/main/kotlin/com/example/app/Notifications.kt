@@ -0,0 +1,11 @@
+package com.example.app
+
+import com.example.core.UserService
+
+class Inbox(val owner: String, val unread: Int)
+
+fun inboxSummary(inbox: Inbox, service: UserService): String {
+    val greeting = "Hello ${inbox.owner}"
+    val hint = "Type \$unread to list unread messages"
+    return "$greeting, you have ${inbox.unread} new of ${service.getAllUsers().size} users. $hint"
+}
@@ -0,0 +1,5 @@
+package com.example.app
+
+import com.example.core.Result
+
+class Cancelled(val reason: String) : Result()
@@ -0,0 +1,7 @@
+package com.example.app
+
+import com.example.core.JavaGreeter
+
+class PoliteGreeter : JavaGreeter() {
+    override fun salutation(): String = "Good day"
+}
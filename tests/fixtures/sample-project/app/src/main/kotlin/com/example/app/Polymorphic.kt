@@ -0,0 +1,17 @@
+package com.example.app
+
+import kotlin.reflect.KClass
+
+annotation class SubType(val value: KClass<*>, val name: String = "")
+
+annotation class SubTypes(vararg val value: SubType)
+
+annotation class Handles(val types: Array<KClass<*>>)
+
+class GuestAccount
+
+class AdminAccount
+
+@SubTypes(SubType(GuestAccount::class, name = "guest"), SubType(AdminAccount::class))
+@Handles([GuestAccount::class, AdminAccount::class])
+interface Principal
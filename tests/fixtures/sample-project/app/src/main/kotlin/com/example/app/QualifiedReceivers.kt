@@ -0,0 +1,27 @@
+package com.example.app
+
+open class Widget {
+    open fun render(): String = "widget"
+}
+
+open class Panel : Widget() {
+    override fun render(): String = "panel"
+}
+
+class Toolbar : Panel() {
+    private val title = "Toolbar"
+
+    override fun render(): String = super.render() + title
+
+    inner class Button {
+        private val title = "Button"
+
+        fun label(): String = this@Toolbar.title + title
+
+        fun draw(): String = this@Toolbar.render()
+    }
+}
+
+class Badge : Widget() {
+    override fun render(): String = "badge " + super<Widget>.render()
+}
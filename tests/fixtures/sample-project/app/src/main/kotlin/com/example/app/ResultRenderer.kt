@@ -0,0 +1,15 @@
+package com.example.app
+
+import com.example.core.Result
+
+fun render(result: Result): String = when (result) {
+    is Result.Success -> "ok: ${result.value}"
+    is Result.Failure -> "error: ${result.error.message}"
+    is Cancelled -> "cancelled: ${result.reason}"
+    Result.Loading -> "loading"
+}
+
+fun isRetryable(code: Int): Boolean = when (code) {
+    in 500..599 -> true
+    else -> false
+}
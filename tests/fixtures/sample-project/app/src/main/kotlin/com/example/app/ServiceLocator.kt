@@ -0,0 +1,16 @@
+package com.example.app
+
+class Stopwatch
+
+object ServiceLocator {
+    val services = mutableMapOf<Class<*>, Any>()
+
+    inline fun <reified T> lookup(): T = services[T::class.java] as T
+
+    fun stopwatch(): Stopwatch = lookup<Stopwatch>()
+}
+
+class Scheduler {
+    val stopwatch = ServiceLocator.lookup<Stopwatch>()
+    val history = mutableListOf<Stopwatch>()
+}
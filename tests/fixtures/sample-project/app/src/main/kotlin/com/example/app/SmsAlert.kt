@@ -0,0 +1,5 @@
+package com.example.app
+
+import com.example.core.Alert
+
+class SmsAlert(val phone: String) : Alert
@@ -0,0 +1,12 @@
+package com.example.app
+
+import com.example.core.Limits.*
+import com.example.core.Priority
+import com.example.core.Priority.*
+
+class TaskTriage {
+    fun attempts(priority: Priority): Int = when (priority) {
+        URGENT -> MAX_RETRIES
+        NORMAL, LOW -> clamp(1)
+    }
+}
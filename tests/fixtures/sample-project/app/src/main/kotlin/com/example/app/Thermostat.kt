@@ -0,0 +1,13 @@
+package com.example.app
+
+class Thermostat {
+    var target: Int = 20
+        get() = field
+        set(v) {
+            field = v.coerceIn(5, 30)
+        }
+}
+
+var thermostatMode: String = "auto"
+    get() = field
+    set(v) { field = v.lowercase() }
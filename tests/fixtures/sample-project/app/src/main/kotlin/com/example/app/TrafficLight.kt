@@ -0,0 +1,17 @@
+package com.example.app
+
+enum class TrafficLight(val seconds: Int) {
+    RED(30) {
+        override fun next() = GREEN
+    },
+    GREEN(25) {
+        override fun next() = YELLOW
+    },
+    YELLOW(5) {
+        override fun next() = RED
+    };
+
+    abstract fun next(): TrafficLight
+}
+
+fun cycleLength(start: TrafficLight): Int = start.seconds + start.next().seconds
@@ -0,0 +1,19 @@
+package com.example.app
+
+class ReportFormatter {
+    fun format(value: Int): String = pad(value.toString())
+
+    private fun pad(text: String): String = text.padStart(8)
+
+    private fun legacyFormat(value: Int): String = "#$value"
+
+    private val unusedPrefix = ">> "
+
+    private fun ate() = Unit
+
+    internal fun tern() = Unit
+
+    override fun toString(): String = format(0)
+}
+
+internal fun formatAll(values: List<Int>) = values.map { ReportFormatter().format(it) }
@@ -0,0 +1,7 @@
+package com.example.app
+
+import com.example.core.UserService
+
+class UserDirectory {
+    lateinit var userService: UserService
+}
@@ -0,0 +1,15 @@
+package com.example.app
+
+import com.example.core.User
+
+/**
+ * Labels a [User] for display; see UserService.getUser and User.name.
+ */
+class UserLabels {
+    // User(id, name) would work too, but UserService.getUser() is cached
+    fun label(user: User): String = user.name /* not User.toString() */
+
+    /*
+     * Block: UserService, getUser(User.id)
+     */
+}
@@ -0,0 +1,11 @@
+package com.example.app
+
+import com.example.core.Preference
+import com.example.core.memoized
+
+class UserSettings {
+    var theme by Preference("theme")
+    val locale by Preference("locale")
+    val greeting by memoized { "Hello, $locale" }
+    val banner by lazy { greeting.uppercase() }
+}
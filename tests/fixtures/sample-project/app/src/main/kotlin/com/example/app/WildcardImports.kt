@@ -0,0 +1,9 @@
+package com.example.app
+
+import com.example.core.*
+
+class UserDirectory(private val repository: Repository<User>) {
+    fun describe(user: User): String = user.name
+
+    fun retries(): Int = Config.maxRetries
+}
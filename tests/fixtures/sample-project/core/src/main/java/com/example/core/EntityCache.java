@@ -0,0 +1,9 @@
+package com.example.core;
+
+public class EntityCache<E extends Entity> {
+    private E last;
+
+    public void remember(E entity) {
+        last = entity;
+    }
+}
@@ -0,0 +1,13 @@
+package com.example.core;
+
+/**
+ * A Java base class implementing the Kotlin-defined Greeter interface.
+ */
+public abstract class JavaGreeter implements Greeter {
+    protected abstract String salutation();
+
+    @Override
+    public String greet(String name) {
+        return salutation() + ", " + name;
+    }
+}
@@ -0,0 +1,12 @@
+package com.example.core;
+
+public final class Limits {
+    public static final int MAX_RETRIES = 3;
+
+    private Limits() {
+    }
+
+    public static int clamp(int value) {
+        return Math.min(Math.max(value, 0), MAX_RETRIES);
+    }
+}
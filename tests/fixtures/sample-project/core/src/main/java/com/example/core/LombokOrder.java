@@ -0,0 +1,17 @@
+package com.example.core;
+
+import lombok.AllArgsConstructor;
+import lombok.Builder;
+import lombok.NonNull;
+import lombok.RequiredArgsConstructor;
+
+@Builder
+@AllArgsConstructor
+@RequiredArgsConstructor
+public class LombokOrder {
+    private final String id;
+    @NonNull private String customer;
+    private int quantity;
+    private final String currency = "EUR";
+    private static int created;
+}
@@ -0,0 +1,24 @@
+package com.example.core;
+
+/**
+ * Paging helpers with a static nested request type and an inner cursor.
+ */
+public class Pagination {
+    public static class PageRequest {
+        public static final int DEFAULT_SIZE = 20;
+
+        private final int page;
+
+        public PageRequest(int page) {
+            this.page = page;
+        }
+
+        public static PageRequest first() {
+            return new PageRequest(0);
+        }
+    }
+
+    public class Cursor {
+        private int position;
+    }
+}
@@ -0,0 +1,10 @@
+package com.example.core;
+
+/**
+ * Marks an operation for tracing, optionally naming the class that records it.
+ */
+public @interface Traced {
+    String value() default "";
+
+    Class<?> by() default Object.class;
+}
@@ -0,0 +1,9 @@
+package com.example.core.shapes;
+
+public sealed interface Shape permits Shape.Circle, Shape.Square, Shape.Rectangle {
+    record Circle(double radius) implements Shape {}
+
+    record Square(double side) implements Shape {}
+
+    record Rectangle(double width, double height) implements Shape {}
+}
@@ -0,0 +1,7 @@
+package com.example.core
+
+class Account {
+    var userName: String = ""
+    var isLocked: Boolean = false
+    val balance: Long = 0L
+}
@@ -0,0 +1,9 @@
+package com.example.core
+
+sealed interface Alert {
+    data class Banner(val message: String) : Alert
+
+    object Siren : Alert
+}
+
+class Toast(val message: String, val seconds: Int) : Alert
@@ -0,0 +1,11 @@
+package com.example.core
+
+typealias UserHandler<T> = (User, T) -> Unit
+
+typealias UserRepo = Repository<User>
+
+typealias Outcome = Result.Success?
+
+class CachedUserRepo(private val delegate: UserRepo) {
+    fun onEach(handler: UserHandler<Int>) {}
+}
@@ -0,0 +1,15 @@
+package com.example.core
+
+object AppConfig {
+    const val NAME = "sample"
+
+    object Network {
+        const val TIMEOUT = 30_000L
+
+        object Retry {
+            const val MAX_ATTEMPTS = 5
+        }
+    }
+
+    val defaultTimeout = Network.TIMEOUT
+}
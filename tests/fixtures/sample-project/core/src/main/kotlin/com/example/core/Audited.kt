@@ -0,0 +1,3 @@
+package com.example.core
+
+annotation class Audited(val reason: String = "")
@@ -0,0 +1,7 @@
+package com.example.core
+
+class Connection private constructor(val url: String) {
+    companion object Factory {
+        fun create(url: String): Connection = Connection(url)
+    }
+}
@@ -0,0 +1,15 @@
+package com.example.core
+
+interface Entity {
+    val key: String
+}
+
+class EntityStore<T : Entity> {
+    private val items = mutableMapOf<String, T>()
+
+    fun store(item: T) {
+        items[item.key] = item
+    }
+}
+
+fun <T> latest(items: List<T>): T? where T : Entity, T : Comparable<T> = items.maxOrNull()
@@ -0,0 +1,9 @@
+package com.example.core
+
+data class Vector2(val x: Int, val y: Int) {
+    infix fun dot(other: Vector2): Int = x * other.x + y * other.y
+}
+
+class Quaternion(val w: Double, val x: Double, val y: Double, val z: Double) {
+    infix fun dot(other: Quaternion): Double = w * other.w + x * other.x + y * other.y + z * other.z
+}
@@ -0,0 +1,11 @@
+package com.example.core
+
+interface Greeter {
+    fun greet(name: String): String
+}
+
+class DefaultGreeter : Greeter {
+    override fun greet(name: String): String = "Hello, $name"
+}
+
+val defaultGreeter = DefaultGreeter()
@@ -0,0 +1,7 @@
+package com.example.core
+
+data class Money(val cents: Long) {
+    operator fun plus(other: Money): Money = Money(cents + other.cents)
+
+    operator fun compareTo(other: Money): Int = cents.compareTo(other.cents)
+}
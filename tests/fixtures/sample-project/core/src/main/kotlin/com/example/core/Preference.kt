@@ -0,0 +1,13 @@
+package com.example.core
+
+import kotlin.reflect.KProperty
+
+class Preference(private val key: String, private var value: String = "") {
+    operator fun getValue(thisRef: Any?, property: KProperty<*>): String = value
+
+    operator fun setValue(thisRef: Any?, property: KProperty<*>, newValue: String) {
+        value = newValue
+    }
+}
+
+fun <T> memoized(initializer: () -> T): Lazy<T> = lazy(initializer)
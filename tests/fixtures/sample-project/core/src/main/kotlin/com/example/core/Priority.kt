@@ -0,0 +1,7 @@
+package com.example.core
+
+enum class Priority {
+    LOW,
+    NORMAL,
+    URGENT
+}
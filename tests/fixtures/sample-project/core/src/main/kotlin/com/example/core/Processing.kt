@@ -0,0 +1,8 @@
+package com.example.core
+
+fun process(value: Int): Int = value * 2
+
+fun process(value: String): String = value.trim()
+
+fun process(values: List<String>, separator: String = ","): String =
+    values.joinToString(separator)
@@ -0,0 +1,5 @@
+package com.example.core
+
+@Target(AnnotationTarget.CLASS, AnnotationTarget.FUNCTION)
+@Retention(AnnotationRetention.RUNTIME)
+annotation class RequiresPermission(val value: String)
@@ -0,0 +1,7 @@
+package com.example.core
+
+sealed class Result {
+    data class Success(val value: String) : Result()
+    data class Failure(val error: Throwable) : Result()
+    object Loading : Result()
+}
@@ -0,0 +1,3 @@
+package com.example.coreutils
+
+fun slugify(text: String): String = text.lowercase().replace(' ', '-')
@@ -0,0 +1,9 @@
+package com.example.feature
+
+class Config(private val values: Map<String, String>) {
+    fun get(key: String): String? = values[key]
+
+    companion object {
+        fun load(): Config = Config(emptyMap())
+    }
+}
@@ -0,0 +1,7 @@
+package com.example.feature
+
+class FeatureFlags {
+    private val config = Config.load()
+
+    fun isEnabled(flag: String): Boolean = config.get(flag) == "true"
+}
@@ -0,0 +1,8 @@
+import com.example.core.User
+import com.example.core.UserRole
+
+fun summarize(users: List<User>): String =
+    users.joinToString { "${it.name} (${it.role})" }
+
+val admins = listOf(User("1", "Ada", "ada@example.com", UserRole.ADMIN))
+println(summarize(admins))
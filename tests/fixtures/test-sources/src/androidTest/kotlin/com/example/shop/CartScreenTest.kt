@@ -0,0 +1,8 @@
+package com.example.shop
+
+class CartScreenTest {
+    fun showsTotal() {
+        val cart = Cart()
+        check(cart.total() == 0)
+    }
+}
@@ -0,0 +1,11 @@
+package com.example.shop
+
+class Cart {
+    private val prices = mutableListOf<Int>()
+
+    fun add(price: Int) {
+        prices.add(price)
+    }
+
+    fun total(): Int = prices.sum()
+}
@@ -0,0 +1,5 @@
+package com.example.shop
+
+class Checkout(private val cart: Cart) {
+    fun amountDue(): Int = cart.total()
+}
@@ -0,0 +1,9 @@
+package com.example.shop
+
+class CartTest {
+    fun totalSumsPrices() {
+        val cart = Cart()
+        cart.add(2)
+        check(cart.total() == 2)
+    }
+}
@@ -1,9 +1,27 @@
 mod integration {
+    mod call_hierarchy_test;
     mod cli_test;
     mod cross_language_test;
+    mod definition_source_test;
+    mod file_references_test;
     mod find_usages_test;
     mod find_definition_test;
+    mod find_implementations_test;
     mod gradle_test;
+    mod health_test;
+    mod hotspots_test;
     mod java_parser_test;
+    mod list_symbols_in_package_test;
     mod lombok_test;
+    mod outline_test;
+    mod prompts_test;
+    mod rename_impact_test;
+    mod resources_test;
+    mod search_symbols_test;
+    mod stale_index_test;
+    mod super_definition_test;
+    mod symbol_at_test;
+    mod symbol_module_test;
+    mod type_hierarchy_test;
+    mod unused_test;
 }
@@ -0,0 +1,77 @@
+use std::path::PathBuf;
+
+use kotlin_java_mcp::indexer::parser::index_files;
+use kotlin_java_mcp::indexer::symbols::{cross_reference, register_companion_aliases};
+use kotlin_java_mcp::tools::call_hierarchy::{format_incoming_calls, incoming_calls, outgoing_calls};
+
+fn fixture_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/sample-project")
+}
+
+fn build_index() -> kotlin_java_mcp::indexer::SymbolIndex {
+    let root = fixture_path();
+    let mut index = index_files(&root);
+    cross_reference(&mut index);
+    register_companion_aliases(&mut index);
+    index
+}
+
+#[test]
+fn test_incoming_calls_grouped_by_caller() {
+    let index = build_index();
+    let groups = incoming_calls(&index, "com.example.core.generateReport");
+
+    let caller = groups
+        .iter()
+        .find_map(|g| g.caller.filter(|c| c.name == "useTopLevel"))
+        .expect("Expected useTopLevel as a caller of generateReport");
+    assert_eq!(caller.fqn.as_deref(), Some("com.example.core.useTopLevel"));
+}
+
+#[test]
+fn test_incoming_calls_from_method_body() {
+    let index = build_index();
+    let groups = incoming_calls(&index, "com.example.core.UserService.Companion.generateId");
+
+    assert!(
+        groups.iter().any(|g| g
+            .caller
+            .is_some_and(|c| c.fqn.as_deref() == Some("com.example.core.UserService.createUser"))),
+        "Expected UserService.createUser to call generateId, got callers: {:?}",
+        groups
+            .iter()
+            .map(|g| g.caller.and_then(|c| c.fqn.clone()))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_outgoing_calls() {
+    let index = build_index();
+    let results = outgoing_calls(&index, "com.example.core.UserService.createUser");
+
+    let names: Vec<&str> = results.iter().map(|o| o.name.as_str()).collect();
+    assert!(names.contains(&"User"), "Expected User constructor call, got: {:?}", names);
+    assert!(names.contains(&"generateId"), "Expected generateId call, got: {:?}", names);
+    assert!(names.contains(&"save"), "Expected repository.save call, got: {:?}", names);
+    // Calls from other methods of the class are not included
+    assert!(!names.contains(&"findAll"), "Did not expect findAll, got: {:?}", names);
+}
+
+#[test]
+fn test_format_incoming_calls() {
+    let index = build_index();
+    let groups = incoming_calls(&index, "com.example.core.generateReport");
+    let output = format_incoming_calls(&groups, &fixture_path());
+
+    assert!(output.contains("caller(s)"), "Expected caller count header: {}", output);
+    assert!(output.contains("com.example.core.useTopLevel"), "Expected caller FQN: {}", output);
+    assert!(output.contains("ReportServiceImpl.kt"), "Expected call site location: {}", output);
+}
+
+#[test]
+fn test_incoming_calls_nonexistent() {
+    let index = build_index();
+    assert!(incoming_calls(&index, "com.example.DoesNotExist").is_empty());
+    assert!(outgoing_calls(&index, "com.example.DoesNotExist").is_empty());
+}
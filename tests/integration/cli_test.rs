@@ -33,6 +33,16 @@ fn test_cli_help() {
     assert!(stdout.contains("--project"), "Help should list --project flag");
 }
 
+#[test]
+fn test_cli_help_lists_transport_options() {
+    let output = run_cli(&["--help"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success());
+    assert!(stdout.contains("--transport"), "Help should list --transport flag");
+    assert!(stdout.contains("--port"), "Help should list --port flag");
+}
+
 #[test]
 fn test_cli_find_usages_help() {
     let output = run_cli(&["find-usages", "--help"]);
@@ -80,6 +90,26 @@ fn test_cli_find_usages_by_fqn() {
     assert!(stdout.contains("[com.example.core.User]"), "Expected FQN in output");
 }
 
+#[test]
+fn test_cli_find_usages_line_format() {
+    let fixture = fixture_path();
+    let output = run_cli(&["-p", fixture.to_str().unwrap(), "find-usages", "getUsername"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    // `user.getUsername()` in Java: location, byte range, kind, name, then the receiver
+    let line = stdout
+        .lines()
+        .find(|l| l.contains("LombokConsumer.java:7:"))
+        .unwrap_or_else(|| panic!("Expected the Java call site: {}", stdout));
+    assert!(
+        line.starts_with("  app/src/main/java/com/example/app/LombokConsumer.java:7:28 (bytes 163..174) - CallSite `getUsername`"),
+        "Unexpected layout: {}",
+        line
+    );
+    assert!(line.contains(" (receiver: user)"), "Expected the receiver: {}", line);
+}
+
 #[test]
 fn test_cli_find_usages_nonexistent() {
     let fixture = fixture_path();
@@ -106,6 +136,158 @@ fn test_cli_find_usages_with_file_and_line() {
     assert!(stdout.contains("Found"), "Expected results with file/line context: {}", stdout);
 }
 
+#[test]
+fn test_cli_find_usages_kind_filter() {
+    let fixture = fixture_path();
+    let output = run_cli(&[
+        "-p", fixture.to_str().unwrap(),
+        "find-usages", "User",
+        "--kind", "TypeReference",
+        "--format", "json",
+    ]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success(), "Expected success, got: {}", stdout);
+    let entries: Vec<serde_json::Value> = serde_json::from_str(&stdout).expect("Expected JSON array");
+    assert!(!entries.is_empty(), "Expected TypeReference usages of User");
+    assert!(
+        entries.iter().all(|e| e["kind"] == "TypeReference"),
+        "Expected only TypeReference occurrences, got: {}",
+        stdout
+    );
+}
+
+#[test]
+fn test_cli_find_usages_pagination() {
+    let fixture = fixture_path();
+    let run = |args: &[&str]| {
+        let mut full = vec!["-p", fixture.to_str().unwrap(), "find-usages", "User", "--format", "json"];
+        full.extend_from_slice(args);
+        let output = run_cli(&full);
+        assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).expect("Expected JSON output")
+    };
+
+    let all = run(&[]);
+    let all = all.as_array().expect("Expected a plain array without paging flags");
+    assert!(all.len() > 3, "Expected several usages of User");
+
+    let page = run(&["--limit", "2", "--offset", "1"]);
+    assert_eq!(page["total"], all.len());
+    assert_eq!(page["offset"], 1);
+    assert_eq!(page["results"].as_array().unwrap(), &all[1..3]);
+
+    let output = run_cli(&["-p", fixture.to_str().unwrap(), "find-usages", "User", "--limit", "2"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.starts_with(&format!("Showing 2 of {} result(s) (offset 0):", all.len())),
+        "Expected a paging header, got: {}",
+        stdout
+    );
+    assert!(stdout.contains("use offset 2 for the next page"), "Expected a next-page hint: {}", stdout);
+}
+
+#[test]
+fn test_cli_find_usages_scope() {
+    let fixture = fixture_path();
+    let output = run_cli(&[
+        "-p", fixture.to_str().unwrap(),
+        "find-usages", "com.example.core.User",
+        "--scope", "app",
+        "--format", "json",
+    ]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success(), "Expected success, got: {}", stdout);
+    let entries: Vec<serde_json::Value> = serde_json::from_str(&stdout).expect("Expected JSON array");
+    assert!(!entries.is_empty(), "Expected usages of User in app");
+    assert!(
+        entries.iter().all(|e| e["file"].as_str().unwrap().starts_with("app/")),
+        "Expected only app usages, got: {}",
+        stdout
+    );
+
+    let output = run_cli(&["-p", fixture.to_str().unwrap(), "find-usages", "User", "--scope", "nope"]);
+    assert!(!output.status.success(), "Expected failure for a missing scope");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Scope path not found"));
+}
+
+#[test]
+fn test_cli_find_usages_grouped_by_file() {
+    let fixture = fixture_path();
+    let project = fixture.to_str().unwrap();
+    let output = run_cli(&["-p", project, "find-usages", "com.example.core.User", "--group-by", "file"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success(), "Expected success, got: {}", stdout);
+    // Each file gets a header with its count, followed by its usages
+    let header = stdout
+        .lines()
+        .find(|l| l.starts_with("  core/src/main/kotlin/com/example/core/UserService.kt ("))
+        .unwrap_or_else(|| panic!("Expected a UserService.kt header, got: {}", stdout));
+    let count: usize = header
+        .trim_end_matches("):")
+        .rsplit('(')
+        .next()
+        .unwrap()
+        .parse()
+        .unwrap();
+    let listed = stdout
+        .lines()
+        .filter(|l| l.starts_with("    core/src/main/kotlin/com/example/core/UserService.kt:"))
+        .count();
+    assert_eq!(count, listed);
+
+    let output = run_cli(&["-p", project, "find-usages", "User", "--group-by", "module"]);
+    assert!(!output.status.success(), "Expected failure for an unknown grouping");
+}
+
+#[test]
+fn test_cli_find_usages_summary_matches_results() {
+    let fixture = fixture_path();
+    let project = fixture.to_str().unwrap();
+    let symbol = "com.example.core.User";
+
+    let output = run_cli(&["-p", project, "find-usages", symbol, "--summary", "--format", "json"]);
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).expect("Expected JSON");
+    let results = json["results"].as_array().expect("Expected a results array");
+    let summary = &json["summary"];
+
+    assert!(!results.is_empty(), "Expected usages of User");
+    assert_eq!(summary["total"], results.len());
+    let mut files: Vec<&str> = results.iter().map(|r| r["file"].as_str().unwrap()).collect();
+    files.sort();
+    files.dedup();
+    assert_eq!(summary["files"], files.len());
+    let kinds = summary["kinds"].as_object().expect("Expected counts per kind");
+    for (kind, count) in kinds {
+        let listed = results.iter().filter(|r| r["kind"] == kind.as_str()).count();
+        assert_eq!(count, &listed, "Count of {} in {}", kind, summary);
+    }
+    assert_eq!(kinds.values().map(|c| c.as_u64().unwrap()).sum::<u64>(), results.len() as u64);
+
+    // The text output starts with the same counts
+    let output = run_cli(&["-p", project, "find-usages", symbol, "--summary"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let first = stdout.lines().next().unwrap_or_default();
+    assert!(first.ends_with(&format!(" across {} files.", files.len())), "{}", stdout);
+    let type_references = kinds.get("TypeReference").and_then(|c| c.as_u64()).unwrap_or(0);
+    assert!(first.contains(&format!("{} type reference", type_references)), "{}", stdout);
+    assert_eq!(stdout.lines().nth(1), Some(format!("Found {} result(s):", results.len()).as_str()));
+}
+
+#[test]
+fn test_cli_find_usages_unknown_kind() {
+    let fixture = fixture_path();
+    let output = run_cli(&["-p", fixture.to_str().unwrap(), "find-usages", "User", "--kind", "Bogus"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(!output.status.success(), "Expected failure for unknown kind");
+    assert!(stderr.contains("Valid kinds:"), "Expected valid kinds in error, got: {}", stderr);
+    assert!(stderr.contains("CallSite"), "Expected CallSite among valid kinds, got: {}", stderr);
+}
+
 // ── find-definition ───────────────────────────────────────────────────
 
 #[test]
@@ -121,6 +303,33 @@ fn test_cli_find_definition_simple_symbol() {
     assert!(stdout.contains("[com.example.core.User]"), "Expected FQN in output");
 }
 
+#[test]
+fn test_cli_exclude_and_include_globs() {
+    let fixture = fixture_path();
+    let project = fixture.to_str().unwrap();
+
+    // User is declared in the core module
+    let output = run_cli(&["-p", project, "--exclude", "core", "find-definition", "User"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(stdout.contains("No results found"), "Expected core to be excluded: {}", stdout);
+
+    let output = run_cli(&["-p", project, "--include", "core/**", "find-definition", "User"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("[com.example.core.User]"), "Expected core to be included: {}", stdout);
+
+    let output = run_cli(&["-p", project, "--include", "feature", "find-definition", "User"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("No results found"), "Expected only feature to be indexed: {}", stdout);
+}
+
+#[test]
+fn test_cli_rejects_invalid_glob() {
+    let fixture = fixture_path();
+    let output = run_cli(&["-p", fixture.to_str().unwrap(), "--exclude", "[", "find-definition", "User"]);
+    assert!(!output.status.success());
+}
+
 #[test]
 fn test_cli_find_definition_by_fqn() {
     let fixture = fixture_path();
@@ -170,6 +379,153 @@ fn test_cli_find_definition_with_file_and_line() {
     assert!(stdout.contains("User.kt"), "Expected definition in User.kt");
 }
 
+// ── --context ─────────────────────────────────────────────────────────
+
+#[test]
+fn test_cli_find_usages_with_context() {
+    let fixture = fixture_path();
+    let output = run_cli(&["-p", fixture.to_str().unwrap(), "find-usages", "AUDIT_REASON", "--context", "1"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(stdout.contains("AuditedService.kt:8:10"), "Expected usage location: {}", stdout);
+    // The usage line, one line on either side, and a caret under column 10
+    assert!(stdout.contains("7 | \n"), "Expected line above the usage: {}", stdout);
+    assert!(stdout.contains("8 | @Audited(AUDIT_REASON)"), "Expected usage source line: {}", stdout);
+    assert!(stdout.contains("9 | class AuditedService {"), "Expected line below the usage: {}", stdout);
+    assert!(
+        stdout.contains(&format!("| {}^", " ".repeat(9))),
+        "Expected caret under column 10: {}",
+        stdout
+    );
+}
+
+// ── stats ─────────────────────────────────────────────────────────────
+
+#[test]
+fn test_cli_stats() {
+    let fixture = fixture_path();
+    let output = run_cli(&["-p", fixture.to_str().unwrap(), "stats"]);
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let stats: serde_json::Value = serde_json::from_slice(&output.stdout).expect("Expected a JSON object");
+
+    let total = stats["total_occurrences"].as_u64().expect("Expected total_occurrences");
+    let sum = |breakdown: &str| -> u64 {
+        stats[breakdown]
+            .as_object()
+            .unwrap_or_else(|| panic!("Expected {} to be an object", breakdown))
+            .values()
+            .map(|count| count.as_u64().unwrap())
+            .sum()
+    };
+    assert_eq!(sum("occurrences_by_kind"), total);
+    assert_eq!(sum("occurrences_by_file"), total);
+    assert!(stats["occurrences_by_kind"]["ClassDeclaration"].as_u64().unwrap() > 0);
+    assert!(stats["occurrences_by_file"]["core/src/main/kotlin/com/example/core/User.kt"].as_u64().unwrap() > 0);
+    assert!(stats["unresolved_references"].as_u64().unwrap() < total);
+}
+
+#[test]
+fn test_cli_hotspots() {
+    let fixture = fixture_path();
+    let output = run_cli(&["-p", fixture.to_str().unwrap(), "hotspots", "--limit", "5"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(stdout.starts_with("Top 5 most-referenced declaration(s):"), "{}", stdout);
+    assert!(stdout.contains("[com.example.core.User]"), "Expected User among hotspots:\n{}", stdout);
+}
+
+#[test]
+fn test_cli_quiet_prints_only_results() {
+    let fixture = fixture_path();
+    let args = ["-p", fixture.to_str().unwrap(), "find-definition", "com.example.core.User"];
+
+    let output = run_cli(&args);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Indexing Kotlin and Java files"), "Expected progress: {}", stderr);
+
+    let quiet = run_cli(&[&["-q"][..], &args[..]].concat());
+    let stdout = String::from_utf8_lossy(&quiet.stdout);
+    assert!(quiet.status.success(), "stderr: {}", String::from_utf8_lossy(&quiet.stderr));
+    assert!(quiet.stderr.is_empty(), "Expected no stderr: {}", String::from_utf8_lossy(&quiet.stderr));
+    assert_eq!(stdout, String::from_utf8_lossy(&output.stdout));
+    assert!(stdout.contains("User.kt"), "{}", stdout);
+
+    // --quiet and -v contradict each other
+    assert!(!run_cli(&[&["--quiet", "-v"][..], &args[..]].concat()).status.success());
+}
+
+// ── --format json ─────────────────────────────────────────────────────
+
+#[test]
+fn test_cli_find_usages_json_format() {
+    let fixture = fixture_path();
+    let output = run_cli(&["-p", fixture.to_str().unwrap(), "find-usages", "com.example.core.User", "--format", "json"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+
+    // Schema: every entry carries the full set of fields, optional ones as null
+    let raw: Vec<serde_json::Value> = serde_json::from_str(&stdout).expect("Expected a JSON array");
+    assert!(!raw.is_empty(), "Expected usages of User");
+    for entry in &raw {
+        for field in [
+            "name", "fqn", "kind", "file", "line", "column", "byte_start", "byte_end", "receiver_type",
+        ] {
+            assert!(entry.get(field).is_some(), "Missing field `{}` in {}", field, entry);
+        }
+    }
+
+    let entries: Vec<kotlin_java_mcp::tools::OccurrenceJson> =
+        serde_json::from_str(&stdout).expect("Expected JSON to deserialize into OccurrenceJson");
+    assert!(entries.iter().all(|e| e.fqn.as_deref() == Some("com.example.core.User")));
+    assert!(entries.iter().all(|e| !e.file.starts_with('/')), "Expected paths relative to the project root");
+    assert!(entries.iter().all(|e| e.byte_start < e.byte_end));
+}
+
+#[test]
+fn test_cli_find_definition_json_format() {
+    let fixture = fixture_path();
+    let output = run_cli(&["-p", fixture.to_str().unwrap(), "find-definition", "User", "--format", "json"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    let entries: Vec<kotlin_java_mcp::tools::OccurrenceJson> =
+        serde_json::from_str(&stdout).expect("Expected JSON to deserialize into OccurrenceJson");
+    let class_decl = entries
+        .iter()
+        .find(|e| e.kind == "ClassDeclaration")
+        .expect("Expected a ClassDeclaration entry");
+    assert_eq!(class_decl.file, "core/src/main/kotlin/com/example/core/User.kt");
+    assert_eq!(class_decl.line, 3);
+}
+
+#[test]
+fn test_cli_json_format_no_results() {
+    let fixture = fixture_path();
+    let output = run_cli(&["-p", fixture.to_str().unwrap(), "find-usages", "DoesNotExist", "--format", "json"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success());
+    assert_eq!(stdout.trim(), "[]");
+}
+
+// ── find-implementations ──────────────────────────────────────────────
+
+#[test]
+fn test_cli_find_implementations_sealed_class() {
+    let fixture = fixture_path();
+    let output = run_cli(&["-p", fixture.to_str().unwrap(), "find-implementations", "Result"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(stdout.contains("[com.example.core.Result.Success]"), "Expected Success subtype: {}", stdout);
+    assert!(stdout.contains("[com.example.core.Result.Failure]"), "Expected Failure subtype: {}", stdout);
+    assert!(stdout.contains("[com.example.core.Result.Loading]"), "Expected Loading subtype: {}", stdout);
+    assert!(stdout.contains("[com.example.app.Cancelled]"), "Expected Cancelled subtype: {}", stdout);
+}
+
 // ── indexing output on stderr ─────────────────────────────────────────
 
 #[test]
@@ -199,3 +555,116 @@ fn test_cli_missing_symbol_argument() {
 
     assert!(!output.status.success(), "Expected failure when symbol argument is missing");
 }
+
+// ── tcp transport ─────────────────────────────────────────────────────
+
+#[test]
+fn test_tcp_transport_initialize() {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::time::{Duration, Instant};
+
+    let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let fixture = fixture_path();
+    let mut child = Command::new(binary_path())
+        .args(["-p", fixture.to_str().unwrap(), "--transport", "tcp", "--port", &port.to_string()])
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .expect("Failed to start server");
+
+    // Indexing runs in the background, so the listener is up as soon as the process starts
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let stream = loop {
+        match TcpStream::connect(("127.0.0.1", port)) {
+            Ok(stream) => break Some(stream),
+            Err(_) if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(100)),
+            Err(_) => break None,
+        }
+    };
+    let mut stream = match stream {
+        Some(stream) => stream,
+        None => {
+            child.kill().ok();
+            panic!("Server did not accept TCP connections on port {}", port);
+        }
+    };
+    stream.set_read_timeout(Some(Duration::from_secs(10))).unwrap();
+
+    let request = r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2024-11-05","capabilities":{},"clientInfo":{"name":"cli-test","version":"0.0.0"}}}"#;
+    writeln!(stream, "{}", request).unwrap();
+
+    let mut response = String::new();
+    let read = BufReader::new(&stream).read_line(&mut response);
+    child.kill().ok();
+    child.wait().ok();
+
+    read.expect("Failed to read initialize response");
+    let json: serde_json::Value = serde_json::from_str(&response).expect("Expected a JSON-RPC response");
+    assert_eq!(json["id"], 1);
+    assert_eq!(json["result"]["serverInfo"]["name"], "kotlin-java-mcp");
+}
+
+// ── json-rpc log ──────────────────────────────────────────────────────
+
+#[test]
+fn test_json_rpc_log_records_handshake() {
+    use std::io::{BufRead, BufReader, Write};
+    use std::process::Stdio;
+
+    let dir = tempfile::tempdir().unwrap();
+    let log_path = dir.path().join("rpc.log");
+    let fixture = fixture_path();
+    let mut child = Command::new(binary_path())
+        .args(["-p", fixture.to_str().unwrap(), "--json-rpc-log", log_path.to_str().unwrap()])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("Failed to start server");
+
+    let request = r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2024-11-05","capabilities":{},"clientInfo":{"name":"cli-test","version":"0.0.0"}}}"#;
+    writeln!(child.stdin.as_mut().unwrap(), "{}", request).unwrap();
+
+    let mut response = String::new();
+    let read = BufReader::new(child.stdout.as_mut().unwrap()).read_line(&mut response);
+    child.kill().ok();
+    child.wait().ok();
+
+    // stdout carries nothing but the protocol
+    read.expect("Failed to read initialize response");
+    let json: serde_json::Value = serde_json::from_str(&response).expect("Expected a JSON-RPC response");
+    assert_eq!(json["id"], 1);
+
+    let log = std::fs::read_to_string(&log_path).expect("Expected the JSON-RPC log to be created");
+    assert!(
+        log.lines().any(|line| line.contains(" <- ") && line.contains(r#""method":"initialize""#)),
+        "Log should record the incoming initialize request:\n{}",
+        log
+    );
+    assert!(
+        log.lines().any(|line| line.contains(" -> ") && line.contains("serverInfo")),
+        "Log should record the outgoing initialize response:\n{}",
+        log
+    );
+}
+
+#[test]
+fn test_json_rpc_log_rejected_with_sse() {
+    let dir = tempfile::tempdir().unwrap();
+    let log_path = dir.path().join("rpc.log");
+    let fixture = fixture_path();
+    let output = run_cli(&[
+        "-p",
+        fixture.to_str().unwrap(),
+        "--transport",
+        "sse",
+        "--port",
+        "0",
+        "--json-rpc-log",
+        log_path.to_str().unwrap(),
+    ]);
+    assert!(!output.status.success(), "Expected --json-rpc-log to be rejected with sse");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--json-rpc-log"));
+    assert!(!log_path.exists(), "No log file should be created");
+}
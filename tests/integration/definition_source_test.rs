@@ -0,0 +1,66 @@
+use std::path::PathBuf;
+
+use kotlin_java_mcp::indexer::parser::index_files;
+use kotlin_java_mcp::indexer::symbols::{cross_reference, register_companion_aliases};
+use kotlin_java_mcp::tools::definition_source::{definition_sources, format_definition_sources};
+
+fn fixture_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/sample-project")
+}
+
+fn build_index() -> kotlin_java_mcp::indexer::SymbolIndex {
+    let root = fixture_path();
+    let mut index = index_files(&root);
+    cross_reference(&mut index);
+    register_companion_aliases(&mut index);
+    index
+}
+
+#[test]
+fn test_definition_source_of_class_spans_its_body() {
+    let index = build_index();
+    let sources = definition_sources(&index, "com.example.core.UserService", None, None);
+
+    assert_eq!(sources.len(), 1);
+    let source = sources[0].source.as_deref().expect("Expected the source of UserService");
+    assert!(source.starts_with("class User"), "Got: {}", source);
+    assert!(source.contains("fun createUser("));
+    assert!(source.ends_with("}"));
+}
+
+#[test]
+fn test_definition_source_of_function() {
+    let index = build_index();
+    let sources = definition_sources(&index, "com.example.core.UserService.getUser", None, None);
+
+    assert_eq!(sources.len(), 1);
+    assert_eq!(
+        sources[0].source.as_deref(),
+        Some("fun getUser(id: UserId): User? {\n        return repository.findById(id)\n    }")
+    );
+}
+
+#[test]
+fn test_definition_source_returns_each_match() {
+    let index = build_index();
+    let sources = definition_sources(&index, "User", None, None);
+
+    let data_class = sources
+        .iter()
+        .find(|s| s.declaration.file.ends_with("core/User.kt"))
+        .expect("Expected the User data class");
+    assert!(data_class.source.as_deref().unwrap().starts_with("data class User("));
+
+    let output = format_definition_sources(&sources, &fixture_path());
+    assert!(output.starts_with(&format!("Found {} definition(s):", sources.len())));
+    assert!(output.contains("```kotlin\ndata class User("));
+}
+
+#[test]
+fn test_definition_source_not_found() {
+    let index = build_index();
+    let sources = definition_sources(&index, "NoSuchSymbol", None, None);
+
+    assert!(sources.is_empty());
+    assert_eq!(format_definition_sources(&sources, &fixture_path()), "No definitions found.");
+}
@@ -0,0 +1,73 @@
+use std::path::PathBuf;
+
+use kotlin_java_mcp::indexer::parser::index_files;
+use kotlin_java_mcp::indexer::symbols::{cross_reference, register_companion_aliases};
+use kotlin_java_mcp::tools::file_references::{file_references, format_file_references, ReferenceGroup};
+
+fn fixture_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/sample-project")
+}
+
+fn build_index() -> kotlin_java_mcp::indexer::SymbolIndex {
+    let root = fixture_path();
+    let mut index = index_files(&root);
+    cross_reference(&mut index);
+    register_companion_aliases(&mut index);
+    index
+}
+
+fn group<'a>(groups: &'a [ReferenceGroup<'a>], fqn: &str) -> &'a ReferenceGroup<'a> {
+    groups
+        .iter()
+        .find(|g| g.fqn == Some(fqn))
+        .unwrap_or_else(|| panic!("Expected references to `{}`", fqn))
+}
+
+#[test]
+fn test_file_references_grouped_by_fqn() {
+    let index = build_index();
+    let file = fixture_path().join("core/src/main/kotlin/com/example/core/UserService.kt");
+    let groups = file_references(&index, &file);
+
+    let user = group(&groups, "com.example.core.User");
+    let lines: Vec<usize> = user.occurrences.iter().map(|o| o.line).collect();
+    for line in [3, 5, 9, 13, 14] {
+        assert!(lines.contains(&line), "Expected `User` on line {}, got {:?}", line, lines);
+    }
+
+    let repository = group(&groups, "com.example.core.Repository");
+    assert!(repository.occurrences.iter().all(|o| o.line == 3));
+    group(&groups, "com.example.core.UserRole");
+
+    // Only references; the file's own declarations belong to the outline
+    assert!(groups
+        .iter()
+        .flat_map(|g| &g.occurrences)
+        .all(|o| o.kind.is_reference()));
+
+    // Most referenced first
+    let counts: Vec<usize> = groups.iter().map(|g| g.occurrences.len()).collect();
+    assert!(counts.windows(2).all(|w| w[0] >= w[1]), "Expected descending counts, got {:?}", counts);
+}
+
+#[test]
+fn test_format_file_references() {
+    let index = build_index();
+    let root = fixture_path();
+    let output = format_file_references(&index, &root.join("Missing.kt"), &root);
+    assert!(output.starts_with("File not indexed"), "{}", output);
+
+    let file = root.join("core/src/main/kotlin/com/example/core/UserService.kt");
+    let output = format_file_references(&index, &file, &root);
+    assert!(
+        output.contains("symbol(s) in core/src/main/kotlin/com/example/core/UserService.kt:"),
+        "{}",
+        output
+    );
+    assert!(
+        output.contains("  com.example.core.Repository (1) - TypeReference; line(s) 3"),
+        "{}",
+        output
+    );
+    assert!(output.contains("  com.example.core.User ("), "{}", output);
+}
@@ -2,8 +2,13 @@ use std::path::PathBuf;
 
 use kotlin_java_mcp::indexer::parser::index_files;
 use kotlin_java_mcp::indexer::symbols::{cross_reference, register_companion_aliases};
-use kotlin_java_mcp::indexer::SymbolKind;
-use kotlin_java_mcp::tools::find_definition::find_definition;
+use kotlin_java_mcp::indexer::{PlatformModifier, SymbolKind};
+use kotlin_java_mcp::indexer::symbols::{kotlin_stdlib_fqn, KOTLIN_STDLIB_SYMBOLS};
+use kotlin_java_mcp::tools::find_definition::{
+    find_definition, find_definition_matching, find_definitions_batch, find_stdlib_definition,
+    format_definitions_batch_json, format_stdlib_definition,
+};
+use kotlin_java_mcp::tools::{format_occurrence, NameMatch};
 
 fn fixture_path() -> PathBuf {
     PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/sample-project")
@@ -109,6 +114,49 @@ fn test_find_definition_of_config_object() {
     );
 }
 
+#[test]
+fn test_find_definition_of_annotation_classes() {
+    let index = build_index();
+
+    let kotlin = find_definition(&index, "com.example.core.RequiresPermission", None, None);
+    assert_eq!(kotlin.len(), 1, "{:?}", kotlin);
+    assert_eq!(kotlin[0].kind, SymbolKind::AnnotationClassDeclaration);
+
+    // Java's `@interface` keeps its own kind
+    let java = find_definition(&index, "com.example.core.Traced", None, None);
+    assert_eq!(java[0].kind, SymbolKind::AnnotationTypeDeclaration);
+}
+
+#[test]
+fn test_find_definition_ranks_the_visible_config_first() {
+    let index = build_index();
+    let fqns = |file: Option<&std::path::Path>| {
+        find_definition(&index, "Config", file, None)
+            .iter()
+            .filter_map(|o| o.fqn.clone())
+            .collect::<Vec<_>>()
+    };
+
+    // Without context, types come first in file order; the order is stable
+    let unranked = fqns(None);
+    assert_eq!(
+        &unranked[..3],
+        ["com.example.app.Config", "com.example.core.Config", "com.example.feature.Config"]
+    );
+    assert_eq!(fqns(None), unranked);
+
+    // ReferencePatterns.kt (package com.example.app) imports com.example.core.Config,
+    // which shadows the Config of its own package
+    let patterns = fixture_path().join("app/src/main/kotlin/com/example/app/ReferencePatterns.kt");
+    let ranked = fqns(Some(&patterns));
+    assert_eq!(ranked[0], "com.example.core.Config");
+    assert_eq!(ranked.len(), unranked.len(), "Every declaration is still returned");
+
+    // FeatureFlags.kt sees the Config of its own package
+    let flags = fixture_path().join("feature/src/main/kotlin/com/example/feature/FeatureFlags.kt");
+    assert_eq!(fqns(Some(&flags))[0], "com.example.feature.Config");
+}
+
 #[test]
 fn test_find_definition_of_extension_function() {
     let index = build_index();
@@ -163,3 +211,533 @@ fn test_find_definition_nonexistent() {
     let results = find_definition(&index, "DoesNotExist", None, None);
     assert!(results.is_empty());
 }
+
+// --- Kotlin standard library fallback ---
+
+#[test]
+fn test_stdlib_symbols_cover_collection_builders() {
+    // The curated table: kotlin.collections builders only
+    let covered: Vec<&str> = KOTLIN_STDLIB_SYMBOLS
+        .iter()
+        .map(|fqn| fqn.rsplit('.').next().unwrap())
+        .collect();
+    assert_eq!(
+        covered,
+        vec![
+            "listOf", "listOfNotNull", "mutableListOf", "arrayListOf", "emptyList", "buildList",
+            "setOf", "mutableSetOf", "hashSetOf", "linkedSetOf", "emptySet", "buildSet",
+            "mapOf", "mutableMapOf", "hashMapOf", "linkedMapOf", "emptyMap", "buildMap",
+        ]
+    );
+    assert!(KOTLIN_STDLIB_SYMBOLS
+        .iter()
+        .all(|fqn| fqn.starts_with("kotlin.collections.")));
+
+    assert_eq!(kotlin_stdlib_fqn("mapOf"), Some("kotlin.collections.mapOf"));
+    assert_eq!(kotlin_stdlib_fqn("kotlin.collections.setOf"), Some("kotlin.collections.setOf"));
+    assert_eq!(kotlin_stdlib_fqn("println"), None);
+    assert_eq!(kotlin_stdlib_fqn("com.example.listOf"), None);
+}
+
+#[test]
+fn test_find_definition_falls_back_to_stdlib() {
+    let index = build_index();
+    let root = fixture_path();
+
+    // `listOf` is called in ReferencePatterns.kt without an import or project declaration
+    let file = root.join("app/src/main/kotlin/com/example/app/ReferencePatterns.kt");
+    assert!(find_definition(&index, "listOf", Some(&file), Some(19)).is_empty());
+    let fqn = find_stdlib_definition(&index, "listOf");
+    assert_eq!(fqn, Some("kotlin.collections.listOf"));
+    assert_eq!(
+        format_stdlib_definition("listOf", fqn.unwrap()),
+        "No declaration of `listOf` in the project. It resolves to the Kotlin standard library: kotlin.collections.listOf"
+    );
+
+    // Project declarations and unknown names get no fallback
+    assert_eq!(find_stdlib_definition(&index, "createUser"), None);
+    assert_eq!(find_stdlib_definition(&index, "DoesNotExist"), None);
+}
+
+// --- Companion object members ---
+
+#[test]
+fn test_find_definition_companion_member_via_class_receiver() {
+    let index = build_index();
+    // `Config.load()` in FeatureFlags.kt calls the function in Config's companion object
+    let flags = fixture_path().join("feature/src/main/kotlin/com/example/feature/FeatureFlags.kt");
+    let results = find_definition(&index, "load", Some(&flags), Some(4));
+
+    assert_eq!(results.len(), 1, "Expected exactly one definition, got: {:?}", results);
+    let decl = results[0];
+    assert_eq!(decl.kind, SymbolKind::FunctionDeclaration);
+    assert_eq!(decl.fqn.as_deref(), Some("com.example.feature.Config.Companion.load"));
+    assert_eq!(decl.file.file_name().unwrap().to_str().unwrap(), "Config.kt");
+    assert_eq!(decl.line, 7);
+}
+
+#[test]
+fn test_companion_member_reference_fqn() {
+    let index = build_index();
+    let call = index
+        .by_name
+        .get("load")
+        .and_then(|occs| {
+            occs.iter().find(|o| {
+                o.kind == SymbolKind::CallSite
+                    && o.file.file_name().unwrap().to_str().unwrap() == "FeatureFlags.kt"
+            })
+        })
+        .expect("Expected the Config.load() call site");
+    assert_eq!(call.fqn.as_deref(), Some("com.example.feature.Config.Companion.load"));
+    assert_eq!(call.receiver_type.as_deref(), Some("Config"));
+}
+
+#[test]
+fn test_named_companion_members_resolve_in_both_forms() {
+    let index = build_index();
+    let named = "com.example.core.Connection.Factory.create";
+
+    // Declared as `companion object Factory`, reachable as `Connection.Factory.create`
+    // and as `Connection.create`
+    for symbol in [named, "com.example.core.Connection.create"] {
+        let results = find_definition(&index, symbol, None, None);
+        assert_eq!(results.len(), 1, "Expected one definition of {}, got: {:?}", symbol, results);
+        assert_eq!(results[0].kind, SymbolKind::FunctionDeclaration);
+        assert_eq!(results[0].fqn.as_deref(), Some(named));
+    }
+
+    // Both call forms resolve to the named companion member
+    let mut calls: Vec<(usize, Option<&str>)> = index
+        .by_name
+        .get("create")
+        .map(|occs| {
+            occs.iter()
+                .filter(|o| o.kind == SymbolKind::CallSite && o.file.ends_with("ConnectionUsage.kt"))
+                .map(|o| (o.line, o.fqn.as_deref()))
+                .collect()
+        })
+        .unwrap_or_default();
+    calls.sort();
+    assert_eq!(calls, vec![(6, Some(named)), (7, Some(named))]);
+}
+
+#[test]
+fn test_find_definition_lists_overloads_with_signatures() {
+    let index = build_index();
+    let root = fixture_path();
+
+    let results = find_definition(&index, "com.example.core.process", None, None);
+    let signatures: Vec<Option<&str>> = results.iter().map(|o| o.signature.as_deref()).collect();
+    assert_eq!(
+        signatures,
+        vec![Some("(Int)"), Some("(String)"), Some("(List<String>, String)")],
+        "Expected each overload with its parameter types, got: {:?}",
+        results
+    );
+    assert!(results
+        .iter()
+        .all(|o| o.fqn.as_deref() == Some("com.example.core.process")));
+    assert!(
+        format_occurrence(results[1], &root).contains("FunctionDeclaration `process(String)`"),
+        "{}",
+        format_occurrence(results[1], &root)
+    );
+
+    // Parameter types pick one overload, with or without the package
+    for symbol in ["com.example.core.process(String)", "process( String )"] {
+        let results = find_definition(&index, symbol, None, None);
+        assert_eq!(results.len(), 1, "Expected one definition of {}, got: {:?}", symbol, results);
+        assert_eq!(results[0].line, 5);
+    }
+    assert!(find_definition(&index, "process(Long)", None, None).is_empty());
+}
+
+// --- Accessor-style calls on Kotlin properties ---
+
+#[test]
+fn test_find_definition_getter_resolves_to_property() {
+    let index = build_index();
+    let results = find_definition(&index, "getBalance", None, None);
+
+    let prop = results
+        .iter()
+        .find(|o| matches!(o.kind, SymbolKind::PropertyDeclaration))
+        .expect("Expected getBalance to resolve to the balance property");
+    assert_eq!(prop.fqn.as_deref(), Some("com.example.core.Account.balance"));
+}
+
+#[test]
+fn test_find_definition_setter_by_fqn_stays_in_class_scope() {
+    let index = build_index();
+
+    let results = find_definition(&index, "com.example.core.Account.setUserName", None, None);
+    assert_eq!(results.len(), 1, "Expected exactly the userName property");
+    assert_eq!(results[0].fqn.as_deref(), Some("com.example.core.Account.userName"));
+
+    // Same accessor on an unrelated class must not resolve to Account.userName
+    let results = find_definition(&index, "com.example.core.User.setUserName", None, None);
+    assert!(results.is_empty(), "Expected no property outside the accessor's class");
+}
+
+#[test]
+fn test_find_definition_setter_never_maps_to_val() {
+    let index = build_index();
+    // `balance` is a `val`: it has a getter but no setter
+    assert!(find_definition(&index, "setBalance", None, None).is_empty());
+    assert!(find_definition(&index, "com.example.core.Account.setBalance", None, None).is_empty());
+}
+
+#[test]
+fn test_find_definition_getter_resolves_to_constructor_property() {
+    let index = build_index();
+    let results = find_definition(&index, "getName", None, None);
+    assert!(
+        results
+            .iter()
+            .any(|o| o.fqn.as_deref() == Some("com.example.core.User.name")),
+        "Expected getName to resolve to the User.name constructor property, got: {:?}",
+        results.iter().map(|o| o.fqn.clone()).collect::<Vec<_>>()
+    );
+
+    let results = find_definition(&index, "com.example.core.User.getName", None, None);
+    assert_eq!(results.len(), 1, "Expected exactly the name property, got: {:?}", results);
+    assert_eq!(results[0].fqn.as_deref(), Some("com.example.core.User.name"));
+    // `name` is a `val` parameter
+    assert!(find_definition(&index, "com.example.core.User.setName", None, None).is_empty());
+}
+
+#[test]
+fn test_find_definition_is_property_accessors() {
+    let index = build_index();
+    // The getter of `var isLocked` is `isLocked()` and its setter `setLocked()`
+    for symbol in ["isLocked", "setLocked", "com.example.core.Account.setLocked"] {
+        let results = find_definition(&index, symbol, None, None);
+        assert_eq!(results.len(), 1, "Expected one definition of {}, got: {:?}", symbol, results);
+        assert_eq!(results[0].fqn.as_deref(), Some("com.example.core.Account.isLocked"));
+    }
+}
+
+#[test]
+fn test_find_definition_setter_from_call_site() {
+    let index = build_index();
+    let file = fixture_path().join("app/src/main/kotlin/com/example/app/AccountUsage.kt");
+    let results = find_definition(&index, "setUserName", Some(&file), Some(7));
+
+    assert!(
+        results
+            .iter()
+            .any(|o| o.fqn.as_deref() == Some("com.example.core.Account.userName")),
+        "Expected setUserName call to resolve to Account.userName, got: {:?}",
+        results.iter().map(|o| o.fqn.clone()).collect::<Vec<_>>()
+    );
+}
+
+// --- Kotlin scripts ---
+
+#[test]
+fn test_find_definition_in_script_without_package() {
+    let index = build_index();
+    let results = find_definition(&index, "summarize", None, None);
+
+    let decl = results
+        .iter()
+        .find(|o| o.kind == SymbolKind::FunctionDeclaration)
+        .expect("Expected summarize to be declared in report.main.kts");
+    assert_eq!(decl.file.file_name().unwrap().to_str().unwrap(), "report.main.kts");
+    // No package header: the FQN is in the file's synthetic default package
+    assert_eq!(decl.fqn.as_deref(), Some("<default@scripts/report>.summarize"));
+}
+
+#[test]
+fn test_find_definition_of_nested_object_constants() {
+    let index = build_index();
+    let root = fixture_path();
+    let settings = root.join("app/src/main/kotlin/com/example/app/HttpSettings.kt");
+    let declaration = |results: Vec<&kotlin_java_mcp::indexer::SymbolOccurrence>| {
+        results
+            .iter()
+            .map(|o| (o.fqn.clone().unwrap(), o.line))
+            .collect::<Vec<_>>()
+    };
+    let timeout = vec![("com.example.core.AppConfig.Network.TIMEOUT".to_string(), 7)];
+    let max_attempts =
+        vec![("com.example.core.AppConfig.Network.Retry.MAX_ATTEMPTS".to_string(), 10)];
+
+    // Each reference in a chain resolves through the object it's nested in
+    let fqns_in = |name: &str, file: &str| -> Vec<String> {
+        index.by_name[name]
+            .iter()
+            .filter(|o| o.kind.is_reference() && o.file.ends_with(file))
+            .filter_map(|o| o.fqn.clone())
+            .collect()
+    };
+    assert_eq!(
+        fqns_in("TIMEOUT", "HttpSettings.kt"),
+        vec!["com.example.core.AppConfig.Network.TIMEOUT"; 2]
+    );
+    assert_eq!(
+        fqns_in("Retry", "HttpSettings.kt"),
+        vec!["com.example.core.AppConfig.Network.Retry"]
+    );
+    assert_eq!(
+        fqns_in("TIMEOUT", "AppConfig.kt"),
+        vec!["com.example.core.AppConfig.Network.TIMEOUT"]
+    );
+
+    assert_eq!(
+        declaration(find_definition(&index, "TIMEOUT", Some(&settings), Some(6))),
+        timeout
+    );
+    assert_eq!(
+        declaration(find_definition(&index, "MAX_ATTEMPTS", Some(&settings), Some(7))),
+        max_attempts
+    );
+
+    // Chains qualified by a type instead of a package
+    assert_eq!(
+        declaration(find_definition(&index, "AppConfig.Network.TIMEOUT", None, None)),
+        timeout
+    );
+    assert_eq!(
+        declaration(find_definition(
+            &index,
+            "AppConfig.Network.TIMEOUT",
+            Some(&settings),
+            Some(6)
+        )),
+        timeout
+    );
+    assert_eq!(
+        declaration(find_definition(&index, "Network.Retry.MAX_ATTEMPTS", None, None)),
+        max_attempts
+    );
+    assert!(find_definition(&index, "AppConfig.Network.MISSING", None, None).is_empty());
+}
+
+// --- Same-name declarations ---
+
+fn ambiguous_fixture_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/ambiguous-names")
+}
+
+#[test]
+fn test_find_definition_disambiguates_by_file_context() {
+    let root = ambiguous_fixture_path();
+    let mut index = index_files(&root);
+    cross_reference(&mut index);
+    register_companion_aliases(&mut index);
+    let sources = root.join("src/main/kotlin/com/example");
+    let class_fqns = |results: Vec<&kotlin_java_mcp::indexer::SymbolOccurrence>| {
+        results
+            .iter()
+            .filter(|o| o.kind == SymbolKind::ClassDeclaration)
+            .filter_map(|o| o.fqn.clone())
+            .collect::<Vec<_>>()
+    };
+
+    // Without context, both classes match
+    assert_eq!(
+        class_fqns(find_definition(&index, "User", None, None)),
+        vec!["com.example.accounts.User", "com.example.billing.User"]
+    );
+
+    // Lines without an indexed reference (here, comments) resolve through the file's
+    // package and imports instead of returning every candidate
+    let invoice = sources.join("billing/Invoice.kt");
+    assert_eq!(
+        class_fqns(find_definition(&index, "User", Some(&invoice), Some(3))),
+        vec!["com.example.billing.User"]
+    );
+    assert_eq!(
+        class_fqns(find_definition(&index, "User", Some(&invoice), Some(4))),
+        vec!["com.example.billing.User"]
+    );
+    let report = sources.join("reports/Report.kt");
+    assert_eq!(
+        class_fqns(find_definition(&index, "User", Some(&report), Some(5))),
+        vec!["com.example.accounts.User"]
+    );
+}
+
+#[test]
+fn test_find_definition_distinguishes_default_package_files() {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/default-package");
+    let mut index = index_files(&root);
+    cross_reference(&mut index);
+    register_companion_aliases(&mut index);
+    let class_fqns = |results: Vec<&kotlin_java_mcp::indexer::SymbolOccurrence>| {
+        results
+            .iter()
+            .filter(|o| o.kind == SymbolKind::ClassDeclaration)
+            .filter_map(|o| o.fqn.clone())
+            .collect::<Vec<_>>()
+    };
+
+    // Neither file has a package header; the simple name still finds both
+    assert_eq!(
+        class_fqns(find_definition(&index, "Clock", None, None)),
+        vec!["<default@legacy/Clock>.Clock", "<default@tools/Clock>.Clock"]
+    );
+    let legacy = find_definition(&index, "<default@legacy/Clock>.Clock", None, None);
+    assert_eq!(legacy.len(), 1);
+    assert_eq!(legacy[0].file, root.join("legacy/Clock.kt"));
+
+    // A file's references to its own declarations stay in its package
+    let tools_clock = root.join("tools/Clock.kt");
+    assert_eq!(
+        class_fqns(find_definition(&index, "Clock", Some(&tools_clock), Some(5))),
+        vec!["<default@tools/Clock>.Clock"]
+    );
+
+    // Other files without a package header share the default package
+    let timing = root.join("legacy/Timing.kt");
+    let stopwatch = index.by_name["Stopwatch"]
+        .iter()
+        .find(|o| o.file == timing && o.kind == SymbolKind::TypeReference)
+        .expect("Expected the Stopwatch parameter type");
+    assert_eq!(stopwatch.fqn.as_deref(), Some("<default@tools/Stopwatch>.Stopwatch"));
+}
+
+#[test]
+fn test_find_definitions_batch() {
+    let index = build_index();
+    let root = fixture_path();
+    let flags = root.join("feature/src/main/kotlin/com/example/feature/FeatureFlags.kt");
+    let queries = [
+        ("com.example.core.Repository", None, None),
+        ("load", Some(flags.as_path()), Some(4)),
+        ("DoesNotExist", None, None),
+    ];
+    let results = find_definitions_batch(&index, &queries);
+    assert_eq!(results.len(), 3);
+
+    let fqns = |i: usize| -> Vec<&str> {
+        results[i].iter().filter_map(|o| o.fqn.as_deref()).collect()
+    };
+    assert_eq!(fqns(0), vec!["com.example.core.Repository"]);
+    // Each entry keeps its own context: `Config.load()` in FeatureFlags.kt
+    assert_eq!(fqns(1), vec!["com.example.feature.Config.Companion.load"]);
+    assert!(results[2].is_empty());
+
+    let keyed: Vec<(String, Vec<&kotlin_java_mcp::indexer::SymbolOccurrence>)> = [
+        "com.example.core.Repository",
+        "load @ feature/src/main/kotlin/com/example/feature/FeatureFlags.kt:4",
+        "DoesNotExist",
+    ]
+    .iter()
+    .map(|key| key.to_string())
+    .zip(results)
+    .collect();
+    let json: serde_json::Value =
+        serde_json::from_str(&format_definitions_batch_json(&keyed, &root)).unwrap();
+    let map = json.as_object().unwrap();
+    assert_eq!(map.len(), 3);
+    assert_eq!(
+        map["com.example.core.Repository"][0]["file"],
+        "core/src/main/kotlin/com/example/core/Repository.kt"
+    );
+    assert_eq!(map["com.example.core.Repository"][0]["line"], 3);
+    assert_eq!(map["DoesNotExist"], serde_json::json!([]));
+}
+
+#[test]
+fn test_find_definition_prefix_and_case_insensitive_match() {
+    let index = build_index();
+    let class_fqns = |results: &[&kotlin_java_mcp::indexer::SymbolOccurrence]| -> Vec<String> {
+        results
+            .iter()
+            .filter(|o| o.kind.is_declaration())
+            .filter_map(|o| o.fqn.clone())
+            .collect()
+    };
+
+    let prefixed = find_definition_matching(&index, "User", None, None, NameMatch::Prefix);
+    let fqns = class_fqns(&prefixed);
+    for expected in [
+        "com.example.core.User",
+        "com.example.core.UserService",
+        "com.example.core.UserRole",
+        "com.example.feature.UserProfile",
+    ] {
+        assert!(fqns.iter().any(|f| f == expected), "Expected {} in {:?}", expected, fqns);
+    }
+    assert!(prefixed.iter().all(|o| o.name.starts_with("User")));
+
+    // A qualified prefix matches against FQNs
+    let qualified =
+        find_definition_matching(&index, "com.example.core.UserR", None, None, NameMatch::Prefix);
+    assert!(class_fqns(&qualified).iter().any(|f| f == "com.example.core.UserRole"));
+    assert!(qualified.iter().all(|o| o.fqn.as_deref().unwrap().starts_with("com.example.core.UserR")));
+
+    // Also matches the `userService` property in UserDirectory.kt
+    let ci = find_definition_matching(&index, "userservice", None, None, NameMatch::CaseInsensitive);
+    let classes: Vec<&str> = ci
+        .iter()
+        .filter(|o| matches!(o.kind, SymbolKind::ClassDeclaration))
+        .filter_map(|o| o.fqn.as_deref())
+        .collect();
+    assert_eq!(classes, vec!["com.example.core.UserService"]);
+    assert!(ci.iter().any(|o| o.name == "userService"));
+    assert!(find_definition(&index, "userservice", None, None).is_empty());
+
+    // Exact matching is plain find_definition
+    let exact = find_definition_matching(&index, "User", None, None, NameMatch::Exact);
+    assert_eq!(
+        exact.iter().map(|o| &o.fqn).collect::<Vec<_>>(),
+        find_definition(&index, "User", None, None).iter().map(|o| &o.fqn).collect::<Vec<_>>()
+    );
+}
+
+// --- Kotlin Multiplatform ---
+
+fn multiplatform_fixture_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/multiplatform")
+}
+
+#[test]
+fn test_find_definition_returns_expect_and_actual_declarations() {
+    let root = multiplatform_fixture_path();
+    let mut index = index_files(&root);
+    cross_reference(&mut index);
+    register_companion_aliases(&mut index);
+    let declarations = |results: &[&kotlin_java_mcp::indexer::SymbolOccurrence], kind: SymbolKind| {
+        results
+            .iter()
+            .filter(|o| o.kind == kind)
+            .map(|o| {
+                let source_set = o.file.strip_prefix(root.join("shared/src")).unwrap().iter().next();
+                (o.platform_modifier, source_set.unwrap().to_str().unwrap().to_string())
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // The expect class comes first, followed by each platform's actual class
+    let results = find_definition(&index, "Platform", None, None);
+    assert_eq!(
+        declarations(&results, SymbolKind::ClassDeclaration),
+        vec![
+            (Some(PlatformModifier::Expect), "commonMain".to_string()),
+            (Some(PlatformModifier::Actual), "iosMain".to_string()),
+            (Some(PlatformModifier::Actual), "jvmMain".to_string()),
+        ]
+    );
+    assert!(results.iter().all(|o| o.fqn.as_deref().unwrap().starts_with("com.example.kmp.Platform")));
+    assert!(format_occurrence(results[0], &root).contains("ClassDeclaration (expect) `Platform`"));
+    assert!(format_occurrence(results[1], &root).contains("ClassDeclaration (actual) `Platform`"));
+
+    // A reference in common code still reaches the actual implementations
+    let common = root.join("shared/src/commonMain/kotlin/com/example/kmp/Platform.kt");
+    let results = find_definition(&index, "currentTimeMillis", Some(&common), Some(9));
+    assert_eq!(
+        declarations(&results, SymbolKind::FunctionDeclaration),
+        vec![
+            (Some(PlatformModifier::Expect), "commonMain".to_string()),
+            (Some(PlatformModifier::Actual), "iosMain".to_string()),
+            (Some(PlatformModifier::Actual), "jvmMain".to_string()),
+        ]
+    );
+    assert!(results
+        .iter()
+        .all(|o| o.fqn.as_deref() == Some("com.example.kmp.currentTimeMillis")));
+}
@@ -0,0 +1,105 @@
+use std::path::PathBuf;
+
+use kotlin_java_mcp::indexer::parser::index_files;
+use kotlin_java_mcp::indexer::symbols::{cross_reference, register_companion_aliases};
+use kotlin_java_mcp::indexer::SymbolKind;
+use kotlin_java_mcp::tools::find_definition::find_definition;
+use kotlin_java_mcp::tools::find_implementations::find_implementations;
+
+fn fixture_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/sample-project")
+}
+
+fn build_index() -> kotlin_java_mcp::indexer::SymbolIndex {
+    let root = fixture_path();
+    let mut index = index_files(&root);
+    cross_reference(&mut index);
+    register_companion_aliases(&mut index);
+    index
+}
+
+#[test]
+fn test_sealed_class_declaration_kind() {
+    let index = build_index();
+    let results = find_definition(&index, "com.example.core.Result", None, None);
+
+    assert!(
+        results
+            .iter()
+            .any(|o| matches!(o.kind, SymbolKind::SealedClassDeclaration)),
+        "Expected Result to be a SealedClassDeclaration, got: {:?}",
+        results.iter().map(|o| format!("{:?}", o.kind)).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_find_implementations_of_sealed_class() {
+    let index = build_index();
+    let results = find_implementations(&index, "Result");
+
+    let fqns: Vec<&str> = results.iter().filter_map(|o| o.fqn.as_deref()).collect();
+    assert!(fqns.contains(&"com.example.core.Result.Success"), "Expected Success subtype, got: {:?}", fqns);
+    assert!(fqns.contains(&"com.example.core.Result.Failure"), "Expected Failure subtype, got: {:?}", fqns);
+    assert!(fqns.contains(&"com.example.core.Result.Loading"), "Expected Loading subtype, got: {:?}", fqns);
+}
+
+#[test]
+fn test_find_implementations_across_packages() {
+    // Cancelled lives in com.example.app and extends Result through an explicit import
+    let index = build_index();
+    let results = find_implementations(&index, "com.example.core.Result");
+
+    let cancelled = results
+        .iter()
+        .find(|o| o.name == "Cancelled")
+        .expect("Expected Cancelled subtype from app module");
+    assert_eq!(cancelled.fqn.as_deref(), Some("com.example.app.Cancelled"));
+    assert_eq!(cancelled.file.file_name().unwrap().to_str().unwrap(), "Outcomes.kt");
+}
+
+#[test]
+fn test_find_implementations_of_sealed_interface() {
+    let index = build_index();
+    let results = find_definition(&index, "com.example.core.Alert", None, None);
+    assert!(
+        results
+            .iter()
+            .any(|o| o.kind == SymbolKind::SealedInterfaceDeclaration),
+        "Expected Alert to be a SealedInterfaceDeclaration, got: {:?}",
+        results.iter().map(|o| format!("{:?}", o.kind)).collect::<Vec<_>>()
+    );
+
+    // Nested, top-level and other-package implementers
+    let results = find_implementations(&index, "com.example.core.Alert");
+    let mut fqns: Vec<&str> = results.iter().filter_map(|o| o.fqn.as_deref()).collect();
+    fqns.sort();
+    assert_eq!(
+        fqns,
+        vec![
+            "com.example.app.SmsAlert",
+            "com.example.core.Alert.Banner",
+            "com.example.core.Alert.Siren",
+            "com.example.core.Toast",
+        ]
+    );
+}
+
+#[test]
+fn test_find_implementations_of_interface() {
+    let index = build_index();
+    let results = find_implementations(&index, "Repository");
+
+    assert!(
+        results.iter().any(|o| o.name == "InMemoryUserRepository"),
+        "Expected InMemoryUserRepository to implement Repository, got: {:?}",
+        results.iter().map(|o| o.name.as_str()).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_find_implementations_nonexistent_symbol() {
+    let index = build_index();
+    let results = find_implementations(&index, "NonExistentSymbol");
+
+    assert!(results.is_empty(), "Expected no implementations for nonexistent symbol");
+}
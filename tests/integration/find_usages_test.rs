@@ -1,9 +1,14 @@
 use std::path::PathBuf;
 
-use kotlin_java_mcp::indexer::parser::index_files;
+use kotlin_java_mcp::indexer::filter::FileFilter;
+use kotlin_java_mcp::indexer::parser::{index_files, index_files_with_filter};
 use kotlin_java_mcp::indexer::symbols::{cross_reference, register_companion_aliases};
 use kotlin_java_mcp::indexer::SymbolKind;
-use kotlin_java_mcp::tools::find_usages::find_usages;
+use kotlin_java_mcp::tools::find_definition::find_definition;
+use kotlin_java_mcp::tools::find_usages::{
+    find_usages, find_usages_matching, retain_in_scope, retain_outside_tests,
+};
+use kotlin_java_mcp::tools::NameMatch;
 
 fn fixture_path() -> PathBuf {
     PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/sample-project")
@@ -20,7 +25,7 @@ fn build_index() -> kotlin_java_mcp::indexer::SymbolIndex {
 #[test]
 fn test_find_usages_of_user_class() {
     let index = build_index();
-    let results = find_usages(&index,"User", None, None, true);
+    let results = find_usages(&index,"User", None, None, true, None);
 
     // User is used in many places: imports, type references, call sites
     assert!(!results.is_empty(), "Expected usages of User, found none");
@@ -40,7 +45,7 @@ fn test_find_usages_of_user_class() {
 #[test]
 fn test_find_usages_of_user_service() {
     let index = build_index();
-    let results = find_usages(&index,"UserService", None, None, true);
+    let results = find_usages(&index,"UserService", None, None, true, None);
 
     assert!(!results.is_empty(), "Expected usages of UserService");
 
@@ -59,7 +64,7 @@ fn test_find_usages_of_user_service() {
 #[test]
 fn test_find_usages_of_repository_interface() {
     let index = build_index();
-    let results = find_usages(&index,"Repository", None, None, true);
+    let results = find_usages(&index,"Repository", None, None, true, None);
 
     assert!(
         !results.is_empty(),
@@ -81,7 +86,7 @@ fn test_find_usages_of_repository_interface() {
 #[test]
 fn test_find_usages_of_user_role_enum() {
     let index = build_index();
-    let results = find_usages(&index,"UserRole", None, None, true);
+    let results = find_usages(&index,"UserRole", None, None, true, None);
 
     assert!(!results.is_empty(), "Expected usages of UserRole");
 }
@@ -89,7 +94,7 @@ fn test_find_usages_of_user_role_enum() {
 #[test]
 fn test_find_usages_by_fqn() {
     let index = build_index();
-    let results = find_usages(&index,"com.example.core.User", None, None, true);
+    let results = find_usages(&index,"com.example.core.User", None, None, true, None);
 
     assert!(
         !results.is_empty(),
@@ -100,7 +105,7 @@ fn test_find_usages_by_fqn() {
 #[test]
 fn test_find_usages_nonexistent_symbol() {
     let index = build_index();
-    let results = find_usages(&index,"NonExistentSymbol", None, None, true);
+    let results = find_usages(&index,"NonExistentSymbol", None, None, true, None);
 
     assert!(results.is_empty(), "Expected no usages for nonexistent symbol");
 }
@@ -108,7 +113,7 @@ fn test_find_usages_nonexistent_symbol() {
 #[test]
 fn test_find_usages_import_has_correct_line_number() {
     let index = build_index();
-    let results = find_usages(&index,"worksheetWorkflowConfig", None, None, true);
+    let results = find_usages(&index,"worksheetWorkflowConfig", None, None, true, None);
 
     let imports: Vec<_> = results
         .iter()
@@ -130,7 +135,7 @@ fn test_find_usages_bare_identifier_reference() {
     // worksheetWorkflowConfig is used as a bare value reference (not a call, not a type)
     // in expressions like `listOf(worksheetWorkflowConfig)` and `val config = worksheetWorkflowConfig`
     let index = build_index();
-    let results = find_usages(&index,"worksheetWorkflowConfig", None, None, true);
+    let results = find_usages(&index,"worksheetWorkflowConfig", None, None, true, None);
 
     // Should have at least the import + two value references in WorkflowUsage.kt
     let in_workflow_usage: Vec<_> = results
@@ -171,7 +176,7 @@ fn usages_in_file<'a>(
 fn test_find_usages_navigation_receiver_property_access() {
     // `Config.maxRetries` — Config is the receiver of a navigation_expression
     let index = build_index();
-    let results = find_usages(&index,"Config", None, None, true);
+    let results = find_usages(&index,"Config", None, None, true, None);
 
     let in_patterns = usages_in_file(&results, "ReferencePatterns.kt");
 
@@ -190,7 +195,7 @@ fn test_find_usages_navigation_receiver_property_access() {
 fn test_find_usages_callable_reference_bare() {
     // `::createUser` — callable reference to a top-level function
     let index = build_index();
-    let results = find_usages(&index,"createUser", None, None, true);
+    let results = find_usages(&index,"createUser", None, None, true, None);
 
     let in_patterns = usages_in_file(&results, "ReferencePatterns.kt");
 
@@ -207,7 +212,7 @@ fn test_find_usages_callable_reference_bare() {
 fn test_find_usages_callable_reference_qualified() {
     // `User::toString` — qualified callable reference, User is the receiver
     let index = build_index();
-    let results = find_usages(&index,"User", None, None, true);
+    let results = find_usages(&index,"User", None, None, true, None);
 
     let in_patterns = usages_in_file(&results, "ReferencePatterns.kt");
 
@@ -224,7 +229,7 @@ fn test_find_usages_callable_reference_qualified() {
 fn test_find_usages_infix_function() {
     // `"key" mapTo "value"` — infix call to mapTo
     let index = build_index();
-    let results = find_usages(&index,"mapTo", None, None, true);
+    let results = find_usages(&index,"mapTo", None, None, true, None);
 
     let in_patterns = usages_in_file(&results, "ReferencePatterns.kt");
 
@@ -245,7 +250,7 @@ fn test_find_usages_fqn_not_shadowed_by_class_method() {
     let index = build_index();
 
     // Search by FQN for the top-level function
-    let results = find_usages(&index,"com.example.core.generateReport", None, None, true);
+    let results = find_usages(&index,"com.example.core.generateReport", None, None, true, None);
 
     // The call `generateReport("test")` in useTopLevel() in ReportServiceImpl.kt
     // should resolve to the top-level function, not to ReportServiceImpl.generateReport
@@ -266,3 +271,886 @@ fn test_find_usages_fqn_not_shadowed_by_class_method() {
         results.iter().map(|o| format!("{}:{} {:?} fqn={:?}", o.file.file_name().unwrap().to_str().unwrap(), o.line, o.kind, o.fqn)).collect::<Vec<_>>()
     );
 }
+
+// --- Delegation ---
+
+#[test]
+fn test_find_usages_interface_delegation_target() {
+    // `class SharedGreeter : Greeter by defaultGreeter` — the delegate expression is a reference
+    let index = build_index();
+    let results = find_usages(&index, "defaultGreeter", None, None, false, None);
+
+    let in_usage = usages_in_file(&results, "DelegationUsage.kt");
+    assert!(
+        in_usage.iter().any(|o| o.line == 8 && matches!(o.kind, SymbolKind::PropertyReference)),
+        "Expected PropertyReference to defaultGreeter on line 8 of DelegationUsage.kt, found: {:?}",
+        in_usage.iter().map(|o| format!("line:{} {:?}", o.line, o.kind)).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_find_usages_interface_delegation_type() {
+    // `: Greeter by impl` — the delegated interface is a type reference
+    let index = build_index();
+    let results = find_usages(&index, "com.example.core.Greeter", None, None, false, None);
+
+    let delegated_lines: Vec<usize> = usages_in_file(&results, "DelegationUsage.kt")
+        .iter()
+        .filter(|o| matches!(o.kind, SymbolKind::TypeReference))
+        .map(|o| o.line)
+        .collect();
+    assert!(delegated_lines.contains(&6), "Expected Greeter type reference on line 6, found: {:?}", delegated_lines);
+    assert!(delegated_lines.contains(&8), "Expected Greeter type reference on line 8, found: {:?}", delegated_lines);
+}
+
+#[test]
+fn test_find_usages_constructor_parameter_delegate() {
+    // `class LoudGreeter(private val impl: Greeter) : Greeter by impl`
+    let index = build_index();
+    let results = find_usages(&index, "impl", None, None, false, None);
+
+    let in_usage = usages_in_file(&results, "DelegationUsage.kt");
+    let delegate = in_usage
+        .iter()
+        .find(|o| o.line == 6 && matches!(o.kind, SymbolKind::PropertyReference))
+        .expect("Expected PropertyReference for `by impl`");
+    assert_eq!(delegate.column, 59, "Expected column of the delegate identifier");
+}
+
+#[test]
+fn test_find_usages_property_delegate_call() {
+    // `val greeting by lazy { ... }` — lazy is a call site
+    let index = build_index();
+    let results = find_usages(&index, "lazy", None, None, false, None);
+
+    let in_usage = usages_in_file(&results, "DelegationUsage.kt");
+    assert!(
+        in_usage.iter().any(|o| matches!(o.kind, SymbolKind::CallSite)),
+        "Expected lazy CallSite in DelegationUsage.kt, found: {:?}",
+        in_usage.iter().map(|o| format!("line:{} {:?}", o.line, o.kind)).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_find_usages_property_delegate_factory_and_operators() {
+    let index = build_index();
+
+    // `val greeting by memoized { ... }` calls the imported factory
+    let results = find_usages(&index, "com.example.core.memoized", None, None, false, None);
+    let lines: Vec<usize> = usages_in_file(&results, "UserSettings.kt")
+        .iter()
+        .filter(|o| o.kind == SymbolKind::CallSite)
+        .map(|o| o.line)
+        .collect();
+    assert_eq!(lines, vec![9], "Expected the memoized call on line 9");
+
+    // `by Preference(...)` reads through getValue, and the `var` writes through setValue
+    let positions = |fqn: &str| -> Vec<(usize, usize)> {
+        let results = find_usages(&index, fqn, None, None, false, None);
+        usages_in_file(&results, "UserSettings.kt")
+            .iter()
+            .filter(|o| o.kind == SymbolKind::CallSite)
+            .map(|o| (o.line, o.column))
+            .collect()
+    };
+    assert_eq!(positions("com.example.core.Preference.getValue"), vec![(7, 15), (8, 16)]);
+    assert_eq!(positions("com.example.core.Preference.setValue"), vec![(7, 15)]);
+    let constructor_lines: Vec<usize> =
+        positions("com.example.core.Preference").iter().map(|&(line, _)| line).collect();
+    assert_eq!(constructor_lines, vec![7, 8]);
+}
+
+// --- Comments ---
+
+/// Byte ranges of the `//` and `/* */` comments in `source`, which has no string literals
+/// containing comment markers.
+fn comment_spans(source: &str) -> Vec<std::ops::Range<usize>> {
+    let mut spans = Vec::new();
+    let mut rest = 0;
+    while let Some(start) = source[rest..].find('/').map(|i| rest + i) {
+        let end = if source[start..].starts_with("//") {
+            source[start..].find('\n').map_or(source.len(), |i| start + i)
+        } else if source[start..].starts_with("/*") {
+            source[start..].find("*/").map_or(source.len(), |i| start + i + 2)
+        } else {
+            rest = start + 1;
+            continue;
+        };
+        spans.push(start..end);
+        rest = end;
+    }
+    spans
+}
+
+#[test]
+fn test_names_in_comments_are_not_indexed() {
+    let index = build_index();
+    for file in ["UserLabels.kt", "UserCaptions.java"] {
+        let occurrences: Vec<_> = index
+            .by_name
+            .values()
+            .flatten()
+            .filter(|o| o.file.file_name().unwrap().to_str().unwrap() == file)
+            .collect();
+        let source = std::fs::read_to_string(&occurrences[0].file).unwrap();
+        let spans = comment_spans(&source);
+        assert!(spans.len() >= 4, "Expected the fixture's comments in {}", file);
+        for occ in &occurrences {
+            assert!(
+                !spans.iter().any(|span| span.contains(&occ.byte_range.start)),
+                "{} in a comment of {} at {}:{}",
+                occ.name,
+                file,
+                occ.line,
+                occ.column
+            );
+        }
+
+        // Only the parameter type is a usage; the comments mention User and UserService often
+        let results = find_usages(&index, "com.example.core.User", None, None, false, None);
+        let lines: Vec<usize> = usages_in_file(&results, file).iter().map(|o| o.line).collect();
+        assert_eq!(lines, vec![10], "Expected User only on line 10 of {}", file);
+        let results = find_usages(&index, "com.example.core.UserService", None, None, false, None);
+        assert!(usages_in_file(&results, file).is_empty(), "{:?}", usages_in_file(&results, file));
+    }
+}
+
+// --- Annotations ---
+
+#[test]
+fn test_find_usages_annotation_applications() {
+    let index = build_index();
+    let results = find_usages(&index, "com.example.core.Audited", None, None, false, None);
+
+    let lines: Vec<usize> = usages_in_file(&results, "AuditedService.kt")
+        .iter()
+        .filter(|o| matches!(o.kind, SymbolKind::AnnotationReference))
+        .map(|o| o.line)
+        .collect();
+    // Plain, with arguments, and with a use-site target
+    assert_eq!(lines, vec![8, 10, 13], "Expected @Audited on lines 8, 10 and 13");
+}
+
+#[test]
+fn test_find_usages_annotation_arguments_traversed() {
+    let index = build_index();
+    let results = find_usages(&index, "AUDIT_REASON", None, None, false, None);
+
+    let lines: Vec<usize> = usages_in_file(&results, "AuditedService.kt")
+        .iter()
+        .map(|o| o.line)
+        .collect();
+    assert!(lines.contains(&8), "Expected AUDIT_REASON argument on line 8, found: {:?}", lines);
+    assert!(lines.contains(&13), "Expected AUDIT_REASON argument on line 13, found: {:?}", lines);
+}
+
+#[test]
+fn test_annotation_targets_recorded() {
+    let index = build_index();
+
+    for target in [
+        "com.example.app.AuditedService",
+        "com.example.app.AuditedService.save",
+        "com.example.app.AuditedService.lastSaved",
+    ] {
+        let annotations = index
+            .annotations
+            .get(target)
+            .unwrap_or_else(|| panic!("Expected annotations recorded for {}", target));
+        assert!(annotations.contains(&"com.example.core.Audited".to_string()));
+    }
+}
+
+#[test]
+fn test_find_usages_nested_annotation_class_literals() {
+    let index = build_index();
+    let positions = |symbol: &str, kind: SymbolKind| -> Vec<(usize, usize)> {
+        let mut positions: Vec<(usize, usize)> =
+            find_usages(&index, symbol, None, None, false, None)
+                .iter()
+                .filter(|o| o.kind == kind && o.file.ends_with("Polymorphic.kt"))
+                .map(|o| (o.line, o.column))
+                .collect();
+        positions.sort();
+        positions
+    };
+
+    // `SubType(GuestAccount::class, ...)` nested in `@SubTypes`, and the array in `@Handles`
+    assert_eq!(
+        positions("com.example.app.GuestAccount", SymbolKind::TypeReference),
+        vec![(15, 19), (16, 11)]
+    );
+    assert_eq!(
+        positions("com.example.app.AdminAccount", SymbolKind::TypeReference),
+        vec![(15, 65), (16, 32)]
+    );
+    // The nested annotations are annotation references, not calls
+    assert_eq!(
+        positions("com.example.app.SubType", SymbolKind::AnnotationReference),
+        vec![(15, 11), (15, 57)]
+    );
+    assert!(positions("com.example.app.SubType", SymbolKind::CallSite).is_empty());
+    // Only the outer annotations apply to the interface
+    let mut applied =
+        index.annotations.get("com.example.app.Principal").cloned().unwrap_or_default();
+    applied.sort();
+    assert_eq!(applied, vec!["com.example.app.Handles", "com.example.app.SubTypes"]);
+}
+
+// --- Wildcard imports ---
+
+#[test]
+fn test_find_usages_through_wildcard_import() {
+    let index = build_index();
+    let results = find_usages(&index, "com.example.core.User", None, None, false, None);
+
+    let lines: Vec<usize> = usages_in_file(&results, "WildcardImports.kt")
+        .iter()
+        .map(|o| o.line)
+        .collect();
+    assert!(lines.contains(&5), "Expected User type argument on line 5, found: {:?}", lines);
+    assert!(lines.contains(&6), "Expected User parameter type on line 6, found: {:?}", lines);
+}
+
+#[test]
+fn test_wildcard_import_preferred_over_same_package() {
+    let index = build_index();
+
+    // Both com.example.app.Config and com.example.core.Config exist; the explicit
+    // `import com.example.core.*` wins over the same-package guess.
+    let core = find_usages(&index, "com.example.core.Config", None, None, false, None);
+    assert!(
+        !usages_in_file(&core, "WildcardImports.kt").is_empty(),
+        "Expected Config in WildcardImports.kt to resolve to com.example.core.Config"
+    );
+
+    let app = find_usages(&index, "com.example.app.Config", None, None, false, None);
+    assert!(
+        usages_in_file(&app, "WildcardImports.kt").is_empty(),
+        "Did not expect Config in WildcardImports.kt to resolve to com.example.app.Config"
+    );
+}
+
+#[test]
+fn test_wildcard_import_of_type_members() {
+    // `import com.example.core.Priority.*` brings the enum's entries into scope, and
+    // `import com.example.core.Limits.*` the Java class's static members
+    let index = build_index();
+    let positions = |fqn: &str| -> Vec<(usize, usize)> {
+        let results = find_usages(&index, fqn, None, None, false, None);
+        usages_in_file(&results, "TaskTriage.kt").iter().map(|o| (o.line, o.column)).collect()
+    };
+    assert_eq!(positions("com.example.core.Priority.URGENT"), vec![(9, 9)]);
+    assert_eq!(positions("com.example.core.Priority.NORMAL"), vec![(10, 9)]);
+    assert_eq!(positions("com.example.core.Priority.LOW"), vec![(10, 17)]);
+    assert_eq!(positions("com.example.core.Limits.MAX_RETRIES"), vec![(9, 19)]);
+    assert_eq!(positions("com.example.core.Limits.clamp"), vec![(10, 24)]);
+
+    let file = fixture_path().join("app/src/main/kotlin/com/example/app/TaskTriage.kt");
+    let definitions = find_definition(&index, "URGENT", Some(&file), Some(9));
+    assert_eq!(
+        definitions.first().and_then(|o| o.fqn.as_deref()),
+        Some("com.example.core.Priority.URGENT")
+    );
+}
+
+#[test]
+fn test_find_usages_of_data_class_property_includes_destructuring() {
+    let index = build_index();
+
+    // `val (id, name) = user` reads User.name through component2()
+    let results = find_usages(&index, "com.example.core.User.name", None, None, false, None);
+    let destructuring = usages_in_file(&results, "Destructuring.kt");
+    assert!(
+        destructuring
+            .iter()
+            .any(|o| o.name == "component2" && o.line == 6),
+        "Expected destructuring of User.name on line 6, found: {:?}",
+        destructuring
+    );
+
+    // Type inferred from the constructor call `Coordinates(52.5, 13.4)`
+    let results = find_usages(&index, "com.example.app.Coordinates.longitude", None, None, false, None);
+    let lines: Vec<usize> = usages_in_file(&results, "Destructuring.kt")
+        .iter()
+        .map(|o| o.line)
+        .collect();
+    assert_eq!(lines, vec![13]);
+}
+
+#[test]
+fn test_find_usages_filtered_by_kind() {
+    let index = build_index();
+    let all = find_usages(&index, "com.example.core.User", None, None, true, None);
+
+    let kinds = [SymbolKind::TypeReference];
+    let type_refs = find_usages(&index, "com.example.core.User", None, None, true, Some(&kinds));
+    assert!(!type_refs.is_empty(), "Expected type references of User");
+    assert!(type_refs.iter().all(|o| o.kind == SymbolKind::TypeReference));
+    assert!(type_refs.len() < all.len(), "Expected the filter to drop imports and call sites");
+
+    let kinds = [SymbolKind::CallSite, SymbolKind::Import];
+    let mixed = find_usages(&index, "com.example.core.User", None, None, true, Some(&kinds));
+    assert!(mixed.iter().any(|o| o.kind == SymbolKind::Import));
+    assert!(mixed
+        .iter()
+        .all(|o| matches!(o.kind, SymbolKind::CallSite | SymbolKind::Import)));
+}
+
+#[test]
+fn test_parse_symbol_kind() {
+    assert_eq!("CallSite".parse::<SymbolKind>(), Ok(SymbolKind::CallSite));
+    assert_eq!("typereference".parse::<SymbolKind>(), Ok(SymbolKind::TypeReference));
+
+    let err = "Callsites".parse::<SymbolKind>().unwrap_err();
+    assert!(err.contains("Unknown symbol kind 'Callsites'"), "{}", err);
+    assert!(err.contains("PropertyReference"), "{}", err);
+}
+
+#[test]
+fn test_find_usages_of_sealed_subtypes_in_when_branches() {
+    let index = build_index();
+
+    for (fqn, line) in [
+        ("com.example.core.Result.Success", 6),
+        ("com.example.core.Result.Failure", 7),
+        ("com.example.app.Cancelled", 8),
+    ] {
+        let results = find_usages(&index, fqn, None, None, false, None);
+        let in_when: Vec<_> = usages_in_file(&results, "ResultRenderer.kt")
+            .into_iter()
+            .filter(|o| o.kind == SymbolKind::TypeReference)
+            .map(|o| o.line)
+            .collect();
+        assert_eq!(in_when, vec![line], "Expected `is` branch type for {}", fqn);
+    }
+
+    // The `when (result)` subject is a reference too
+    let subject = index
+        .by_name
+        .get("result")
+        .and_then(|occs| {
+            occs.iter().find(|o| {
+                o.kind.is_reference()
+                    && o.line == 5
+                    && o.file.file_name().unwrap().to_str().unwrap() == "ResultRenderer.kt"
+            })
+        });
+    assert!(subject.is_some(), "Expected the when subject to be captured");
+}
+
+#[test]
+fn test_find_usages_of_java_switch_pattern_types() {
+    let index = build_index();
+
+    for (fqn, lines) in [
+        ("com.example.core.shapes.Shape.Circle", vec![10, 11, 19]),
+        ("com.example.core.shapes.Shape.Square", vec![12]),
+        ("com.example.core.shapes.Shape.Rectangle", vec![13]),
+    ] {
+        let results = find_usages(&index, fqn, None, None, false, None);
+        let in_switch: Vec<_> = usages_in_file(&results, "ShapeDescriber.java")
+            .into_iter()
+            .filter(|o| o.kind == SymbolKind::TypeReference)
+            .map(|o| o.line)
+            .collect();
+        assert_eq!(in_switch, lines, "Expected case label types for {}", fqn);
+    }
+
+    // The guard of `case Circle c when c.radius() > 10` is still scanned
+    let guard_call = index.by_name.get("radius").and_then(|occs| {
+        occs.iter().find(|o| {
+            o.kind == SymbolKind::CallSite
+                && o.line == 10
+                && o.file.file_name().unwrap().to_str().unwrap() == "ShapeDescriber.java"
+        })
+    });
+    assert!(guard_call.is_some(), "Expected the guard call to be captured");
+}
+
+#[test]
+fn test_find_usages_of_extension_property_by_receiver() {
+    let index = build_index();
+
+    // `isAdmin` is both the `User.isAdmin` extension and a ProfileData property
+    let results = find_usages(&index, "User.isAdmin", None, None, false, None);
+    assert!(!results.is_empty(), "Expected usages of User.isAdmin");
+    assert!(results
+        .iter()
+        .all(|o| o.fqn.as_deref() == Some("com.example.core.isAdmin")));
+    let lines: Vec<_> = usages_in_file(&results, "UserProfile.kt")
+        .into_iter()
+        .map(|o| o.line)
+        .collect();
+    assert!(lines.contains(&15), "Expected `user.isAdmin` on line 15, got {:?}", lines);
+
+    let declaration = index
+        .by_fqn
+        .get("com.example.core.isAdmin")
+        .and_then(|occs| occs.iter().find(|o| o.kind == SymbolKind::ExtensionPropertyDeclaration))
+        .expect("Expected an extension property declaration");
+    assert_eq!(declaration.receiver_type.as_deref(), Some("User"));
+}
+
+#[test]
+fn test_usages_carry_enclosing_declaration() {
+    let index = build_index();
+
+    let results = find_usages(&index, "findById", None, None, false, None);
+    let call = usages_in_file(&results, "UserService.kt")
+        .into_iter()
+        .find(|o| o.line == 6)
+        .expect("Expected repository.findById in getUser");
+    assert_eq!(call.enclosing_fqn.as_deref(), Some("com.example.core.UserService.getUser"));
+
+    // A call in a local variable's initializer belongs to the function, not the variable
+    let results = find_usages(&index, "generateId", None, None, false, None);
+    let call = usages_in_file(&results, "UserService.kt")
+        .into_iter()
+        .find(|o| o.line == 15)
+        .expect("Expected generateId() in createUser");
+    assert_eq!(call.enclosing_fqn.as_deref(), Some("com.example.core.UserService.createUser"));
+
+    // Declarations are enclosed by their class; top-level declarations by nothing
+    let declaration_in = |fqn: &str| {
+        index
+            .by_fqn
+            .get(fqn)
+            .and_then(|occs| occs.iter().find(|o| o.kind.is_declaration()))
+            .map(|o| o.enclosing_fqn.clone())
+            .unwrap_or_else(|| panic!("Expected a declaration of {}", fqn))
+    };
+    assert_eq!(
+        declaration_in("com.example.core.UserService.getUser").as_deref(),
+        Some("com.example.core.UserService")
+    );
+    assert_eq!(declaration_in("com.example.core.UserService"), None);
+}
+
+#[test]
+fn test_find_usages_type_parameter_bounds() {
+    let index = build_index();
+    let results = find_usages(&index, "Entity", None, None, false, None);
+
+    // `class EntityStore<T : Entity>` and `where T : Entity`
+    let lines: Vec<_> = usages_in_file(&results, "Entity.kt")
+        .into_iter()
+        .map(|o| o.line)
+        .collect();
+    assert!(lines.contains(&7), "Expected the class bound on line 7, got {:?}", lines);
+    assert!(lines.contains(&15), "Expected the where-clause bound on line 15, got {:?}", lines);
+
+    // `class EntityCache<E extends Entity>`
+    let java = usages_in_file(&results, "EntityCache.java");
+    assert!(
+        java.iter().any(|o| o.line == 3 && o.kind == SymbolKind::TypeReference),
+        "Expected the Java bound on line 3, got: {:?}",
+        java
+    );
+
+    // The type parameter names on those lines are not indexed
+    let type_variables: Vec<_> = ["T", "E"]
+        .iter()
+        .filter_map(|name| index.by_name.get(*name))
+        .flatten()
+        .filter(|o| {
+            (o.file.ends_with("Entity.kt") && o.line == 7)
+                || (o.file.ends_with("EntityCache.java") && o.line == 3)
+        })
+        .collect();
+    assert!(type_variables.is_empty(), "Unexpected type parameter occurrences: {:?}", type_variables);
+}
+
+#[test]
+fn test_type_alias_targets_are_navigable() {
+    let index = build_index();
+    let target = |alias: &str| index.type_aliases.get(alias).map(String::as_str);
+
+    // Generic, nested and nullable targets resolve to the declaration they name
+    assert_eq!(target("com.example.core.UserRepo"), Some("com.example.core.Repository"));
+    assert_eq!(target("com.example.core.Outcome"), Some("com.example.core.Result.Success"));
+    assert_eq!(target("com.example.core.UserHandler"), Some("kotlin.Function2"));
+    assert_eq!(target("com.example.core.UserId"), Some("String"));
+
+    // Types inside the targets are usages; the alias's own type parameter is not
+    let results = find_usages(&index, "com.example.core.User", None, None, false, None);
+    let lines: Vec<_> = usages_in_file(&results, "Aliases.kt")
+        .into_iter()
+        .map(|o| o.line)
+        .collect();
+    assert!(lines.contains(&3), "Expected User in the function type, got {:?}", lines);
+    assert!(lines.contains(&5), "Expected User in the generic target, got {:?}", lines);
+    assert!(
+        index.by_name.get("T").into_iter().flatten().all(|o| !o.file.ends_with("Aliases.kt")),
+        "Type parameter T should not be indexed in Aliases.kt"
+    );
+
+    // A usage of the alias is a usage of its target
+    let results = find_usages(&index, "com.example.core.Repository", None, None, false, None);
+    assert!(
+        usages_in_file(&results, "Aliases.kt")
+            .iter()
+            .any(|o| o.name == "UserRepo" && o.line == 9),
+        "Expected the UserRepo parameter type among Repository usages"
+    );
+}
+
+#[test]
+fn test_find_usages_in_string_templates() {
+    let index = build_index();
+
+    // `${inbox.unread}` reads the property; `\$unread` and the word "unread" are literal text
+    let results = find_usages(&index, "unread", None, None, false, None);
+    let lines: Vec<usize> = usages_in_file(&results, "Notifications.kt")
+        .iter()
+        .map(|o| o.line)
+        .collect();
+    assert_eq!(lines, vec![10]);
+
+    let reference_line = |name: &str, kind: SymbolKind| {
+        index.by_name.get(name).and_then(|occs| {
+            occs.iter()
+                .find(|o| o.kind == kind && o.file.file_name().unwrap().to_str().unwrap() == "Notifications.kt")
+                .map(|o| o.line)
+        })
+    };
+    assert_eq!(reference_line("owner", SymbolKind::PropertyReference), Some(8));
+    assert_eq!(reference_line("greeting", SymbolKind::PropertyReference), Some(10));
+    assert_eq!(reference_line("getAllUsers", SymbolKind::CallSite), Some(10));
+    assert_eq!(reference_line("hint", SymbolKind::PropertyReference), Some(10));
+}
+
+#[test]
+fn test_find_usages_of_operator_function_includes_operator_calls() {
+    let index = build_index();
+
+    // `sum = sum + price` calls `Money.plus`
+    let results = find_usages(&index, "com.example.core.Money.plus", None, None, false, None);
+    let plus_calls: Vec<(usize, usize)> = usages_in_file(&results, "Checkout.kt")
+        .iter()
+        .filter(|o| o.kind == SymbolKind::CallSite)
+        .map(|o| (o.line, o.column))
+        .collect();
+    assert_eq!(plus_calls, vec![(9, 23)]);
+
+    // `total() <= Money(0)` calls `Money.compareTo`
+    let results = find_usages(&index, "com.example.core.Money.compareTo", None, None, false, None);
+    let compare_lines: Vec<usize> = usages_in_file(&results, "Checkout.kt")
+        .iter()
+        .map(|o| o.line)
+        .collect();
+    assert_eq!(compare_lines, vec![14]);
+}
+
+#[test]
+fn test_find_usages_scoped_to_app_module() {
+    let index = build_index();
+    let root = fixture_path();
+    let all = find_usages(&index, "com.example.core.User", None, None, true, None);
+    assert!(all.iter().any(|o| o.file.starts_with(root.join("core"))));
+
+    // The FQN is resolved over the whole index, but only app usages are reported
+    let mut scoped = all.clone();
+    retain_in_scope(&mut scoped, &root.join("app"));
+    assert!(!scoped.is_empty(), "Expected usages of User in app");
+    assert!(
+        scoped.iter().all(|o| o.file.starts_with(root.join("app"))),
+        "Expected no usages outside app, got: {:?}",
+        scoped
+    );
+    assert_eq!(
+        scoped.len(),
+        all.iter().filter(|o| o.file.starts_with(root.join("app"))).count()
+    );
+
+    // `app` must not match a sibling directory that merely starts with it
+    let mut prefix_only = all.clone();
+    retain_in_scope(&mut prefix_only, &root.join("ap"));
+    assert!(prefix_only.is_empty());
+}
+
+fn in_qualified_receivers(o: &kotlin_java_mcp::indexer::SymbolOccurrence) -> bool {
+    o.file.ends_with("QualifiedReceivers.kt")
+}
+
+#[test]
+fn test_qualified_this_references_outer_class() {
+    let index = build_index();
+    // `this@Toolbar` inside the inner class refers to Toolbar itself
+    let toolbar = find_usages(&index, "com.example.app.Toolbar", None, None, true, None);
+    let mut positions: Vec<(usize, usize)> = toolbar
+        .iter()
+        .filter(|o| in_qualified_receivers(o) && o.kind == SymbolKind::TypeReference)
+        .map(|o| (o.line, o.column))
+        .collect();
+    positions.sort();
+    assert_eq!(positions, vec![(19, 36), (21, 35)]);
+
+    // ... and its members to the outer class's, not the inner class's `title`
+    let title = find_usages(&index, "com.example.app.Toolbar.title", None, None, true, None);
+    assert!(
+        title
+            .iter()
+            .any(|o| in_qualified_receivers(o) && o.line == 19 && o.column == 44),
+        "Expected this@Toolbar.title as a usage of Toolbar.title, got: {:?}",
+        title
+    );
+    let render = find_usages(&index, "com.example.app.Toolbar.render", None, None, true, None);
+    assert!(render.iter().any(|o| in_qualified_receivers(o) && o.line == 21));
+}
+
+#[test]
+fn test_super_call_resolves_to_supertype_member() {
+    let index = build_index();
+    let lines = |fqn: &str| -> Vec<usize> {
+        find_usages(&index, fqn, None, None, true, None)
+            .iter()
+            .filter(|o| in_qualified_receivers(o))
+            .map(|o| o.line)
+            .collect()
+    };
+
+    // `super.render()` in Toolbar calls the nearest supertype's render, Panel's
+    assert!(lines("com.example.app.Panel.render").contains(&14));
+    assert!(!lines("com.example.app.Toolbar.render").contains(&14));
+    assert!(!lines("com.example.app.Widget.render").contains(&14));
+
+    // `super<Widget>.render()` names the supertype explicitly
+    assert!(lines("com.example.app.Widget.render").contains(&26));
+    assert!(lines("com.example.app.Widget").contains(&26));
+}
+
+#[test]
+fn test_context_receiver_types_are_usages() {
+    let index = build_index();
+    let positions = |fqn: &str| -> Vec<(usize, usize)> {
+        let mut positions: Vec<(usize, usize)> = find_usages(&index, fqn, None, None, true, None)
+            .iter()
+            .filter(|o| {
+                o.file.ends_with("ContextReceivers.kt") && o.kind == SymbolKind::TypeReference
+            })
+            .map(|o| (o.line, o.column))
+            .collect();
+        positions.sort();
+        positions
+    };
+
+    // `context(AuditLog)` and the context parameters of `context(log: AuditLog, clock: Clock)`
+    assert_eq!(positions("com.example.app.AuditLog"), vec![(11, 9), (16, 14)]);
+    assert_eq!(positions("com.example.app.Clock"), vec![(16, 31)]);
+}
+
+fn test_sources_fixture_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/test-sources")
+}
+
+fn is_under_test_root(o: &kotlin_java_mcp::indexer::SymbolOccurrence) -> bool {
+    let path = o.file.to_string_lossy();
+    path.contains("/src/test/") || path.contains("/src/androidTest/")
+}
+
+#[test]
+fn test_no_tests_filter_skips_test_sources() {
+    let root = test_sources_fixture_path();
+    let mut index = index_files(&root);
+    cross_reference(&mut index);
+    let all = find_usages(&index, "com.example.shop.Cart", None, None, true, None);
+    assert!(all.iter().any(|o| is_under_test_root(o)), "Expected usages in test sources");
+
+    let filter = FileFilter::default().with_exclude_tests(true);
+    let mut index = index_files_with_filter(&root, &filter);
+    cross_reference(&mut index);
+    assert!(!index.files.keys().any(|path| {
+        let path = path.to_string_lossy();
+        path.contains("/src/test/") || path.contains("/src/androidTest/")
+    }));
+    let results = find_usages(&index, "com.example.shop.Cart", None, None, true, None);
+    assert!(!results.is_empty(), "Expected production usages of Cart");
+    assert!(
+        results.iter().all(|o| o.file.ends_with("Checkout.kt")),
+        "Expected only usages in Checkout.kt, got: {:?}",
+        results
+    );
+}
+
+#[test]
+fn test_find_usages_outside_tests() {
+    let root = test_sources_fixture_path();
+    let mut index = index_files(&root);
+    cross_reference(&mut index);
+    let mut results = find_usages(&index, "com.example.shop.Cart", None, None, true, None);
+    assert!(results.iter().any(|o| is_under_test_root(o)));
+
+    retain_outside_tests(&mut results, &root);
+    assert!(!results.is_empty(), "Expected production usages of Cart");
+    assert!(
+        results.iter().all(|o| !is_under_test_root(o)),
+        "Expected no test usages, got: {:?}",
+        results
+    );
+}
+
+#[test]
+fn test_lambda_parameters_shadow_outer_names() {
+    let index = build_index();
+    // Each lambda declares its parameters under its own position
+    let outer = "com.example.app.LambdaScopes.<lambda@12:24>.user";
+    let inner = "com.example.app.LambdaScopes.<lambda@13:22>.user";
+    for fqn in [outer, inner] {
+        let declared = index.by_fqn.get(fqn).map_or(0, |occs| {
+            occs.iter().filter(|o| o.kind == SymbolKind::ParameterDeclaration).count()
+        });
+        assert_eq!(declared, 1, "Expected one parameter declared as {}", fqn);
+    }
+
+    // `user.map { user -> user.email }`: the receiver is the outer parameter,
+    // the use inside the inner lambda is the inner one
+    let positions = |fqn: &str| -> Vec<(usize, usize)> {
+        find_usages(&index, fqn, None, None, false, None)
+            .iter()
+            .map(|o| (o.line, o.column))
+            .collect()
+    };
+    assert_eq!(positions(outer), vec![(13, 13)]);
+    assert_eq!(positions(inner), vec![(13, 32)]);
+
+    // `names.map { name -> name.length }` doesn't use the `name` property
+    let property_usages = find_usages(&index, "com.example.app.LambdaScopes.name", None, None, false, None);
+    assert!(
+        !property_usages
+            .iter()
+            .any(|o| o.file.ends_with("LambdaScopes.kt") && o.line == 20),
+        "Expected the lambda parameter not to count as a property usage, got: {:?}",
+        property_usages
+    );
+
+    // The implicit `it` is not indexed as a reference
+    let it_refs = index.by_name.get("it").map_or(0, |occs| {
+        occs.iter().filter(|o| o.file.ends_with("LambdaScopes.kt")).count()
+    });
+    assert_eq!(it_refs, 0);
+}
+
+#[test]
+fn test_for_loop_variables_shadow_outer_names() {
+    let index = build_index();
+    // `for ((key, value) in settings)` at line 8 declares both components locally
+    let loop_key = "com.example.app.LoopVariables.<for@8:9>.key";
+    for fqn in [loop_key, "com.example.app.LoopVariables.<for@11:9>.entry"] {
+        let declared = index.by_fqn.get(fqn).map_or(0, |occs| {
+            occs.iter().filter(|o| o.kind == SymbolKind::ParameterDeclaration).count()
+        });
+        assert_eq!(declared, 1, "Expected one loop variable declared as {}", fqn);
+    }
+
+    // `"$key=$value"` uses the loop variable
+    let loop_key_lines: Vec<usize> = find_usages(&index, loop_key, None, None, false, None)
+        .iter()
+        .map(|o| o.line)
+        .collect();
+    assert_eq!(loop_key_lines, vec![9]);
+
+    // Only the `key` after the second loop, outside any destructuring, is the property
+    let property_lines: Vec<usize> =
+        find_usages(&index, "com.example.app.LoopVariables.key", None, None, false, None)
+            .iter()
+            .filter(|o| o.file.ends_with("LoopVariables.kt") && o.kind.is_reference())
+            .map(|o| o.line)
+            .collect();
+    assert_eq!(property_lines, vec![12]);
+}
+
+#[test]
+fn test_find_usages_includes_call_type_arguments() {
+    let index = build_index();
+    let mut positions: Vec<(usize, usize)> =
+        find_usages(&index, "com.example.app.Stopwatch", None, None, false, None)
+            .iter()
+            .filter(|o| o.kind == SymbolKind::TypeReference)
+            .map(|o| (o.line, o.column))
+            .collect();
+    positions.sort();
+
+    // The return type, `lookup<Stopwatch>()` with and without a receiver, and
+    // `mutableListOf<Stopwatch>()`
+    assert_eq!(positions, vec![(10, 22), (10, 41), (14, 43), (15, 33)]);
+}
+
+#[test]
+fn test_find_usages_prefix_and_case_insensitive_match() {
+    let index = build_index();
+    let positions = |results: &[&kotlin_java_mcp::indexer::SymbolOccurrence]| -> Vec<(PathBuf, usize)> {
+        results.iter().map(|o| (o.file.clone(), o.byte_range.start)).collect()
+    };
+
+    let exact = find_usages(&index, "UserRole", None, None, true, None);
+    assert!(!exact.is_empty());
+    let prefixed = find_usages_matching(&index, "UserRo", None, None, true, None, NameMatch::Prefix);
+    assert_eq!(positions(&prefixed), positions(&exact));
+
+    // `User` covers the usages of UserService and UserRole as well as User itself
+    let prefixed = find_usages_matching(&index, "User", None, None, false, None, NameMatch::Prefix);
+    for name in ["User", "UserService", "UserRole"] {
+        assert!(prefixed.iter().any(|o| o.name == name), "Expected a usage of {}", name);
+    }
+    assert!(prefixed.iter().all(|o| o.kind.is_reference()));
+
+    let ci = find_usages_matching(&index, "userrole", None, None, true, None, NameMatch::CaseInsensitive);
+    assert_eq!(positions(&ci), positions(&exact));
+}
+
+#[test]
+fn test_find_usages_in_when_without_subject() {
+    let index = build_index();
+    let positions = |symbol: &str| -> Vec<(usize, usize)> {
+        let results = find_usages(&index, symbol, None, None, false, None);
+        usages_in_file(&results, "AccessPolicy.kt")
+            .iter()
+            .map(|o| (o.line, o.column))
+            .collect()
+    };
+
+    // Branch conditions of `when { ... }` are plain boolean expressions
+    assert_eq!(positions("isActive"), vec![(9, 16), (11, 17)]);
+    assert_eq!(positions("strikes"), vec![(9, 35), (10, 16)]);
+    assert_eq!(positions("MAX_STRIKES"), vec![(10, 26)]);
+}
+
+#[test]
+fn test_find_usages_includes_backing_field_references() {
+    let index = build_index();
+    let positions = |symbol: &str| -> Vec<(usize, usize)> {
+        let results = find_usages(&index, symbol, None, None, false, None);
+        usages_in_file(&results, "Thermostat.kt")
+            .iter()
+            .map(|o| (o.line, o.column))
+            .collect()
+    };
+
+    // `field` in a getter or setter is the property's backing field
+    assert_eq!(positions("com.example.app.Thermostat.target"), vec![(5, 17), (7, 13)]);
+    assert_eq!(positions("com.example.app.thermostatMode"), vec![(12, 13), (13, 14)]);
+}
+
+#[test]
+fn test_find_usages_infix_call_resolves_through_receiver_type() {
+    let index = build_index();
+    let positions = |symbol: &str| -> Vec<(usize, usize)> {
+        let results = find_usages(&index, symbol, None, None, false, None);
+        usages_in_file(&results, "Alignment.kt")
+            .iter()
+            .filter(|o| o.kind == SymbolKind::CallSite)
+            .map(|o| (o.line, o.column))
+            .collect()
+    };
+
+    // `a dot b` with a parameter, a constructor call or a local of each type as receiver
+    assert_eq!(positions("com.example.core.Vector2.dot"), vec![(7, 20), (10, 30)]);
+    assert_eq!(positions("com.example.core.Quaternion.dot"), vec![(9, 28), (11, 41)]);
+
+    let results = find_usages(&index, "mapTo", None, None, false, None);
+    let infix = usages_in_file(&results, "ReferencePatterns.kt");
+    assert_eq!(infix.len(), 1, "Expected the one mapTo call: {:?}", infix);
+    assert_eq!(infix[0].kind, SymbolKind::CallSite);
+    assert_eq!(infix[0].fqn.as_deref(), Some("com.example.core.mapTo"));
+}
@@ -1,4 +1,13 @@
+use kotlin_java_mcp::gradle::kts_parser::parse_build_file;
 use kotlin_java_mcp::gradle::parser::{parse_dependencies_output, parse_projects_output};
+use kotlin_java_mcp::gradle::{
+    detect_project_root, validate_configuration, DependencyNode, GradleRunner,
+};
+use kotlin_java_mcp::tools::dependency_tree::{
+    dependency_tree, dependency_tree_json, find_version_conflicts, flatten_dependencies,
+    format_dependency_nodes, format_dependency_nodes_json, format_flat_dependencies,
+    format_version_conflicts, DependencyTreeError, GradleErrorCode,
+};
 use std::path::PathBuf;
 
 fn fixture_path(name: &str) -> PathBuf {
@@ -87,3 +96,407 @@ fn test_parse_dependencies_transitive() {
         kotlin_stdlib.children
     );
 }
+
+#[test]
+fn test_find_version_conflicts_with_request_paths() {
+    let content = std::fs::read_to_string(fixture_path("dependencies_output.txt")).unwrap();
+    let conflicts = find_version_conflicts(&parse_dependencies_output(&content));
+
+    let artifacts: Vec<&str> = conflicts.iter().map(|c| c.artifact.as_str()).collect();
+    // The coroutines BOM is requested twice at the same version: not a conflict
+    assert_eq!(artifacts, vec!["kotlin-stdlib", "kotlin-stdlib-common"]);
+
+    let stdlib = &conflicts[0];
+    assert_eq!(stdlib.group, "org.jetbrains.kotlin");
+    assert_eq!(stdlib.resolved_version.as_deref(), Some("1.9.22"));
+    let requests: Vec<(&str, String)> = stdlib
+        .requests
+        .iter()
+        .map(|r| (r.version.as_str(), r.path.join(" > ")))
+        .collect();
+    assert_eq!(
+        requests,
+        vec![
+            ("1.9.22", String::new()),
+            (
+                "1.8.20",
+                "org.jetbrains.kotlinx:kotlinx-coroutines-core:1.7.3 > \
+                 org.jetbrains.kotlinx:kotlinx-coroutines-core-jvm:1.7.3"
+                    .to_string()
+            ),
+            (
+                "1.9.10",
+                "com.squareup.okhttp3:okhttp:4.12.0 > com.squareup.okio:okio:3.6.0 > \
+                 com.squareup.okio:okio-jvm:3.6.0"
+                    .to_string()
+            ),
+            ("1.8.21", "com.squareup.okhttp3:okhttp:4.12.0".to_string()),
+        ]
+    );
+
+    let common = &conflicts[1];
+    assert_eq!(common.resolved_version.as_deref(), Some("1.9.22"));
+    assert_eq!(common.requests.len(), 2);
+    assert_eq!(common.requests[0].path, vec!["org.jetbrains.kotlin:kotlin-stdlib:1.9.22"]);
+}
+
+#[test]
+fn test_format_version_conflicts() {
+    let content = std::fs::read_to_string(fixture_path("dependencies_output.txt")).unwrap();
+    let conflicts = find_version_conflicts(&parse_dependencies_output(&content));
+    let output = format_version_conflicts(&conflicts, ":app");
+
+    assert!(output.starts_with("Version conflicts in module ':app' (2 total):"), "{}", output);
+    assert!(output.contains("  org.jetbrains.kotlin:kotlin-stdlib -> 1.9.22\n"), "{}", output);
+    assert!(output.contains("    1.9.22 (direct)\n"), "{}", output);
+    assert!(
+        output.contains("    1.8.21 (via com.squareup.okhttp3:okhttp:4.12.0)\n"),
+        "{}",
+        output
+    );
+    assert!(!output.contains("gson"), "{}", output);
+
+    assert_eq!(
+        format_version_conflicts(&[], ":core"),
+        "No version conflicts in module ':core'."
+    );
+}
+
+#[test]
+fn test_format_dependency_tree_with_max_depth() {
+    let content = std::fs::read_to_string(fixture_path("dependencies_output.txt")).unwrap();
+    let deps = parse_dependencies_output(&content);
+
+    // Depth 1 keeps the direct dependencies and counts what each one hides
+    assert_eq!(
+        format_dependency_nodes(&deps, Some(1)),
+        "org.jetbrains.kotlin:kotlin-stdlib:1.9.22 (+2 more)\n\
+         com.google.code.gson:gson:2.10.1\n\
+         project :core\n\
+         org.jetbrains.kotlinx:kotlinx-coroutines-core:1.7.3 (+4 more)\n\
+         com.squareup.okhttp3:okhttp:4.12.0 (+5 more)\n"
+    );
+
+    let two_levels = format_dependency_nodes(&deps, Some(2));
+    assert!(
+        two_levels.contains("  ├── org.jetbrains.kotlinx:kotlinx-coroutines-core-jvm:1.7.3 (+2 more)\n"),
+        "{}",
+        two_levels
+    );
+    assert!(two_levels.contains("  ├── com.squareup.okio:okio:3.6.0 (+3 more)\n"), "{}", two_levels);
+    assert!(two_levels.contains("  ├── org.jetbrains:annotations:24.0.0\n"), "{}", two_levels);
+    assert!(!two_levels.contains("okio-jvm"), "{}", two_levels);
+
+    // Deep enough for the whole tree: nothing is cut
+    let unlimited = format_dependency_nodes(&deps, None);
+    assert_eq!(format_dependency_nodes(&deps, Some(4)), unlimited);
+    assert!(!unlimited.contains("more)"), "{}", unlimited);
+}
+
+#[test]
+fn test_flatten_dependency_tree() {
+    let content = std::fs::read_to_string(fixture_path("dependencies_output.txt")).unwrap();
+    let deps = parse_dependencies_output(&content);
+
+    // One entry per artifact at its resolved version; `(*)` repeats and projects are dropped
+    assert_eq!(
+        flatten_dependencies(&deps, None),
+        vec![
+            "com.google.code.gson:gson:2.10.1",
+            "com.squareup.okhttp3:okhttp:4.12.0",
+            "com.squareup.okio:okio-jvm:3.6.0",
+            "com.squareup.okio:okio:3.6.0",
+            "org.jetbrains.kotlin:kotlin-stdlib-common:1.9.22",
+            "org.jetbrains.kotlin:kotlin-stdlib:1.9.22",
+            "org.jetbrains.kotlinx:kotlinx-coroutines-bom:1.7.3",
+            "org.jetbrains.kotlinx:kotlinx-coroutines-core-jvm:1.7.3",
+            "org.jetbrains.kotlinx:kotlinx-coroutines-core:1.7.3",
+            "org.jetbrains:annotations:24.0.0",
+        ]
+    );
+    assert_eq!(
+        flatten_dependencies(&deps, Some(1)),
+        vec![
+            "com.google.code.gson:gson:2.10.1",
+            "com.squareup.okhttp3:okhttp:4.12.0",
+            "org.jetbrains.kotlin:kotlin-stdlib:1.9.22",
+            "org.jetbrains.kotlinx:kotlinx-coroutines-core:1.7.3",
+        ]
+    );
+
+    let output = format_flat_dependencies(&deps, None);
+    assert!(output.starts_with("10 unique artifact(s):\n"), "{}", output);
+    assert!(output.contains("\n  com.squareup.okio:okio:3.6.0\n"), "{}", output);
+}
+
+#[test]
+fn test_dependency_tree_json_round_trips() {
+    let content = std::fs::read_to_string(fixture_path("dependencies_output.txt")).unwrap();
+    let deps = parse_dependencies_output(&content);
+
+    let json = format_dependency_nodes_json(&deps, None);
+    let parsed: Vec<DependencyNode> = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, deps);
+
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let stdlib = &value[0];
+    assert_eq!(stdlib["group"], "org.jetbrains.kotlin");
+    assert_eq!(stdlib["artifact"], "kotlin-stdlib");
+    assert_eq!(stdlib["version"], "1.9.22");
+    assert_eq!(stdlib["isProject"], false);
+    assert_eq!(stdlib["isTransitiveDuplicate"], false);
+    assert!(stdlib["resolvedVersion"].is_null());
+    assert!(stdlib["children"].as_array().is_some_and(|c| !c.is_empty()), "{}", json);
+
+    // Depth 1 keeps the direct dependencies without their children
+    let direct: Vec<DependencyNode> =
+        serde_json::from_str(&format_dependency_nodes_json(&deps, Some(1))).unwrap();
+    assert_eq!(direct.len(), deps.len());
+    assert!(direct.iter().all(|dep| dep.children.is_empty()));
+}
+
+#[test]
+fn test_dependency_tree_json_of_declared_dependencies() {
+    let runner = GradleRunner::new(fixture_path(""), true);
+    let json = dependency_tree_json(&runner, Some(":kotlin-dsl"), None, None, false).unwrap();
+    let deps: Vec<DependencyNode> = serde_json::from_str(&json).unwrap();
+
+    let core = find_declared(&deps, "core");
+    assert!(core.is_project);
+    assert_eq!(core.configuration.as_deref(), Some("implementation"));
+
+    let flat = dependency_tree_json(&runner, Some(":kotlin-dsl"), None, None, true).unwrap();
+    let artifacts: Vec<String> = serde_json::from_str(&flat).unwrap();
+    assert!(artifacts.contains(&"com.google.code.gson:gson:2.10.1".to_string()), "{}", flat);
+}
+
+// --- Declared dependencies from build scripts ---
+
+fn find_declared<'a>(deps: &'a [DependencyNode], artifact: &str) -> &'a DependencyNode {
+    deps.iter()
+        .find(|d| d.artifact == artifact)
+        .unwrap_or_else(|| panic!("Expected {} dependency, got: {:?}", artifact, deps))
+}
+
+#[test]
+fn test_parse_kotlin_dsl_build_file() {
+    let content = std::fs::read_to_string(fixture_path("kotlin-dsl/build.gradle.kts")).unwrap();
+    let deps = parse_build_file(&content);
+
+    let artifacts: Vec<&str> = deps.iter().map(|d| d.artifact.as_str()).collect();
+    assert_eq!(
+        artifacts,
+        vec!["core", "gson", "commons-lang3", "okhttp", "junit-jupiter"],
+        "buildscript classpath, catalog accessors and comments should be skipped"
+    );
+
+    let core = find_declared(&deps, "core");
+    assert!(core.is_project);
+    assert_eq!(core.configuration.as_deref(), Some("implementation"));
+
+    let gson = find_declared(&deps, "gson");
+    assert_eq!(gson.group, "com.google.code.gson");
+    assert_eq!(gson.version, "2.10.1");
+    assert!(gson.resolved_version.is_none());
+
+    let commons = find_declared(&deps, "commons-lang3");
+    assert_eq!(commons.group, "org.apache.commons");
+    assert_eq!(commons.version, "3.14.0");
+    assert_eq!(commons.configuration.as_deref(), Some("api"));
+
+    let junit = find_declared(&deps, "junit-jupiter");
+    assert_eq!(junit.configuration.as_deref(), Some("testImplementation"));
+}
+
+#[test]
+fn test_parse_groovy_dsl_build_file() {
+    let content = std::fs::read_to_string(fixture_path("groovy-dsl/build.gradle")).unwrap();
+    let deps = parse_build_file(&content);
+
+    let artifacts: Vec<&str> = deps.iter().map(|d| d.artifact.as_str()).collect();
+    assert_eq!(
+        artifacts,
+        vec!["core", "gson", "commons-lang3", "lombok", "junit-jupiter"]
+    );
+
+    assert!(find_declared(&deps, "core").is_project);
+    let commons = find_declared(&deps, "commons-lang3");
+    assert_eq!(commons.group, "org.apache.commons");
+    assert_eq!(commons.version, "3.14.0");
+    assert_eq!(commons.configuration.as_deref(), Some("api"));
+    assert_eq!(
+        find_declared(&deps, "lombok").configuration.as_deref(),
+        Some("compileOnly")
+    );
+}
+
+#[test]
+fn test_runner_falls_back_to_build_file_without_gradlew() {
+    // tests/fixtures/gradle has no gradlew, so get_dependencies reads the module's build script
+    let runner = GradleRunner::new(fixture_path(""), false);
+    assert!(runner.uses_declared_dependencies());
+
+    let kotlin_deps = runner.get_dependencies(":kotlin-dsl", None).unwrap();
+    assert_eq!(find_declared(&kotlin_deps, "gson").version, "2.10.1");
+
+    let groovy_deps = runner.get_dependencies("groovy-dsl", None).unwrap();
+    assert_eq!(find_declared(&groovy_deps, "lombok").group, "org.projectlombok");
+
+    assert!(runner.get_dependencies(":missing", None).is_err());
+}
+
+#[test]
+fn test_dependency_tree_shows_declared_configurations() {
+    let runner = GradleRunner::new(fixture_path(""), true);
+    let output = dependency_tree(&runner, Some(":kotlin-dsl"), None, None, false).unwrap();
+
+    assert!(output.contains("Declared dependencies for module ':kotlin-dsl'"), "{}", output);
+    assert!(output.contains("project :core [implementation]"), "{}", output);
+    assert!(output.contains("com.google.code.gson:gson:2.10.1 [implementation]"), "{}", output);
+}
+
+#[test]
+fn test_dependency_tree_lists_declared_modules_offline_without_wrapper() {
+    // The sample project has a settings script but no wrapper
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/sample-project");
+    for offline in [true, false] {
+        let runner = GradleRunner::new(root.clone(), offline);
+        let output = dependency_tree(&runner, None, None, None, false).unwrap();
+        assert!(output.contains("Project modules (3 total, from settings script)"), "{}", output);
+        assert!(output.contains(":feature (feature)"), "{}", output);
+
+        let json = dependency_tree_json(&runner, None, None, None, false).unwrap();
+        let modules: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(modules.as_array().map(Vec::len), Some(3), "{}", json);
+    }
+}
+
+#[test]
+fn test_dependency_tree_reports_wrapper_not_found_code() {
+    // `gradlew projects` needs the wrapper, and tests/fixtures/gradle has none
+    let runner = GradleRunner::new(fixture_path(""), false);
+    let err = runner.get_modules().unwrap_err();
+
+    let error = DependencyTreeError::from(&err);
+    assert_eq!(error.code, GradleErrorCode::WrapperNotFound);
+    assert!(error.message.starts_with("Gradle wrapper not found at:"), "{}", error.message);
+
+    let json: serde_json::Value = serde_json::from_str(&error.to_json()).unwrap();
+    assert_eq!(json["code"], "wrapper_not_found");
+    assert_eq!(json["message"], error.message.as_str());
+}
+
+#[test]
+fn test_dependency_tree_reports_build_file_not_found_code() {
+    let runner = GradleRunner::new(fixture_path(""), true);
+    let err = dependency_tree(&runner, Some(":missing"), None, None, false).unwrap_err();
+    assert_eq!(DependencyTreeError::from(&err).code, GradleErrorCode::BuildFileNotFound);
+}
+
+#[test]
+fn test_parse_runtime_classpath_fixture() {
+    let content = std::fs::read_to_string(fixture_path("runtime_classpath_output.txt")).unwrap();
+    let deps = parse_dependencies_output(&content);
+
+    // runtimeOnly dependencies appear on the runtime classpath
+    let postgresql = deps
+        .iter()
+        .find(|d| d.artifact == "postgresql")
+        .expect("Expected postgresql dependency");
+    assert_eq!(postgresql.version, "42.7.1");
+    assert_eq!(postgresql.children[0].artifact, "checker-qual");
+
+    let conflicts = find_version_conflicts(&deps);
+    assert_eq!(conflicts.len(), 1, "{:?}", conflicts);
+    assert_eq!(conflicts[0].artifact, "slf4j-api");
+    assert_eq!(conflicts[0].resolved_version.as_deref(), Some("2.0.9"));
+}
+
+#[test]
+fn test_declared_dependencies_of_a_configuration() {
+    let runner = GradleRunner::new(fixture_path(""), true);
+    let artifacts = |module: &str, configuration: &str| -> Vec<String> {
+        runner
+            .get_dependencies(module, Some(configuration))
+            .unwrap()
+            .into_iter()
+            .map(|d| d.artifact)
+            .collect()
+    };
+
+    // Test dependencies stay off the main classpaths
+    assert_eq!(
+        artifacts(":kotlin-dsl", "compileClasspath"),
+        vec!["core", "gson", "commons-lang3", "okhttp"]
+    );
+    assert_eq!(
+        artifacts(":kotlin-dsl", "testRuntimeClasspath"),
+        vec!["core", "gson", "commons-lang3", "okhttp", "junit-jupiter"]
+    );
+    assert_eq!(artifacts(":kotlin-dsl", "testImplementation"), vec!["junit-jupiter"]);
+    // compileOnly dependencies are not on the runtime classpath
+    assert!(!artifacts(":groovy-dsl", "runtimeClasspath").contains(&"lombok".to_string()));
+    assert!(artifacts(":groovy-dsl", "compileClasspath").contains(&"lombok".to_string()));
+
+    let output =
+        dependency_tree(&runner, Some(":kotlin-dsl"), Some("testImplementation"), None, false)
+            .unwrap();
+    assert!(
+        output.starts_with("Declared dependencies for module ':kotlin-dsl' on testImplementation"),
+        "{}",
+        output
+    );
+    assert!(!output.contains("gson"), "{}", output);
+}
+
+#[test]
+fn test_invalid_configuration_is_rejected() {
+    assert!(validate_configuration("runtimeClasspath").is_ok());
+    assert!(validate_configuration("debug_runtime-Classpath2").is_ok());
+    for name in ["", "--offline", "compile Classpath", "runtimeClasspath;rm", "1st"] {
+        assert!(validate_configuration(name).is_err(), "{:?} should be rejected", name);
+    }
+
+    let runner = GradleRunner::new(fixture_path(""), true);
+    let err = dependency_tree(&runner, Some(":kotlin-dsl"), Some("--scan"), None, false).unwrap_err();
+    let error = DependencyTreeError::from(&err);
+    assert_eq!(error.code, GradleErrorCode::InvalidConfiguration);
+    assert_eq!(error.message, "Invalid Gradle configuration name: --scan");
+}
+
+#[test]
+fn test_detect_project_root_walks_up_to_settings() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().canonicalize().unwrap();
+    let module = root.join("services/billing/src/main/kotlin");
+    std::fs::create_dir_all(&module).unwrap();
+    std::fs::create_dir_all(root.join(".git")).unwrap();
+    std::fs::write(root.join("settings.gradle.kts"), "include(\":services:billing\")\n").unwrap();
+    // An included build with its own settings file doesn't hide the outer build
+    std::fs::write(root.join("services/settings.gradle"), "").unwrap();
+
+    assert_eq!(detect_project_root(&module), root);
+    assert_eq!(detect_project_root(&root), root);
+}
+
+#[test]
+fn test_detect_project_root_falls_back_to_repository_root() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().canonicalize().unwrap();
+    let nested = root.join("repo/libs/core");
+    std::fs::create_dir_all(&nested).unwrap();
+    std::fs::create_dir_all(root.join("repo/.git")).unwrap();
+    // Settings above the repository root belong to some other project
+    std::fs::write(root.join("settings.gradle"), "").unwrap();
+
+    assert_eq!(detect_project_root(&nested), root.join("repo"));
+}
+
+#[test]
+fn test_detect_project_root_without_markers() {
+    let dir = tempfile::tempdir().unwrap();
+    let start = dir.path().canonicalize().unwrap().join("plain");
+    std::fs::create_dir_all(&start).unwrap();
+
+    assert_eq!(detect_project_root(&start), start);
+}
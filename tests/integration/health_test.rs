@@ -0,0 +1,38 @@
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use kotlin_java_mcp::indexer::parser::index_files;
+use kotlin_java_mcp::indexer::SymbolIndex;
+use kotlin_java_mcp::tools::health::{format_health_json, health};
+
+fn fixture_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/ambiguous-names")
+}
+
+#[test]
+fn test_health_reports_index_readiness() {
+    let before = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+    let index = index_files(&fixture_path());
+
+    let report = health(&index, true, false);
+    assert!(report.indexed);
+    assert!(!report.reindex_in_progress);
+    assert_eq!(report.files, 4);
+    assert_eq!(report.total_occurrences, index.stats().total_occurrences);
+    assert!(report.last_indexed_at.unwrap() >= before);
+
+    let json: serde_json::Value =
+        serde_json::from_str(&format_health_json(&health(&index, true, true))).unwrap();
+    assert_eq!(json["indexed"], true);
+    assert_eq!(json["reindex_in_progress"], true);
+    assert_eq!(json["files"], 4);
+}
+
+#[test]
+fn test_health_before_indexing() {
+    // What the server reports while the initial index is still being built
+    let report = health(&SymbolIndex::default(), false, false);
+    assert!(!report.indexed);
+    assert_eq!(report.files, 0);
+    assert_eq!(report.last_indexed_at, None);
+}
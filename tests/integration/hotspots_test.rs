@@ -0,0 +1,53 @@
+use std::path::PathBuf;
+
+use kotlin_java_mcp::indexer::parser::index_files;
+use kotlin_java_mcp::indexer::symbols::{cross_reference, register_companion_aliases};
+use kotlin_java_mcp::indexer::SymbolKind;
+use kotlin_java_mcp::tools::hotspots::{format_hotspots, hotspots};
+
+fn fixture_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/sample-project")
+}
+
+fn build_index() -> kotlin_java_mcp::indexer::SymbolIndex {
+    let root = fixture_path();
+    let mut index = index_files(&root);
+    cross_reference(&mut index);
+    register_companion_aliases(&mut index);
+    index
+}
+
+#[test]
+fn test_user_ranks_among_hotspots() {
+    let index = build_index();
+    let top = hotspots(&index, 5);
+    assert_eq!(top.len(), 5);
+
+    let user = top
+        .iter()
+        .find(|h| h.declaration.fqn.as_deref() == Some("com.example.core.User"))
+        .expect("Expected User among the five most-referenced declarations");
+    assert_eq!(user.declaration.kind, SymbolKind::ClassDeclaration);
+    assert!(user.references >= 20, "Expected User to be widely used, got {}", user.references);
+    assert!(user.files >= 5, "Expected User used across files, got {}", user.files);
+    // Imports are reported separately, not counted as references
+    assert!(user.imports > 0);
+
+    assert!(top.windows(2).all(|pair| pair[0].references >= pair[1].references));
+}
+
+#[test]
+fn test_hotspots_respect_limit_and_format() {
+    let index = build_index();
+    let root = fixture_path();
+    assert!(hotspots(&index, 0).is_empty());
+
+    let top = hotspots(&index, 3);
+    assert_eq!(top.len(), 3);
+    assert!(top.iter().all(|h| h.references > 0 && h.declaration.kind.is_declaration()));
+
+    let output = format_hotspots(&top, &root);
+    assert!(output.starts_with("Top 3 most-referenced declaration(s):"), "{}", output);
+    assert!(output.contains("  1. "), "{}", output);
+    assert!(!output.contains(root.to_str().unwrap()), "Expected relative paths:\n{}", output);
+}
@@ -3,6 +3,7 @@ use std::path::PathBuf;
 use kotlin_java_mcp::indexer::parser::index_files;
 use kotlin_java_mcp::indexer::symbols::{cross_reference, register_companion_aliases};
 use kotlin_java_mcp::indexer::SymbolKind;
+use kotlin_java_mcp::tools::find_usages::find_usages;
 
 fn fixture_path() -> PathBuf {
     PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/sample-project")
@@ -190,3 +191,271 @@ fn test_java_imports_indexed() {
     assert!(import_paths.contains(&"java.util.List"));
     assert!(import_paths.contains(&"java.util.ArrayList"));
 }
+
+#[test]
+fn test_java_nested_class_references_resolve_through_outer() {
+    let index = build_index();
+
+    let in_loader = |name: &str| {
+        index
+            .by_name
+            .get(name)
+            .map(|occs| {
+                occs.iter()
+                    .filter(|o| o.file.ends_with("PageLoader.java") && o.kind.is_reference())
+                    .map(|o| (o.line, o.column, o.kind.clone(), o.fqn.clone().unwrap_or_default()))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default()
+    };
+
+    let nested = "com.example.core.Pagination.PageRequest".to_string();
+    let page_request = in_loader("PageRequest");
+    for expected in [
+        (6, 24, SymbolKind::TypeReference, nested.clone()),
+        (6, 61, SymbolKind::CallSite, nested.clone()),
+        (9, 27, SymbolKind::TypeReference, nested.clone()),
+        (12, 23, SymbolKind::TypeReference, nested.clone()),
+    ] {
+        assert!(
+            page_request.contains(&expected),
+            "Expected {:?} in {:?}",
+            expected,
+            page_request
+        );
+    }
+    assert!(
+        page_request.iter().all(|(_, _, _, fqn)| *fqn == nested),
+        "Every PageRequest reference should resolve to the nested class: {:?}",
+        page_request
+    );
+
+    // Members accessed through the nested type resolve into it
+    assert!(in_loader("DEFAULT_SIZE")
+        .iter()
+        .any(|(_, _, _, fqn)| fqn == "com.example.core.Pagination.PageRequest.DEFAULT_SIZE"));
+    assert!(in_loader("first")
+        .iter()
+        .any(|(_, _, _, fqn)| fqn == "com.example.core.Pagination.PageRequest.first"));
+}
+
+#[test]
+fn test_java_annotation_usages_and_argument_classes() {
+    let index = build_index();
+    let in_service = |fqn: &str, kind: SymbolKind| {
+        let mut lines: Vec<usize> = index
+            .by_fqn
+            .get(fqn)
+            .map(|occs| {
+                occs.iter()
+                    .filter(|o| o.file.ends_with("TracedService.java") && o.kind == kind)
+                    .map(|o| o.line)
+                    .collect()
+            })
+            .unwrap_or_default();
+        lines.sort();
+        lines
+    };
+
+    // Marker, single-element and element-value-pair forms
+    assert_eq!(
+        in_service("com.example.core.Traced", SymbolKind::AnnotationReference),
+        vec![6, 8, 12]
+    );
+    // `by = JavaHelper.class` references the class
+    assert_eq!(
+        in_service("com.example.core.JavaHelper", SymbolKind::TypeReference),
+        vec![12]
+    );
+    // The element name is not a reference
+    assert!(!index
+        .by_name
+        .get("by")
+        .is_some_and(|occs| occs
+            .iter()
+            .any(|o| o.file.ends_with("TracedService.java") && o.kind.is_reference())));
+}
+
+#[test]
+fn test_java_static_import_calls_resolve_to_members() {
+    let index = build_index();
+    let call_lines = |fqn: &str| -> Vec<usize> {
+        index
+            .by_fqn
+            .get(fqn)
+            .into_iter()
+            .flatten()
+            .filter(|o| o.kind == SymbolKind::CallSite && o.file.ends_with("StaticImports.java"))
+            .map(|o| o.line)
+            .collect()
+    };
+
+    // `import static java.util.Collections.emptyList`
+    assert_eq!(call_lines("java.util.Collections.emptyList"), vec![10]);
+    // `import static org.junit.Assert.*`
+    assert_eq!(call_lines("org.junit.Assert.assertEquals"), vec![14]);
+    assert_eq!(call_lines("org.junit.Assert.assertTrue"), vec![15]);
+    // The class's own methods win over the static wildcard import
+    assert_eq!(call_lines("com.example.app.StaticImports.isEmpty"), vec![15]);
+    assert!(call_lines("org.junit.Assert.isEmpty").is_empty());
+    assert!(call_lines("org.junit.Assert.names").is_empty());
+}
+
+#[test]
+fn test_java_method_references_are_usages() {
+    let index = build_index();
+    let usages = |symbol: &str| -> Vec<(usize, usize)> {
+        find_usages(&index, symbol, None, None, false, None)
+            .into_iter()
+            .filter(|o| {
+                o.kind == SymbolKind::CallSite && o.file.ends_with("MethodReferences.java")
+            })
+            .map(|o| (o.line, o.column))
+            .collect()
+    };
+
+    // `.map(MethodReferences::normalize)`
+    assert_eq!(usages("com.example.app.MethodReferences.normalize"), vec![(10, 40)]);
+    // `.map(this::quote)`
+    assert_eq!(usages("com.example.app.MethodReferences.quote"), vec![(12, 28)]);
+
+    // `raw.forEach(System.out::println)` keeps its receiver
+    let println = index
+        .by_name
+        .get("println")
+        .into_iter()
+        .flatten()
+        .find(|o| o.file.ends_with("MethodReferences.java"))
+        .expect("Expected println method reference in MethodReferences.java");
+    assert_eq!(println.kind, SymbolKind::CallSite);
+    assert_eq!(println.receiver_type.as_deref(), Some("System.out"));
+}
+
+#[test]
+fn test_java_text_blocks_keep_positions() {
+    let index = build_index();
+    let path = fixture_path().join("app/src/main/java/com/example/app/ReportQueries.java");
+    assert!(index.files.contains_key(&path), "Expected ReportQueries.java to be indexed");
+    assert!(!index.parse_errors.contains_key(&path), "Expected the text block to parse");
+
+    let occurrences: Vec<_> =
+        index.by_name.values().flatten().filter(|o| o.file == path).collect();
+    let lines = |name: &str, kind: SymbolKind| -> Vec<usize> {
+        let mut lines: Vec<usize> = occurrences
+            .iter()
+            .filter(|o| o.name == name && o.kind == kind)
+            .map(|o| o.line)
+            .collect();
+        lines.sort();
+        lines
+    };
+
+    // The call after the three-line text block is on its own line, not shifted
+    assert_eq!(lines("withOwner", SymbolKind::CallSite), vec![11]);
+    // `""" + OWNER_TABLE` and the later use are references
+    assert_eq!(lines("OWNER_TABLE", SymbolKind::PropertyReference), vec![10, 15]);
+    // `owner.trim()` inside the text block is not code
+    assert!(lines("trim", SymbolKind::CallSite).is_empty());
+    assert_eq!(lines("owner", SymbolKind::PropertyReference), vec![11, 15]);
+}
+
+#[test]
+fn test_java_var_declares_local_without_type_reference() {
+    let index = build_index();
+    let path = fixture_path().join("app/src/main/java/com/example/app/UserRoster.java");
+    let occurrences: Vec<_> =
+        index.by_name.values().flatten().filter(|o| o.file == path).collect();
+
+    // `var` infers the type; it is never a reference
+    assert!(
+        occurrences.iter().all(|o| o.name != "var"),
+        "Expected no occurrence of `var`, got {:?}",
+        occurrences.iter().filter(|o| o.name == "var").collect::<Vec<_>>()
+    );
+
+    let declared = |line: usize| {
+        occurrences
+            .iter()
+            .find(|o| {
+                o.name == "roster" && o.kind == SymbolKind::PropertyDeclaration && o.line == line
+            })
+            .unwrap_or_else(|| panic!("Expected `roster` to be declared on line {}", line))
+    };
+    // The field keeps its member FQN; the locals shadowing it are scoped to their methods
+    assert_eq!(declared(8).fqn.as_deref(), Some("com.example.app.UserRoster.roster"));
+    let roster = declared(11);
+    assert_eq!(roster.fqn.as_deref(), Some("com.example.app.UserRoster.<local@11:13>.roster"));
+    assert_eq!((roster.line, roster.column), (11, 13));
+    // An explicitly typed local is declared the same way
+    assert_eq!(
+        declared(19).fqn.as_deref(),
+        Some("com.example.app.UserRoster.<local@19:13>.roster")
+    );
+
+    // Uses bind to the local in scope; `this.roster` is the field
+    let uses: Vec<_> = occurrences
+        .iter()
+        .filter(|o| o.name == "roster" && o.kind == SymbolKind::PropertyReference)
+        .map(|o| (o.line, o.fqn.as_deref().unwrap_or_default()))
+        .collect();
+    assert!(uses.contains(&(13, "com.example.app.UserRoster.<local@11:13>.roster")), "{:?}", uses);
+    assert!(uses.contains(&(15, "com.example.app.UserRoster.<local@11:13>.roster")), "{:?}", uses);
+    assert!(uses.contains(&(20, "com.example.app.UserRoster.<local@19:13>.roster")), "{:?}", uses);
+    assert!(uses.contains(&(19, "com.example.app.UserRoster.roster")), "{:?}", uses);
+
+    // The initializer's types are still captured
+    assert!(occurrences
+        .iter()
+        .any(|o| o.name == "ArrayList" && o.kind == SymbolKind::CallSite && o.line == 11));
+    assert!(occurrences.iter().any(|o| {
+        o.name == "User"
+            && o.kind == SymbolKind::TypeReference
+            && o.line == 11
+            && o.fqn.as_deref() == Some("com.example.core.User")
+    }));
+}
+
+#[test]
+fn test_java_enhanced_for_and_try_with_resources_scoping() {
+    let index = build_index();
+    let path = fixture_path().join("app/src/main/java/com/example/app/RosterExport.java");
+    let occurrences: Vec<_> =
+        index.by_name.values().flatten().filter(|o| o.file == path).collect();
+
+    // The iterable of an enhanced for is a reference
+    assert!(
+        occurrences.iter().any(|o| {
+            o.name == "users" && o.kind == SymbolKind::PropertyReference && o.line == 10
+        }),
+        "Expected `users` to be referenced on the for line, got {:?}",
+        occurrences.iter().filter(|o| o.name == "users").collect::<Vec<_>>()
+    );
+
+    // The loop variable is a declaration scoped to the loop, and uses in the body bind to it
+    let loop_variable = occurrences
+        .iter()
+        .find(|o| o.name == "u" && o.kind == SymbolKind::ParameterDeclaration)
+        .expect("Expected the loop variable to be declared");
+    assert_eq!((loop_variable.line, loop_variable.column), (10, 19));
+    let loop_fqn = loop_variable.fqn.as_deref().unwrap();
+    assert!(loop_fqn.contains("<for@10:9>.u"), "Unexpected loop variable fqn {}", loop_fqn);
+    assert!(!occurrences
+        .iter()
+        .any(|o| o.name == "u" && o.kind == SymbolKind::PropertyReference && o.line == 10));
+    let body_use = occurrences
+        .iter()
+        .find(|o| o.name == "u" && o.kind == SymbolKind::PropertyReference && o.line == 11)
+        .expect("Expected the loop variable to be used in the body");
+    assert_eq!(body_use.fqn.as_deref(), Some(loop_fqn));
+
+    // A resource's name is not a reference; its initializer and later uses are
+    assert!(!occurrences
+        .iter()
+        .any(|o| o.name == "reader" && o.kind == SymbolKind::PropertyReference && o.line == 19));
+    assert!(occurrences
+        .iter()
+        .any(|o| o.name == "text" && o.kind == SymbolKind::PropertyReference && o.line == 19));
+    assert!(occurrences
+        .iter()
+        .any(|o| o.name == "reader" && o.kind == SymbolKind::PropertyReference && o.line == 20));
+}
@@ -0,0 +1,89 @@
+use std::path::PathBuf;
+
+use kotlin_java_mcp::indexer::parser::index_files;
+use kotlin_java_mcp::indexer::symbols::{cross_reference, register_companion_aliases};
+use kotlin_java_mcp::indexer::SymbolKind;
+use kotlin_java_mcp::tools::list_symbols_in_package::{format_package_symbols, list_symbols_in_package};
+
+fn fixture_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/sample-project")
+}
+
+fn build_index() -> kotlin_java_mcp::indexer::SymbolIndex {
+    let root = fixture_path();
+    let mut index = index_files(&root);
+    cross_reference(&mut index);
+    register_companion_aliases(&mut index);
+    index
+}
+
+#[test]
+fn test_list_top_level_symbols_in_package() {
+    let index = build_index();
+    let symbols = list_symbols_in_package(&index, "com.example.core", false);
+    let fqns: Vec<&str> = symbols.iter().filter_map(|o| o.fqn.as_deref()).collect();
+
+    assert!(fqns.contains(&"com.example.core.User"), "got: {:?}", fqns);
+    assert!(fqns.contains(&"com.example.core.UserService"), "got: {:?}", fqns);
+    // Java declarations in the same package are included
+    assert!(fqns.contains(&"com.example.core.JavaHelper"), "got: {:?}", fqns);
+
+    // Nested declarations and members need `recursive`
+    assert!(!fqns.contains(&"com.example.core.Result.Success"), "got: {:?}", fqns);
+    assert!(!fqns.contains(&"com.example.core.UserService.getUser"), "got: {:?}", fqns);
+    assert!(symbols.iter().all(|o| o.kind.is_declaration()));
+}
+
+#[test]
+fn test_list_symbols_in_package_recursive() {
+    let index = build_index();
+    let symbols = list_symbols_in_package(&index, "com.example.core", true);
+    let fqns: Vec<&str> = symbols.iter().filter_map(|o| o.fqn.as_deref()).collect();
+
+    assert!(fqns.contains(&"com.example.core.Result.Success"), "got: {:?}", fqns);
+    assert!(fqns.contains(&"com.example.core.UserService.getUser"), "got: {:?}", fqns);
+    assert!(symbols
+        .iter()
+        .all(|o| o.kind != SymbolKind::ParameterDeclaration));
+
+    // The companion alias of generateId is not listed twice
+    let generate_id = symbols.iter().filter(|o| o.name == "generateId").count();
+    assert_eq!(generate_id, 1);
+}
+
+#[test]
+fn test_list_symbols_in_package_matches_segment_boundaries() {
+    let index = build_index();
+
+    for recursive in [false, true] {
+        let symbols = list_symbols_in_package(&index, "com.example.core", recursive);
+        assert!(
+            symbols.iter().all(|o| o.name != "slugify"),
+            "com.example.coreutils must not match com.example.core"
+        );
+    }
+
+    let symbols = list_symbols_in_package(&index, "com.example.coreutils", false);
+    assert_eq!(symbols.len(), 1);
+    assert_eq!(symbols[0].fqn.as_deref(), Some("com.example.coreutils.slugify"));
+
+    assert!(list_symbols_in_package(&index, "com.example.cor", true).is_empty());
+}
+
+#[test]
+fn test_format_package_symbols() {
+    let index = build_index();
+    let root = fixture_path();
+    let symbols = list_symbols_in_package(&index, "com.example.coreutils", false);
+    let output = format_package_symbols(&symbols, "com.example.coreutils", &root);
+
+    assert!(
+        output.starts_with("Found 1 symbol(s) in package com.example.coreutils across 1 file(s):"),
+        "{}",
+        output
+    );
+    assert!(output.contains("  core/src/main/kotlin/com/example/coreutils/Strings.kt:"), "{}", output);
+    assert!(output.contains("    FunctionDeclaration `slugify` (line 3)"), "{}", output);
+
+    assert_eq!(format_package_symbols(&[], "com.example.missing", &root), "No results found.");
+}
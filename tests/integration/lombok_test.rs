@@ -123,6 +123,7 @@ fn test_lombok_find_usages_of_field_includes_getter_calls() {
         None,
         None,
         false,
+        None,
     );
 
     // Should find getter/setter call sites in LombokConsumer.java
@@ -150,6 +151,33 @@ fn test_lombok_find_usages_of_field_includes_getter_calls() {
     );
 }
 
+#[test]
+fn test_lombok_find_usages_reports_each_access_once() {
+    let index = build_index();
+
+    // `user.username` in Kotlin is found both by FQN and by the field's simple name
+    let results = find_usages(
+        &index,
+        "com.example.core.LombokUser.username",
+        None,
+        None,
+        false,
+        None,
+    );
+
+    let kotlin_lines: Vec<_> = results
+        .iter()
+        .filter(|o| o.file.file_name().unwrap().to_str().unwrap() == "LombokUsage.kt")
+        .map(|o| o.line)
+        .collect();
+    assert_eq!(kotlin_lines, vec![7, 13], "Expected each property access once");
+
+    let mut positions: Vec<_> = results.iter().map(|o| (&o.file, o.byte_range.start)).collect();
+    let total = positions.len();
+    positions.dedup();
+    assert_eq!(positions.len(), total, "Duplicate occurrences in {:?}", results);
+}
+
 #[test]
 fn test_lombok_accessor_mappings_in_index() {
     let index = build_index();
@@ -179,3 +207,61 @@ fn test_lombok_accessor_mappings_in_index() {
         accessors
     );
 }
+
+#[test]
+fn test_lombok_find_definition_of_builder() {
+    let index = build_index();
+
+    let results = find_definition(&index, "builder", None, None);
+    let decl = results
+        .iter()
+        .find(|o| o.file.file_name().unwrap().to_str().unwrap() == "LombokOrder.java")
+        .expect("Expected builder() synthesized by @Builder in LombokOrder.java");
+    assert!(matches!(decl.kind, SymbolKind::FunctionDeclaration));
+    assert_eq!(decl.fqn.as_deref(), Some("com.example.core.LombokOrder.builder"));
+
+    let builder_class = &index.by_fqn["com.example.core.LombokOrder.LombokOrderBuilder"];
+    assert!(builder_class
+        .iter()
+        .any(|o| matches!(o.kind, SymbolKind::ClassDeclaration)));
+}
+
+#[test]
+fn test_lombok_generated_constructors() {
+    let index = build_index();
+
+    // @RequiredArgsConstructor and @AllArgsConstructor, anchored at their annotations
+    let mut lines: Vec<usize> = index.by_fqn["com.example.core.LombokOrder.LombokOrder"]
+        .iter()
+        .filter(|o| matches!(o.kind, SymbolKind::ConstructorDeclaration))
+        .map(|o| o.line)
+        .collect();
+    lines.sort();
+    assert_eq!(lines, vec![9, 10]);
+
+    // @Data with a final field and no constructor gets a required-args constructor
+    assert!(index.by_fqn["com.example.core.LombokUser.LombokUser"]
+        .iter()
+        .any(|o| matches!(o.kind, SymbolKind::ConstructorDeclaration)));
+}
+
+#[test]
+fn test_lombok_find_usages_of_field_includes_builder_calls() {
+    let index = build_index();
+
+    let results = find_usages(
+        &index,
+        "com.example.core.LombokOrder.customer",
+        None,
+        None,
+        false,
+        None,
+    );
+    let factory_lines: Vec<usize> = results
+        .iter()
+        .filter(|o| o.file.file_name().unwrap().to_str().unwrap() == "OrderFactory.java")
+        .filter(|o| o.kind == SymbolKind::CallSite)
+        .map(|o| o.line)
+        .collect();
+    assert_eq!(factory_lines, vec![9], "All results: {:?}", results);
+}
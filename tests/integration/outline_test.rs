@@ -0,0 +1,93 @@
+use std::path::PathBuf;
+
+use kotlin_java_mcp::indexer::parser::index_files;
+use kotlin_java_mcp::indexer::symbols::{cross_reference, register_companion_aliases};
+use kotlin_java_mcp::indexer::SymbolKind;
+use kotlin_java_mcp::tools::outline::{format_outline, outline, OutlineEntry};
+
+fn fixture_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/sample-project")
+}
+
+fn build_index() -> kotlin_java_mcp::indexer::SymbolIndex {
+    let root = fixture_path();
+    let mut index = index_files(&root);
+    cross_reference(&mut index);
+    register_companion_aliases(&mut index);
+    index
+}
+
+fn entry<'a>(entries: &'a [OutlineEntry<'a>], name: &str) -> &'a OutlineEntry<'a> {
+    entries
+        .iter()
+        .find(|e| e.symbol.name == name)
+        .unwrap_or_else(|| panic!("Expected `{}` in outline", name))
+}
+
+#[test]
+fn test_outline_kotlin_nesting() {
+    let index = build_index();
+    let file = fixture_path().join("core/src/main/kotlin/com/example/core/UserService.kt");
+    let entries = outline(&index, &file);
+
+    let class = entry(&entries, "UserService");
+    assert_eq!(class.depth, 0);
+    assert_eq!(class.symbol.kind, SymbolKind::ClassDeclaration);
+
+    assert_eq!(entry(&entries, "getUser").depth, 1);
+    assert_eq!(entry(&entries, "createUser").depth, 1);
+    assert_eq!(entry(&entries, "Companion").depth, 1);
+    assert_eq!(entry(&entries, "generateId").depth, 2);
+    assert_eq!(entry(&entries, "MAX_USERS").depth, 2);
+
+    // Ordered by source position
+    let lines: Vec<usize> = entries.iter().map(|e| e.symbol.line).collect();
+    let mut sorted = lines.clone();
+    sorted.sort();
+    assert_eq!(lines, sorted);
+
+    // Parameters are not part of the outline
+    assert!(entries
+        .iter()
+        .all(|e| e.symbol.kind != SymbolKind::ParameterDeclaration));
+}
+
+#[test]
+fn test_outline_java_file() {
+    let index = build_index();
+    let file = fixture_path().join("core/src/main/java/com/example/core/JavaHelper.java");
+    let entries = outline(&index, &file);
+
+    assert_eq!(entry(&entries, "JavaHelper").depth, 0);
+    let getter = entry(&entries, "getUserNames");
+    assert_eq!(getter.depth, 1);
+    assert_eq!(getter.symbol.line, 25);
+    assert_eq!(entry(&entries, "getPrefix").depth, 1);
+}
+
+#[test]
+fn test_format_outline() {
+    let index = build_index();
+    let root = fixture_path();
+    let output = format_outline(
+        &index,
+        &root.join("core/src/main/java/com/example/core/JavaHelper.java"),
+        &root,
+    );
+
+    assert!(output.contains("Outline of core/src/main/java/com/example/core/JavaHelper.java"), "{}", output);
+    assert!(output.contains("package com.example.core"), "{}", output);
+    assert!(output.contains("java.util.List (line 3)"), "{}", output);
+    assert!(output.contains("ClassDeclaration `JavaHelper` (line 9)"), "{}", output);
+    assert!(output.contains("      FunctionDeclaration `getPrefix` (line 33)"), "{}", output);
+}
+
+#[test]
+fn test_outline_unknown_file() {
+    let index = build_index();
+    let root = fixture_path();
+    let missing = root.join("core/DoesNotExist.kt");
+
+    assert!(outline(&index, &missing).is_empty());
+    assert!(format_outline(&index, &missing, &root).starts_with("File not indexed"));
+}
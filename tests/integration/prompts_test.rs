@@ -0,0 +1,66 @@
+use kotlin_java_mcp::prompts::{get_prompt, list_prompts, ASSESS_RENAME, TRACE_SYMBOL};
+use rmcp::model::{JsonObject, PromptMessageContent, PromptMessageRole};
+use serde_json::json;
+
+fn arguments(value: serde_json::Value) -> JsonObject {
+    value.as_object().cloned().unwrap()
+}
+
+fn message_text(result: &rmcp::model::GetPromptResult) -> &str {
+    assert_eq!(result.messages.len(), 1);
+    assert_eq!(result.messages[0].role, PromptMessageRole::User);
+    match &result.messages[0].content {
+        PromptMessageContent::Text { text } => text,
+        other => panic!("Expected a text message, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_list_prompts() {
+    let prompts = list_prompts();
+    let names: Vec<&str> = prompts.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, vec![ASSESS_RENAME, TRACE_SYMBOL]);
+
+    let trace = &prompts[1];
+    let args = trace.arguments.as_ref().expect("Expected trace-symbol to take arguments");
+    assert_eq!(args.len(), 1);
+    assert_eq!(args[0].name, "symbol");
+    assert_eq!(args[0].required, Some(true));
+}
+
+#[test]
+fn test_get_trace_symbol_prompt_fills_in_symbol() {
+    let args = arguments(json!({ "symbol": "UserService" }));
+    let result = get_prompt(TRACE_SYMBOL, Some(&args)).expect("Expected trace-symbol");
+    let text = message_text(&result);
+
+    assert!(text.contains("`UserService`"), "{}", text);
+    assert!(!text.contains("{symbol}"), "{}", text);
+    // The workflow goes from the definition to the usages to the callers
+    let definition = text.find("find_definition").unwrap();
+    let usages = text.find("find_usages").unwrap();
+    let callers = text.find("incoming_calls").unwrap();
+    assert!(definition < usages && usages < callers, "{}", text);
+    assert!(result.description.is_some());
+}
+
+#[test]
+fn test_get_assess_rename_prompt_with_optional_argument() {
+    let args = arguments(json!({ "symbol": "findById", "new_name": "lookup" }));
+    let result = get_prompt(ASSESS_RENAME, Some(&args)).unwrap();
+    let text = message_text(&result);
+    assert!(text.starts_with("Assess renaming `findById` to `lookup`"), "{}", text);
+    assert!(text.contains("rename_impact"), "{}", text);
+
+    let args = arguments(json!({ "symbol": "findById" }));
+    let result = get_prompt(ASSESS_RENAME, Some(&args)).unwrap();
+    assert!(message_text(&result).starts_with("Assess renaming `findById`:"));
+}
+
+#[test]
+fn test_get_prompt_rejects_unknown_prompt_and_missing_argument() {
+    assert!(get_prompt("no-such-prompt", None).is_err());
+    assert!(get_prompt(TRACE_SYMBOL, None).is_err());
+    let blank = arguments(json!({ "symbol": "  " }));
+    assert!(get_prompt(TRACE_SYMBOL, Some(&blank)).is_err());
+}
@@ -0,0 +1,115 @@
+use std::path::PathBuf;
+
+use kotlin_java_mcp::indexer::parser::index_files;
+use kotlin_java_mcp::indexer::symbols::{cross_reference, register_companion_aliases};
+use kotlin_java_mcp::indexer::{SymbolKind, SymbolOccurrence};
+use kotlin_java_mcp::tools::rename_impact::{format_rename_impacts, rename_impact};
+
+fn fixture_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/sample-project")
+}
+
+fn build_index() -> kotlin_java_mcp::indexer::SymbolIndex {
+    let root = fixture_path();
+    let mut index = index_files(&root);
+    cross_reference(&mut index);
+    register_companion_aliases(&mut index);
+    index
+}
+
+fn in_file<'a>(occurrences: &[&'a SymbolOccurrence], filename: &str) -> Vec<&'a SymbolOccurrence> {
+    occurrences
+        .iter()
+        .filter(|o| o.file.file_name().unwrap().to_str().unwrap() == filename)
+        .copied()
+        .collect()
+}
+
+#[test]
+fn test_rename_impact_includes_declaration_references_and_imports() {
+    let index = build_index();
+    let impacts = rename_impact(&index, "com.example.core.User");
+    assert_eq!(impacts.len(), 1);
+    let occurrences = &impacts[0].occurrences;
+
+    assert!(
+        in_file(occurrences, "User.kt")
+            .iter()
+            .any(|o| o.kind == SymbolKind::ClassDeclaration && o.line == 3),
+        "Expected the User declaration"
+    );
+    assert!(occurrences.iter().any(|o| o.kind == SymbolKind::Import));
+    assert!(occurrences.iter().any(|o| o.kind.is_reference()));
+
+    // Sorted by file, then position, without duplicates
+    for pair in occurrences.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        assert!((&a.file, a.line, a.column) <= (&b.file, b.line, b.column));
+        assert!(!(a.file == b.file && a.byte_range == b.byte_range && a.kind == b.kind));
+    }
+}
+
+#[test]
+fn test_rename_impact_follows_companion_aliases() {
+    let index = build_index();
+    let impacts = rename_impact(&index, "com.example.feature.Config.load");
+    assert_eq!(impacts.len(), 1);
+    let occurrences = &impacts[0].occurrences;
+
+    let declarations: Vec<_> = in_file(occurrences, "Config.kt")
+        .into_iter()
+        .filter(|o| o.kind.is_declaration())
+        .collect();
+    assert_eq!(declarations.len(), 1, "Companion alias should not duplicate the declaration");
+    assert_eq!(declarations[0].line, 7);
+    assert!(
+        in_file(occurrences, "FeatureFlags.kt").iter().any(|o| o.line == 4),
+        "Expected the Config.load() call in FeatureFlags.kt"
+    );
+}
+
+#[test]
+fn test_rename_impact_follows_lombok_accessors() {
+    let index = build_index();
+    let impacts = rename_impact(&index, "com.example.core.LombokUser.username");
+    assert_eq!(impacts.len(), 1);
+    let occurrences = &impacts[0].occurrences;
+
+    let consumer_lines: Vec<usize> = in_file(occurrences, "LombokConsumer.java")
+        .iter()
+        .map(|o| o.line)
+        .collect();
+    assert!(consumer_lines.contains(&7), "Expected getUsername() call, got: {:?}", consumer_lines);
+    assert!(consumer_lines.contains(&12), "Expected setUsername() call, got: {:?}", consumer_lines);
+
+    let kotlin_lines: Vec<usize> = in_file(occurrences, "LombokUsage.kt").iter().map(|o| o.line).collect();
+    assert!(kotlin_lines.contains(&7), "Expected property access, got: {:?}", kotlin_lines);
+    assert!(kotlin_lines.contains(&13), "Expected property assignment, got: {:?}", kotlin_lines);
+
+    assert!(in_file(occurrences, "LombokUser.java")
+        .iter()
+        .any(|o| o.kind.is_declaration() && o.name == "username"));
+}
+
+#[test]
+fn test_format_rename_impacts_groups_by_file() {
+    let index = build_index();
+    let root = fixture_path();
+    let impacts = rename_impact(&index, "com.example.feature.Config.load");
+    let output = format_rename_impacts(&impacts, &root);
+
+    assert!(
+        output.starts_with("Rename impact of com.example.feature.Config.load:"),
+        "{}",
+        output
+    );
+    assert!(output.contains("1 declaration(s)"), "{}", output);
+    assert!(
+        output.contains("  feature/src/main/kotlin/com/example/feature/Config.kt ("),
+        "{}",
+        output
+    );
+    assert!(output.contains("    7:"), "{}", output);
+
+    assert_eq!(format_rename_impacts(&[], &root), "No results found.");
+}
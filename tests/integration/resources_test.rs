@@ -0,0 +1,57 @@
+use std::path::PathBuf;
+
+use kotlin_java_mcp::indexer::parser::index_files;
+use kotlin_java_mcp::resources::{file_uri, list_file_resources, read_file_resource};
+use rmcp::model::ResourceContents;
+
+fn fixture_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/sample-project")
+}
+
+#[test]
+fn test_list_resources_covers_indexed_files() {
+    let root = fixture_path();
+    let index = index_files(&root);
+    let resources = list_file_resources(&index, &root);
+
+    assert_eq!(resources.len(), index.files.len());
+    let repository = resources
+        .iter()
+        .find(|r| r.raw.name == "core/src/main/kotlin/com/example/core/Repository.kt")
+        .expect("Expected Repository.kt listed by its project-relative path");
+    let path = root.join("core/src/main/kotlin/com/example/core/Repository.kt");
+    assert_eq!(repository.raw.uri, format!("file://{}", path.display()));
+    assert_eq!(repository.raw.mime_type.as_deref(), Some("text/x-kotlin"));
+    assert!(resources.iter().any(|r| {
+        r.raw.name.ends_with(".java") && r.raw.mime_type.as_deref() == Some("text/x-java")
+    }));
+}
+
+#[test]
+fn test_read_resource_returns_file_contents() {
+    let root = fixture_path();
+    let index = index_files(&root);
+    let path = root.join("core/src/main/kotlin/com/example/core/Repository.kt");
+    let uri = file_uri(&path);
+
+    let result = read_file_resource(&index, &uri).expect("Expected Repository.kt to be readable");
+    assert_eq!(result.contents.len(), 1);
+    match &result.contents[0] {
+        ResourceContents::TextResourceContents { uri: read_uri, text, .. } => {
+            assert_eq!(read_uri, &uri);
+            assert_eq!(text, &std::fs::read_to_string(&path).unwrap());
+        }
+        other => panic!("Expected text contents, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_read_resource_rejects_files_outside_the_index() {
+    let root = fixture_path();
+    let index = index_files(&root);
+
+    let manifest = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+    assert!(read_file_resource(&index, &file_uri(&manifest)).is_err());
+    assert!(read_file_resource(&index, "file:///etc/passwd").is_err());
+    assert!(read_file_resource(&index, "https://example.com/Repository.kt").is_err());
+}
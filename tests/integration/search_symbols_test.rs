@@ -0,0 +1,72 @@
+use std::path::PathBuf;
+
+use kotlin_java_mcp::indexer::parser::index_files;
+use kotlin_java_mcp::indexer::symbols::{cross_reference, register_companion_aliases};
+use kotlin_java_mcp::indexer::SymbolKind;
+use kotlin_java_mcp::tools::search_symbols::search_symbols;
+
+fn fixture_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/sample-project")
+}
+
+fn build_index() -> kotlin_java_mcp::indexer::SymbolIndex {
+    let root = fixture_path();
+    let mut index = index_files(&root);
+    cross_reference(&mut index);
+    register_companion_aliases(&mut index);
+    index
+}
+
+#[test]
+fn test_search_symbols_abbreviation() {
+    let index = build_index();
+    let results = search_symbols(&index, "UsrSvc", 10, None);
+
+    assert!(!results.is_empty(), "Expected matches for UsrSvc");
+    assert_eq!(results[0].fqn.as_deref(), Some("com.example.core.UserService"));
+}
+
+#[test]
+fn test_search_symbols_only_declarations() {
+    let index = build_index();
+    let results = search_symbols(&index, "User", 100, None);
+
+    assert!(!results.is_empty());
+    assert!(
+        results.iter().all(|o| o.kind.is_declaration()),
+        "Expected only declarations, got: {:?}",
+        results.iter().map(|o| format!("{} {:?}", o.name, o.kind)).collect::<Vec<_>>()
+    );
+    // Exact name match ranks first
+    assert_eq!(results[0].name, "User");
+}
+
+#[test]
+fn test_search_symbols_kind_filter() {
+    let index = build_index();
+    let results = search_symbols(&index, "user", 100, Some("FunctionDeclaration"));
+
+    assert!(!results.is_empty(), "Expected function declarations matching 'user'");
+    assert!(results.iter().all(|o| matches!(o.kind, SymbolKind::FunctionDeclaration)));
+}
+
+#[test]
+fn test_search_symbols_matches_fqn() {
+    // "feature" only appears in the package part of the FQN
+    let index = build_index();
+    let results = search_symbols(&index, "featureProfile", 10, None);
+
+    assert!(
+        results.iter().any(|o| o.fqn.as_deref() == Some("com.example.feature.UserProfile")),
+        "Expected UserProfile via FQN match, got: {:?}",
+        results.iter().map(|o| o.fqn.clone()).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_search_symbols_limit() {
+    let index = build_index();
+    let results = search_symbols(&index, "e", 3, None);
+
+    assert_eq!(results.len(), 3);
+}
@@ -0,0 +1,71 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use kotlin_java_mcp::indexer::parser::index_files;
+use kotlin_java_mcp::tools::find_usages::find_usages;
+use kotlin_java_mcp::tools::format_stale_warning;
+
+fn fixture_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/test-sources")
+}
+
+const SHOP: &str = "src/main/kotlin/com/example/shop";
+
+/// Copy the shop fixtures into a fresh directory, so they can be edited.
+fn copy_fixtures() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(dir.path().join(SHOP)).unwrap();
+    for name in ["Cart.kt", "Checkout.kt"] {
+        let file = Path::new(SHOP).join(name);
+        std::fs::copy(fixture_path().join(&file), dir.path().join(&file)).unwrap();
+    }
+    dir
+}
+
+/// Append to `path` and date the change a minute ahead, so it's newer than the index
+/// regardless of the file system's timestamp granularity.
+fn edit(path: &Path) {
+    let mut source = std::fs::read_to_string(path).unwrap();
+    source.push_str("\n// edited\n");
+    std::fs::write(path, source).unwrap();
+    let file = std::fs::File::options().write(true).open(path).unwrap();
+    file.set_modified(SystemTime::now() + Duration::from_secs(60)).unwrap();
+}
+
+#[test]
+fn test_warns_about_files_changed_since_indexing() {
+    let dir = copy_fixtures();
+    let root = dir.path();
+    let cart = root.join(SHOP).join("Cart.kt");
+    let index = index_files(root);
+
+    let results = find_usages(&index, "com.example.shop.Cart.prices", None, None, false, None);
+    assert!(!results.is_empty(), "Expected usages of Cart.prices");
+    let files = || results.iter().map(|o| o.file.as_path());
+    assert!(index.changed_since_indexing(files()).is_empty());
+    assert_eq!(format_stale_warning(&[], root), None);
+
+    edit(&cart);
+    let changed = index.changed_since_indexing(files());
+    assert_eq!(changed, vec![cart.clone()]);
+    let warning = format_stale_warning(&changed, root).unwrap();
+    assert!(warning.starts_with("Warning: 1 file(s) changed"), "{}", warning);
+    assert!(warning.ends_with("\n  src/main/kotlin/com/example/shop/Cart.kt"), "{}", warning);
+}
+
+#[test]
+fn test_deleted_files_count_as_changed() {
+    let dir = copy_fixtures();
+    let root = dir.path();
+    let checkout = root.join(SHOP).join("Checkout.kt");
+    let index = index_files(root);
+
+    std::fs::remove_file(&checkout).unwrap();
+    let cart = root.join(SHOP).join("Cart.kt");
+    assert_eq!(
+        index.changed_since_indexing([checkout.as_path(), cart.as_path()]),
+        vec![checkout.clone()]
+    );
+    // Files that were never indexed aren't reported
+    assert!(index.changed_since_indexing([root.join("Missing.kt").as_path()]).is_empty());
+}
@@ -0,0 +1,82 @@
+use std::path::PathBuf;
+
+use kotlin_java_mcp::indexer::parser::index_files;
+use kotlin_java_mcp::indexer::symbols::{cross_reference, register_companion_aliases};
+use kotlin_java_mcp::indexer::SymbolKind;
+use kotlin_java_mcp::tools::super_definition::find_super_definition;
+
+fn fixture_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/sample-project")
+}
+
+fn build_index() -> kotlin_java_mcp::indexer::SymbolIndex {
+    let root = fixture_path();
+    let mut index = index_files(&root);
+    cross_reference(&mut index);
+    register_companion_aliases(&mut index);
+    index
+}
+
+#[test]
+fn test_override_resolves_to_interface_method() {
+    let index = build_index();
+    let results = find_super_definition(&index, "com.example.app.InMemoryUserRepository.findById");
+
+    assert_eq!(results.len(), 1, "Expected one super method, got: {:?}", results);
+    let found = results[0];
+    assert_eq!(found.fqn.as_deref(), Some("com.example.core.Repository.findById"));
+    assert!(matches!(found.kind, SymbolKind::FunctionDeclaration));
+    assert!(found.file.ends_with("Repository.kt"));
+    assert_eq!(found.line, 4);
+}
+
+#[test]
+fn test_override_with_generic_parameter_matches_by_arity() {
+    let index = build_index();
+    // `save(entity: User)` overrides `save(entity: T)`; the simple class name is resolved too.
+    let results = find_super_definition(&index, "InMemoryUserRepository.save");
+
+    let fqns: Vec<&str> = results.iter().filter_map(|o| o.fqn.as_deref()).collect();
+    assert_eq!(fqns, vec!["com.example.core.Repository.save"]);
+    assert_eq!(results[0].line, 6);
+}
+
+#[test]
+fn test_java_override_resolves_to_kotlin_interface_method() {
+    let index = build_index();
+    let results = find_super_definition(&index, "com.example.core.JavaGreeter.greet");
+
+    assert_eq!(results.len(), 1, "Expected one super method, got: {:?}", results);
+    assert_eq!(results[0].fqn.as_deref(), Some("com.example.core.Greeter.greet"));
+    assert!(results[0].file.ends_with("Greeter.kt"));
+    assert_eq!(results[0].line, 4);
+}
+
+#[test]
+fn test_method_without_supertype_declaration_returns_nothing() {
+    let index = build_index();
+
+    assert!(find_super_definition(&index, "com.example.core.Repository.findById").is_empty());
+    assert!(find_super_definition(&index, "com.example.app.InMemoryUserRepository.nonexistent")
+        .is_empty());
+    assert!(find_super_definition(&index, "findById").is_empty());
+}
+
+#[test]
+fn test_enum_entry_override_resolves_to_enum_member() {
+    let index = build_index();
+    // `GREEN(25) { override fun next() = YELLOW }` declares `TrafficLight.GREEN.next`
+    let green_next = index
+        .by_fqn
+        .get("com.example.app.TrafficLight.GREEN.next")
+        .and_then(|occs| occs.iter().find(|o| o.kind == SymbolKind::FunctionDeclaration))
+        .expect("Expected next() declared in the GREEN entry's body");
+    assert_eq!(green_next.line, 8);
+
+    let results = find_super_definition(&index, "com.example.app.TrafficLight.GREEN.next");
+    let found: Vec<(&str, usize)> = results
+        .iter()
+        .filter_map(|o| Some((o.fqn.as_deref()?, o.line)))
+        .collect();
+    assert_eq!(found, vec![("com.example.app.TrafficLight.next", 14)]);
+}
@@ -0,0 +1,68 @@
+use std::path::PathBuf;
+
+use kotlin_java_mcp::indexer::parser::index_files;
+use kotlin_java_mcp::indexer::symbols::{cross_reference, register_companion_aliases};
+use kotlin_java_mcp::indexer::SymbolKind;
+use kotlin_java_mcp::tools::symbol_at::{format_symbol_at, symbol_at};
+
+fn fixture_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/sample-project")
+}
+
+fn build_index() -> kotlin_java_mcp::indexer::SymbolIndex {
+    let root = fixture_path();
+    let mut index = index_files(&root);
+    cross_reference(&mut index);
+    register_companion_aliases(&mut index);
+    index
+}
+
+#[test]
+fn test_symbol_at_middle_of_class_name_is_the_declaration() {
+    let index = build_index();
+    let file = fixture_path().join("core/src/main/kotlin/com/example/core/UserService.kt");
+
+    // `class UserService(...)`: column 11 is inside the name
+    let occ = symbol_at(&index, &file, 3, 11).expect("Expected a symbol at the class name");
+    assert_eq!(occ.name, "UserService");
+    assert_eq!(occ.kind, SymbolKind::ClassDeclaration);
+    assert_eq!(occ.fqn.as_deref(), Some("com.example.core.UserService"));
+
+    let output = format_symbol_at(Some(occ), &fixture_path());
+    assert!(output.contains("kind: ClassDeclaration"), "Got: {}", output);
+    assert!(output.contains("role: declaration"), "Got: {}", output);
+}
+
+#[test]
+fn test_symbol_at_reference_wins_over_enclosing_declaration() {
+    let index = build_index();
+    let file = fixture_path().join("app/src/main/kotlin/com/example/app/Config.kt");
+
+    // `fun createUserService(): UserService {`: the return type, not the function
+    let occ = symbol_at(&index, &file, 8, 33).expect("Expected a symbol at the return type");
+    assert_eq!(occ.name, "UserService");
+    assert_eq!(occ.kind, SymbolKind::TypeReference);
+    assert_eq!(occ.fqn.as_deref(), Some("com.example.core.UserService"));
+
+    // `return UserService(repo)`: the constructor call
+    let occ = symbol_at(&index, &file, 10, 21).expect("Expected a symbol at the constructor call");
+    assert_eq!(occ.name, "UserService");
+    assert!(occ.kind.is_reference(), "Got: {:?}", occ.kind);
+    assert_eq!(occ.fqn.as_deref(), Some("com.example.core.UserService"));
+    assert!(format_symbol_at(Some(occ), &fixture_path()).contains("role: reference"));
+
+    // The function name itself
+    let occ = symbol_at(&index, &file, 8, 10).expect("Expected a symbol at the function name");
+    assert_eq!(occ.fqn.as_deref(), Some("com.example.app.Config.createUserService"));
+}
+
+#[test]
+fn test_symbol_at_blank_line_or_unknown_file() {
+    let index = build_index();
+    let file = fixture_path().join("app/src/main/kotlin/com/example/app/Config.kt");
+
+    assert!(symbol_at(&index, &file, 2, 1).is_none());
+    assert!(symbol_at(&index, &file, 100, 1).is_none());
+    assert!(symbol_at(&index, &fixture_path().join("Missing.kt"), 1, 1).is_none());
+    assert_eq!(format_symbol_at(None, &fixture_path()), "No symbol at this position.");
+}
@@ -0,0 +1,87 @@
+use std::path::{Path, PathBuf};
+
+use kotlin_java_mcp::gradle::GradleRunner;
+use kotlin_java_mcp::indexer::parser::index_files;
+use kotlin_java_mcp::indexer::symbols::{cross_reference, register_companion_aliases};
+use kotlin_java_mcp::tools::find_definition::find_definition;
+use kotlin_java_mcp::tools::symbol_module::{
+    format_symbol_modules, retain_in_module_dependencies, symbol_modules,
+};
+
+fn fixture_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/sample-project")
+}
+
+fn build_index() -> kotlin_java_mcp::indexer::SymbolIndex {
+    index_project(&fixture_path())
+}
+
+fn index_project(root: &Path) -> kotlin_java_mcp::indexer::SymbolIndex {
+    let mut index = index_files(root);
+    cross_reference(&mut index);
+    register_companion_aliases(&mut index);
+    index
+}
+
+/// Offline, modules are read from the fixture's settings.gradle.kts
+fn runner() -> GradleRunner {
+    GradleRunner::new(fixture_path(), true)
+}
+
+#[test]
+fn test_symbol_module_maps_declaration_to_module() {
+    let index = build_index();
+    let runner = runner();
+
+    let modules = symbol_modules(&index, &runner, "com.example.core.User").unwrap();
+    assert_eq!(modules.len(), 1);
+    let module = modules[0].module.as_ref().expect("Expected User to be in a module");
+    assert_eq!((module.path.as_str(), module.name.as_str()), (":core", "core"));
+
+    let flags = symbol_modules(&index, &runner, "FeatureFlags").unwrap();
+    assert_eq!(flags[0].module.as_ref().map(|m| m.path.as_str()), Some(":feature"));
+
+    let output = format_symbol_modules(&modules, &fixture_path());
+    assert!(output.contains("core/src/main/kotlin/com/example/core/User.kt"), "{}", output);
+    assert!(output.contains("module: :core (core)"), "{}", output);
+}
+
+#[test]
+fn test_symbol_module_outside_any_module() {
+    let index = build_index();
+    // scripts/ isn't included in the build
+    let modules = symbol_modules(&index, &runner(), "summarize").unwrap();
+    assert!(!modules.is_empty(), "Expected the script's summarize function");
+    assert!(modules.iter().all(|m| m.module.is_none()));
+    assert!(format_symbol_modules(&modules, &fixture_path()).contains("module: none"));
+
+    assert!(symbol_modules(&index, &runner(), "NoSuchSymbol").unwrap().is_empty());
+}
+
+#[test]
+fn test_module_dependencies_pick_the_reachable_declaration() {
+    // :app -> :platform -> :realtime; :simulated declares the same com.example.time.Clock
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/module-graph");
+    let index = index_project(&root);
+    let runner = GradleRunner::new(root.clone(), true);
+    let clock = "com.example.time.Clock";
+    assert_eq!(find_definition(&index, clock, None, None).len(), 2);
+
+    let scheduler = root.join("app/src/main/kotlin/com/example/scheduler/Scheduler.kt");
+    let mut results = find_definition(&index, clock, None, None);
+    retain_in_module_dependencies(&mut results, &runner, &scheduler).unwrap();
+    assert_eq!(results.len(), 1, "{:?}", results);
+    assert!(results[0].file.starts_with(root.join("realtime")), "{:?}", results[0].file);
+
+    // A module sees its own declaration
+    let simulated = root.join("simulated/src/main/kotlin/com/example/time/Clock.kt");
+    let mut results = find_definition(&index, clock, None, None);
+    retain_in_module_dependencies(&mut results, &runner, &simulated).unwrap();
+    assert_eq!(results.len(), 1, "{:?}", results);
+    assert_eq!(results[0].file, simulated);
+
+    // Files outside every module leave the results alone
+    let mut results = find_definition(&index, clock, None, None);
+    retain_in_module_dependencies(&mut results, &runner, &root.join("build.gradle.kts")).unwrap();
+    assert_eq!(results.len(), 2);
+}
@@ -0,0 +1,85 @@
+use std::path::PathBuf;
+
+use kotlin_java_mcp::indexer::parser::index_files;
+use kotlin_java_mcp::indexer::symbols::{cross_reference, register_companion_aliases};
+use kotlin_java_mcp::tools::type_hierarchy::{format_type_hierarchies, type_hierarchy, TypeHierarchyNode};
+
+fn fixture_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/sample-project")
+}
+
+fn build_index() -> kotlin_java_mcp::indexer::SymbolIndex {
+    let root = fixture_path();
+    let mut index = index_files(&root);
+    cross_reference(&mut index);
+    register_companion_aliases(&mut index);
+    index
+}
+
+fn child<'a>(nodes: &'a [TypeHierarchyNode], fqn: &str) -> &'a TypeHierarchyNode {
+    nodes.iter().find(|n| n.fqn == fqn).unwrap_or_else(|| {
+        panic!(
+            "Expected {} in hierarchy, got: {:?}",
+            fqn,
+            nodes.iter().map(|n| n.fqn.as_str()).collect::<Vec<_>>()
+        )
+    })
+}
+
+#[test]
+fn test_supertypes_cross_language() {
+    // PoliteGreeter (Kotlin) extends JavaGreeter (Java), which implements Greeter (Kotlin)
+    let index = build_index();
+    let hierarchies = type_hierarchy(&index, "com.example.app.PoliteGreeter", &fixture_path());
+    assert_eq!(hierarchies.len(), 1);
+    let hierarchy = &hierarchies[0];
+
+    assert_eq!(hierarchy.root.kind.as_deref(), Some("ClassDeclaration"));
+    let java_greeter = child(&hierarchy.supertypes, "com.example.core.JavaGreeter");
+    assert_eq!(java_greeter.file.as_deref(), Some("core/src/main/java/com/example/core/JavaGreeter.java"));
+    let greeter = child(&java_greeter.children, "com.example.core.Greeter");
+    assert_eq!(greeter.kind.as_deref(), Some("InterfaceDeclaration"));
+    assert!(hierarchy.subtypes.is_empty());
+}
+
+#[test]
+fn test_subtypes_transitive() {
+    let index = build_index();
+    let hierarchies = type_hierarchy(&index, "Greeter", &fixture_path());
+    assert_eq!(hierarchies.len(), 1);
+    let subtypes = &hierarchies[0].subtypes;
+
+    child(subtypes, "com.example.core.DefaultGreeter");
+    child(subtypes, "com.example.app.LoudGreeter");
+    let java_greeter = child(subtypes, "com.example.core.JavaGreeter");
+    child(&java_greeter.children, "com.example.app.PoliteGreeter");
+}
+
+#[test]
+fn test_subtypes_map_is_inverse_of_supertypes() {
+    let index = build_index();
+    let subtypes = index
+        .subtypes
+        .get("com.example.core.Result")
+        .expect("Expected subtypes of Result");
+    assert!(subtypes.contains(&"com.example.core.Result.Success".to_string()));
+    assert!(subtypes.contains(&"com.example.app.Cancelled".to_string()));
+}
+
+#[test]
+fn test_format_type_hierarchy() {
+    let index = build_index();
+    let hierarchies = type_hierarchy(&index, "com.example.core.JavaGreeter", &fixture_path());
+    let output = format_type_hierarchies(&hierarchies);
+
+    assert!(output.starts_with("Type hierarchy of com.example.core.JavaGreeter (ClassDeclaration)"), "{}", output);
+    assert!(output.contains("  Supertypes:\n    com.example.core.Greeter (InterfaceDeclaration)"), "{}", output);
+    assert!(output.contains("  Subtypes:\n    com.example.app.PoliteGreeter (ClassDeclaration)"), "{}", output);
+}
+
+#[test]
+fn test_type_hierarchy_nonexistent() {
+    let index = build_index();
+    assert!(type_hierarchy(&index, "DoesNotExist", &fixture_path()).is_empty());
+    assert!(type_hierarchy(&index, "com.example.DoesNotExist", &fixture_path()).is_empty());
+}
@@ -0,0 +1,77 @@
+use std::path::PathBuf;
+
+use kotlin_java_mcp::indexer::parser::index_files;
+use kotlin_java_mcp::indexer::symbols::{cross_reference, register_companion_aliases};
+use kotlin_java_mcp::indexer::Visibility;
+use kotlin_java_mcp::tools::unused::{find_unused_declarations, UnusedDeclaration};
+
+fn fixture_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/sample-project")
+}
+
+fn build_index() -> kotlin_java_mcp::indexer::SymbolIndex {
+    let root = fixture_path();
+    let mut index = index_files(&root);
+    cross_reference(&mut index);
+    register_companion_aliases(&mut index);
+    index
+}
+
+fn find<'a>(unused: &'a [UnusedDeclaration<'a>], fqn: &str) -> Option<&'a UnusedDeclaration<'a>> {
+    unused.iter().find(|u| u.declaration.fqn.as_deref() == Some(fqn))
+}
+
+#[test]
+fn test_unused_private_function_is_flagged() {
+    let index = build_index();
+    let unused = find_unused_declarations(&index, false);
+
+    let legacy = find(&unused, "com.example.app.ReportFormatter.legacyFormat")
+        .expect("legacyFormat is never called");
+    assert_eq!(legacy.visibility, Visibility::Private);
+    assert!(legacy.declaration.file.ends_with("UnusedCode.kt"));
+    assert_eq!(legacy.declaration.line, 8);
+
+    let prefix = find(&unused, "com.example.app.ReportFormatter.unusedPrefix")
+        .expect("unusedPrefix is never read");
+    assert_eq!(prefix.visibility, Visibility::Private);
+
+    let format_all =
+        find(&unused, "com.example.app.formatAll").expect("formatAll is never called");
+    assert_eq!(format_all.visibility, Visibility::Internal);
+}
+
+#[test]
+fn test_visibility_of_names_inside_modifiers() {
+    let index = build_index();
+    let unused = find_unused_declarations(&index, false);
+
+    // `ate` and `tern` also occur inside `private` and `internal`.
+    let ate = find(&unused, "com.example.app.ReportFormatter.ate").expect("ate is never called");
+    assert_eq!(ate.visibility, Visibility::Private);
+    let tern = find(&unused, "com.example.app.ReportFormatter.tern").expect("tern is never called");
+    assert_eq!(tern.visibility, Visibility::Internal);
+}
+
+#[test]
+fn test_used_and_public_declarations_are_not_flagged() {
+    let index = build_index();
+    let unused = find_unused_declarations(&index, false);
+
+    assert!(find(&unused, "com.example.app.ReportFormatter.pad").is_none());
+    // `format` is called, and public declarations are excluded by default anyway.
+    assert!(find(&unused, "com.example.app.ReportFormatter.format").is_none());
+    assert!(unused.iter().all(|u| u.visibility != Visibility::Public));
+}
+
+#[test]
+fn test_include_public_still_skips_referenced_declarations() {
+    let index = build_index();
+    let unused = find_unused_declarations(&index, true);
+
+    assert!(find(&unused, "com.example.app.ReportFormatter.pad").is_none());
+    assert!(find(&unused, "com.example.app.ReportFormatter.format").is_none());
+    assert!(find(&unused, "com.example.app.ReportFormatter.legacyFormat").is_some());
+    // Called through `Any.toString()`.
+    assert!(find(&unused, "com.example.app.ReportFormatter.toString").is_none());
+}